@@ -37,6 +37,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let rpc_url = std::env::var("MONAD_RPC_URL").expect("MONAD_RPC_URL not set");
     let private_key = std::env::var("PRIVATE_KEY").expect("PRIVATE_KEY not set");
+    // Same floor as the bot's front-run buys; ALLOW_ZERO_MIN=true dumps at any price
+    let max_slippage_pct: f64 = std::env::var("MAX_ACCEPTABLE_SLIPPAGE_PCT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(50.0);
+    let allow_zero_min = std::env::var("ALLOW_ZERO_MIN").map(|v| v == "true").unwrap_or(false);
+    // A sell still pending after this long reverts instead of filling at a stale price
+    let deadline_secs: u64 = std::env::var("TX_DEADLINE_SEC")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(120);
 
     let core = Core::new(rpc_url, private_key, Network::Mainnet).await?;
     let wallet = core.wallet_address();
@@ -65,7 +76,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Get Router via get_amount_out to be sure
         println!("🔍 Fetching quote to find router...");
-        let (router, _) = core.get_amount_out(token, balance, false).await.unwrap_or_else(|_| {
+        let (router, expected_mon) = core.get_amount_out(token, balance, false).await.unwrap_or_else(|_| {
             // Fallback to default bonding curve address from env if quote fails
             let router_env = std::env::var("ROUTER_ADDRESS").unwrap_or("0x6F6B8F1a20703309951a5127c45B49b1CD981A22".to_string());
             let r_addr = Address::from_str(&router_env).unwrap();
@@ -82,13 +93,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("✅ Approved: {:?}", tx);
         }
 
+        let amount_out_min = if allow_zero_min {
+            println!("☠️ Selling {} with ZERO min out (ALLOW_ZERO_MIN) - open to sandwiching", pos.name);
+            U256::ZERO
+        } else if expected_mon.is_zero() {
+            println!("⚠️ No quote for {}, skipping (set ALLOW_ZERO_MIN=true to sell blind)", pos.name);
+            continue;
+        } else {
+            let bps = (max_slippage_pct.clamp(0.0, 100.0) * 100.0).round() as u64;
+            expected_mon * U256::from(10_000 - bps) / U256::from(10_000)
+        };
+        println!("🛡️ Min out: {} (quote {}, max slippage {}%)", amount_out_min, expected_mon, max_slippage_pct);
+
         // Sell
         let sell_params = SellParams {
             token,
             amount_in: balance,
-            amount_out_min: U256::ZERO, // 100% Slippage
+            amount_out_min,
             to: wallet,
-            deadline: U256::from(9999999999999999u64),
+            deadline: U256::from(chrono::Utc::now().timestamp() as u64 + deadline_secs),
             gas_limit: Some(500000),
             gas_price: None,
             nonce: None,
@@ -0,0 +1,206 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Read-only HTTP status API for live bot state.
+//!
+//! `GET /health` is open; `/positions`, `/wallets` and `/pnl` require
+//! `Authorization: Bearer <API_TOKEN>`.
+
+use crate::health::HealthStatus;
+use crate::position::{Position, PositionTracker};
+use crate::trade_history::{TradeHistory, TradeSummary};
+use crate::validators::wallet_tracker::{WalletStats, WalletTracker};
+use alloy::primitives::Address;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+/// Shared trackers the API reads from (the same ones `main` mutates).
+pub struct ApiState {
+    pub token: String,
+    pub health: Arc<HealthStatus>,
+    pub positions: Arc<Mutex<PositionTracker>>,
+    pub wallet_tracker: Arc<Mutex<WalletTracker>>,
+    pub trade_history: Arc<Mutex<TradeHistory>>,
+}
+
+#[derive(Debug, Serialize)]
+struct HealthView {
+    status: &'static str,
+    uptime_secs: u64,
+    stream_up: bool,
+    mempool_up: bool,
+    last_event_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct WalletView {
+    wallet: Address,
+    score: f64,
+    #[serde(flatten)]
+    stats: WalletStats,
+}
+
+/// Serve the API on `port` in the background.
+pub fn spawn_api_server(port: u16, state: ApiState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        if let Err(e) = start_api_server(port, Arc::new(state)).await {
+            error!("❌ Status API stopped: {}", e);
+        }
+    })
+}
+
+async fn start_api_server(port: u16, state: Arc<ApiState>) -> Result<(), String> {
+    let app = Router::new()
+        .route("/health", get(health_check))
+        .route("/positions", get(get_positions))
+        .route("/wallets", get(get_wallets))
+        .route("/pnl", get(get_pnl))
+        .with_state(state);
+
+    let addr = format!("0.0.0.0:{}", port);
+    info!("🌐 Starting status API on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("Failed to bind: {}", e))?;
+
+    axum::serve(listener, app)
+        .await
+        .map_err(|e| format!("Server error: {}", e))
+}
+
+/// Check `Authorization: Bearer <token>`.
+fn authorize(state: &ApiState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+
+    if bearer != state.token {
+        warn!("Rejected status API request: invalid bearer token");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(())
+}
+
+async fn health_check(State(state): State<Arc<ApiState>>) -> Json<HealthView> {
+    Json(HealthView {
+        status: "ok",
+        uptime_secs: state.health.uptime().as_secs(),
+        stream_up: state.health.stream_up(),
+        mempool_up: state.health.mempool_up(),
+        last_event_at: state.health.last_event_at(),
+    })
+}
+
+async fn get_positions(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Position>>, StatusCode> {
+    authorize(&state, &headers)?;
+    let positions = state.positions.lock().await.all().into_iter().cloned().collect();
+    Ok(Json(positions))
+}
+
+async fn get_wallets(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WalletView>>, StatusCode> {
+    authorize(&state, &headers)?;
+    let tracker = state.wallet_tracker.lock().await;
+    let mut wallets: Vec<WalletView> = tracker
+        .stats
+        .iter()
+        .map(|(wallet, stats)| WalletView {
+            wallet: *wallet,
+            score: tracker.get_score(wallet),
+            stats: stats.clone(),
+        })
+        .collect();
+    wallets.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(Json(wallets))
+}
+
+async fn get_pnl(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+) -> Result<Json<TradeSummary>, StatusCode> {
+    authorize(&state, &headers)?;
+    Ok(Json(state.trade_history.lock().await.get_summary()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use std::collections::HashMap;
+
+    fn state() -> Arc<ApiState> {
+        Arc::new(ApiState {
+            token: "secret".to_string(),
+            health: Arc::new(HealthStatus::new()),
+            positions: Arc::new(Mutex::new(PositionTracker::in_memory())),
+            wallet_tracker: Arc::new(Mutex::new(WalletTracker {
+                stats: HashMap::new(),
+                active_positions: HashMap::new(),
+            })),
+            trade_history: Arc::new(Mutex::new(TradeHistory::in_memory())),
+        })
+    }
+
+    fn bearer(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn only_the_configured_bearer_token_is_accepted() {
+        let state = state();
+        assert_eq!(authorize(&state, &bearer("Bearer secret")), Ok(()));
+        assert_eq!(authorize(&state, &bearer("Bearer wrong")), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(authorize(&state, &bearer("secret")), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(authorize(&state, &HeaderMap::new()), Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn guarded_routes_reject_missing_tokens_and_health_does_not() {
+        let state = state();
+        assert!(get_positions(State(state.clone()), HeaderMap::new()).await.is_err());
+        assert!(get_wallets(State(state.clone()), HeaderMap::new()).await.is_err());
+        assert!(get_pnl(State(state.clone()), HeaderMap::new()).await.is_err());
+
+        let Json(positions) = get_positions(State(state.clone()), bearer("Bearer secret")).await.unwrap();
+        assert!(positions.is_empty());
+        assert_eq!(health_check(State(state)).await.0.status, "ok");
+    }
+
+    #[tokio::test]
+    async fn wallets_are_listed_best_score_first() {
+        let state = state();
+        {
+            let mut tracker = state.wallet_tracker.lock().await;
+            let mut winner = WalletStats::default();
+            let mut loser = WalletStats::default();
+            for _ in 0..3 {
+                winner.record_trade(1.0, 1.0, 100.0, 60, 0);
+                loser.record_trade(1.0, -0.5, -50.0, 60, 0);
+            }
+            tracker.stats.insert(Address::repeat_byte(1), loser);
+            tracker.stats.insert(Address::repeat_byte(2), winner);
+        }
+
+        let Json(wallets) = get_wallets(State(state), bearer("Bearer secret")).await.unwrap();
+        assert_eq!(wallets[0].wallet, Address::repeat_byte(2));
+        assert!(wallets[0].score > wallets[1].score);
+    }
+}
@@ -6,11 +6,14 @@
 
 use crate::arbitrage::{ArbitrageOpportunity, DexType};
 use crate::config::Config;
-use alloy::primitives::Address;
+use crate::executor::receipt::wait_receipt;
+use crate::executor::TradeError;
+use alloy::primitives::{Address, B256};
 use alloy::providers::Provider;
 use alloy::rpc::types::TransactionRequest;
 use alloy::sol;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tracing::{error, info};
 
 // FlashArbitrage contract interface
@@ -28,11 +31,17 @@ sol! {
 }
 
 /// Router addresses for each DEX.
-fn get_router(dex: DexType) -> Address {
-    match dex {
-        DexType::ZKSwap => "0x68225b5ba7cE309fD0d3f0C9A74b947c7d7e03dA".parse().unwrap(),
-        DexType::OctoSwap => "0x60fd5Aa15Debd5ffdEfB5129FD9FD8A34d80d608".parse().unwrap(),
-    }
+fn get_router(dex: DexType) -> Result<Address, TradeError> {
+    let address = match dex {
+        DexType::ZKSwap => "0x68225b5ba7cE309fD0d3f0C9A74b947c7d7e03dA",
+        DexType::OctoSwap => "0x60fd5Aa15Debd5ffdEfB5129FD9FD8A34d80d608",
+        DexType::Kuru => {
+            return Err(TradeError::Config("FlashArbitrage has no Kuru pair".to_string()));
+        }
+    };
+    address
+        .parse()
+        .map_err(|e| TradeError::Config(format!("Invalid {} router address {}: {}", dex, address, e)))
 }
 
 /// Arbitrage executor.
@@ -41,14 +50,15 @@ pub struct ArbitrageExecutor<P: Provider + Clone> {
     flash_contract: Address,
     nonce: AtomicU64,
     gas_limit: u64,
+    receipt_timeout: Duration,
 }
 
 impl<P: Provider + Clone> ArbitrageExecutor<P> {
-    pub async fn new(provider: P, config: &Config) -> Result<Self, String> {
+    pub async fn new(provider: P, config: &Config) -> Result<Self, TradeError> {
         let nonce = provider
             .get_transaction_count(config.wallet_address)
             .await
-            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to get nonce", e))?;
 
         // Use arbitrage contract address from config or default
         let flash_contract = config.arbitrage_contract
@@ -59,13 +69,14 @@ impl<P: Provider + Clone> ArbitrageExecutor<P> {
             flash_contract,
             nonce: AtomicU64::new(nonce),
             gas_limit: config.gas_limit,
+            receipt_timeout: config.receipt_timeout(),
         })
     }
 
-    /// Execute arbitrage opportunity.
-    pub async fn execute(&self, opp: &ArbitrageOpportunity) -> Result<(), String> {
+    /// Execute arbitrage opportunity. Returns the tx hash once it's mined successfully.
+    pub async fn execute(&self, opp: &ArbitrageOpportunity) -> Result<B256, TradeError> {
         if self.flash_contract == Address::ZERO {
-            return Err("FlashArbitrage contract not deployed".to_string());
+            return Err(TradeError::Config("FlashArbitrage contract not deployed".to_string()));
         }
 
         info!(
@@ -73,14 +84,19 @@ impl<P: Provider + Clone> ArbitrageExecutor<P> {
             opp.token_a, opp.token_b, opp.buy_on, opp.sell_on
         );
 
+        // The flash contract only swaps between its OctoSwap and ZKSwap pairs
+        if opp.buy_on == DexType::Kuru || opp.sell_on == DexType::Kuru {
+            return Err(TradeError::Config("FlashArbitrage can't route through Kuru".to_string()));
+        }
+
         let contract = IFlashArbitrage::new(self.flash_contract, &self.provider);
 
         // Determine which pair to borrow from
         let borrow_from_a = opp.buy_on == DexType::OctoSwap;
 
         let call = contract.executeArbitrage(
-            get_router(DexType::OctoSwap), // pairA
-            get_router(DexType::ZKSwap),   // pairB
+            get_router(DexType::OctoSwap)?, // pairA
+            get_router(DexType::ZKSwap)?,   // pairB
             opp.token_a,
             opp.amount_in,
             borrow_from_a,
@@ -94,33 +110,93 @@ impl<P: Provider + Clone> ArbitrageExecutor<P> {
             .nonce(nonce)
             .gas_limit(self.gas_limit);
 
-        match self.provider.send_transaction(tx).await {
-            Ok(pending) => {
-                info!("📤 Arb TX sent: {:?}", pending.tx_hash());
-
-                match pending.get_receipt().await {
-                    Ok(receipt) => {
-                        if receipt.status() {
-                            info!(
-                                "✅ ARB SUCCESS! Profit: {} bps, TX: {:?}",
-                                opp.profit_bps, receipt.transaction_hash
-                            );
-                        } else {
-                            error!(
-                                "❌ ARB REVERTED (no profit): {:?}",
-                                receipt.transaction_hash
-                            );
-                        }
-                    }
-                    Err(e) => error!("Failed to get receipt: {}", e),
-                }
-            }
-            Err(e) => {
-                self.nonce.fetch_sub(1, Ordering::SeqCst);
-                error!("Failed to send arb TX: {}", e);
-            }
+        let pending = self.provider.send_transaction(tx).await.map_err(|e| {
+            self.nonce.fetch_sub(1, Ordering::SeqCst);
+            TradeError::classify("Failed to send arb TX", e)
+        })?;
+        info!("📤 Arb TX sent: {:?}", pending.tx_hash());
+
+        let tx_hash = *pending.tx_hash();
+        let receipt = wait_receipt(tx_hash, self.receipt_timeout, "Failed to get receipt", pending.get_receipt()).await?;
+        if !receipt.status() {
+            error!("❌ ARB REVERTED (no profit): {:?}", receipt.transaction_hash);
+            return Err(TradeError::Reverted {
+                hash: format!("{:?}", receipt.transaction_hash),
+            });
         }
 
-        Ok(())
+        info!(
+            "✅ ARB SUCCESS! Profit: {} bps, TX: {:?}",
+            opp.profit_bps, receipt.transaction_hash
+        );
+        Ok(receipt.transaction_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, MockProvider};
+    use alloy::primitives::U256;
+
+    #[test]
+    fn every_flash_arbitrage_router_parses() {
+        assert!(get_router(DexType::ZKSwap).is_ok());
+        assert!(get_router(DexType::OctoSwap).is_ok());
+        assert_ne!(get_router(DexType::ZKSwap), get_router(DexType::OctoSwap));
+        assert!(matches!(get_router(DexType::Kuru), Err(TradeError::Config(_))));
+    }
+
+    fn executor(provider: MockProvider, flash_contract: Address) -> ArbitrageExecutor<MockProvider> {
+        ArbitrageExecutor {
+            provider,
+            flash_contract,
+            nonce: AtomicU64::new(7),
+            gas_limit: 500_000,
+            receipt_timeout: Duration::from_secs(1),
+        }
+    }
+
+    fn opportunity(buy_on: DexType, sell_on: DexType) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            token_a: Address::repeat_byte(1),
+            token_b: Address::repeat_byte(2),
+            amount_in: U256::from(1_000u64),
+            buy_on,
+            sell_on,
+            expected_profit: U256::from(10u64),
+            profit_bps: 100,
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_contract_or_kuru_leg_is_a_config_error() {
+        // Neither gets as far as an RPC call, so nothing is scripted
+        let (provider, _) = mock_provider();
+        let err = executor(provider, Address::ZERO)
+            .execute(&opportunity(DexType::OctoSwap, DexType::ZKSwap))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TradeError::Config(_)));
+        assert!(!err.is_transient());
+
+        let (provider, _) = mock_provider();
+        let err = executor(provider, Address::repeat_byte(0xf1))
+            .execute(&opportunity(DexType::Kuru, DexType::ZKSwap))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TradeError::Config(_)));
+    }
+
+    #[tokio::test]
+    async fn failed_send_is_classified_and_frees_the_nonce() {
+        let (provider, asserter) = mock_provider();
+        asserter.push_failure_msg("nonce too low");
+        let executor = executor(provider, Address::repeat_byte(0xf1));
+
+        let err = executor.execute(&opportunity(DexType::OctoSwap, DexType::ZKSwap)).await.unwrap_err();
+        assert!(matches!(err, TradeError::NonceError(_)), "{:?}", err);
+        assert!(err.is_transient());
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), 7);
     }
 }
@@ -0,0 +1,42 @@
+// Copyright (C) 2025 Category Labs, Inc.
+#![allow(unused)]
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Kuru DEX price feed for Monad.
+//! Kuru's router has no fixed address here; it is set with `KURU_ROUTER`.
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::sol;
+
+sol! {
+    #[sol(rpc)]
+    interface IKuruRouter {
+        function getAmountsOut(uint256 amountIn, address[] calldata path)
+            external view returns (uint256[] memory amounts);
+    }
+}
+
+/// Get quote from the Kuru router at `router` for a token pair.
+pub async fn get_quote<P: Provider + Clone>(
+    provider: &P,
+    router: Address,
+    token_in: Address,
+    token_out: Address,
+    amount_in: U256,
+) -> Result<U256, String> {
+    let contract = IKuruRouter::new(router, provider);
+
+    let path = vec![token_in, token_out];
+
+    match contract.getAmountsOut(amount_in, path).call().await {
+        Ok(amounts) => {
+            if amounts.len() >= 2 {
+                Ok(amounts[1])
+            } else {
+                Err("Invalid amounts returned".to_string())
+            }
+        }
+        Err(e) => Err(format!("Kuru quote failed: {}", e)),
+    }
+}
@@ -5,8 +5,11 @@
 //! Arbitrage module for DEX price comparison.
 
 pub mod executor;
+pub mod kuru;
 pub mod octoswap;
+pub mod quoter;
 pub mod scanner;
 pub mod zkswap;
 
-pub use scanner::{spawn_scanner, ArbitrageOpportunity, DexType, TokenPair};
+pub use quoter::{default_quoters, DexQuoter};
+pub use scanner::{spawn_scanner, ArbCosts, ArbitrageOpportunity, DexType, TokenPair};
@@ -0,0 +1,114 @@
+// Copyright (C) 2025 Category Labs, Inc.
+#![allow(unused)]
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Price quoting abstraction so the scanner can compare any number of DEXs.
+
+use super::scanner::DexType;
+use super::{kuru, octoswap, zkswap};
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Future returned by `DexQuoter::get_quote`.
+pub type QuoteFuture<'a> = Pin<Box<dyn Future<Output = Result<U256, String>> + Send + 'a>>;
+
+/// A venue that can quote `amount_in` of `token_in` into `token_out`.
+pub trait DexQuoter: Send + Sync {
+    fn dex(&self) -> DexType;
+    fn get_quote(&self, token_in: Address, token_out: Address, amount_in: U256) -> QuoteFuture<'_>;
+}
+
+/// Uniswap V2-style router quote (`getAmountsOut`).
+pub struct RouterQuoter<P> {
+    provider: P,
+    dex: DexType,
+}
+
+impl<P: Provider + Clone> RouterQuoter<P> {
+    pub fn new(provider: P, dex: DexType) -> Self {
+        Self { provider, dex }
+    }
+}
+
+impl<P: Provider + Clone + Send + Sync> DexQuoter for RouterQuoter<P> {
+    fn dex(&self) -> DexType {
+        self.dex
+    }
+
+    fn get_quote(&self, token_in: Address, token_out: Address, amount_in: U256) -> QuoteFuture<'_> {
+        Box::pin(async move {
+            match self.dex {
+                DexType::ZKSwap => zkswap::get_quote(&self.provider, token_in, token_out, amount_in).await,
+                DexType::OctoSwap => octoswap::get_quote(&self.provider, token_in, token_out, amount_in).await,
+                DexType::Kuru => Err("Kuru has no fixed router; use KuruQuoter".to_string()),
+            }
+        })
+    }
+}
+
+/// Kuru quote through its configured router.
+pub struct KuruQuoter<P> {
+    provider: P,
+    router: Address,
+}
+
+impl<P: Provider + Clone> KuruQuoter<P> {
+    pub fn new(provider: P, router: Address) -> Self {
+        Self { provider, router }
+    }
+}
+
+impl<P: Provider + Clone + Send + Sync> DexQuoter for KuruQuoter<P> {
+    fn dex(&self) -> DexType {
+        DexType::Kuru
+    }
+
+    fn get_quote(&self, token_in: Address, token_out: Address, amount_in: U256) -> QuoteFuture<'_> {
+        Box::pin(async move { kuru::get_quote(&self.provider, self.router, token_in, token_out, amount_in).await })
+    }
+}
+
+/// Quoters for every DEX venue: ZKSwap, OctoSwap, and Kuru when its router is configured.
+pub fn default_quoters<P: Provider + Clone + Send + Sync + 'static>(
+    provider: P,
+    kuru_router: Option<Address>,
+) -> Vec<Box<dyn DexQuoter>> {
+    let mut quoters: Vec<Box<dyn DexQuoter>> = vec![
+        Box::new(RouterQuoter::new(provider.clone(), DexType::ZKSwap)),
+        Box::new(RouterQuoter::new(provider.clone(), DexType::OctoSwap)),
+    ];
+    if let Some(router) = kuru_router {
+        quoters.push(Box::new(KuruQuoter::new(provider, router)));
+    }
+    quoters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_call};
+
+    #[test]
+    fn kuru_is_quoted_only_when_its_router_is_set() {
+        let (provider, _) = mock_provider();
+        let dexes = |quoters: Vec<Box<dyn DexQuoter>>| quoters.iter().map(|q| q.dex()).collect::<Vec<_>>();
+
+        assert_eq!(dexes(default_quoters(provider.clone(), None)), [DexType::ZKSwap, DexType::OctoSwap]);
+        assert_eq!(
+            dexes(default_quoters(provider, Some(Address::repeat_byte(9)))),
+            [DexType::ZKSwap, DexType::OctoSwap, DexType::Kuru]
+        );
+    }
+
+    #[tokio::test]
+    async fn kuru_quote_is_the_last_hop_of_get_amounts_out() {
+        let (provider, asserter) = mock_provider();
+        push_call::<kuru::IKuruRouter::getAmountsOutCall>(&asserter, &vec![U256::from(100u64), U256::from(42u64)]);
+        let quoter = KuruQuoter::new(provider, Address::repeat_byte(9));
+
+        let out = quoter.get_quote(Address::repeat_byte(1), Address::repeat_byte(2), U256::from(100u64)).await;
+        assert_eq!(out, Ok(U256::from(42u64)));
+    }
+}
@@ -3,17 +3,23 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Arbitrage opportunity scanner for Monad DEXs.
-//! Compares prices between ZKSwap and OctoSwap.
+//! Compares prices across every configured DEX quoter.
 
-use super::{octoswap, zkswap};
+use super::quoter::DexQuoter;
+use crate::config::Config;
+use alloy::eips::BlockNumberOrTag;
 use alloy::primitives::{Address, U256};
 use alloy::providers::Provider;
+use futures_util::future::join_all;
 use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
-/// Minimum profit threshold (0.3% = 30 bps).
+/// Minimum profit threshold, after gas and fees (0.3% = 30 bps).
 const MIN_PROFIT_BPS: u64 = 30;
 
+/// Swap fee assumed for a DEX without a configured one (Uniswap V2's 0.3%).
+const DEFAULT_DEX_FEE_BPS: u64 = 30;
+
 /// Arbitrage opportunity detected.
 #[derive(Debug, Clone)]
 pub struct ArbitrageOpportunity {
@@ -22,6 +28,7 @@ pub struct ArbitrageOpportunity {
     pub amount_in: U256,
     pub buy_on: DexType,
     pub sell_on: DexType,
+    /// Net of gas and swap fees, in `token_b`.
     pub expected_profit: U256,
     pub profit_bps: u64,
 }
@@ -30,6 +37,19 @@ pub struct ArbitrageOpportunity {
 pub enum DexType {
     ZKSwap,
     OctoSwap,
+    Kuru,
+}
+
+impl DexType {
+    /// Parse a DEX name, case-insensitively.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "zkswap" => Some(DexType::ZKSwap),
+            "octoswap" => Some(DexType::OctoSwap),
+            "kuru" => Some(DexType::Kuru),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for DexType {
@@ -37,6 +57,7 @@ impl std::fmt::Display for DexType {
         match self {
             DexType::ZKSwap => write!(f, "ZKSwap"),
             DexType::OctoSwap => write!(f, "OctoSwap"),
+            DexType::Kuru => write!(f, "Kuru"),
         }
     }
 }
@@ -49,33 +70,69 @@ pub struct TokenPair {
     pub name: String,
 }
 
+/// What executing an arbitrage costs besides the quoted spread.
+#[derive(Debug, Clone)]
+pub struct ArbCosts {
+    /// Gas limit of the flash-arb transaction.
+    pub gas_limit: u64,
+    /// Swap fee per DEX, in bps of the traded amount.
+    pub dex_fees_bps: Vec<(DexType, u64)>,
+    /// Gas is paid in MON, so it can only be priced for pairs starting in WMON.
+    pub wmon: Address,
+}
+
+impl ArbCosts {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            gas_limit: config.gas_limit,
+            dex_fees_bps: config.arb_dex_fees_bps.clone(),
+            wmon: config.wmon_address,
+        }
+    }
+
+    /// Swap fee on `dex`, in bps.
+    pub fn fee_bps(&self, dex: DexType) -> u64 {
+        self.dex_fees_bps
+            .iter()
+            .find(|(d, _)| *d == dex)
+            .map_or(DEFAULT_DEX_FEE_BPS, |(_, bps)| *bps)
+    }
+
+    /// Gas cost of one arb at `base_fee`, in wei of MON.
+    pub fn gas_cost_wei(&self, base_fee: u128) -> U256 {
+        U256::from(self.gas_limit) * U256::from(base_fee)
+    }
+}
+
 /// Arbitrage scanner that compares prices across DEXs.
-pub struct ArbitrageScanner<P: Provider + Clone> {
-    provider: P,
+pub struct ArbitrageScanner {
+    quoters: Vec<Box<dyn DexQuoter>>,
     pairs: Vec<TokenPair>,
     scan_amount: U256,
     min_profit_bps: u64,
+    costs: ArbCosts,
 }
 
-impl<P: Provider + Clone + Send + Sync + 'static> ArbitrageScanner<P> {
-    pub fn new(provider: P, pairs: Vec<TokenPair>, scan_amount: U256) -> Self {
+impl ArbitrageScanner {
+    pub fn new(quoters: Vec<Box<dyn DexQuoter>>, pairs: Vec<TokenPair>, scan_amount: U256, costs: ArbCosts) -> Self {
         Self {
-            provider,
+            quoters,
             pairs,
             scan_amount,
             min_profit_bps: MIN_PROFIT_BPS,
+            costs,
         }
     }
 
-    /// Scan all pairs for arbitrage opportunities.
-    pub async fn scan(&self) -> Vec<ArbitrageOpportunity> {
+    /// Scan all pairs for arbitrage opportunities, with gas priced at `base_fee`.
+    pub async fn scan(&self, base_fee: u128) -> Vec<ArbitrageOpportunity> {
         let mut opportunities = Vec::new();
 
         for pair in &self.pairs {
-            match self.check_pair(pair).await {
+            match self.check_pair(pair, base_fee).await {
                 Ok(Some(opp)) => {
                     info!(
-                        "💰 ARB FOUND: {} - Buy on {}, Sell on {} - Profit: {} bps",
+                        "💰 ARB FOUND: {} - Buy on {}, Sell on {} - Net profit: {} bps",
                         pair.name, opp.buy_on, opp.sell_on, opp.profit_bps
                     );
                     opportunities.push(opp);
@@ -92,75 +149,119 @@ impl<P: Provider + Clone + Send + Sync + 'static> ArbitrageScanner<P> {
         opportunities
     }
 
-    async fn check_pair(&self, pair: &TokenPair) -> Result<Option<ArbitrageOpportunity>, String> {
-        // Get quotes from both DEXs
-        let (zkswap_quote, octo_quote) = tokio::join!(
-            zkswap::get_quote(&self.provider, pair.token_a, pair.token_b, self.scan_amount),
-            octoswap::get_quote(&self.provider, pair.token_a, pair.token_b, self.scan_amount)
-        );
+    async fn check_pair(&self, pair: &TokenPair, base_fee: u128) -> Result<Option<ArbitrageOpportunity>, String> {
+        if pair.token_a != self.costs.wmon {
+            return Err("gas can only be priced for pairs quoted from WMON".to_string());
+        }
 
-        let zkswap_out = zkswap_quote?;
-        let octo_out = octo_quote?;
+        // Quote every DEX concurrently; a venue without the pair just drops out
+        let results = join_all(
+            self.quoters
+                .iter()
+                .map(|q| q.get_quote(pair.token_a, pair.token_b, self.scan_amount)),
+        )
+        .await;
 
-        debug!(
-            "{}: ZKSwap={}, OctoSwap={}",
-            pair.name, zkswap_out, octo_out
-        );
-
-        // Check if there's profitable arbitrage
-        if zkswap_out > octo_out {
-            // Buy on OctoSwap (cheaper), sell on ZKSwap (more expensive)
-            let profit = zkswap_out - octo_out;
-            let profit_bps = (profit * U256::from(10000) / octo_out).to::<u64>();
-
-            if profit_bps >= self.min_profit_bps {
-                return Ok(Some(ArbitrageOpportunity {
-                    token_a: pair.token_a,
-                    token_b: pair.token_b,
-                    amount_in: self.scan_amount,
-                    buy_on: DexType::OctoSwap,
-                    sell_on: DexType::ZKSwap,
-                    expected_profit: profit,
-                    profit_bps,
-                }));
-            }
-        } else if octo_out > zkswap_out {
-            // Buy on ZKSwap (cheaper), sell on OctoSwap (more expensive)
-            let profit = octo_out - zkswap_out;
-            let profit_bps = (profit * U256::from(10000) / zkswap_out).to::<u64>();
-
-            if profit_bps >= self.min_profit_bps {
-                return Ok(Some(ArbitrageOpportunity {
-                    token_a: pair.token_a,
-                    token_b: pair.token_b,
-                    amount_in: self.scan_amount,
-                    buy_on: DexType::ZKSwap,
-                    sell_on: DexType::OctoSwap,
-                    expected_profit: profit,
-                    profit_bps,
-                }));
+        let mut quotes = Vec::with_capacity(results.len());
+        for (quoter, result) in self.quoters.iter().zip(results) {
+            match result {
+                Ok(out) if out > U256::ZERO => quotes.push((quoter.dex(), out)),
+                Ok(_) => debug!("{}: {} quoted zero", pair.name, quoter.dex()),
+                Err(e) => debug!("{}: {} quote failed: {}", pair.name, quoter.dex(), e),
             }
         }
 
-        Ok(None)
+        if quotes.len() < 2 {
+            return Err(format!("only {} DEX quotes available", quotes.len()));
+        }
+
+        debug!("{}: {:?}", pair.name, quotes);
+
+        // Gas in token_b at the pair's own rate (the lowest quote, to stay conservative)
+        let low = quotes.iter().map(|(_, out)| *out).min().unwrap_or(U256::ZERO);
+        let gas_cost = self.costs.gas_cost_wei(base_fee) * low / self.scan_amount;
+
+        Ok(best_route(&quotes, self.min_profit_bps, &self.costs, gas_cost).map(|(buy_on, sell_on, profit, profit_bps)| {
+            ArbitrageOpportunity {
+                token_a: pair.token_a,
+                token_b: pair.token_b,
+                amount_in: self.scan_amount,
+                buy_on,
+                sell_on,
+                expected_profit: profit,
+                profit_bps,
+            }
+        }))
     }
 }
 
+/// Widest spread across `quotes` (DEX, output for the same input):
+/// buy on the DEX with the lowest output (cheaper), sell on the highest.
+/// The spread is reduced by both DEXes' swap fees and `gas_cost` (in the
+/// output token); returns `(buy_on, sell_on, profit, profit_bps)` if what's
+/// left clears `min_profit_bps`.
+pub fn best_route(
+    quotes: &[(DexType, U256)],
+    min_profit_bps: u64,
+    costs: &ArbCosts,
+    gas_cost: U256,
+) -> Option<(DexType, DexType, U256, u64)> {
+    let &(buy_on, low) = quotes.iter().min_by_key(|(_, out)| *out)?;
+    let &(sell_on, high) = quotes.iter().max_by_key(|(_, out)| *out)?;
+    if buy_on == sell_on || low.is_zero() {
+        return None;
+    }
+
+    let gross = high - low;
+    let fees = low * U256::from(costs.fee_bps(buy_on) + costs.fee_bps(sell_on)) / U256::from(10000);
+    let cost = fees + gas_cost;
+    if gross <= cost {
+        debug!(
+            "{} -> {}: spread {} doesn't cover fees {} + gas {}",
+            buy_on, sell_on, gross, fees, gas_cost
+        );
+        return None;
+    }
+
+    let profit = gross - cost;
+    let profit_bps = (profit * U256::from(10000) / low).to::<u64>();
+    (profit_bps >= min_profit_bps).then_some((buy_on, sell_on, profit, profit_bps))
+}
+
 /// Spawn scanner as background task.
 pub fn spawn_scanner<P: Provider + Clone + Send + Sync + 'static>(
     provider: P,
+    quoters: Vec<Box<dyn DexQuoter>>,
     pairs: Vec<TokenPair>,
     scan_amount: U256,
     interval_ms: u64,
+    costs: ArbCosts,
     tx: mpsc::Sender<ArbitrageOpportunity>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        let scanner = ArbitrageScanner::new(provider, pairs, scan_amount);
+        let scanner = ArbitrageScanner::new(quoters, pairs, scan_amount, costs);
         
-        info!("🔍 Arbitrage scanner started (ZKSwap ↔ OctoSwap, {}ms interval)", interval_ms);
+        let dexes: Vec<String> = scanner.quoters.iter().map(|q| q.dex().to_string()).collect();
+        info!("🔍 Arbitrage scanner started ({}, {}ms interval)", dexes.join(" ↔ "), interval_ms);
 
         loop {
-            let opportunities = scanner.scan().await;
+            // Without a base fee the gas cost is unknown, so nothing can be called profitable
+            let base_fee = match provider.get_block_by_number(BlockNumberOrTag::Latest).await {
+                Ok(Some(block)) => block.header.base_fee_per_gas.map(u128::from),
+                Ok(None) => None,
+                Err(e) => {
+                    debug!("Arb scan: failed to get block: {}", e);
+                    None
+                }
+            };
+
+            let opportunities = match base_fee {
+                Some(base_fee) => scanner.scan(base_fee).await,
+                None => {
+                    warn!("⚠️ No base fee, skipping arbitrage scan");
+                    Vec::new()
+                }
+            };
 
             for opp in opportunities {
                 if let Err(e) = tx.send(opp).await {
@@ -172,3 +273,123 @@ pub fn spawn_scanner<P: Provider + Clone + Send + Sync + 'static>(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::quoter::QuoteFuture;
+
+    /// Quotes a fixed output for every pair.
+    struct FixedQuoter(DexType, Result<u64, &'static str>);
+
+    impl DexQuoter for FixedQuoter {
+        fn dex(&self) -> DexType {
+            self.0
+        }
+
+        fn get_quote(&self, _: Address, _: Address, _: U256) -> QuoteFuture<'_> {
+            let quote = self.1.map(U256::from).map_err(str::to_string);
+            Box::pin(async move { quote })
+        }
+    }
+
+    fn costs() -> ArbCosts {
+        ArbCosts {
+            gas_limit: 0,
+            dex_fees_bps: vec![(DexType::Kuru, 10)],
+            wmon: Address::repeat_byte(1),
+        }
+    }
+
+    fn pair() -> TokenPair {
+        TokenPair {
+            token_a: Address::repeat_byte(1),
+            token_b: Address::repeat_byte(2),
+            name: "WMON/TEST".to_string(),
+        }
+    }
+
+    #[test]
+    fn dex_names_parse_case_insensitively() {
+        assert_eq!(DexType::parse("Kuru"), Some(DexType::Kuru));
+        assert_eq!(DexType::parse("OCTOSWAP"), Some(DexType::OctoSwap));
+        assert_eq!(DexType::parse("uniswap"), None);
+        assert_eq!(DexType::Kuru.to_string(), "Kuru");
+    }
+
+    #[test]
+    fn best_route_nets_fees_and_gas() {
+        let quotes = [(DexType::ZKSwap, U256::from(10_000u64)), (DexType::Kuru, U256::from(10_200u64))];
+        // 200 spread - (30 + 10) bps of 10_000 in fees = 160
+        let (buy_on, sell_on, profit, bps) = best_route(&quotes, 0, &costs(), U256::ZERO).unwrap();
+        assert_eq!((buy_on, sell_on), (DexType::ZKSwap, DexType::Kuru));
+        assert_eq!((profit, bps), (U256::from(160u64), 160));
+
+        assert!(best_route(&quotes, 0, &costs(), U256::from(160u64)).is_none());
+        assert!(best_route(&quotes, 200, &costs(), U256::ZERO).is_none());
+        assert!(best_route(&quotes[..1], 0, &costs(), U256::ZERO).is_none());
+    }
+
+    #[tokio::test]
+    async fn scan_compares_every_venue_and_skips_failed_quotes() {
+        let quoters: Vec<Box<dyn DexQuoter>> = vec![
+            Box::new(FixedQuoter(DexType::ZKSwap, Ok(10_000))),
+            Box::new(FixedQuoter(DexType::OctoSwap, Err("no pair"))),
+            Box::new(FixedQuoter(DexType::Kuru, Ok(10_500))),
+        ];
+        let scanner = ArbitrageScanner::new(quoters, vec![pair()], U256::from(10_000u64), costs());
+
+        let opportunities = scanner.scan(0).await;
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].buy_on, DexType::ZKSwap);
+        assert_eq!(opportunities[0].sell_on, DexType::Kuru);
+    }
+
+    #[tokio::test]
+    async fn one_quote_is_not_an_opportunity() {
+        let quoters: Vec<Box<dyn DexQuoter>> = vec![
+            Box::new(FixedQuoter(DexType::ZKSwap, Ok(10_000))),
+            Box::new(FixedQuoter(DexType::Kuru, Ok(0))),
+        ];
+        let scanner = ArbitrageScanner::new(quoters, vec![pair()], U256::from(10_000u64), costs());
+        assert!(scanner.scan(0).await.is_empty());
+    }
+
+    #[test]
+    fn unlisted_dex_gets_the_default_fee() {
+        assert_eq!(costs().fee_bps(DexType::Kuru), 10);
+        assert_eq!(costs().fee_bps(DexType::ZKSwap), DEFAULT_DEX_FEE_BPS);
+        let costs = ArbCosts { gas_limit: 300_000, ..costs() };
+        assert_eq!(costs.gas_cost_wei(50), U256::from(15_000_000u64));
+    }
+
+    #[tokio::test]
+    async fn gas_at_the_base_fee_can_eat_the_spread() {
+        let quoters = || -> Vec<Box<dyn DexQuoter>> {
+            vec![
+                Box::new(FixedQuoter(DexType::ZKSwap, Ok(10_000))),
+                Box::new(FixedQuoter(DexType::Kuru, Ok(10_500))),
+            ]
+        };
+        // 500 spread - 40 in fees leaves 460; gas of 1 per unit over 10_000 in is 400 in token_b at the low rate
+        let costs = ArbCosts { gas_limit: 400, ..costs() };
+        let scanner = ArbitrageScanner::new(quoters(), vec![pair()], U256::from(10_000u64), costs.clone());
+        assert!(scanner.scan(10).await.is_empty());
+
+        let scanner = ArbitrageScanner::new(quoters(), vec![pair()], U256::from(10_000u64), costs);
+        let opportunities = scanner.scan(1).await;
+        assert_eq!(opportunities[0].expected_profit, U256::from(60u64));
+    }
+
+    #[tokio::test]
+    async fn pairs_not_quoted_from_wmon_are_skipped() {
+        let quoters: Vec<Box<dyn DexQuoter>> = vec![
+            Box::new(FixedQuoter(DexType::ZKSwap, Ok(10_000))),
+            Box::new(FixedQuoter(DexType::Kuru, Ok(20_000))),
+        ];
+        let pair = TokenPair { token_a: Address::repeat_byte(3), ..pair() };
+        let scanner = ArbitrageScanner::new(quoters, vec![pair.clone()], U256::from(10_000u64), costs());
+        assert!(scanner.check_pair(&pair, 0).await.is_err());
+        assert!(scanner.scan(0).await.is_empty());
+    }
+}
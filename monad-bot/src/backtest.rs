@@ -0,0 +1,427 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Offline replay of recorded CurveStream events (`--backtest <events.jsonl>`).
+//!
+//! Launches go through `SniperStrategy::should_buy` and held tokens through
+//! `PositionMonitor`, priced from the recording instead of the chain, so a
+//! strategy change can be compared against past data without sending anything.
+
+use crate::config::Config;
+use crate::health::HealthStatus;
+use crate::listeners::NewTokenEvent;
+use crate::position::{
+    cost_basis, Position, PositionMonitor, PriceFuture, PriceSource, SellDecision,
+    TrailingStopLossConfig,
+};
+use crate::price::MonPriceOracle;
+use crate::strategies::sniper::BuyBucket;
+use crate::strategies::SniperStrategy;
+use crate::trade_history::{TradeHistory, TradeRecord, TradeType};
+use crate::units::{mon_to_wei, token_units_to_f64, tokens_to_units, wei_to_mon};
+use crate::validators::TokenAnalysis;
+use alloy::primitives::{Address, U256};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+/// One recorded CurveStream event. Prices are MON per whole token.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ReplayEvent {
+    /// Token launch, with the on-chain analysis the live bot would have run.
+    Create {
+        timestamp: u64,
+        token: Address,
+        name: String,
+        symbol: String,
+        #[serde(default)]
+        creator: Option<Address>,
+        /// Token decimals; recordings without it are nad.fun (18-decimal) tokens.
+        #[serde(default = "default_decimals")]
+        decimals: u8,
+        liquidity_mon: f64,
+        price_mon: f64,
+        #[serde(default)]
+        dev_holding_pct: f64,
+        #[serde(default)]
+        top_holder_pct: f64,
+        #[serde(default)]
+        is_bundled: bool,
+        /// Why the safety analysis failed; unset = safe.
+        #[serde(default)]
+        unsafe_reason: Option<String>,
+    },
+    /// Curve buy; only the resulting price is used.
+    Buy { timestamp: u64, token: Address, price_mon: f64 },
+    /// Curve sell; only the resulting price is used.
+    Sell { timestamp: u64, token: Address, price_mon: f64 },
+}
+
+fn default_decimals() -> u8 {
+    18
+}
+
+impl ReplayEvent {
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            Self::Create { timestamp, .. } | Self::Buy { timestamp, .. } | Self::Sell { timestamp, .. } => *timestamp,
+        }
+    }
+
+    pub fn token(&self) -> Address {
+        match self {
+            Self::Create { token, .. } | Self::Buy { token, .. } | Self::Sell { token, .. } => *token,
+        }
+    }
+
+    pub fn price_mon(&self) -> f64 {
+        match self {
+            Self::Create { price_mon, .. } | Self::Buy { price_mon, .. } | Self::Sell { price_mon, .. } => *price_mon,
+        }
+    }
+}
+
+/// Load a JSONL recording, oldest event first. Blank lines are skipped.
+pub fn load_events(path: &str) -> Result<Vec<ReplayEvent>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let mut events = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("{} line {}: {}", path, i + 1, e))
+        })
+        .collect::<Result<Vec<ReplayEvent>, String>>()?;
+    events.sort_by_key(ReplayEvent::timestamp);
+    Ok(events)
+}
+
+/// Last recorded price per token, shared between the replay loop and the monitor.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayPrices {
+    prices: Arc<RwLock<HashMap<Address, f64>>>,
+    /// Decimals from each token's Create event; 18 if it wasn't recorded.
+    decimals: Arc<RwLock<HashMap<Address, u8>>>,
+}
+
+impl ReplayPrices {
+    fn set(&self, token: Address, price_mon: f64) {
+        if let Ok(mut prices) = self.prices.write() {
+            prices.insert(token, price_mon);
+        }
+    }
+
+    fn set_decimals(&self, token: Address, decimals: u8) {
+        if let Ok(mut all) = self.decimals.write() {
+            all.insert(token, decimals);
+        }
+    }
+}
+
+impl PriceSource for ReplayPrices {
+    fn price_mon(&self, token: Address, amount: U256) -> PriceFuture<'_> {
+        let price = self.prices.read().ok().and_then(|prices| prices.get(&token).copied());
+        let decimals = self
+            .decimals
+            .read()
+            .ok()
+            .and_then(|all| all.get(&token).copied())
+            .unwrap_or_else(default_decimals);
+        Box::pin(async move {
+            price
+                .map(|price| price * token_units_to_f64(amount, decimals))
+                .ok_or_else(|| format!("No recorded price for {:?}", token))
+        })
+    }
+}
+
+/// Outcome of a replay.
+#[derive(Debug)]
+pub struct BacktestResult {
+    pub history: TradeHistory,
+    /// Positions still held when the recording ends, unsold.
+    pub open_positions: Vec<Position>,
+    /// MON the open positions would fetch at their last recorded price.
+    pub open_value_mon: f64,
+}
+
+/// Replay `events` through the configured strategy and exit rules.
+///
+/// Rate limits and the trading-enabled precheck are skipped: the first runs on
+/// wall-clock time, which a replay compresses, and the second needs the chain.
+pub async fn replay(config: &Config, events: &[ReplayEvent]) -> BacktestResult {
+    let mut strategy = SniperStrategy::from_config(config);
+    strategy.enabled = true;
+    strategy.reject_log = None;
+    strategy.snipe_bucket = BuyBucket::new(0);
+    strategy.priority_bucket = BuyBucket::new(0);
+
+    let prices = ReplayPrices::default();
+    let tsl_config = TrailingStopLossConfig::from_config(config);
+    let check_interval = tsl_config.check_interval_sec;
+    // Recordings carry no supply, so the migration exit never fires in a replay
+    let monitor = PositionMonitor::new(
+        Box::new(prices.clone()),
+        tsl_config,
+        strategy.filters.clone(),
+        MonPriceOracle::new(config.mon_price_usd),
+        Arc::new(HealthStatus::new()),
+    );
+
+    let mut history = TradeHistory::in_memory();
+    let mut positions: HashMap<Address, Position> = HashMap::new();
+    let mut next_check = 0;
+
+    for event in events {
+        let now = event.timestamp();
+        prices.set(event.token(), event.price_mon());
+        if let ReplayEvent::Create { token, decimals, .. } = event {
+            prices.set_decimals(*token, *decimals);
+        }
+
+        if let ReplayEvent::Create { .. } = event {
+            if let Some(position) = evaluate_launch(&strategy, event, positions.get(&event.token())).await {
+                history.record(TradeRecord {
+                    token: position.token,
+                    token_name: position.name.clone(),
+                    token_symbol: position.symbol.clone(),
+                    trade_type: TradeType::Buy,
+                    amount_tokens: position.amount.to_string(),
+                    amount_mon: position.buy_price_mon,
+                    timestamp: now,
+                    tx_hash: "backtest".to_string(),
+                    realized_pnl_mon: None,
+                });
+                positions.insert(position.token, position);
+            }
+        }
+
+        // The live monitor checks every position once per interval
+        if now < next_check {
+            continue;
+        }
+        next_check = now + check_interval;
+
+        let tokens: Vec<Address> = positions.keys().copied().collect();
+        for token in tokens {
+            let Some(position) = positions.get_mut(&token) else {
+                continue;
+            };
+            let decision = monitor.check_position_at(position, now).await;
+            if matches!(decision, SellDecision::Hold) {
+                continue;
+            }
+
+            let sold = decision.sell_amount(position.amount);
+            let mon_received = match prices.price_mon(token, sold).await {
+                Ok(mon) => mon,
+                Err(e) => {
+                    warn!("⚠️ Can't price backtest sell of {:?}: {}", token, e);
+                    continue;
+                }
+            };
+            let cost = cost_basis(position.buy_price_mon, sold, position.amount);
+            history.record(TradeRecord {
+                token,
+                token_name: position.name.clone(),
+                token_symbol: position.symbol.clone(),
+                trade_type: TradeType::Sell,
+                amount_tokens: sold.to_string(),
+                amount_mon: mon_received,
+                timestamp: now,
+                tx_hash: "backtest".to_string(),
+                realized_pnl_mon: Some(mon_received - cost),
+            });
+
+            // Same bookkeeping as the live sell handler
+            if sold < position.amount {
+                decision.record_taken(position);
+                let kept = (position.buy_price_mon - cost) / position.buy_price_mon;
+                position.amount -= sold;
+                position.buy_price_mon -= cost;
+                position.highest_price *= kept;
+            } else {
+                positions.remove(&token);
+            }
+        }
+    }
+
+    let mut open_value_mon = 0.0;
+    for position in positions.values() {
+        open_value_mon += prices.price_mon(position.token, position.amount).await.unwrap_or(0.0);
+    }
+
+    BacktestResult {
+        history,
+        open_positions: positions.into_values().collect(),
+        open_value_mon,
+    }
+}
+
+/// Run `should_buy` on a recorded launch; the position it would open, if any.
+async fn evaluate_launch(
+    strategy: &SniperStrategy,
+    event: &ReplayEvent,
+    existing: Option<&Position>,
+) -> Option<Position> {
+    let ReplayEvent::Create {
+        timestamp,
+        token,
+        name,
+        symbol,
+        creator,
+        decimals,
+        liquidity_mon,
+        price_mon,
+        dev_holding_pct,
+        top_holder_pct,
+        is_bundled,
+        unsafe_reason,
+    } = event
+    else {
+        return None;
+    };
+
+    let launch = NewTokenEvent {
+        token_address: *token,
+        name: name.clone(),
+        symbol: symbol.clone(),
+        creator: *creator,
+        bonding_curve: None,
+        initial_liquidity: Some(mon_to_wei(*liquidity_mon)),
+        // The strategy measures age against the wall clock; launches are evaluated as they happen
+        timestamp: Some(chrono::Utc::now().timestamp() as u64),
+        tx_hash: None,
+    };
+    let analysis = TokenAnalysis {
+        token: *token,
+        dev_wallet: *creator,
+        dev_holding_pct: *dev_holding_pct,
+        top_holder_pct: *top_holder_pct,
+        holder_count: None,
+        holders_complete: false,
+        is_bundled: *is_bundled,
+        total_supply: U256::ZERO,
+        market_cap_usd: 0.0,
+        age_minutes: 0,
+        is_safe: unsafe_reason.is_none(),
+        rejection_reason: unsafe_reason.clone(),
+    };
+
+    let decision = strategy.should_buy(&launch, &analysis, existing).await?;
+    if *price_mon <= 0.0 {
+        warn!("⚠️ No launch price for {} ({}), skipping backtest buy", name, symbol);
+        return None;
+    }
+
+    let buy_mon = wei_to_mon(decision.amount_wei);
+    Some(Position {
+        token: *token,
+        name: name.clone(),
+        symbol: symbol.clone(),
+        amount: tokens_to_units(buy_mon / price_mon, *decimals),
+        buy_price_mon: buy_mon,
+        buy_time: *timestamp,
+        highest_price: buy_mon,
+        tx_hash: "backtest".to_string(),
+        provisional: false,
+        fired_tp_levels: Vec::new(),
+        sell_failures: 0,
+        stuck: false,
+        graduated: false,
+        peak_liquidity_mon: 0.0,
+        in_runner_mode: false,
+        decimals: *decimals,
+    })
+}
+
+/// Load and replay `path`, then log the trade summary.
+pub async fn run_backtest(config: &Config, path: &str) -> Result<BacktestResult, String> {
+    let events = load_events(path)?;
+    info!("⏪ Replaying {} recorded events from {}", events.len(), path);
+
+    let result = replay(config, &events).await;
+    let summary = result.history.get_summary();
+
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    info!("📊 Backtest Summary:");
+    info!("   Buys: {} trades, {:.4} MON total", summary.buy_count, summary.total_bought);
+    info!("   Sells: {} trades, {:.4} MON total", summary.sell_count, summary.total_sold);
+    info!("   Realized P/L: {:+.4} MON", summary.realized_pnl);
+    match summary.win_rate {
+        Some(rate) => info!("   Win rate: {:.0}%", rate * 100.0),
+        None => info!("   Win rate: n/a (no sells)"),
+    }
+    if !result.open_positions.is_empty() {
+        info!(
+            "   Still open: {} positions worth {:.4} MON at their last price",
+            result.open_positions.len(),
+            result.open_value_mon
+        );
+    }
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::tests::config_with;
+    use std::collections::HashSet;
+
+    const RECORDING: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/backtest_events.jsonl");
+
+    #[test]
+    fn example_recording_loads_oldest_first() {
+        let events = load_events(RECORDING).unwrap();
+        assert_eq!(events.len(), 9);
+        assert!(events.windows(2).all(|pair| pair[0].timestamp() <= pair[1].timestamp()));
+        assert!(matches!(&events[0], ReplayEvent::Create { decimals: 18, symbol, .. } if symbol == "MCAT"));
+    }
+
+    #[test]
+    fn bad_lines_are_reported_by_number() {
+        let path = std::env::temp_dir().join(format!("backtest-{}.jsonl", std::process::id()));
+        let line = r#"{"type":"buy","timestamp":1,"token":"0x1111111111111111111111111111111111111111","price_mon":1.0}"#;
+        fs::write(&path, format!("{line}\n\n{{\"type\":\"swap\"}}\n")).unwrap();
+        let err = load_events(path.to_str().unwrap()).unwrap_err();
+        let _ = fs::remove_file(&path);
+        assert!(err.contains("line 3"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn replay_prices_use_the_recorded_decimals() {
+        let prices = ReplayPrices::default();
+        let token = Address::repeat_byte(1);
+        assert!(prices.price_mon(token, U256::from(1u64)).await.is_err());
+
+        prices.set(token, 0.5);
+        assert_eq!(prices.price_mon(token, mon_to_wei(4.0)).await, Ok(2.0));
+        prices.set_decimals(token, 6);
+        assert_eq!(prices.price_mon(token, U256::from(4_000_000u64)).await, Ok(2.0));
+    }
+
+    #[tokio::test]
+    async fn replay_buys_launches_and_books_exits() {
+        let config = config_with(&[("SNIPER_ENABLED", "true"), ("PAPER_TRADING", "true")]).unwrap();
+        let events = load_events(RECORDING).unwrap();
+        let result = replay(&config, &events).await;
+
+        let summary = result.history.get_summary();
+        assert!(summary.buy_count > 0);
+        // Every bought position is either sold or still open
+        let sold: HashSet<Address> = result
+            .history
+            .traded_tokens()
+            .into_iter()
+            .filter(|token| result.open_positions.iter().all(|p| p.token != *token))
+            .collect();
+        assert_eq!(sold.len() + result.open_positions.len(), summary.buy_count);
+        assert!(result.open_value_mon >= 0.0);
+    }
+}
@@ -4,30 +4,82 @@
 
 //! Configuration module - loads settings from environment variables.
 
+use crate::arbitrage::DexType;
+use crate::error::BotError;
+use crate::executor::routes::parse_dex_routes;
+use crate::executor::{ApprovalMode, DexRoute, GasMode};
+use crate::handlers::SellRoute;
+use crate::jitter::Jitter;
+use crate::logging::LogFormat;
+use crate::position::ExitMode;
+use crate::rpc::{urls_from_env, MONAD_MAINNET_CHAIN_ID};
+use crate::units;
 use alloy::primitives::{Address, U256};
 use std::str::FromStr;
+use std::time::Duration;
+use tracing::warn;
 
 /// Main configuration for the sniper bot.
 #[derive(Debug, Clone)]
 pub struct Config {
     // RPC
+    /// First of `rpc_urls`, for the SDK and other single-endpoint clients
     pub rpc_url: String,
+    pub rpc_urls: Vec<String>,
+    /// First of `ws_urls`
     pub ws_url: String,
+    pub ws_urls: Vec<String>,
+    pub rpc_failover_errors: u32,
+    pub rpc_request_timeout_secs: u64,
     pub chain_id: u64,
 
     // Wallet
     pub private_key: String,
     pub wallet_address: Address,
+    pub paper_trading: bool,
 
     // Contracts
     pub router_address: Address,
     pub wmon_address: Address,
+    /// DEXes graduated tokens may trade on besides ROUTER_ADDRESS (see `dex_routes`).
+    pub extra_dex_routes: Vec<DexRoute>,
+    pub auto_wrap: bool,
+    pub wmon_sweep_interval_secs: u64,
+    pub wmon_sweep_threshold_mon: f64,
 
     // Sniper settings
     pub auto_snipe_enabled: bool,
     pub snipe_amount_mon: f64,
+    pub snipe_amount_jitter_pct: f64,
     pub whale_min_amount: f64,
     pub whale_max_amount: f64,
+    pub min_liquidity_mon: f64,
+    pub min_liquidity_usd: Option<f64>,
+    pub min_token_age_minutes: u64,
+    pub require_known_age: bool,
+    pub bundling_check_enabled: bool,
+    pub honeypot_check_enabled: bool,
+    pub presume_largest_holder_dev: bool,
+    pub min_holder_count: usize,
+    pub holder_count_min_age_minutes: u64,
+    pub max_log_scan_blocks: u64,
+    pub min_total_supply: u64,
+    pub max_total_supply: u64,
+    pub supply_recheck_secs: u64,
+    pub reject_log_enabled: bool,
+    pub reject_log_max_mb: u64,
+
+    // Limit buy
+    pub limit_buy_enabled: bool,
+    pub limit_buy_poll_interval_sec: u64,
+    pub limit_buy_max_wait_minutes: u64,
+
+    // Pending (dip) orders
+    pub pending_orders_enabled: bool,
+    /// Dip orders fill at or below this MCap (None = top of the entry zone).
+    pub pending_order_target_mcap_usd: Option<f64>,
+    pub pending_order_poll_interval_sec: u64,
+    pub pending_order_ttl_minutes: u64,
 
     // AI Filter
     pub ai_filter_enabled: bool,
@@ -37,64 +89,194 @@ pub struct Config {
     // Gas
     pub gas_limit: u64,
     pub priority_fee: u128,
-    pub gas_multiplier: f64,
+    pub snipe_gas_multiplier: f64,
+    pub frontrun_gas_multiplier: f64,
+    /// Highest gas price a front-run may bid, in gwei.
+    pub max_frontrun_gas_gwei: f64,
+    pub gas_mode: GasMode,
+    pub gas_jitter_pct: f64,
+    /// Fixed seed for the amount and gas jitter (reproducible runs); unset = clock.
+    pub jitter_seed: Option<u64>,
+
+    // Transaction submission
+    pub private_tx_url: Option<String>,
+    pub private_tx_buys: bool,
+    pub tx_receipt_timeout_secs: u64,
+    pub tx_deadline_secs: u64,
+    /// Resend a buy with more gas after this long unconfirmed (0 = never).
+    pub buy_gas_bump_timeout_secs: u64,
+    pub buy_gas_bump_pct: f64,
+    pub buy_gas_bump_max_attempts: u32,
+    pub buy_gas_bump_max_gwei: f64,
 
     // Trailing Stop Loss
     pub trailing_drop_pct: f64,
+    /// Trailing drop % by position age as (minutes held, drop %), ascending; empty = constant.
+    pub trailing_decay: Vec<(f64, f64)>,
     pub trailing_min_profit: f64,
+    /// Gas used by a buy and a sell together, for the net-of-fees profit check.
+    pub round_trip_gas: u64,
+    /// Gas price assumed when the base fee can't be read.
+    pub fee_gas_price_gwei: f64,
+    /// Slippage allowance on the exit, in % of the position's value.
+    pub fee_slippage_pct: f64,
     pub hard_stop_loss_pct: f64,
-    pub secure_profit_pct: f64,
-    pub secure_sell_portion: f64,
+    /// Sell immediately when pool liquidity falls this % below its peak (0 = off).
+    pub rug_liquidity_drop_pct: f64,
+    /// Exit positions whose market cap nears the migration cap (0.8x `migration_mcap_usd`).
+    pub sell_before_migration: bool,
+    /// Take-profit tiers as (profit %, portion of the remaining position), ascending.
+    pub tp_ladder: Vec<(f64, f64)>,
+    /// `standard` (take-profit ladder) or `recoup_then_runner`.
+    pub exit_mode: ExitMode,
+    /// Recoup the cost basis once the position is worth this multiple of it.
+    pub recoup_multiple: f64,
+    /// Trailing drop % for what's left after the recoup.
+    pub runner_trailing_drop_pct: f64,
     pub max_hold_hours: u64,
+    /// Merge repeat buys of a held token into one averaged position.
+    pub position_averaging: bool,
     pub check_interval_sec: u64,
+    pub monitor_concurrency: usize,
+    pub deadman_enabled: bool,
+    pub deadman_sell_after_secs: u64,
+    pub watchdog_enabled: bool,
+    pub watchdog_stale_secs: u64,
+    pub shutdown_drain_secs: u64,
 
     // Blacklist
     pub blacklist: Vec<String>,
+    pub blacklist_strict_substring: bool,
+
+    // Allowlist (priority snipes)
+    pub snipe_allowlist: Vec<Address>,
+    pub creator_allowlist: Vec<Address>,
+    pub creator_allowlist_only: bool,
+    pub allowlist_snipe_amount_mon: f64,
+    pub max_snipes_per_minute: u32,
+    pub max_priority_snipes_per_minute: u32,
+    pub min_seconds_between_buys: u64,
+    pub max_open_positions: usize,
 
     // Arbitrage
     pub arbitrage_enabled: bool,
     pub arbitrage_contract: Option<Address>,
+    pub kuru_router: Option<Address>,
     pub arb_scan_interval_ms: u64,
     pub arb_amount_mon: f64,
+    pub arb_dex_fees_bps: Vec<(DexType, u64)>,
+    pub usdt_address: Address,
+
+    // Pricing
+    pub mon_price_usd: Option<f64>,
+    pub usdc_address: Address,
+    pub mon_price_ttl_secs: u64,
 
     // Telegram
     pub telegram_token: Option<String>,
     pub telegram_chat_id: Option<String>,
+    pub telegram_commands_enabled: bool,
+
+    // Discord
+    pub discord_webhook_url: Option<String>,
+
+    // Status API (disabled unless API_TOKEN is set)
+    pub api_token: Option<String>,
+    pub api_port: u16,
+
+    // Logging
+    pub log_format: LogFormat,
+
+    // Notifications
+    pub notify_new_token: bool,
+    pub notify_buy: bool,
+    pub notify_sell: bool,
+    pub notify_copy: bool,
+    pub notify_scout_promotion: bool,
+    pub notify_errors: bool,
+    pub notify_pnl_summary: bool,
+    pub notify_heartbeat: bool,
+    pub heartbeat_interval_secs: u64,
 
     // Copy Trade settings
     pub smart_wallets: Vec<String>,
+    /// JSON file with per-wallet copy settings; replaces `smart_wallets` when readable.
+    pub smart_wallets_file: Option<String>,
     pub whale_copy_pct: f64,
     pub max_snipe_amount: f64,
+    pub max_per_token_mon: f64,
+    pub max_copy_delay_ms: u64,
+    pub score_sizing_enabled: bool,
+    pub score_sizing_tiers: Vec<(f64, f64)>,
+    pub create_only_without_wallets: bool,
+    pub stream_replay_max_blocks: u64,
+
+    // Event channels
+    pub channel_capacity: usize,
+    /// Window in which a repeated launch of the same token is ignored.
+    pub new_token_dedupe_secs: u64,
 
     // Slippage settings
     pub buy_slippage_pct: f64,
+    pub copy_trade_slippage_pct: f64,
     pub sell_slippage_pct: f64,
+    pub sell_retry_slippage_pct: f64,
+    pub max_sell_failures: u32,
+    pub sell_route: SellRoute,
+    pub buy_slippage_step_pct: f64,
+    pub buy_max_slippage_pct: f64,
+    pub dex_sell_slippage_pct: f64,
+    pub max_acceptable_slippage_pct: f64,
+    pub allow_zero_min: bool,
+    pub dex_sell_deadline_secs: u64,
+    pub approve_gas_limit: u64,
+    pub approval_mode: ApprovalMode,
 }
 
 impl Config {
     /// Load configuration from environment variables.
-    pub fn from_env() -> Result<Self, String> {
+    pub fn from_env() -> Result<Self, BotError> {
         dotenvy::dotenv().ok();
 
+        // Comma-separated endpoint lists; the single-URL variables still work
+        let rpc_urls = urls_from_env("MONAD_RPC_URLS", "MONAD_RPC_URL").map_err(BotError::Config)?;
+        let ws_urls = urls_from_env("MONAD_WS_URLS", "MONAD_WS_URL").map_err(BotError::Config)?;
+
         Ok(Self {
             // RPC
-            rpc_url: env_var("MONAD_RPC_URL")?,
-            ws_url: env_var("MONAD_WS_URL")?,
-            chain_id: env_var_or("CHAIN_ID", "10143").parse().unwrap_or(10143),
+            rpc_url: rpc_urls[0].clone(),
+            rpc_urls,
+            ws_url: ws_urls[0].clone(),
+            ws_urls,
+            // Consecutive errors/timeouts before moving to the next RPC or WS endpoint
+            rpc_failover_errors: env_var_or("RPC_FAILOVER_ERRORS", "3").parse().unwrap_or(3),
+            // An RPC request slower than this counts as a failure
+            rpc_request_timeout_secs: env_var_or("RPC_REQUEST_TIMEOUT_SEC", "10").parse().unwrap_or(10),
+            // Mainnet: the nad.fun contract addresses are mainnet deployments
+            chain_id: env_var_or("CHAIN_ID", "143")
+                .parse()
+                .unwrap_or(MONAD_MAINNET_CHAIN_ID),
 
             // Wallet
             private_key: env_var("PRIVATE_KEY")?,
-            wallet_address: parse_address(&env_var("WALLET_ADDRESS")?)?,
+            wallet_address: parse_address("WALLET_ADDRESS", &env_var("WALLET_ADDRESS")?)?,
+            // Quote every trade against live state but never send a transaction
+            paper_trading: env_var_or("PAPER_TRADING", "false").parse().unwrap_or(false),
 
             // Contracts
-            router_address: parse_address(&env_var_or(
-                "ROUTER_ADDRESS",
-                "0x6F6B8F1a20703309951a5127c45B49b1CD981A22",
-            ))?,
-            wmon_address: parse_address(&env_var_or(
-                "WMON_ADDRESS",
-                "0x760AfE86e5de5fa0Ee542fc7B7B713e1c5425701",
-            ))?,
+            router_address: address_var_or("ROUTER_ADDRESS", "0x6F6B8F1a20703309951a5127c45B49b1CD981A22")?,
+            wmon_address: address_var_or("WMON_ADDRESS", "0x760AfE86e5de5fa0Ee542fc7B7B713e1c5425701")?,
+            // e.g. DEX_ROUTES=kuru:0xRouter:0xWmon,octoswap:0xRouter:0xWmon
+            extra_dex_routes: parse_dex_routes(&env_var_or("DEX_ROUTES", "")),
+            auto_wrap: env_var_or("AUTO_WRAP", "false").parse().unwrap_or(false),
+            // Unwrap stranded WMON back to MON at startup and every N seconds (0 = off)
+            wmon_sweep_interval_secs: env_var_or("WMON_SWEEP_INTERVAL_SECS", "0")
+                .parse()
+                .unwrap_or(0),
+            // Only sweep once the WMON balance is above this
+            wmon_sweep_threshold_mon: env_var_or("WMON_SWEEP_THRESHOLD_MON", "1.0")
+                .parse()
+                .unwrap_or(1.0),
 
             // Sniper settings
             auto_snipe_enabled: env_var_or("AUTO_SNIPE_ENABLED", "true")
@@ -103,12 +285,83 @@ impl Config {
             snipe_amount_mon: env_var_or("AUTO_SNIPE_AMOUNT_MON", "5.0")
                 .parse()
                 .unwrap_or(5.0),
+            // Randomize each buy within ±X% of its amount so buys don't fingerprint the wallet (0 = exact)
+            snipe_amount_jitter_pct: env_var_or("SNIPE_AMOUNT_JITTER_PCT", "0")
+                .parse()
+                .unwrap_or(0.0),
             whale_min_amount: env_var_or("WHALE_MIN_AMOUNT_MON", "5.0")
                 .parse()
                 .unwrap_or(5.0),
             whale_max_amount: env_var_or("WHALE_MAX_AMOUNT_MON", "50.0")
                 .parse()
                 .unwrap_or(50.0),
+            min_liquidity_mon: env_var_or("MIN_LIQUIDITY_MON", "100.0")
+                .parse()
+                .unwrap_or(100.0),
+            // USD floor takes precedence over the MON floor while a MON price is known
+            min_liquidity_usd: std::env::var("MIN_LIQUIDITY_USD")
+                .ok()
+                .and_then(|s| s.parse().ok()),
+            min_token_age_minutes: env_var_or("MIN_TOKEN_AGE_MINUTES", "0")
+                .parse()
+                .unwrap_or(0),
+            // Strict: reject tokens whose creation time is unknown instead of treating them as fresh
+            require_known_age: env_var_or("REQUIRE_KNOWN_AGE", "false")
+                .parse()
+                .unwrap_or(false),
+            // Reject tokens whose top holders share funding or look coordinated (RPC-heavy)
+            bundling_check_enabled: env_var_or("BUNDLING_CHECK_ENABLED", "false")
+                .parse()
+                .unwrap_or(false),
+            // Reject tokens whose simulated DEX sell reverts (bonding-curve tokens are skipped)
+            honeypot_check_enabled: env_var_or("HONEYPOT_CHECK_ENABLED", "false")
+                .parse()
+                .unwrap_or(false),
+            // Creator unknown: apply the dev-holding cap to the largest holder instead
+            presume_largest_holder_dev: env_var_or("PRESUME_LARGEST_HOLDER_DEV", "false")
+                .parse()
+                .unwrap_or(false),
+            // Reject tokens with fewer distinct holders (0 = off), once they are old enough to have them
+            min_holder_count: env_var_or("MIN_HOLDER_COUNT", "10").parse().unwrap_or(10),
+            holder_count_min_age_minutes: env_var_or("HOLDER_COUNT_MIN_AGE_MINUTES", "10")
+                .parse()
+                .unwrap_or(10),
+            // Block range per Transfer log request when paging back for holder balances
+            max_log_scan_blocks: env_var_or("MAX_LOG_SCAN_BLOCKS", "10000")
+                .parse()
+                .unwrap_or(10_000),
+            // Total supply bounds in whole tokens (0 = no bound); nad.fun launches mint 1B
+            min_total_supply: env_var_or("MIN_TOTAL_SUPPLY", "0").parse().unwrap_or(0),
+            max_total_supply: env_var_or("MAX_TOTAL_SUPPLY", "1000000000000")
+                .parse()
+                .unwrap_or(1_000_000_000_000),
+            // Re-read totalSupply this long after the first read; a change means it's mintable (0 = off)
+            supply_recheck_secs: env_var_or("SUPPLY_RECHECK_SECS", "2").parse().unwrap_or(2),
+            // Rejected tokens are appended to rejects.jsonl (rotated past the size cap)
+            reject_log_enabled: env_var_or("REJECT_LOG_ENABLED", "true").parse().unwrap_or(true),
+            reject_log_max_mb: env_var_or("REJECT_LOG_MAX_MB", "10").parse().unwrap_or(10),
+
+            // Limit buy
+            limit_buy_enabled: env_var_or("LIMIT_BUY_ENABLED", "false")
+                .parse()
+                .unwrap_or(false),
+            limit_buy_poll_interval_sec: env_var_or("LIMIT_BUY_POLL_INTERVAL_SEC", "10")
+                .parse()
+                .unwrap_or(10),
+            limit_buy_max_wait_minutes: env_var_or("LIMIT_BUY_MAX_WAIT_MIN", "30")
+                .parse()
+                .unwrap_or(30),
+
+            // Pending orders: safe tokens past the entry zone wait for a dip to the target
+            pending_orders_enabled: env_var_or("PENDING_ORDERS_ENABLED", "false").parse().unwrap_or(false),
+            pending_order_target_mcap_usd: std::env::var("PENDING_ORDER_TARGET_MCAP_USD")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            pending_order_poll_interval_sec: env_var_or("PENDING_ORDER_POLL_INTERVAL_SEC", "10")
+                .parse()
+                .unwrap_or(10),
+            // Orders not filled within this many minutes are dropped (also across restarts)
+            pending_order_ttl_minutes: env_var_or("PENDING_ORDER_TTL_MIN", "60").parse().unwrap_or(60),
 
             // AI Filter
             ai_filter_enabled: env_var_or("AI_FILTER_ENABLED", "true")
@@ -124,38 +377,147 @@ impl Config {
             priority_fee: env_var_or("AUTO_SNIPE_PRIORITY_FEE", "500000000000")
                 .parse()
                 .unwrap_or(500_000_000_000),
-            gas_multiplier: env_var_or("MEMPOOL_GAS_MULTIPLIER", "1.5")
+            // Falls back to the deprecated MEMPOOL_GAS_MULTIPLIER it replaced
+            snipe_gas_multiplier: env_var_or_renamed("SNIPE_GAS_MULTIPLIER", "MEMPOOL_GAS_MULTIPLIER", "1.5")
                 .parse()
                 .unwrap_or(1.5),
+            frontrun_gas_multiplier: env_var_or("FRONTRUN_GAS_MULTIPLIER", "1.25")
+                .parse()
+                .unwrap_or(1.25),
+            // Ceiling on the front-run bid; victims paying more than this aren't front-run
+            max_frontrun_gas_gwei: env_var_or("MAX_FRONTRUN_GAS_GWEI", "200")
+                .parse()
+                .unwrap_or(200.0),
+            // Fee model for SDK buys: `legacy` (scaled gas price) or `eip1559` (from base fee)
+            gas_mode: GasMode::parse(&env_var_or("GAS_MODE", "legacy")),
+            // Randomize buy priority fees within ±X% (0 = exact tier)
+            gas_jitter_pct: env_var_or("GAS_JITTER_PCT", "0").parse().unwrap_or(0.0),
+            jitter_seed: std::env::var("JITTER_SEED")
+                .ok()
+                .and_then(|s| s.parse().ok()),
+
+            // Transaction submission (private relay keeps sells, and buys with PRIVATE_TX_BUYS, out of the public mempool)
+            private_tx_url: std::env::var("PRIVATE_TX_URL").ok().filter(|url| !url.is_empty()),
+            private_tx_buys: env_var_or("PRIVATE_TX_BUYS", "false").parse().unwrap_or(false),
+            // Give up waiting for a receipt after this long (the tx may still land)
+            tx_receipt_timeout_secs: env_var_or("TX_RECEIPT_TIMEOUT_SEC", "30")
+                .parse()
+                .unwrap_or(30),
+            // Bonding-curve trades revert if still pending this long after sending
+            tx_deadline_secs: env_var_or("TX_DEADLINE_SEC", "120").parse().unwrap_or(120),
+            // Stuck buys are resent at the same nonce with fees raised by BUY_GAS_BUMP_PCT each time
+            buy_gas_bump_timeout_secs: env_var_or("BUY_GAS_BUMP_TIMEOUT_SEC", "5").parse().unwrap_or(5),
+            buy_gas_bump_pct: env_var_or("BUY_GAS_BUMP_PCT", "30.0").parse().unwrap_or(30.0),
+            buy_gas_bump_max_attempts: env_var_or("BUY_GAS_BUMP_MAX_ATTEMPTS", "3").parse().unwrap_or(3),
+            // Resends never bid above this per gas
+            buy_gas_bump_max_gwei: env_var_or("BUY_GAS_BUMP_MAX_GWEI", "300")
+                .parse()
+                .unwrap_or(300.0),
 
             // Trailing Stop Loss
             trailing_drop_pct: env_var_or("TRAILING_DROP_PCT", "20.0")
                 .parse()
                 .unwrap_or(20.0),
+            // e.g. TRAILING_DECAY=0:30,10:30,60:10 tightens the stop from 30% to 10%
+            // between minute 10 and 60; unset = constant TRAILING_DROP_PCT
+            trailing_decay: parse_decay_curve(&env_var_or("TRAILING_DECAY", "")),
             trailing_min_profit: env_var_or("TRAILING_MIN_PROFIT", "50.0")
                 .parse()
                 .unwrap_or(50.0),
+            // Take-profit and trailing exits need the PnL to stay positive after a
+            // round trip's gas (at the live base fee) and the exit slippage allowance
+            round_trip_gas: env_var_or("ROUND_TRIP_GAS", "600000").parse().unwrap_or(600_000),
+            fee_gas_price_gwei: env_var_or("FEE_GAS_PRICE_GWEI", "100").parse().unwrap_or(100.0),
+            fee_slippage_pct: env_var_or("FEE_SLIPPAGE_PCT", "1.0").parse().unwrap_or(1.0),
             hard_stop_loss_pct: env_var_or("HARD_STOP_LOSS_PCT", "-40.0")
                 .parse()
                 .unwrap_or(-40.0),
-            secure_profit_pct: env_var_or("SECURE_PROFIT_PCT", "100.0")
+            rug_liquidity_drop_pct: env_var_or("RUG_LIQUIDITY_DROP_PCT", "50.0")
                 .parse()
-                .unwrap_or(100.0),
-            secure_sell_portion: env_var_or("SECURE_SELL_PORTION", "0.3")
+                .unwrap_or(50.0),
+            // Sell out before graduation volatility instead of riding the migration
+            sell_before_migration: env_var_or("SELL_BEFORE_MIGRATION", "false")
+                .parse()
+                .unwrap_or(false),
+            // e.g. TP_LADDER=50:0.25,100:0.25,200:0.25; unset = the single
+            // SECURE_PROFIT_PCT / SECURE_SELL_PORTION tier
+            tp_ladder: match std::env::var("TP_LADDER") {
+                Ok(ladder) => parse_tp_ladder(&ladder),
+                Err(_) => vec![(
+                    env_var_or("SECURE_PROFIT_PCT", "100.0").parse().unwrap_or(100.0),
+                    env_var_or("SECURE_SELL_PORTION", "0.3").parse().unwrap_or(0.3),
+                )],
+            },
+            // recoup_then_runner: at RECOUP_MULTIPLE x, sell what returns the cost basis
+            // and hold the rest on a RUNNER_TRAILING_DROP_PCT stop; replaces TP_LADDER
+            exit_mode: ExitMode::parse(&env_var_or("EXIT_MODE", "standard")),
+            recoup_multiple: env_var_or("RECOUP_MULTIPLE", "2.0").parse().unwrap_or(2.0),
+            runner_trailing_drop_pct: env_var_or("RUNNER_TRAILING_DROP_PCT", "40.0")
                 .parse()
-                .unwrap_or(0.3),
+                .unwrap_or(40.0),
             max_hold_hours: env_var_or("MAX_HOLD_HOURS", "48")
                 .parse()
                 .unwrap_or(48),
+            // false = a repeat buy replaces the held position instead of averaging into it
+            position_averaging: env_var_or("POSITION_AVERAGING", "true")
+                .parse()
+                .unwrap_or(true),
             check_interval_sec: env_var_or("CHECK_INTERVAL_SEC", "5")
                 .parse()
                 .unwrap_or(5),
+            // Positions priced in parallel per check (RPC load vs. scan time)
+            monitor_concurrency: env_var_or("MONITOR_CONCURRENCY", "8")
+                .parse::<usize>()
+                .unwrap_or(8)
+                .max(1),
+            // Dead-man's switch (opt-in): panic-sell if events and price checks stop
+            deadman_enabled: env_var_or("DEADMAN_ENABLED", "false").parse().unwrap_or(false),
+            deadman_sell_after_secs: env_var_or("DEADMAN_SELL_AFTER_SECS", "1800")
+                .parse()
+                .unwrap_or(1800),
+            // Restart the listener/monitor/mempool tasks if they exit, or the first two go
+            // this long without a heartbeat (0 = restart on exit only)
+            watchdog_enabled: env_var_or("WATCHDOG_ENABLED", "true").parse().unwrap_or(true),
+            watchdog_stale_secs: env_var_or("WATCHDOG_STALE_SECS", "300")
+                .parse()
+                .unwrap_or(300),
+            // On Ctrl-C, wait this long for in-flight sells and front-runs before saving
+            shutdown_drain_secs: env_var_or("SHUTDOWN_DRAIN_SECS", "60").parse().unwrap_or(60),
 
-            // Blacklist
+            // Blacklist: whole words, or `re:<pattern>` regexes (no commas; entries are comma-separated)
             blacklist: env_var_or("AUTO_SNIPE_BLACKLIST", "test,scam,rug,honeypot,fake")
                 .split(',')
-                .map(|s| s.trim().to_lowercase())
+                .map(|s| s.trim().to_string())
                 .collect(),
+            // Match blacklist words anywhere in the name ("rug" rejects "Ruger")
+            blacklist_strict_substring: env_var_or("BLACKLIST_STRICT_SUBSTRING", "false")
+                .parse()
+                .unwrap_or(false),
+
+            // Allowlist (priority snipes)
+            snipe_allowlist: parse_address_list(&env_var_or("SNIPE_ALLOWLIST", "")),
+            creator_allowlist: parse_address_list(&env_var_or("CREATOR_ALLOWLIST", "")),
+            // Snipe only creators on CREATOR_ALLOWLIST, through the regular filters
+            creator_allowlist_only: env_var_or("CREATOR_ALLOWLIST_ONLY", "false")
+                .parse()
+                .unwrap_or(false),
+            allowlist_snipe_amount_mon: env_var_or("ALLOWLIST_SNIPE_AMOUNT_MON", "10.0")
+                .parse()
+                .unwrap_or(10.0),
+            // Rate limits per bucket (0 = unlimited)
+            max_snipes_per_minute: env_var_or("MAX_SNIPES_PER_MIN", "0")
+                .parse()
+                .unwrap_or(0),
+            max_priority_snipes_per_minute: env_var_or("MAX_PRIORITY_SNIPES_PER_MIN", "0")
+                .parse()
+                .unwrap_or(0),
+            // Global throttle across snipe, limit and copy buys (0 = off)
+            min_seconds_between_buys: env_var_or("MIN_SECONDS_BETWEEN_BUYS", "0")
+                .parse()
+                .unwrap_or(0),
+            max_open_positions: env_var_or("MAX_OPEN_POSITIONS", "0")
+                .parse()
+                .unwrap_or(0),
 
             // Arbitrage
             arbitrage_enabled: env_var_or("ARBITRAGE_ENABLED", "true")
@@ -163,17 +525,69 @@ impl Config {
                 .unwrap_or(true),
             arbitrage_contract: std::env::var("ARBITRAGE_CONTRACT")
                 .ok()
-                .and_then(|s| parse_address(&s).ok()),
+                .and_then(|s| Address::from_str(&s).ok()),
+            // Kuru router to quote in the arbitrage scanner (unset = Kuru not scanned)
+            kuru_router: std::env::var("KURU_ROUTER")
+                .ok()
+                .and_then(|s| Address::from_str(&s).ok()),
             arb_scan_interval_ms: env_var_or("ARB_SCAN_INTERVAL_MS", "500")
                 .parse()
                 .unwrap_or(500),
             arb_amount_mon: env_var_or("ARB_AMOUNT_MON", "10.0")
                 .parse()
                 .unwrap_or(10.0),
+            // Swap fee each DEX charges per leg (`dex:bps,...`); unlisted DEXes use 30
+            arb_dex_fees_bps: parse_dex_fees(&env_var_or("ARB_DEX_FEES_BPS", "zkswap:30,octoswap:30")),
+            // Quote side of the WMON/USDT pair (WMON/USDC uses USDC_ADDRESS)
+            usdt_address: address_var_or("USDT_ADDRESS", "0xf817257fed379853cDe0fa4F97AB987181B1E5Ea")?,
+
+            // Pricing (unset = USD values omitted until a live price is known)
+            mon_price_usd: std::env::var("MON_PRICE_USD")
+                .ok()
+                .and_then(|s| s.parse().ok()),
+            // Live price from the WMON/USDC pool, refreshed every TTL (0 = static MON_PRICE_USD)
+            usdc_address: address_var_or("USDC_ADDRESS", "0x754704Bc059F8C67012fEd69BC8A327a5aafb603")?,
+            mon_price_ttl_secs: env_var_or("MON_PRICE_TTL_SECS", "60")
+                .parse()
+                .unwrap_or(60),
 
             // Telegram
             telegram_token: std::env::var("TELEGRAM_TOKEN").ok(),
             telegram_chat_id: std::env::var("TELEGRAM_CHAT_ID").ok(),
+            // Accept /sell, /pause, /resume, /status from TELEGRAM_CHAT_ID
+            telegram_commands_enabled: env_var_or("TELEGRAM_COMMANDS_ENABLED", "false")
+                .parse()
+                .unwrap_or(false),
+
+            // Discord (notifications only; same toggles as Telegram)
+            discord_webhook_url: std::env::var("DISCORD_WEBHOOK_URL").ok().filter(|url| !url.is_empty()),
+
+            // Status API
+            api_token: std::env::var("API_TOKEN").ok().filter(|t| !t.is_empty()),
+            api_port: env_var_or("API_PORT", "8080").parse().unwrap_or(8080),
+
+            // Logging (`json` for log aggregators)
+            log_format: LogFormat::parse(&env_var_or("LOG_FORMAT", "text")),
+
+            // Notifications (quiet by default: trades, errors, promotions)
+            notify_new_token: env_var_or("NOTIFY_NEW_TOKEN", "false")
+                .parse()
+                .unwrap_or(false),
+            notify_buy: env_var_or("NOTIFY_BUY", "true").parse().unwrap_or(true),
+            notify_sell: env_var_or("NOTIFY_SELL", "true").parse().unwrap_or(true),
+            notify_copy: env_var_or("NOTIFY_COPY", "false").parse().unwrap_or(false),
+            notify_scout_promotion: env_var_or("NOTIFY_SCOUT_PROMOTION", "true")
+                .parse()
+                .unwrap_or(true),
+            notify_errors: env_var_or("NOTIFY_ERRORS", "true").parse().unwrap_or(true),
+            notify_pnl_summary: env_var_or("NOTIFY_PNL_SUMMARY", "false")
+                .parse()
+                .unwrap_or(false),
+            notify_heartbeat: env_var_or("NOTIFY_HEARTBEAT", "true").parse().unwrap_or(true),
+            // Status push interval (0 = no heartbeat)
+            heartbeat_interval_secs: env_var_or("HEARTBEAT_INTERVAL_SECS", "3600")
+                .parse()
+                .unwrap_or(3600),
 
             // Smart Money Tracking
             smart_wallets: env_var_or("SMART_WALLETS", "0x038a0ce5f55a715bd14688cbeec1433c6cf2009e,0x571b6770ed63863d7cc7d461b1c4ec5504f17faa,0x28ddf82febffc3696dd66738af1ec162dc1189c8")
@@ -181,6 +595,8 @@ impl Config {
                 .map(|s| s.trim().to_lowercase())
                 .filter(|s| !s.is_empty())
                 .collect(),
+            // Per-wallet copy_pct/max_mon/enabled, re-read on SIGHUP (SMART_WALLETS is the fallback)
+            smart_wallets_file: std::env::var("SMART_WALLETS_FILE").ok().filter(|path| !path.is_empty()),
 
             whale_copy_pct: env_var_or("WHALE_COPY_PCT", "20.0")
                 .parse()
@@ -188,32 +604,598 @@ impl Config {
             max_snipe_amount: env_var_or("MAX_SNIPE_AMOUNT", "50.0")
                 .parse()
                 .unwrap_or(50.0),
+            // Total MON committed to one token across repeated buys (0 = no cap)
+            max_per_token_mon: env_var_or("MAX_PER_TOKEN_MON", "0")
+                .parse()
+                .unwrap_or(0.0),
+            // Skip copy buys whose source trade is older than this (0 = no limit)
+            max_copy_delay_ms: env_var_or("MAX_COPY_DELAY_MS", "3000")
+                .parse()
+                .unwrap_or(3000),
+            // Scale copy buys by the wallet's tracker score (`min_score:multiplier,...`)
+            score_sizing_enabled: env_var_or("SCORE_SIZING_ENABLED", "false")
+                .parse()
+                .unwrap_or(false),
+            score_sizing_tiers: parse_score_tiers(&env_var_or(
+                "SCORE_SIZING_TIERS",
+                "0:0.25,40:0.5,60:1.0,90:1.5",
+            )),
+            // With no SMART_WALLETS, subscribe to Create only (turns off scout tracking)
+            create_only_without_wallets: env_var_or("STREAM_CREATE_ONLY_WITHOUT_WALLETS", "false")
+                .parse()
+                .unwrap_or(false),
+            // After a CurveStream reconnect, replay up to this many missed blocks from logs (0 = off)
+            stream_replay_max_blocks: env_var_or("STREAM_REPLAY_MAX_BLOCKS", "1000")
+                .parse()
+                .unwrap_or(1000),
+
+            // Buffer size of the event channels between listeners and the main loop
+            channel_capacity: env_var_or("CHANNEL_CAPACITY", "100")
+                .parse::<usize>()
+                .unwrap_or(100)
+                .max(1),
+            // A token launch seen again within this window is dropped before analysis
+            new_token_dedupe_secs: env_var_or("NEW_TOKEN_DEDUPE_SECS", "600")
+                .parse()
+                .unwrap_or(600),
 
             // Slippage settings
             buy_slippage_pct: env_var_or("BUY_SLIPPAGE_PCT", "5.0")
                 .parse()
                 .unwrap_or(5.0),
+            // First-attempt slippage for copy buys (whale tokens move fast); defaults to BUY_SLIPPAGE_PCT
+            copy_trade_slippage_pct: env_var_or("COPY_TRADE_SLIPPAGE_PCT", &env_var_or("BUY_SLIPPAGE_PCT", "5.0"))
+                .parse()
+                .unwrap_or(5.0),
             sell_slippage_pct: env_var_or("SELL_SLIPPAGE_PCT", "15.0")
                 .parse()
                 .unwrap_or(15.0),
+            // Second SDK sell attempt after the first misses
+            sell_retry_slippage_pct: env_var_or("SELL_RETRY_SLIPPAGE_PCT", "25.0")
+                .parse()
+                .unwrap_or(25.0),
+            // Consecutive all-venue sell failures before a position is marked stuck (0 = never)
+            max_sell_failures: env_var_or("MAX_SELL_FAILURES", "5").parse().unwrap_or(5),
+            // Venue order for sells: `sdk_first`, `dex_first` or `auto` (DEX only once graduated)
+            sell_route: SellRoute::parse(&env_var_or("SELL_ROUTE_PREFERENCE", "sdk_first"))
+                .map_err(|e| BotError::Config(format!("SELL_ROUTE_PREFERENCE: {}", e)))?,
+            buy_slippage_step_pct: env_var_or("BUY_SLIPPAGE_STEP_PCT", "5.0")
+                .parse()
+                .unwrap_or(5.0),
+            buy_max_slippage_pct: env_var_or("BUY_MAX_SLIPPAGE_PCT", "20.0")
+                .parse()
+                .unwrap_or(20.0),
+            dex_sell_slippage_pct: env_var_or("DEX_SELL_SLIPPAGE_PCT", "15.0")
+                .parse()
+                .unwrap_or(15.0),
+            // amount_out_min floor for front-run buys, which skip the normal slippage settings
+            max_acceptable_slippage_pct: env_var_or("MAX_ACCEPTABLE_SLIPPAGE_PCT", "50.0")
+                .parse()
+                .unwrap_or(50.0),
+            // Send front-run buys with amount_out_min = 0 (open to sandwiching)
+            allow_zero_min: env_var_or("ALLOW_ZERO_MIN", "false").parse().unwrap_or(false),
+            dex_sell_deadline_secs: env_var_or("DEX_SELL_DEADLINE_SECS", "600")
+                .parse()
+                .unwrap_or(600),
+            approve_gas_limit: env_var_or("APPROVE_GAS_LIMIT", "100000")
+                .parse()
+                .unwrap_or(100_000),
+            // Sell approvals: "exact" (default, no standing allowance) or "max"
+            approval_mode: ApprovalMode::parse(&env_var_or("APPROVAL_MODE", "exact")),
         })
     }
 
+    /// Every DEX route for graduated-token sells and pricing, the primary router first.
+    pub fn dex_routes(&self) -> Vec<DexRoute> {
+        let primary = DexRoute {
+            name: "default".to_string(),
+            router: self.router_address,
+            wmon: self.wmon_address,
+        };
+        let mut routes = vec![primary];
+        for route in &self.extra_dex_routes {
+            if !routes.iter().any(|r| r.router == route.router) {
+                routes.push(route.clone());
+            }
+        }
+        routes
+    }
+
+    /// How long to wait for a transaction receipt.
+    pub fn receipt_timeout(&self) -> Duration {
+        Duration::from_secs(self.tx_receipt_timeout_secs)
+    }
+
+    /// Spread applied to buy amounts (`SNIPE_AMOUNT_JITTER_PCT`).
+    pub fn amount_jitter(&self) -> Jitter {
+        Jitter::new(self.snipe_amount_jitter_pct, self.jitter_seed)
+    }
+
+    /// Spread applied to buy priority fees (`GAS_JITTER_PCT`). Seeded apart
+    /// from the amount jitter so the two don't move together.
+    pub fn gas_jitter(&self) -> Jitter {
+        Jitter::new(self.gas_jitter_pct, self.jitter_seed.map(|seed| seed.wrapping_add(1)))
+    }
+
     /// Convert MON amount to wei (18 decimals).
     pub fn mon_to_wei(&self, mon: f64) -> U256 {
-        let wei = (mon * 1e18) as u128;
-        U256::from(wei)
+        units::mon_to_wei(mon)
     }
 }
 
-fn env_var(name: &str) -> Result<String, String> {
-    std::env::var(name).map_err(|_| format!("{} not set", name))
+fn env_var(name: &str) -> Result<String, BotError> {
+    std::env::var(name).map_err(|_| BotError::MissingEnv(name.to_string()))
 }
 
 fn env_var_or(name: &str, default: &str) -> String {
     std::env::var(name).unwrap_or_else(|_| default.to_string())
 }
 
-fn parse_address(s: &str) -> Result<Address, String> {
-    Address::from_str(s).map_err(|e| format!("Invalid address {}: {}", s, e))
+/// Settings that were renamed, as (old name, new name). The old name is still
+/// read when the new one is unset.
+const RENAMED_VARS: &[(&str, &str)] = &[("MEMPOOL_GAS_MULTIPLIER", "SNIPE_GAS_MULTIPLIER")];
+
+/// `name`, else its deprecated former name `old`, else `default`.
+fn env_var_or_renamed(name: &str, old: &str, default: &str) -> String {
+    std::env::var(name)
+        .or_else(|_| std::env::var(old))
+        .unwrap_or_else(|_| default.to_string())
+}
+
+/// Warn about renamed settings still set under their old name. `from_env`
+/// runs before logging is set up, so this is called separately once it is.
+pub fn warn_deprecated_vars() {
+    for (old, new) in RENAMED_VARS {
+        if std::env::var(old).is_err() {
+            continue;
+        }
+        if std::env::var(new).is_ok() {
+            warn!("⚠️ {} is deprecated and ignored since {} is set", old, new);
+        } else {
+            warn!("⚠️ {} is deprecated, rename it to {}", old, new);
+        }
+    }
+}
+
+/// The address in `name`, else `default`.
+fn address_var_or(name: &str, default: &str) -> Result<Address, BotError> {
+    parse_address(name, &env_var_or(name, default))
+}
+
+/// `s`, read from the variable `var`, as an address.
+fn parse_address(var: &str, s: &str) -> Result<Address, BotError> {
+    Address::from_str(s).map_err(|e| BotError::InvalidAddress {
+        var: var.to_string(),
+        value: s.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Comma-separated `profit_pct:portion` tiers, sorted by profit.
+/// Invalid entries and portions outside (0, 1] are skipped.
+fn parse_tp_ladder(s: &str) -> Vec<(f64, f64)> {
+    let mut ladder: Vec<(f64, f64)> = s
+        .split(',')
+        .filter_map(|tier| {
+            let (pct, portion) = tier.trim().split_once(':')?;
+            let pct: f64 = pct.trim().parse().ok()?;
+            let portion: f64 = portion.trim().parse().ok()?;
+            (pct.is_finite() && portion > 0.0 && portion <= 1.0).then_some((pct, portion))
+        })
+        .collect();
+    ladder.sort_by(|a, b| a.0.total_cmp(&b.0));
+    ladder
+}
+
+/// Comma-separated `minutes:drop_pct` points, sorted by age.
+/// Invalid entries and drops outside (0, 100] are skipped.
+fn parse_decay_curve(s: &str) -> Vec<(f64, f64)> {
+    let mut curve: Vec<(f64, f64)> = s
+        .split(',')
+        .filter_map(|point| {
+            let (minutes, drop_pct) = point.trim().split_once(':')?;
+            let minutes: f64 = minutes.trim().parse().ok()?;
+            let drop_pct: f64 = drop_pct.trim().parse().ok()?;
+            (minutes.is_finite() && minutes >= 0.0 && drop_pct > 0.0 && drop_pct <= 100.0)
+                .then_some((minutes, drop_pct))
+        })
+        .collect();
+    curve.sort_by(|a, b| a.0.total_cmp(&b.0));
+    curve
+}
+
+/// Comma-separated `min_score:multiplier` tiers, sorted by score.
+/// Invalid entries and non-positive multipliers are skipped.
+fn parse_score_tiers(s: &str) -> Vec<(f64, f64)> {
+    let mut tiers: Vec<(f64, f64)> = s
+        .split(',')
+        .filter_map(|tier| {
+            let (score, multiplier) = tier.trim().split_once(':')?;
+            let score: f64 = score.trim().parse().ok()?;
+            let multiplier: f64 = multiplier.trim().parse().ok()?;
+            (score.is_finite() && multiplier.is_finite() && multiplier > 0.0).then_some((score, multiplier))
+        })
+        .collect();
+    tiers.sort_by(|a, b| a.0.total_cmp(&b.0));
+    tiers
+}
+
+/// `dex:bps` pairs, e.g. `zkswap:30,octoswap:25`. Unknown DEXes and bad fees are skipped.
+fn parse_dex_fees(s: &str) -> Vec<(DexType, u64)> {
+    s.split(',')
+        .filter_map(|entry| {
+            let (dex, bps) = entry.trim().split_once(':')?;
+            let dex = DexType::parse(dex.trim())?;
+            let bps: u64 = bps.trim().parse().ok()?;
+            (bps < 10_000).then_some((dex, bps))
+        })
+        .collect()
+}
+
+/// Comma-separated addresses; invalid entries are skipped.
+fn parse_address_list(s: &str) -> Vec<Address> {
+    s.split(',')
+        .map(|a| a.trim())
+        .filter(|a| !a.is_empty())
+        .filter_map(|a| Address::from_str(a).ok())
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// `from_env` reads process-wide variables; tests loading a whole config take turns.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// `Config::from_env` with the required variables plus `vars` set.
+    /// Shared with other modules' tests that need a whole `Config`.
+    pub(crate) fn config_with(vars: &[(&str, &str)]) -> Result<Config, BotError> {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let required = [
+            ("MONAD_RPC_URL", "http://127.0.0.1:8545"),
+            ("MONAD_WS_URL", "ws://127.0.0.1:8546"),
+            ("PRIVATE_KEY", "0x0101010101010101010101010101010101010101010101010101010101010101"),
+            ("WALLET_ADDRESS", "0x1111111111111111111111111111111111111111"),
+        ];
+        for (name, value) in required.iter().chain(vars) {
+            std::env::set_var(name, value);
+        }
+        let config = Config::from_env();
+        for (name, _) in vars {
+            std::env::remove_var(name);
+        }
+        config
+    }
+
+    #[test]
+    fn slippage_settings_are_read_from_env() {
+        let config = config_with(&[
+            ("BUY_SLIPPAGE_PCT", "7.5"),
+            ("SELL_SLIPPAGE_PCT", "12"),
+            ("SELL_RETRY_SLIPPAGE_PCT", "30"),
+        ])
+        .unwrap();
+        assert_eq!(config.buy_slippage_pct, 7.5);
+        assert_eq!(config.sell_slippage_pct, 12.0);
+        assert_eq!(config.sell_retry_slippage_pct, 30.0);
+    }
+
+    #[test]
+    fn unparsable_slippage_falls_back_to_the_default() {
+        let config = config_with(&[("SELL_RETRY_SLIPPAGE_PCT", "lots")]).unwrap();
+        assert_eq!(config.sell_retry_slippage_pct, 25.0);
+    }
+
+    #[test]
+    fn renamed_var_prefers_the_new_name() {
+        std::env::set_var("TEST_RENAMED_NEW_1", "2.0");
+        std::env::set_var("TEST_RENAMED_OLD_1", "3.0");
+        assert_eq!(env_var_or_renamed("TEST_RENAMED_NEW_1", "TEST_RENAMED_OLD_1", "1.5"), "2.0");
+    }
+
+    #[test]
+    fn renamed_var_falls_back_to_the_old_name() {
+        std::env::set_var("TEST_RENAMED_OLD_2", "3.0");
+        assert_eq!(env_var_or_renamed("TEST_RENAMED_NEW_2", "TEST_RENAMED_OLD_2", "1.5"), "3.0");
+    }
+
+    #[test]
+    fn renamed_var_defaults_when_neither_is_set() {
+        assert_eq!(env_var_or_renamed("TEST_RENAMED_NEW_3", "TEST_RENAMED_OLD_3", "1.5"), "1.5");
+    }
+
+    #[test]
+    fn tp_ladder_is_sorted_and_skips_bad_tiers() {
+        assert_eq!(
+            parse_tp_ladder("200:0.5, 50:0.25,abc,100:0,100:1.5,100"),
+            vec![(50.0, 0.25), (200.0, 0.5)]
+        );
+        assert!(parse_tp_ladder("").is_empty());
+    }
+
+    #[test]
+    fn tp_ladder_defaults_to_the_single_secure_profit_tier() {
+        let config = config_with(&[("SECURE_PROFIT_PCT", "80"), ("SECURE_SELL_PORTION", "0.4")]).unwrap();
+        assert_eq!(config.tp_ladder, vec![(80.0, 0.4)]);
+
+        let config = config_with(&[("TP_LADDER", "50:0.25,100:0.25"), ("SECURE_PROFIT_PCT", "80")]).unwrap();
+        assert_eq!(config.tp_ladder, vec![(50.0, 0.25), (100.0, 0.25)]);
+    }
+
+    #[test]
+    fn score_tiers_are_sorted_and_skip_bad_entries() {
+        assert_eq!(
+            parse_score_tiers("90:1.5, 0:0.25,x:1,40:0,60:-1,NaN:2,60:1"),
+            vec![(0.0, 0.25), (60.0, 1.0), (90.0, 1.5)]
+        );
+        assert!(parse_score_tiers("").is_empty());
+    }
+
+    #[test]
+    fn score_sizing_is_off_by_default_with_the_stock_tiers() {
+        let config = config_with(&[]).unwrap();
+        assert!(!config.score_sizing_enabled);
+        assert_eq!(config.score_sizing_tiers, vec![(0.0, 0.25), (40.0, 0.5), (60.0, 1.0), (90.0, 1.5)]);
+    }
+
+    #[test]
+    fn channel_capacity_is_at_least_one() {
+        assert_eq!(config_with(&[]).unwrap().channel_capacity, 100);
+        assert_eq!(config_with(&[("CHANNEL_CAPACITY", "0")]).unwrap().channel_capacity, 1);
+        assert_eq!(config_with(&[("CHANNEL_CAPACITY", "512")]).unwrap().channel_capacity, 512);
+    }
+
+    #[test]
+    fn decay_curve_is_sorted_and_skips_bad_points() {
+        assert_eq!(
+            parse_decay_curve("60:10, 0:30,10:30,-5:20,20:0,30:150,x:5,40"),
+            vec![(0.0, 30.0), (10.0, 30.0), (60.0, 10.0)]
+        );
+        assert!(config_with(&[]).unwrap().trailing_decay.is_empty());
+    }
+
+    #[test]
+    fn bad_quote_token_address_names_the_variable() {
+        let err = config_with(&[("USDT_ADDRESS", "0xnotanaddress")]).unwrap_err();
+        assert!(matches!(&err, BotError::InvalidAddress { var, .. } if var == "USDT_ADDRESS"), "{}", err);
+        let err = config_with(&[("USDC_ADDRESS", "")]).unwrap_err();
+        assert!(err.to_string().starts_with("USDC_ADDRESS:"), "{}", err);
+
+        let usdt = "0x1111111111111111111111111111111111111111";
+        assert_eq!(config_with(&[("USDT_ADDRESS", usdt)]).unwrap().usdt_address, usdt.parse::<Address>().unwrap());
+    }
+
+    #[test]
+    fn dex_routes_put_the_primary_router_first_without_duplicates() {
+        let config = config_with(&[]).unwrap();
+        let extra = "0x2222222222222222222222222222222222222222";
+        let wmon = "0x3333333333333333333333333333333333333333";
+        let routes = format!("dup:{:?}:{wmon},kuru:{extra}:{wmon}", config.router_address);
+
+        let routes = config_with(&[("DEX_ROUTES", &routes)]).unwrap().dex_routes();
+        let names: Vec<&str> = routes.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["default", "kuru"]);
+        assert_eq!(routes[0].router, config.router_address);
+    }
+
+    #[test]
+    fn receipt_timeout_is_read_in_seconds() {
+        assert_eq!(config_with(&[]).unwrap().receipt_timeout(), Duration::from_secs(30));
+        let config = config_with(&[("TX_RECEIPT_TIMEOUT_SEC", "5")]).unwrap();
+        assert_eq!(config.receipt_timeout(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn monitor_concurrency_defaults_to_8_and_is_at_least_1() {
+        assert_eq!(config_with(&[]).unwrap().monitor_concurrency, 8);
+        assert_eq!(config_with(&[("MONITOR_CONCURRENCY", "0")]).unwrap().monitor_concurrency, 1);
+    }
+
+    #[test]
+    fn amount_and_gas_jitter_are_seeded_apart() {
+        let config = config_with(&[
+            ("SNIPE_AMOUNT_JITTER_PCT", "10"),
+            ("GAS_JITTER_PCT", "10"),
+            ("JITTER_SEED", "42"),
+        ])
+        .unwrap();
+        assert_eq!(config.jitter_seed, Some(42));
+        let (amount, gas) = (config.amount_jitter(), config.gas_jitter());
+        let draws = |jitter: &Jitter| (0..5).map(|_| jitter.apply_fee(1_000_000)).collect::<Vec<_>>();
+        assert_ne!(draws(&amount), draws(&gas));
+        assert_eq!(draws(&config.amount_jitter()), draws(&Jitter::new(10.0, Some(42))));
+
+        assert!(config_with(&[]).unwrap().amount_jitter().is_off());
+    }
+
+    #[test]
+    fn chain_id_defaults_to_mainnet() {
+        assert_eq!(config_with(&[]).unwrap().chain_id, MONAD_MAINNET_CHAIN_ID);
+        assert_eq!(config_with(&[("CHAIN_ID", "10143")]).unwrap().chain_id, 10143);
+    }
+
+    #[test]
+    fn copy_delay_defaults_to_three_seconds() {
+        assert_eq!(config_with(&[]).unwrap().max_copy_delay_ms, 3000);
+        assert_eq!(config_with(&[("MAX_COPY_DELAY_MS", "0")]).unwrap().max_copy_delay_ms, 0);
+    }
+
+    #[test]
+    fn wmon_sweep_is_off_by_default() {
+        let config = config_with(&[]).unwrap();
+        assert_eq!((config.wmon_sweep_interval_secs, config.wmon_sweep_threshold_mon), (0, 1.0));
+    }
+
+    #[test]
+    fn dex_fees_skip_unknown_venues_and_impossible_fees() {
+        assert_eq!(
+            parse_dex_fees("zkswap:30, Kuru:10,uniswap:5,octoswap:10000,octoswap:x"),
+            vec![(DexType::ZKSwap, 30), (DexType::Kuru, 10)]
+        );
+        assert_eq!(parse_dex_fees(""), Vec::new());
+    }
+
+    #[test]
+    fn largest_holder_presumption_is_opt_in() {
+        assert!(!config_with(&[]).unwrap().presume_largest_holder_dev);
+        let config = config_with(&[("PRESUME_LARGEST_HOLDER_DEV", "true")]).unwrap();
+        assert!(crate::validators::token_analysis::FilterConfig::from_config(&config).presume_largest_holder_dev);
+    }
+
+    #[test]
+    fn bad_wallet_address_is_an_invalid_address_error() {
+        let err = config_with(&[("WALLET_ADDRESS", "wallet")]).unwrap_err();
+        assert!(matches!(&err, BotError::InvalidAddress { var, value, .. } if var == "WALLET_ADDRESS" && value == "wallet"));
+    }
+
+    #[test]
+    fn blacklist_keeps_regex_entries_verbatim() {
+        let config = config_with(&[]).unwrap();
+        assert_eq!(config.blacklist, ["test", "scam", "rug", "honeypot", "fake"]);
+        assert!(!config.blacklist_strict_substring);
+
+        let config = config_with(&[("AUTO_SNIPE_BLACKLIST", "rug, re:^Elon\\d"), ("BLACKLIST_STRICT_SUBSTRING", "true")]).unwrap();
+        assert_eq!(config.blacklist, ["rug", "re:^Elon\\d"]);
+        assert!(config.blacklist_strict_substring);
+    }
+
+    #[test]
+    fn migration_exit_is_opt_in() {
+        assert!(!config_with(&[]).unwrap().sell_before_migration);
+        let config = config_with(&[("SELL_BEFORE_MIGRATION", "true")]).unwrap();
+        assert!(crate::position::TrailingStopLossConfig::from_config(&config).sell_before_migration);
+    }
+
+    #[test]
+    fn position_averaging_defaults_on() {
+        assert!(config_with(&[]).unwrap().position_averaging);
+        assert!(!config_with(&[("POSITION_AVERAGING", "false")]).unwrap().position_averaging);
+    }
+
+    #[test]
+    fn front_run_gas_ceiling_defaults_to_200_gwei() {
+        assert_eq!(config_with(&[]).unwrap().max_frontrun_gas_gwei, 200.0);
+        assert_eq!(config_with(&[("MAX_FRONTRUN_GAS_GWEI", "75.5")]).unwrap().max_frontrun_gas_gwei, 75.5);
+    }
+
+    #[test]
+    fn empty_discord_webhook_is_unset() {
+        assert_eq!(config_with(&[("DISCORD_WEBHOOK_URL", "")]).unwrap().discord_webhook_url, None);
+        let url = "https://discord.com/api/webhooks/1/abc";
+        assert_eq!(config_with(&[("DISCORD_WEBHOOK_URL", url)]).unwrap().discord_webhook_url.as_deref(), Some(url));
+    }
+
+    #[test]
+    fn holder_count_settings_reach_the_filters() {
+        let config = config_with(&[
+            ("MIN_HOLDER_COUNT", "25"),
+            ("HOLDER_COUNT_MIN_AGE_MINUTES", "3"),
+            ("MAX_LOG_SCAN_BLOCKS", "2000"),
+        ])
+        .unwrap();
+        let filters = crate::validators::token_analysis::FilterConfig::from_config(&config);
+        assert_eq!(filters.min_holder_count, 25);
+        assert_eq!(filters.holder_count_min_age_minutes, 3);
+        assert_eq!(filters.max_log_scan_blocks, 2000);
+
+        let defaults = config_with(&[]).unwrap();
+        assert_eq!((defaults.min_holder_count, defaults.holder_count_min_age_minutes, defaults.max_log_scan_blocks), (10, 10, 10_000));
+    }
+
+    #[test]
+    fn launches_are_deduped_for_ten_minutes_by_default() {
+        assert_eq!(config_with(&[]).unwrap().new_token_dedupe_secs, 600);
+        assert_eq!(config_with(&[("NEW_TOKEN_DEDUPE_SECS", "60")]).unwrap().new_token_dedupe_secs, 60);
+    }
+
+    #[test]
+    fn watchdog_defaults_to_a_five_minute_window() {
+        let config = config_with(&[]).unwrap();
+        assert!(config.watchdog_enabled);
+        assert_eq!(config.watchdog_stale_secs, 300);
+    }
+
+    #[test]
+    fn trade_deadline_defaults_to_two_minutes() {
+        assert_eq!(config_with(&[]).unwrap().tx_deadline_secs, 120);
+        assert_eq!(config_with(&[("TX_DEADLINE_SEC", "30")]).unwrap().tx_deadline_secs, 30);
+    }
+
+    #[test]
+    fn gas_bump_settings_reach_the_policy() {
+        let config = config_with(&[("BUY_GAS_BUMP_MAX_GWEI", "150"), ("BUY_GAS_BUMP_TIMEOUT_SEC", "0")]).unwrap();
+        let bump = crate::executor::GasBump::from_config(&config);
+        assert_eq!(bump.max_fee_per_gas, 150_000_000_000);
+        assert_eq!((bump.bump_pct, bump.max_attempts), (30.0, 3));
+        // Zero timeout turns resending off
+        assert!(!bump.enabled());
+    }
+
+    #[test]
+    fn copy_slippage_falls_back_to_the_buy_slippage() {
+        let config = config_with(&[("BUY_SLIPPAGE_PCT", "8")]).unwrap();
+        assert_eq!(config.copy_trade_slippage_pct, 8.0);
+
+        let config = config_with(&[("BUY_SLIPPAGE_PCT", "8"), ("COPY_TRADE_SLIPPAGE_PCT", "15")]).unwrap();
+        assert_eq!((config.buy_slippage_pct, config.copy_trade_slippage_pct), (8.0, 15.0));
+    }
+
+    #[test]
+    fn supply_checks_reach_the_filters() {
+        let config = config_with(&[("MIN_TOTAL_SUPPLY", "1000"), ("SUPPLY_RECHECK_SECS", "0")]).unwrap();
+        let filters = crate::validators::token_analysis::FilterConfig::from_config(&config);
+        assert_eq!(filters.min_total_supply, 1_000);
+        assert_eq!(filters.max_total_supply, 1_000_000_000_000);
+        assert_eq!(filters.supply_recheck_secs, 0);
+    }
+
+    #[test]
+    fn shutdown_waits_a_minute_for_trades_by_default() {
+        assert_eq!(config_with(&[]).unwrap().shutdown_drain_secs, 60);
+        assert_eq!(config_with(&[("SHUTDOWN_DRAIN_SECS", "5")]).unwrap().shutdown_drain_secs, 5);
+    }
+
+    #[test]
+    fn endpoint_lists_take_over_from_the_single_urls() {
+        let config = config_with(&[("MONAD_RPC_URLS", "http://a:8545,http://b:8545"), ("RPC_FAILOVER_ERRORS", "0")]).unwrap();
+        assert_eq!(config.rpc_urls, vec!["http://a:8545", "http://b:8545"]);
+        assert_eq!(config.rpc_url, "http://a:8545");
+        assert_eq!(config.ws_urls, vec![config.ws_url.clone()]);
+        // At least one failure before switching
+        assert_eq!(crate::rpc::FailoverPolicy::from_config(&config).max_failures, 1);
+    }
+
+    #[test]
+    fn round_trip_fee_settings_reach_the_monitor() {
+        let config = config_with(&[("ROUND_TRIP_GAS", "0"), ("FEE_SLIPPAGE_PCT", "2.5")]).unwrap();
+        let cost = crate::position::TrailingStopLossConfig::from_config(&config).round_trip_cost;
+        assert_eq!((cost.gas, cost.fallback_gas_price_gwei, cost.slippage_pct), (0, 100.0, 2.5));
+    }
+
+    #[test]
+    fn dip_orders_are_off_with_no_target_by_default() {
+        let config = config_with(&[]).unwrap();
+        assert!(!config.pending_orders_enabled);
+        assert_eq!(config.pending_order_target_mcap_usd, None);
+        assert_eq!((config.pending_order_poll_interval_sec, config.pending_order_ttl_minutes), (10, 60));
+    }
+
+    #[test]
+    fn recoup_then_runner_settings_reach_the_monitor() {
+        let config = config_with(&[("EXIT_MODE", "recoup_then_runner"), ("RECOUP_MULTIPLE", "3")]).unwrap();
+        let monitor = crate::position::TrailingStopLossConfig::from_config(&config);
+        assert_eq!(monitor.exit_mode, ExitMode::RecoupThenRunner);
+        assert_eq!((monitor.recoup_multiple, monitor.runner_drop_pct), (3.0, 40.0));
+        assert_eq!(config_with(&[]).unwrap().exit_mode, ExitMode::Standard);
+    }
+
+    #[test]
+    fn sell_route_defaults_to_sdk_first_and_refuses_typos() {
+        assert_eq!(config_with(&[]).unwrap().sell_route, SellRoute::SdkFirst);
+        assert_eq!(config_with(&[("SELL_ROUTE_PREFERENCE", "auto")]).unwrap().sell_route, SellRoute::Auto);
+        let err = config_with(&[("SELL_ROUTE_PREFERENCE", "dex_frist")]).unwrap_err();
+        assert!(matches!(err, BotError::Config(ref msg) if msg.starts_with("SELL_ROUTE_PREFERENCE: unknown sell route")));
+    }
 }
@@ -0,0 +1,182 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Discord notifier: posts to a channel webhook (`DISCORD_WEBHOOK_URL`).
+
+use crate::notify::{FlushFuture, MessageQueue, Notifier};
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Discord rejects message content longer than 2000 chars; batches stay under this.
+const MAX_BATCH_CHARS: usize = 1900;
+
+/// Minimum gap between posts (webhooks allow ~5 requests per 2s).
+const SEND_INTERVAL_MS: u64 = 500;
+
+/// Attempts per batch when Discord answers 429 Too Many Requests.
+const MAX_SEND_ATTEMPTS: u32 = 3;
+
+/// Wait used when a 429 doesn't say how long to back off.
+const DEFAULT_RETRY_AFTER_SECS: f64 = 1.0;
+
+/// Webhook execute body.
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload {
+    pub content: String,
+    /// Never ping @everyone/@here or roles from a token name.
+    pub allowed_mentions: AllowedMentions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AllowedMentions {
+    pub parse: Vec<String>,
+}
+
+impl WebhookPayload {
+    pub fn new(content: String) -> Self {
+        Self {
+            content,
+            allowed_mentions: AllowedMentions { parse: Vec::new() },
+        }
+    }
+}
+
+/// Notifier posting to a Discord webhook.
+#[derive(Clone)]
+pub struct DiscordNotifier {
+    queue: MessageQueue,
+}
+
+impl DiscordNotifier {
+    /// Create the notifier and spawn its background sender.
+    pub fn new(webhook_url: String) -> Self {
+        info!("💬 Initializing Discord webhook notifier");
+        let client = reqwest::Client::new();
+
+        let queue = MessageQueue::spawn(
+            "Discord",
+            Duration::from_millis(SEND_INTERVAL_MS),
+            MAX_BATCH_CHARS,
+            move |text| {
+                let client = client.clone();
+                let url = webhook_url.clone();
+                async move { send_with_retry(&client, &url, text).await }
+            },
+        );
+        Self { queue }
+    }
+}
+
+impl Notifier for DiscordNotifier {
+    fn send(&self, message: &str) {
+        self.queue.push(discord_markdown(message));
+    }
+
+    fn flush(&self) -> FlushFuture<'_> {
+        Box::pin(self.queue.flush())
+    }
+}
+
+/// Adapt a message written for Telegram's Markdown: `*bold*` becomes `**bold**`.
+/// Italics (`_x_`) and code spans are written the same way on both.
+pub fn discord_markdown(message: &str) -> String {
+    message.replace('*', "**")
+}
+
+/// Post one message, honouring Discord's `retry_after` on 429.
+async fn send_with_retry(client: &reqwest::Client, url: &str, content: String) {
+    let payload = WebhookPayload::new(content);
+
+    for attempt in 1..=MAX_SEND_ATTEMPTS {
+        let response = match client.post(url).json(&payload).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Failed to send Discord message: {}", e);
+                return;
+            }
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            info!("📤 Sent Discord message");
+            return;
+        }
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS && attempt < MAX_SEND_ATTEMPTS {
+            let retry_after = response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|body| body.get("retry_after").and_then(|v| v.as_f64()))
+                .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+            warn!("⏳ Discord rate limited, retrying in {:.1}s", retry_after);
+            tokio::time::sleep(Duration::from_secs_f64(retry_after.max(0.0))).await;
+            continue;
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        error!("Discord webhook returned {}: {}", status, body);
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn telegram_bold_becomes_discord_bold() {
+        assert_eq!(discord_markdown("*BUY* _TST_ `0xab`"), "**BUY** _TST_ `0xab`");
+    }
+
+    #[test]
+    fn payload_disables_every_mention() {
+        let payload = serde_json::to_value(WebhookPayload::new("@everyone".to_string())).unwrap();
+        assert_eq!(payload, serde_json::json!({"content": "@everyone", "allowed_mentions": {"parse": []}}));
+    }
+
+    /// A webhook answering 429 to the first `limited` posts, recording every body.
+    async fn webhook(limited: usize) -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let hits = Arc::new(AtomicUsize::new(0));
+        let recorded = Arc::clone(&bodies);
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let bodies = Arc::clone(&recorded);
+                let hits = Arc::clone(&hits);
+                async move {
+                    bodies.lock().unwrap().push(body);
+                    if hits.fetch_add(1, Ordering::SeqCst) < limited {
+                        (StatusCode::TOO_MANY_REQUESTS, Json(serde_json::json!({"retry_after": 0.01})))
+                    } else {
+                        (StatusCode::NO_CONTENT, Json(serde_json::Value::Null))
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, bodies)
+    }
+
+    #[tokio::test]
+    async fn rate_limited_post_is_retried_after_the_wait() {
+        let (url, bodies) = webhook(1).await;
+        send_with_retry(&reqwest::Client::new(), &url, "hello".to_string()).await;
+        assert_eq!(bodies.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_retries_give_up_after_the_last_attempt() {
+        let (url, bodies) = webhook(usize::MAX).await;
+        send_with_retry(&reqwest::Client::new(), &url, "hello".to_string()).await;
+        assert_eq!(bodies.lock().unwrap().len(), MAX_SEND_ATTEMPTS as usize);
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Errors from starting the bot: loading its configuration and the setup
+//! steps that follow.
+
+use crate::executor::TradeError;
+use thiserror::Error;
+
+/// Why the bot could not start.
+#[derive(Debug, Error)]
+pub enum BotError {
+    /// A required environment variable is unset.
+    #[error("{0} not set")]
+    MissingEnv(String),
+    /// `var` holds something that is not an address.
+    #[error("{var}: invalid address {value:?}: {reason}")]
+    InvalidAddress { var: String, value: String, reason: String },
+    /// Any other bad setting.
+    #[error("{0}")]
+    Config(String),
+    /// A setup step that talks to the chain failed.
+    #[error(transparent)]
+    Trade(#[from] TradeError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_name_the_setting() {
+        assert_eq!(BotError::MissingEnv("PRIVATE_KEY".to_string()).to_string(), "PRIVATE_KEY not set");
+        let err = BotError::InvalidAddress {
+            var: "USDT_ADDRESS".to_string(),
+            value: "0xnope".to_string(),
+            reason: "odd number of digits".to_string(),
+        };
+        assert_eq!(err.to_string(), "USDT_ADDRESS: invalid address \"0xnope\": odd number of digits");
+    }
+
+    #[test]
+    fn trade_errors_convert_and_keep_their_message() {
+        fn setup() -> Result<(), BotError> {
+            Err(TradeError::Config("Chain id mismatch".to_string()))?
+        }
+        let err = setup().unwrap_err();
+        assert!(matches!(err, BotError::Trade(TradeError::Config(_))));
+        assert_eq!(err.to_string(), "Chain id mismatch");
+    }
+}
@@ -0,0 +1,33 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Token approval policy for sells.
+
+use alloy::primitives::U256;
+
+/// How much allowance a sell grants the router.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalMode {
+    /// Approve exactly the amount being sold (no standing allowance left behind).
+    Exact,
+    /// Approve `U256::MAX` once per token and router (saves an approve on later sells).
+    Max,
+}
+
+impl ApprovalMode {
+    /// Parse `exact` / `max` (case-insensitive). Anything else falls back to `Exact`.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "max" => Self::Max,
+            _ => Self::Exact,
+        }
+    }
+
+    /// Allowance to grant for selling `amount`.
+    pub fn allowance_for(&self, amount: U256) -> U256 {
+        match self {
+            Self::Exact => amount,
+            Self::Max => U256::MAX,
+        }
+    }
+}
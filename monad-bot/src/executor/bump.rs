@@ -0,0 +1,256 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Gas-bump resubmission for buys that sit unconfirmed.
+//!
+//! An under-priced buy can wait in the mempool until the launch is long gone.
+//! After `timeout` without a receipt the buy is resent at the same nonce with
+//! its fees raised by `bump_pct`, up to `max_attempts` times and never above
+//! the fee ceiling. Every version shares the nonce, so at most one of them can
+//! be mined; whichever receipt shows up first is the buy's.
+
+use crate::config::Config;
+use crate::executor::TradeError;
+use alloy::primitives::B256;
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionReceipt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// How often the sent versions of a buy are checked for a receipt.
+const POLL_INTERVAL_MS: u64 = 500;
+
+/// Replacement-fee policy for stuck buys.
+#[derive(Debug, Clone, Copy)]
+pub struct GasBump {
+    /// Wait this long for a receipt before resending (zero = never resend).
+    pub timeout: Duration,
+    /// Fee increase per resend, in percent (nodes want at least 10% to replace).
+    pub bump_pct: f64,
+    /// Resends after the first submission.
+    pub max_attempts: u32,
+    /// No resend bids more than this per gas, in wei.
+    pub max_fee_per_gas: u128,
+}
+
+impl GasBump {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            timeout: Duration::from_secs(config.buy_gas_bump_timeout_secs),
+            bump_pct: config.buy_gas_bump_pct,
+            max_attempts: config.buy_gas_bump_max_attempts,
+            max_fee_per_gas: (config.buy_gas_bump_max_gwei * 1e9) as u128,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.timeout.is_zero() && self.max_attempts > 0 && self.bump_pct > 0.0
+    }
+
+    /// `fee` raised for resend `attempt` (1 = first resend).
+    pub fn scale(&self, fee: u128, attempt: u32) -> u128 {
+        (fee as f64 * (1.0 + self.bump_pct / 100.0).powi(attempt as i32)) as u128
+    }
+
+    /// Whether a resend may bid `fee` per gas.
+    pub fn allows(&self, fee: u128) -> bool {
+        fee <= self.max_fee_per_gas
+    }
+}
+
+/// Wait for the buy sent as `first` to be mined, resending it with bumped fees
+/// while it sits unconfirmed. `resend(attempt)` sends the replacement and
+/// returns its hash, or `Ok(None)` when the bumped fee would pass the ceiling.
+///
+/// After the last resend every version is waited on for `receipt_timeout`.
+/// If none is mined by then this fails with `ReceiptTimeout` for the newest
+/// version, the one most likely to land.
+pub async fn confirm_with_bumps<P, F, Fut>(
+    provider: &P,
+    first: B256,
+    bump: &GasBump,
+    receipt_timeout: Duration,
+    mut resend: F,
+) -> Result<TransactionReceipt, TradeError>
+where
+    P: Provider + ?Sized,
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<Option<B256>, TradeError>>,
+{
+    let mut hashes = vec![first];
+    let mut attempt = 0;
+    let mut bumping = bump.enabled();
+
+    loop {
+        let can_bump = bumping && attempt < bump.max_attempts;
+        let wait = if can_bump { bump.timeout } else { receipt_timeout };
+        if let Some(receipt) = first_receipt(provider, &hashes, wait).await {
+            return Ok(receipt);
+        }
+        if !can_bump {
+            let newest = hashes.last().copied().unwrap_or(first);
+            return Err(TradeError::ReceiptTimeout {
+                hash: format!("{:?}", newest),
+            });
+        }
+
+        attempt += 1;
+        match resend(attempt).await {
+            Ok(Some(hash)) => {
+                warn!(
+                    "⛽ Buy unconfirmed after {}s, resent with {}% more gas (attempt {}/{}): {:?}",
+                    bump.timeout.as_secs(),
+                    bump.bump_pct,
+                    attempt,
+                    bump.max_attempts,
+                    hash
+                );
+                hashes.push(hash);
+            }
+            Ok(None) => {
+                warn!("⛽ Gas bump ceiling reached, waiting on the {} buy tx(s) already sent", hashes.len());
+                bumping = false;
+            }
+            // Usually "nonce too low": an earlier version was mined in the meantime
+            Err(e) => {
+                warn!("⚠️ Gas bump resend failed ({}), waiting on the {} buy tx(s) already sent", e, hashes.len());
+                bumping = false;
+            }
+        }
+    }
+}
+
+/// The first receipt found for any of `hashes` within `wait`.
+async fn first_receipt<P: Provider + ?Sized>(provider: &P, hashes: &[B256], wait: Duration) -> Option<TransactionReceipt> {
+    let deadline = Instant::now() + wait;
+
+    loop {
+        for hash in hashes {
+            match provider.get_transaction_receipt(*hash).await {
+                Ok(Some(receipt)) => return Some(receipt),
+                Ok(None) => {}
+                Err(e) => debug!("Receipt query failed for {:?}: {}", hash, e),
+            }
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::mock_provider;
+    use alloy::transports::mock::Asserter;
+    use std::cell::RefCell;
+
+    fn bump(max_attempts: u32) -> GasBump {
+        GasBump {
+            timeout: Duration::from_millis(1),
+            bump_pct: 30.0,
+            max_attempts,
+            max_fee_per_gas: 300_000_000_000,
+        }
+    }
+
+    fn push_pending(asserter: &Asserter) {
+        asserter.push_success(&Option::<TransactionReceipt>::None);
+    }
+
+    fn push_mined(asserter: &Asserter, hash: B256) {
+        asserter.push_success(&serde_json::json!({
+            "type": "0x2",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x5208",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "transactionHash": hash,
+            "transactionIndex": "0x0",
+            "blockHash": B256::repeat_byte(0xbb),
+            "blockNumber": "0x1",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "contractAddress": null
+        }));
+    }
+
+    #[test]
+    fn fees_compound_per_resend_up_to_the_ceiling() {
+        let bump = bump(3);
+        assert!(bump.enabled());
+        assert_eq!(bump.scale(100_000_000_000, 1), 130_000_000_000);
+        assert_eq!(bump.scale(100_000_000_000, 2), 169_000_000_000);
+        assert!(bump.allows(300_000_000_000));
+        assert!(!bump.allows(300_000_000_001));
+
+        assert!(!GasBump { timeout: Duration::ZERO, ..bump }.enabled());
+        assert!(!GasBump { max_attempts: 0, ..bump }.enabled());
+        assert!(!GasBump { bump_pct: 0.0, ..bump }.enabled());
+    }
+
+    #[tokio::test]
+    async fn a_resent_buy_is_confirmed_by_its_own_receipt() {
+        let (provider, asserter) = mock_provider();
+        let (first, resent) = (B256::repeat_byte(1), B256::repeat_byte(2));
+        push_pending(&asserter);
+        // Next poll: the original is still pending, the resend was mined
+        push_pending(&asserter);
+        push_mined(&asserter, resent);
+
+        let attempts = RefCell::new(Vec::new());
+        let receipt = confirm_with_bumps(&provider, first, &bump(3), Duration::ZERO, |attempt| {
+            attempts.borrow_mut().push(attempt);
+            async move { Ok(Some(resent)) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(receipt.transaction_hash, resent);
+        assert_eq!(attempts.into_inner(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn out_of_resends_times_out_on_the_newest_version() {
+        let (provider, asserter) = mock_provider();
+        let (first, resent) = (B256::repeat_byte(1), B256::repeat_byte(2));
+        for _ in 0..3 {
+            push_pending(&asserter);
+        }
+
+        let err = confirm_with_bumps(&provider, first, &bump(1), Duration::ZERO, |_| async move { Ok(Some(resent)) })
+            .await
+            .unwrap_err();
+        assert_eq!(err, TradeError::ReceiptTimeout { hash: format!("{:?}", resent) });
+    }
+
+    #[tokio::test]
+    async fn ceiling_or_rejected_resend_stops_bumping() {
+        let (provider, asserter) = mock_provider();
+        let first = B256::repeat_byte(1);
+        push_pending(&asserter);
+        push_pending(&asserter);
+        let resends = RefCell::new(0);
+        let err = confirm_with_bumps(&provider, first, &bump(3), Duration::ZERO, |_| {
+            *resends.borrow_mut() += 1;
+            async move { Ok(None) }
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err, TradeError::ReceiptTimeout { hash: format!("{:?}", first) });
+        assert_eq!(resends.into_inner(), 1);
+
+        // "nonce too low": the first version was mined meanwhile
+        push_pending(&asserter);
+        push_mined(&asserter, first);
+        let receipt = confirm_with_bumps(&provider, first, &bump(3), Duration::ZERO, |_| async move {
+            Err(TradeError::RpcError("nonce too low".to_string()))
+        })
+        .await
+        .unwrap();
+        assert_eq!(receipt.transaction_hash, first);
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Error type shared by the trade executors.
+
+use alloy::hex;
+use alloy::sol;
+use alloy::sol_types::{Panic, Revert, SolError, SolInterface};
+use std::fmt;
+
+sol! {
+    /// Custom errors the nad.fun bonding-curve router, DEX router and curve
+    /// revert with that say why a trade failed.
+    #[derive(Debug)]
+    interface INadFun {
+        error InsufficientAmountOut();
+        error InsufficientAmountInMax();
+        error InsufficientOutput();
+        error InsufficientMon();
+        error DeadlineExpired();
+        error ExpiredDeadLine();
+        error AlreadyGraduated();
+        error BondingCurveLocked();
+    }
+}
+
+/// Why a trade (or one of its RPC steps) failed.
+/// Callers branch on the category; `Display` keeps the original message for logs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TradeError {
+    /// Node/RPC call failed (connection, node error, contract read).
+    RpcError(String),
+    /// The venue could not quote the trade.
+    QuoteFailed(String),
+    /// The transaction was mined but reverted.
+    Reverted { hash: String },
+    /// The fill would miss (or missed) the minimum output.
+    SlippageExceeded(String),
+    /// Nonce too low / already used / gapped.
+    NonceError(String),
+    /// Not enough MON for value + gas, or no tokens to sell.
+    InsufficientFunds(String),
+    /// No pool or route can take the trade (e.g. mid-graduation).
+    NoLiquidity(String),
+    /// The transaction was not confirmed in time.
+    Timeout(String),
+    /// The transaction was sent but no receipt arrived within the receipt timeout.
+    /// It may still be mined; `hash` lets the caller look it up later.
+    ReceiptTimeout { hash: String },
+    /// Bad or missing setting (address, URL, chain id); retrying won't help.
+    Config(String),
+}
+
+impl TradeError {
+    /// Categorize a failed RPC step from its error message.
+    /// `context` says which step failed, e.g. `"Failed to send tx"`.
+    ///
+    /// Revert data in the message (a node's `execution reverted` error) is
+    /// decoded first: a known router/curve custom error, `Error(string)` or
+    /// `Panic(uint256)`. Matching on the message text is the fallback.
+    pub fn classify(context: &str, err: impl fmt::Display) -> Self {
+        let message = format!("{}: {}", context, err);
+        match decode_revert(&message) {
+            Some(RevertReason::Custom(error)) => Self::from_custom_error(error, message),
+            Some(RevertReason::Message(reason)) => Self::classify_text(&reason.to_lowercase(), message),
+            Some(RevertReason::Panic) => Self::RpcError(message),
+            None => Self::classify_text(&message.to_lowercase(), message),
+        }
+    }
+
+    /// Category of a decoded router/curve custom error.
+    fn from_custom_error(error: INadFun::INadFunErrors, message: String) -> Self {
+        use INadFun::INadFunErrors as E;
+        match error {
+            E::InsufficientAmountOut(_) | E::InsufficientAmountInMax(_) | E::InsufficientOutput(_) => {
+                Self::SlippageExceeded(message)
+            }
+            E::InsufficientMon(_) => Self::InsufficientFunds(message),
+            E::DeadlineExpired(_) | E::ExpiredDeadLine(_) => Self::Timeout(message),
+            E::AlreadyGraduated(_) | E::BondingCurveLocked(_) => Self::NoLiquidity(message),
+        }
+    }
+
+    /// Category from the words in `lower` (a lowercased message or revert reason).
+    fn classify_text(lower: &str, message: String) -> Self {
+        let matches = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+
+        if matches(&["insufficient funds", "insufficient balance"]) {
+            Self::InsufficientFunds(message)
+        } else if matches(&["nonce too low", "nonce too high", "invalid nonce", "already known"]) {
+            Self::NonceError(message)
+        } else if matches(&[
+            "amount_out_min",
+            "amountoutmin",
+            "insufficient_output",
+            "insufficient output",
+            "slippage",
+        ]) {
+            Self::SlippageExceeded(message)
+        } else if matches(&[
+            "insufficient_liquidity",
+            "insufficient liquidity",
+            "no liquidity",
+            "no route",
+        ]) {
+            Self::NoLiquidity(message)
+        } else if matches(&["timed out", "timeout"]) {
+            Self::Timeout(message)
+        } else {
+            Self::RpcError(message)
+        }
+    }
+
+    /// Like `classify`, but an otherwise uncategorized failure is a failed quote.
+    pub fn quote(context: &str, err: impl fmt::Display) -> Self {
+        match Self::classify(context, err) {
+            Self::RpcError(message) => Self::QuoteFailed(message),
+            other => other,
+        }
+    }
+
+    /// Whether the trade missed its minimum output. A bare `Reverted` doesn't
+    /// count: a revert is only slippage once its reason says so, which
+    /// `classify` turns into `SlippageExceeded`.
+    pub fn is_slippage(&self) -> bool {
+        matches!(self, Self::SlippageExceeded(_))
+    }
+
+    /// Whether no venue could route the trade (as opposed to slippage).
+    pub fn is_no_liquidity(&self) -> bool {
+        matches!(self, Self::NoLiquidity(_) | Self::QuoteFailed(_))
+    }
+
+    /// Whether the tx was sent but its outcome is unknown, so resending could trade twice.
+    pub fn is_receipt_timeout(&self) -> bool {
+        matches!(self, Self::ReceiptTimeout { .. })
+    }
+
+    /// Whether trying again (re-quoted, with more slippage or a fresh nonce)
+    /// could succeed. Missing funds, bad config and unknown outcomes can't be
+    /// fixed by a retry; no-liquidity has its own delayed retry.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::RpcError(_)
+                | Self::Timeout(_)
+                | Self::NonceError(_)
+                | Self::SlippageExceeded(_)
+                | Self::Reverted { .. }
+        )
+    }
+}
+
+/// What a revert's return data decoded to.
+enum RevertReason {
+    Custom(INadFun::INadFunErrors),
+    /// `Error(string)`, i.e. `require(cond, "reason")`.
+    Message(String),
+    /// `Panic(uint256)`: an assert, overflow or similar.
+    Panic,
+}
+
+/// Decode the first `0x` hex blob in `message` that is revert data we know.
+fn decode_revert(message: &str) -> Option<RevertReason> {
+    message
+        .split("0x")
+        .skip(1)
+        .filter_map(|rest| {
+            let end = rest.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(rest.len());
+            // A selector is 4 bytes; anything shorter can't be revert data
+            (end >= 8 && end % 2 == 0).then(|| hex::decode(&rest[..end]).ok()).flatten()
+        })
+        .find_map(|data| decode_revert_data(&data))
+}
+
+/// Decode raw revert data: a known custom error, `Error(string)` or `Panic(uint256)`.
+fn decode_revert_data(data: &[u8]) -> Option<RevertReason> {
+    if let Ok(error) = INadFun::INadFunErrors::abi_decode(data) {
+        return Some(RevertReason::Custom(error));
+    }
+    if let Ok(revert) = Revert::abi_decode(data) {
+        return Some(RevertReason::Message(revert.reason));
+    }
+    Panic::abi_decode(data).ok().map(|_| RevertReason::Panic)
+}
+
+impl fmt::Display for TradeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reverted { hash } => write!(f, "Transaction reverted: {}", hash),
+            Self::ReceiptTimeout { hash } => write!(f, "No receipt in time for {} (may still land)", hash),
+            Self::RpcError(message)
+            | Self::QuoteFailed(message)
+            | Self::SlippageExceeded(message)
+            | Self::NonceError(message)
+            | Self::InsufficientFunds(message)
+            | Self::NoLiquidity(message)
+            | Self::Timeout(message)
+            | Self::Config(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for TradeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reverted_with(data: Vec<u8>) -> String {
+        format!("server returned an error response: execution reverted, data: \"0x{}\"", hex::encode(data))
+    }
+
+    #[test]
+    fn custom_router_errors_are_classified_by_selector() {
+        let data = INadFun::InsufficientAmountOut {}.abi_encode();
+        assert!(TradeError::classify("Failed to send tx", reverted_with(data)).is_slippage());
+
+        let data = INadFun::InsufficientMon {}.abi_encode();
+        let err = TradeError::classify("Failed to send tx", reverted_with(data));
+        assert!(matches!(err, TradeError::InsufficientFunds(_)));
+
+        let data = INadFun::AlreadyGraduated {}.abi_encode();
+        assert!(TradeError::classify("Failed to send tx", reverted_with(data)).is_no_liquidity());
+    }
+
+    #[test]
+    fn revert_reason_string_is_matched_on_the_decoded_text() {
+        let data = Revert::from("UniswapV2Router: INSUFFICIENT_OUTPUT_AMOUNT").abi_encode();
+        assert!(TradeError::classify("Failed to send tx", reverted_with(data)).is_slippage());
+    }
+
+    #[test]
+    fn panic_is_not_transient_slippage() {
+        let data = Panic::from(0x11).abi_encode();
+        let err = TradeError::classify("Failed to send tx", reverted_with(data));
+        assert!(matches!(err, TradeError::RpcError(_)));
+    }
+
+    #[test]
+    fn addresses_and_hashes_are_not_mistaken_for_revert_data() {
+        let err = TradeError::classify(
+            "Failed to send tx",
+            "nonce too low for 0x1111111111111111111111111111111111111111",
+        );
+        assert!(matches!(err, TradeError::NonceError(_)));
+    }
+
+    #[test]
+    fn plain_messages_fall_back_to_string_matching() {
+        assert!(TradeError::classify("Swap failed", "amountOutMin not met").is_slippage());
+        assert!(matches!(TradeError::classify("Send", "request timed out"), TradeError::Timeout(_)));
+        assert!(matches!(TradeError::quote("Quote", "boom"), TradeError::QuoteFailed(_)));
+    }
+
+    #[test]
+    fn only_failures_a_retry_could_fix_are_transient() {
+        let transient = [
+            TradeError::RpcError("connection reset".to_string()),
+            TradeError::Timeout("timed out".to_string()),
+            TradeError::NonceError("nonce too low".to_string()),
+            TradeError::SlippageExceeded("amountOutMin".to_string()),
+            TradeError::Reverted { hash: "0xa".to_string() },
+        ];
+        for err in &transient {
+            assert!(err.is_transient(), "{:?}", err);
+        }
+
+        let permanent = [
+            TradeError::InsufficientFunds("No tokens to sell".to_string()),
+            TradeError::Config("Invalid router address".to_string()),
+            TradeError::ReceiptTimeout { hash: "0xa".to_string() },
+            TradeError::NoLiquidity("no route".to_string()),
+            TradeError::QuoteFailed("no quote".to_string()),
+        ];
+        for err in &permanent {
+            assert!(!err.is_transient(), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn config_errors_display_their_message() {
+        let err = TradeError::Config("Private relay URL is not https".to_string());
+        assert_eq!(err.to_string(), "Private relay URL is not https");
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Pre-flight balance check for buys.
+//!
+//! A buy the wallet can't pay for still burns a nonce and comes back as an
+//! opaque RPC or revert error; checking first gives a clear shortfall instead.
+
+use crate::executor::TradeError;
+use crate::units::wei_to_mon;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+
+/// Fail with `InsufficientFunds` unless `wallet` holds `amount_in` plus `gas_cost` in native MON.
+pub async fn check_affordable<P: Provider + ?Sized>(
+    provider: &P,
+    wallet: Address,
+    amount_in: U256,
+    gas_cost: U256,
+) -> Result<(), TradeError> {
+    let balance = provider
+        .get_balance(wallet)
+        .await
+        .map_err(|e| TradeError::classify("Failed to get MON balance", e))?;
+
+    affordable(balance, amount_in, gas_cost)
+}
+
+/// `check_affordable` against a known balance.
+pub fn affordable(balance: U256, amount_in: U256, gas_cost: U256) -> Result<(), TradeError> {
+    let needed = amount_in.saturating_add(gas_cost);
+    if balance >= needed {
+        return Ok(());
+    }
+
+    Err(TradeError::InsufficientFunds(format!(
+        "Need {:.4} MON ({:.4} + {:.4} gas) but wallet holds {:.4} MON (short {:.4})",
+        wei_to_mon(needed),
+        wei_to_mon(amount_in),
+        wei_to_mon(gas_cost),
+        wei_to_mon(balance),
+        wei_to_mon(needed - balance)
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_quantity};
+    use crate::units::mon_to_wei;
+
+    #[test]
+    fn balance_must_cover_amount_and_gas() {
+        let amount = mon_to_wei(1.0);
+        let gas = mon_to_wei(0.1);
+
+        assert!(affordable(mon_to_wei(1.1), amount, gas).is_ok());
+        assert!(affordable(mon_to_wei(2.0), amount, gas).is_ok());
+
+        let err = affordable(mon_to_wei(1.05), amount, gas).unwrap_err();
+        assert!(matches!(err, TradeError::InsufficientFunds(_)));
+        assert!(err.to_string().contains("short 0.0500"), "{}", err);
+    }
+
+    #[test]
+    fn huge_amounts_do_not_overflow() {
+        assert!(affordable(U256::MAX, U256::MAX, U256::from(1)).is_ok());
+        assert!(affordable(U256::ZERO, U256::MAX, U256::MAX).is_err());
+    }
+
+    #[tokio::test]
+    async fn check_reads_the_wallet_balance() {
+        let (provider, asserter) = mock_provider();
+        push_quantity(&asserter, 500);
+        assert!(check_affordable(&provider, Address::ZERO, U256::from(400), U256::from(100)).await.is_ok());
+
+        push_quantity(&asserter, 499);
+        let err = check_affordable(&provider, Address::ZERO, U256::from(400), U256::from(100)).await;
+        assert!(matches!(err, Err(TradeError::InsufficientFunds(_))));
+    }
+
+    #[tokio::test]
+    async fn balance_rpc_failure_is_an_rpc_error() {
+        let (provider, asserter) = mock_provider();
+        asserter.push_failure_msg("connection reset");
+        let err = check_affordable(&provider, Address::ZERO, U256::ZERO, U256::ZERO).await;
+        assert!(matches!(err, Err(TradeError::RpcError(_))));
+    }
+}
@@ -3,6 +3,8 @@
 
 //! Gas strategy for transaction priority.
 
+use crate::jitter::Jitter;
+
 /// Gas strategy determines how aggressively we bid for transaction inclusion.
 #[derive(Debug, Clone, Copy)]
 pub enum GasStrategy {
@@ -43,6 +45,15 @@ impl GasStrategy {
         }
     }
 
+    /// Legacy gas price for SDK trades, scaled from the network gas price.
+    pub fn legacy_gas_price(&self, network_gas_price: u128) -> u128 {
+        match self {
+            Self::Normal => network_gas_price * 110 / 100,    // 1.1x
+            Self::Aggressive => network_gas_price * 300 / 100, // 3x
+            Self::Frontrun => network_gas_price * 500 / 100,   // 5x
+        }
+    }
+
     /// Get strategy from config multiplier.
     pub fn from_multiplier(multiplier: f64) -> Self {
         if multiplier >= 2.0 {
@@ -60,3 +71,90 @@ impl Default for GasStrategy {
         Self::Aggressive
     }
 }
+
+/// `(max_fee, priority_fee)` from `GasStrategy::calculate` with the priority
+/// fee jittered; the max fee moves by the same amount so the base-fee headroom is kept.
+pub fn jitter_priority(jitter: &Jitter, (max_fee, priority_fee): (u128, u128)) -> (u128, u128) {
+    let jittered = jitter.apply_fee(priority_fee);
+    (max_fee - priority_fee + jittered, jittered)
+}
+
+/// Fee model for SDK (bonding curve) buys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GasMode {
+    /// Single gas price scaled from `eth_gasPrice` (`GasStrategy::legacy_gas_price`).
+    #[default]
+    Legacy,
+    /// Max fee and priority fee from the latest base fee (`GasStrategy::calculate`),
+    /// the same policy as DEX swaps.
+    Eip1559,
+}
+
+impl GasMode {
+    /// Parse `legacy` / `eip1559` (case-insensitive). Anything else falls back to `Legacy`.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "eip1559" | "1559" => Self::Eip1559,
+            _ => Self::Legacy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    #[test]
+    fn max_fee_covers_the_scaled_base_fee_plus_the_tip() {
+        assert_eq!(GasStrategy::Normal.calculate(100 * GWEI), (111 * GWEI, GWEI));
+        assert_eq!(GasStrategy::Aggressive.calculate(100 * GWEI), (160 * GWEI, 10 * GWEI));
+        assert_eq!(GasStrategy::Frontrun.calculate(100 * GWEI), (700 * GWEI, 500 * GWEI));
+    }
+
+    #[test]
+    fn legacy_price_scales_the_network_price() {
+        assert_eq!(GasStrategy::Normal.legacy_gas_price(100 * GWEI), 110 * GWEI);
+        assert_eq!(GasStrategy::Aggressive.legacy_gas_price(100 * GWEI), 300 * GWEI);
+        assert_eq!(GasStrategy::Frontrun.legacy_gas_price(100 * GWEI), 500 * GWEI);
+    }
+
+    #[test]
+    fn strategy_from_multiplier_thresholds() {
+        assert!(matches!(GasStrategy::from_multiplier(1.2), GasStrategy::Normal));
+        assert!(matches!(GasStrategy::from_multiplier(1.5), GasStrategy::Aggressive));
+        assert!(matches!(GasStrategy::from_multiplier(2.0), GasStrategy::Frontrun));
+    }
+
+    #[test]
+    fn jittered_tip_keeps_the_base_fee_headroom() {
+        let (max_fee, priority) = GasStrategy::Aggressive.calculate(100 * GWEI);
+        assert_eq!(jitter_priority(&Jitter::new(0.0, None), (max_fee, priority)), (max_fee, priority));
+
+        let (jittered_max, jittered_tip) = jitter_priority(&Jitter::new(20.0, Some(7)), (max_fee, priority));
+        assert_eq!(jittered_max - jittered_tip, max_fee - priority);
+    }
+
+    #[test]
+    fn gas_mode_parse_defaults_to_legacy() {
+        assert_eq!(GasMode::parse(" EIP1559 "), GasMode::Eip1559);
+        assert_eq!(GasMode::parse("1559"), GasMode::Eip1559);
+        assert_eq!(GasMode::parse("legacy"), GasMode::Legacy);
+        assert_eq!(GasMode::parse("fast"), GasMode::Legacy);
+    }
+
+    #[test]
+    fn jittered_priority_keeps_the_base_fee_headroom() {
+        let strategy = GasStrategy::Aggressive;
+        let (max_fee, priority_fee) = strategy.calculate(100 * GWEI);
+        assert_eq!(jitter_priority(&Jitter::new(0.0, None), (max_fee, priority_fee)), (max_fee, priority_fee));
+
+        let jitter = Jitter::new(20.0, Some(5));
+        for _ in 0..20 {
+            let (jittered_max, jittered_priority) = jitter_priority(&jitter, (max_fee, priority_fee));
+            assert_eq!(jittered_max - jittered_priority, max_fee - priority_fee);
+            assert!((8 * GWEI..=12 * GWEI).contains(&jittered_priority));
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Floor on `amount_out_min` for trades that would otherwise accept any fill.
+//!
+//! Front-run buys used to send `amount_out_min = 0` so they could never miss
+//! on slippage, which lets a sandwich bot take the whole trade. They now get
+//! a floor of the quote minus `MAX_ACCEPTABLE_SLIPPAGE_PCT`; a zero minimum
+//! needs an explicit `ALLOW_ZERO_MIN=true`.
+
+use crate::config::Config;
+use crate::executor::swap::min_amount_out;
+use alloy::primitives::{Address, U256};
+use tracing::{info, warn};
+
+/// How far below the quote an aggressive trade may fill.
+#[derive(Debug, Clone, Copy)]
+pub struct MinOutFloor {
+    /// Largest accepted shortfall from the quote, in percent.
+    pub max_slippage_pct: f64,
+    /// Send `amount_out_min = 0` instead of the floor.
+    pub allow_zero_min: bool,
+}
+
+impl MinOutFloor {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_slippage_pct: config.max_acceptable_slippage_pct,
+            allow_zero_min: config.allow_zero_min,
+        }
+    }
+
+    /// `amount_out_min` for a trade of `token` quoted at `expected`.
+    pub fn amount_out_min(&self, token: Address, expected: U256, kind: &str) -> U256 {
+        if self.allow_zero_min {
+            warn!(
+                "☠️ {} of {:?} sent with ZERO amount_out_min (ALLOW_ZERO_MIN) - any sandwich can take the whole trade",
+                kind, token
+            );
+            return U256::ZERO;
+        }
+
+        let floor = min_amount_out(expected, self.max_slippage_pct);
+        if floor.is_zero() {
+            warn!(
+                "☠️ {} of {:?} has a zero amount_out_min (quote {}, max slippage {}%)",
+                kind, token, expected, self.max_slippage_pct
+            );
+        } else {
+            info!(
+                "🛡️ {} min out with {}% max slippage: {}",
+                kind, self.max_slippage_pct, floor
+            );
+        }
+        floor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: Address = Address::new([0x11; 20]);
+
+    #[test]
+    fn floor_is_the_quote_minus_the_max_slippage() {
+        let floor = MinOutFloor { max_slippage_pct: 50.0, allow_zero_min: false };
+        assert_eq!(floor.amount_out_min(TOKEN, U256::from(1_000u64), "Front-run"), U256::from(500u64));
+
+        let tight = MinOutFloor { max_slippage_pct: 5.0, allow_zero_min: false };
+        assert_eq!(tight.amount_out_min(TOKEN, U256::from(1_000u64), "Front-run"), U256::from(950u64));
+    }
+
+    #[test]
+    fn zero_min_only_when_allowed() {
+        let open = MinOutFloor { max_slippage_pct: 5.0, allow_zero_min: true };
+        assert_eq!(open.amount_out_min(TOKEN, U256::from(1_000u64), "Front-run"), U256::ZERO);
+        // No quote, no floor
+        let floor = MinOutFloor { max_slippage_pct: 5.0, allow_zero_min: false };
+        assert_eq!(floor.amount_out_min(TOKEN, U256::ZERO, "Front-run"), U256::ZERO);
+    }
+
+    #[test]
+    fn reads_its_settings_from_config() {
+        let defaults = MinOutFloor::from_config(&crate::config::tests::config_with(&[]).unwrap());
+        assert_eq!((defaults.max_slippage_pct, defaults.allow_zero_min), (50.0, false));
+
+        let config = crate::config::tests::config_with(&[
+            ("MAX_ACCEPTABLE_SLIPPAGE_PCT", "10"),
+            ("ALLOW_ZERO_MIN", "true"),
+        ])
+        .unwrap();
+        let floor = MinOutFloor::from_config(&config);
+        assert_eq!((floor.max_slippage_pct, floor.allow_zero_min), (10.0, true));
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Scripted provider for executor tests.
+//!
+//! Each RPC call takes the next response pushed onto the `Asserter`, in
+//! order, whatever the method. Fillers are off so a test scripts exactly the
+//! calls the executor makes.
+
+use crate::config::tests::config_with;
+use crate::executor::{GasStrategy, SdkExecutor};
+use alloy::primitives::{Bytes, U256};
+use alloy::providers::{ProviderBuilder, RootProvider};
+use alloy::rpc::types::{Block, Header};
+use alloy::sol_types::SolCall;
+use alloy::transports::mock::Asserter;
+
+pub type MockProvider = RootProvider;
+
+/// A provider answering from the returned `Asserter`.
+pub fn mock_provider() -> (MockProvider, Asserter) {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .disable_recommended_fillers()
+        .connect_mocked_client(asserter.clone());
+    (provider, asserter)
+}
+
+/// Queue a latest block with `base_fee` wei per gas.
+pub fn push_block(asserter: &Asserter, base_fee: u64) {
+    let mut header: Header = Header::default();
+    header.inner.base_fee_per_gas = Some(base_fee);
+    asserter.push_success(&Block::<()>::empty(header));
+}
+
+/// Queue a balance, nonce or other quantity result.
+pub fn push_quantity(asserter: &Asserter, value: u64) {
+    asserter.push_success(&U256::from(value));
+}
+
+/// Queue the return value of a contract call `C`.
+pub fn push_call<C: SolCall>(asserter: &Asserter, ret: &C::Return) {
+    asserter.push_success(&Bytes::from(C::abi_encode_returns(ret)));
+}
+
+/// An SDK executor whose node refuses every connection: for code that needs
+/// one to exist but should see each of its RPC calls fail.
+pub async fn offline_sdk_executor() -> SdkExecutor {
+    let config = config_with(&[
+        ("MONAD_RPC_URL", "http://127.0.0.1:1"),
+        ("SELL_SLIPPAGE_PCT", "5"),
+        ("PAPER_TRADING", "true"),
+        ("TX_RECEIPT_TIMEOUT_SEC", "1"),
+        ("BUY_GAS_BUMP_TIMEOUT_SEC", "0"),
+        ("BUY_GAS_BUMP_MAX_ATTEMPTS", "0"),
+        ("MAX_ACCEPTABLE_SLIPPAGE_PCT", "10"),
+    ])
+    .expect("test config");
+    SdkExecutor::new(&config, GasStrategy::Normal)
+    .await
+    .expect("SDK executor without a node")
+}
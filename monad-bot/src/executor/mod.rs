@@ -3,12 +3,33 @@
 
 //! Transaction execution module.
 
+pub mod approval;
+pub mod bump;
+pub mod error;
+pub mod funds;
 pub mod gas;
+pub mod min_out;
+#[cfg(test)]
+pub mod mock;
+pub mod nonce;
+pub mod paper;
+pub mod receipt;
+pub mod routes;
 pub mod sdk_executor;
 pub mod sell;
+pub mod submit;
 pub mod swap;
+pub mod wmon;
 
-pub use gas::GasStrategy;
+pub use approval::ApprovalMode;
+pub use bump::GasBump;
+pub use error::TradeError;
+pub use gas::{GasMode, GasStrategy};
+pub use min_out::MinOutFloor;
+pub use paper::is_paper_tx;
+pub use receipt::MonReceived;
+pub use routes::DexRoute;
 pub use sdk_executor::SdkExecutor;
 pub use sell::SellExecutor;
+pub use submit::{build_submitter, TxSubmitter};
 pub use swap::SwapExecutor;
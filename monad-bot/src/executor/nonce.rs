@@ -0,0 +1,145 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Nonce recovery for the DEX executors.
+//!
+//! `SwapExecutor` and `SellExecutor` hand out nonces from a local counter. A
+//! send that fails after reaching the node, or a tx stuck on low gas, leaves
+//! the counter out of step with the chain and every later tx fails. These
+//! helpers re-read the chain and clear the stuck tx.
+
+use crate::executor::receipt::wait_receipt;
+use crate::executor::{GasStrategy, TradeError};
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Gas for a plain MON transfer.
+const TRANSFER_GAS: u64 = 21_000;
+
+/// Next nonce for `wallet`, counting txs still in the mempool.
+pub async fn pending_nonce<P: Provider>(provider: &P, wallet: Address) -> Result<u64, TradeError> {
+    provider
+        .get_transaction_count(wallet)
+        .pending()
+        .await
+        .map_err(|e| TradeError::classify("Failed to get nonce", e))
+}
+
+/// Reset `nonce` to the chain's pending nonce. Returns the new value.
+pub async fn resync_nonce<P: Provider>(
+    provider: &P,
+    wallet: Address,
+    nonce: &AtomicU64,
+) -> Result<u64, TradeError> {
+    match pending_nonce(provider, wallet).await {
+        Ok(next) => {
+            let previous = nonce.swap(next, Ordering::SeqCst);
+            if previous != next {
+                warn!("🔢 Nonce resynced: {} -> {}", previous, next);
+            }
+            Ok(next)
+        }
+        Err(e) => {
+            warn!("⚠️ Nonce resync failed: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Replace the oldest pending tx of `wallet` with a zero-value self-transfer
+/// at the same nonce, priced to outbid it. Later txs queued behind it can then
+/// be mined. Returns `None` when nothing is pending.
+pub async fn cancel_pending_tx<P: Provider>(
+    provider: &P,
+    wallet: Address,
+    receipt_timeout: Duration,
+) -> Result<Option<B256>, TradeError> {
+    let mined = provider
+        .get_transaction_count(wallet)
+        .latest()
+        .await
+        .map_err(|e| TradeError::classify("Failed to get nonce", e))?;
+    let pending = pending_nonce(provider, wallet).await?;
+    if pending <= mined {
+        info!("✅ No pending transactions for {:?}", wallet);
+        return Ok(None);
+    }
+
+    let base_fee = provider
+        .get_block_by_number(BlockNumberOrTag::Latest)
+        .await
+        .map_err(|e| TradeError::classify("Failed to get block", e))?
+        .and_then(|block| block.header.base_fee_per_gas)
+        .ok_or_else(|| TradeError::RpcError("No base fee".to_string()))?;
+    // Highest tier so the replacement outbids whatever the stuck tx paid
+    let (max_fee, priority_fee) = GasStrategy::Frontrun.calculate(base_fee as u128);
+
+    warn!(
+        "🧹 Cancelling stuck nonce {} ({} pending) with a self-transfer",
+        mined,
+        pending - mined
+    );
+    let tx = TransactionRequest::default()
+        .from(wallet)
+        .to(wallet)
+        .value(U256::ZERO)
+        .nonce(mined)
+        .gas_limit(TRANSFER_GAS)
+        .max_fee_per_gas(max_fee)
+        .max_priority_fee_per_gas(priority_fee);
+    let pending_tx = provider
+        .send_transaction(tx)
+        .await
+        .map_err(|e| TradeError::classify("Failed to send cancel tx", e))?;
+
+    let tx_hash = *pending_tx.tx_hash();
+    wait_receipt(tx_hash, receipt_timeout, "Cancel receipt failed", pending_tx.get_receipt()).await?;
+    info!("✅ Nonce {} cleared: {:?}", mined, tx_hash);
+    Ok(Some(tx_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_block, push_quantity};
+
+    const WALLET: Address = Address::new([0x11; 20]);
+
+    #[tokio::test]
+    async fn resync_takes_the_pending_nonce() {
+        let (provider, asserter) = mock_provider();
+        let nonce = AtomicU64::new(9);
+        push_quantity(&asserter, 4);
+        assert_eq!(resync_nonce(&provider, WALLET, &nonce).await, Ok(4));
+        assert_eq!(nonce.load(Ordering::SeqCst), 4);
+
+        asserter.push_failure_msg("connection reset");
+        assert!(resync_nonce(&provider, WALLET, &nonce).await.is_err());
+        assert_eq!(nonce.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn nothing_to_cancel_without_pending_txs() {
+        let (provider, asserter) = mock_provider();
+        push_quantity(&asserter, 7); // mined
+        push_quantity(&asserter, 7); // pending
+        assert_eq!(cancel_pending_tx(&provider, WALLET, Duration::from_secs(1)).await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn failed_replacement_is_reported() {
+        let (provider, asserter) = mock_provider();
+        push_quantity(&asserter, 7);
+        push_quantity(&asserter, 9);
+        push_block(&asserter, 50_000_000_000);
+        asserter.push_failure_msg("replacement transaction underpriced");
+
+        let err = cancel_pending_tx(&provider, WALLET, Duration::from_secs(1)).await.unwrap_err();
+        assert!(err.to_string().starts_with("Failed to send cancel tx"), "{}", err);
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Paper trading: quote trades against live state but never send them.
+
+use alloy::primitives::{keccak256, Address, U256};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::info;
+
+/// Prefix marking a synthetic tx hash from a simulated fill.
+pub const PAPER_TX_PREFIX: &str = "PAPER-";
+
+static PAPER_TX_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Unique synthetic tx hash, e.g. `PAPER-0x1f3a...`.
+pub fn paper_tx_hash() -> String {
+    let seq = PAPER_TX_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    let hash = keccak256(format!("{}:{}", nanos, seq));
+    format!("{}{:?}", PAPER_TX_PREFIX, hash)
+}
+
+/// Whether `tx_hash` came from a simulated fill.
+pub fn is_paper_tx(tx_hash: &str) -> bool {
+    tx_hash.starts_with(PAPER_TX_PREFIX)
+}
+
+/// Log a simulated fill at the quoted output and return its synthetic hash.
+pub fn paper_fill(side: &str, token: Address, amount_in: U256, quoted_out: U256) -> String {
+    let tx_hash = paper_tx_hash();
+    info!(
+        "📝 PAPER {}: {:?} - {} in, {} out (quoted) - {}",
+        side, token, amount_in, quoted_out, tx_hash
+    );
+    tx_hash
+}
@@ -0,0 +1,163 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Receipt parsing helpers for executed swaps.
+
+use crate::executor::TradeError;
+use alloy::eips::BlockId;
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::Log;
+use alloy::sol;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+}
+
+/// Tokens received by a confirmed buy.
+#[derive(Debug, Clone)]
+pub struct TokenReceived {
+    pub tx_hash: String,
+    pub amount: U256,
+}
+
+/// MON received by a confirmed sell.
+#[derive(Debug, Clone)]
+pub struct MonReceived {
+    pub tx_hash: String,
+    pub amount: U256,
+    /// Tokens actually sold (may be less than requested if the balance was short).
+    pub sold: U256,
+}
+
+/// Wait at most `timeout` for a receipt future. On expiry fail with
+/// `ReceiptTimeout` for `tx_hash` instead of blocking forever; other
+/// failures are classified under `context`.
+pub async fn wait_receipt<T, E: fmt::Display>(
+    tx_hash: B256,
+    timeout: Duration,
+    context: &str,
+    receipt: impl Future<Output = Result<T, E>>,
+) -> Result<T, TradeError> {
+    match tokio::time::timeout(timeout, receipt).await {
+        Ok(result) => result.map_err(|e| TradeError::classify(context, e)),
+        Err(_) => Err(TradeError::ReceiptTimeout {
+            hash: format!("{:?}", tx_hash),
+        }),
+    }
+}
+
+/// Sum the ERC20 `Transfer`s of `token` into `wallet` found in a receipt's logs.
+///
+/// Returns `None` if the receipt holds no such transfer.
+pub fn tokens_received(logs: &[Log], token: Address, wallet: Address) -> Option<U256> {
+    let mut total: Option<U256> = None;
+
+    for log in logs.iter().filter(|log| log.address() == token) {
+        if let Ok(transfer) = log.log_decode::<Transfer>() {
+            let transfer = transfer.inner.data;
+            if transfer.to == wallet {
+                total = Some(total.unwrap_or(U256::ZERO) + transfer.value);
+            }
+        }
+    }
+
+    total
+}
+
+/// Native MON paid to `wallet` by a mined tx: the balance change across its block
+/// plus the gas it cost. Assumes no other tx of ours landed in the same block.
+pub async fn mon_received<P: Provider + ?Sized>(
+    provider: &P,
+    wallet: Address,
+    tx_hash: B256,
+) -> Result<U256, TradeError> {
+    let receipt = provider
+        .get_transaction_receipt(tx_hash)
+        .await
+        .map_err(|e| TradeError::classify("Failed to get receipt", e))?
+        .ok_or_else(|| TradeError::RpcError(format!("No receipt for {:?}", tx_hash)))?;
+    let block = receipt
+        .block_number
+        .ok_or_else(|| TradeError::RpcError(format!("Receipt for {:?} has no block", tx_hash)))?;
+
+    let balance_at = |number: u64| async move {
+        provider
+            .get_balance(wallet)
+            .block_id(BlockId::number(number))
+            .await
+            .map_err(|e| TradeError::classify("Failed to get historical balance", e))
+    };
+    let after = balance_at(block).await?;
+    let before = balance_at(block.saturating_sub(1)).await?;
+    let gas_paid = U256::from(receipt.gas_used as u128 * receipt.effective_gas_price);
+
+    Ok((after + gas_paid).saturating_sub(before))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::sol_types::SolEvent;
+
+    fn transfer(token: Address, from: Address, to: Address, value: u64) -> Log {
+        let data = Transfer { from, to, value: U256::from(value) }.encode_log_data();
+        Log {
+            inner: alloy::primitives::Log { address: token, data },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sums_transfers_of_the_token_into_the_wallet() {
+        let token = Address::repeat_byte(1);
+        let wallet = Address::repeat_byte(2);
+        let pool = Address::repeat_byte(3);
+        let logs = [
+            transfer(token, pool, wallet, 70),
+            transfer(token, pool, wallet, 30),
+            // Another recipient, and another token into the wallet
+            transfer(token, pool, Address::repeat_byte(4), 500),
+            transfer(Address::repeat_byte(5), pool, wallet, 900),
+        ];
+
+        assert_eq!(tokens_received(&logs, token, wallet), Some(U256::from(100)));
+    }
+
+    #[test]
+    fn none_without_a_matching_transfer() {
+        let token = Address::repeat_byte(1);
+        let wallet = Address::repeat_byte(2);
+        let logs = [transfer(token, wallet, Address::repeat_byte(3), 10)];
+
+        assert_eq!(tokens_received(&logs, token, wallet), None);
+        assert_eq!(tokens_received(&[], token, wallet), None);
+    }
+
+    #[tokio::test]
+    async fn receipt_wait_gives_up_with_the_tx_hash() {
+        let hash = B256::repeat_byte(7);
+        let pending = std::future::pending::<Result<(), String>>();
+        let err = wait_receipt(hash, Duration::from_millis(10), "Failed to get receipt", pending)
+            .await
+            .unwrap_err();
+
+        assert!(err.is_receipt_timeout());
+        assert!(!err.is_transient());
+        assert_eq!(err, TradeError::ReceiptTimeout { hash: format!("{:?}", hash) });
+    }
+
+    #[tokio::test]
+    async fn receipt_in_time_or_failed_is_passed_through() {
+        let hash = B256::ZERO;
+        let ready = async { Ok::<u32, String>(1) };
+        assert_eq!(wait_receipt(hash, Duration::from_secs(1), "receipt", ready).await, Ok(1));
+
+        let failed = async { Err::<u32, String>("request timed out".to_string()) };
+        let err = wait_receipt(hash, Duration::from_secs(1), "receipt", failed).await.unwrap_err();
+        assert!(matches!(err, TradeError::Timeout(_)));
+    }
+}
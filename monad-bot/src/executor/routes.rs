@@ -0,0 +1,261 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! DEX routes for graduated tokens.
+//!
+//! A graduated token trades on whichever DEX it migrated to, so the sell and
+//! pricing fallbacks pick a route per token: the one the SDK reports, else the
+//! configured route quoting the most MON.
+
+use crate::executor::swap::final_amount_out;
+use crate::executor::TradeError;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::sol;
+use std::str::FromStr;
+use tracing::debug;
+
+sol! {
+    #[sol(rpc)]
+    interface IQuoteRouter {
+        function getAmountsOut(uint256 amountIn, address[] calldata path)
+            external view returns (uint256[] memory amounts);
+
+        function factory() external view returns (address);
+    }
+
+    #[sol(rpc)]
+    interface IPairFactory {
+        function getPair(address tokenA, address tokenB) external view returns (address);
+    }
+
+    #[sol(rpc)]
+    interface IWmonBalance {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+/// A UniswapV2-style router and the wrapped MON it pairs against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DexRoute {
+    pub name: String,
+    pub router: Address,
+    pub wmon: Address,
+}
+
+/// Parse comma-separated `name:router:wmon` entries. Invalid entries are skipped.
+pub fn parse_dex_routes(s: &str) -> Vec<DexRoute> {
+    s.split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.trim().splitn(3, ':');
+            let name = parts.next()?.trim();
+            let router = Address::from_str(parts.next()?.trim()).ok()?;
+            let wmon = Address::from_str(parts.next()?.trim()).ok()?;
+            (!name.is_empty()).then(|| DexRoute { name: name.to_string(), router, wmon })
+        })
+        .collect()
+}
+
+/// Route to use for `token`: the configured route for `preferred` (the SDK's
+/// router for the token) if there is one, else the route quoting the most MON
+/// for `amount`. Returns the route and its quote.
+pub async fn select_route<'a, P: Provider>(
+    provider: &P,
+    routes: &'a [DexRoute],
+    token: Address,
+    amount: U256,
+    preferred: Option<Address>,
+) -> Result<(&'a DexRoute, U256), TradeError> {
+    if let Some(route) = preferred.and_then(|router| routes.iter().find(|r| r.router == router)) {
+        let quote = quote(provider, route, token, amount).await?;
+        return Ok((route, quote));
+    }
+
+    let mut best: Option<(&DexRoute, U256)> = None;
+    let mut last_error = None;
+    for route in routes {
+        match quote(provider, route, token, amount).await {
+            Ok(out) if best.is_none_or(|(_, best_out)| out > best_out) => best = Some((route, out)),
+            Ok(_) => {}
+            Err(e) => {
+                debug!("No {} quote for {:?}: {}", route.name, token, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    best.ok_or_else(|| {
+        last_error.unwrap_or_else(|| TradeError::QuoteFailed("No DEX routes configured".to_string()))
+    })
+}
+
+/// WMON held by `token`'s deepest pair across `routes` (its DEX liquidity).
+pub async fn pool_liquidity<P: Provider>(
+    provider: &P,
+    routes: &[DexRoute],
+    token: Address,
+) -> Result<U256, TradeError> {
+    let mut deepest: Option<U256> = None;
+    let mut last_error = None;
+    for route in routes {
+        match pair_wmon(provider, route, token).await {
+            Ok(Some(wmon)) => deepest = Some(deepest.map_or(wmon, |d| d.max(wmon))),
+            Ok(None) => {}
+            Err(e) => {
+                debug!("No {} pair reserve for {:?}: {}", route.name, token, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    deepest.ok_or_else(|| {
+        last_error.unwrap_or_else(|| TradeError::NoLiquidity(format!("No DEX pair for {:?}", token)))
+    })
+}
+
+/// WMON balance of the `token`/WMON pair on `route`; `None` if there is no pair.
+async fn pair_wmon<P: Provider>(
+    provider: &P,
+    route: &DexRoute,
+    token: Address,
+) -> Result<Option<U256>, TradeError> {
+    let factory = IQuoteRouter::new(route.router, provider)
+        .factory()
+        .call()
+        .await
+        .map_err(|e| TradeError::quote(&format!("{} factory() failed", route.name), e))?;
+    let pair = IPairFactory::new(factory, provider)
+        .getPair(token, route.wmon)
+        .call()
+        .await
+        .map_err(|e| TradeError::quote(&format!("{} getPair failed", route.name), e))?;
+    if pair.is_zero() {
+        return Ok(None);
+    }
+
+    IWmonBalance::new(route.wmon, provider)
+        .balanceOf(pair)
+        .call()
+        .await
+        .map(Some)
+        .map_err(|e| TradeError::quote(&format!("{} pair balance failed", route.name), e))
+}
+
+/// MON out for selling `amount` of `token` on `route`.
+async fn quote<P: Provider>(
+    provider: &P,
+    route: &DexRoute,
+    token: Address,
+    amount: U256,
+) -> Result<U256, TradeError> {
+    let path = vec![token, route.wmon];
+    let amounts = IQuoteRouter::new(route.router, provider)
+        .getAmountsOut(amount, path.clone())
+        .call()
+        .await
+        .map_err(|e| TradeError::quote(&format!("{} getAmountsOut failed", route.name), e))?;
+    final_amount_out(&amounts, path.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_call};
+
+    const TOKEN: Address = Address::new([0x11; 20]);
+
+    fn route(name: &str, byte: u8) -> DexRoute {
+        DexRoute {
+            name: name.to_string(),
+            router: Address::repeat_byte(byte),
+            wmon: Address::repeat_byte(0xee),
+        }
+    }
+
+    fn push_quote(asserter: &alloy::providers::mock::Asserter, out: u64) {
+        push_call::<IQuoteRouter::getAmountsOutCall>(asserter, &vec![U256::from(1u64), U256::from(out)]);
+    }
+
+    #[test]
+    fn parses_name_router_wmon_entries() {
+        let router = "0x2222222222222222222222222222222222222222";
+        let wmon = "0x3333333333333333333333333333333333333333";
+        let routes = parse_dex_routes(&format!("kuru:{router}:{wmon}, bad:0x12:{wmon},:{router}:{wmon},octo:{router}"));
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].name, "kuru");
+        assert_eq!(routes[0].router, Address::from_str(router).unwrap());
+        assert!(parse_dex_routes("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn preferred_router_is_used_without_comparing() {
+        let routes = [route("a", 1), route("b", 2)];
+        let (provider, asserter) = mock_provider();
+        push_quote(&asserter, 50);
+
+        let (chosen, out) = select_route(&provider, &routes, TOKEN, U256::from(1u64), Some(routes[1].router))
+            .await
+            .unwrap();
+        assert_eq!((chosen.name.as_str(), out), ("b", U256::from(50u64)));
+    }
+
+    #[tokio::test]
+    async fn best_quote_wins_and_failing_routes_are_skipped() {
+        let routes = [route("a", 1), route("b", 2), route("c", 3)];
+        let (provider, asserter) = mock_provider();
+        push_quote(&asserter, 40);
+        push_quote(&asserter, 90);
+        asserter.push_failure_msg("execution reverted");
+
+        // An unknown preferred router falls back to comparing every route
+        let (chosen, out) = select_route(&provider, &routes, TOKEN, U256::from(1u64), Some(Address::ZERO))
+            .await
+            .unwrap();
+        assert_eq!((chosen.name.as_str(), out), ("b", U256::from(90u64)));
+    }
+
+    #[tokio::test]
+    async fn no_quotes_reports_the_last_error() {
+        let routes = [route("a", 1)];
+        let (provider, asserter) = mock_provider();
+        asserter.push_failure_msg("execution reverted");
+        let err = select_route(&provider, &routes, TOKEN, U256::from(1u64), None).await.unwrap_err();
+        assert!(err.is_no_liquidity());
+
+        let err = select_route(&provider, &[], TOKEN, U256::from(1u64), None).await.unwrap_err();
+        assert!(matches!(err, TradeError::QuoteFailed(_)));
+    }
+
+    /// Responses for `pair_wmon` on one route: its factory, the pair, then the pair's WMON.
+    fn push_pair(asserter: &alloy::providers::mock::Asserter, pair: Address, wmon: u64) {
+        push_call::<IQuoteRouter::factoryCall>(asserter, &Address::repeat_byte(0xfa));
+        push_call::<IPairFactory::getPairCall>(asserter, &pair);
+        if !pair.is_zero() {
+            push_call::<IWmonBalance::balanceOfCall>(asserter, &U256::from(wmon));
+        }
+    }
+
+    #[tokio::test]
+    async fn liquidity_is_the_deepest_pair_across_routes() {
+        let routes = [route("a", 1), route("b", 2), route("c", 3)];
+        let (provider, asserter) = mock_provider();
+        push_pair(&asserter, Address::repeat_byte(0xa1), 300);
+        push_pair(&asserter, Address::ZERO, 0);
+        push_pair(&asserter, Address::repeat_byte(0xc1), 700);
+
+        assert_eq!(pool_liquidity(&provider, &routes, TOKEN).await, Ok(U256::from(700u64)));
+    }
+
+    #[tokio::test]
+    async fn no_pair_anywhere_is_no_liquidity() {
+        let routes = [route("a", 1)];
+        let (provider, asserter) = mock_provider();
+        push_pair(&asserter, Address::ZERO, 0);
+        let err = pool_liquidity(&provider, &routes, TOKEN).await.unwrap_err();
+        assert!(matches!(err, TradeError::NoLiquidity(_)));
+
+        let (provider, asserter) = mock_provider();
+        asserter.push_failure_msg("connection reset");
+        assert!(pool_liquidity(&provider, &routes, TOKEN).await.is_err());
+    }
+}
@@ -5,13 +5,33 @@
 //! SDK-based trade executor using nadfun_sdk Core.
 //! Based on official buy.rs example from SDK.
 
+use crate::config::Config;
+use crate::executor::bump::{confirm_with_bumps, GasBump};
+use crate::executor::funds::check_affordable;
+use crate::executor::receipt::{mon_received, tokens_received, wait_receipt, MonReceived, TokenReceived};
+use crate::executor::gas::jitter_priority;
+use crate::executor::{paper, ApprovalMode, GasMode, GasStrategy, MinOutFloor, TradeError, TxSubmitter};
+use crate::jitter::Jitter;
+use crate::rpc::{MONAD_MAINNET_CHAIN_ID, MONAD_TESTNET_CHAIN_ID};
+use crate::token_meta::{TokenMeta, TokenMetadataCache};
+use crate::units::{token_units_to_f64, wei_to_mon};
 use alloy::eips::BlockId;
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{Address, B256, U256};
 use alloy::providers::Provider;
+use alloy::rpc::types::{Log, TransactionRequest};
 use alloy::sol;
+use alloy::sol_types::SolCall;
 use nadfun_sdk::{Core, GasEstimationParams, Network, SlippageUtils};
 use nadfun_sdk::types::{BuyParams, GasPricing, SellParams, Router};
-use tracing::{error, info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Delay between `confirm_buy` receipt polls.
+const RECEIPT_POLL_INTERVAL_MS: u64 = 1000;
+
+/// Gas limit on front-run buys, sent without an estimate to save the round trip.
+const FRONTRUN_GAS_LIMIT: u64 = 8_000_000;
 
 // ERC20 interface for balance, approval, and token info
 sol! {
@@ -20,59 +40,181 @@ sol! {
         function balanceOf(address account) external view returns (uint256);
         function approve(address spender, uint256 amount) external returns (bool);
         function allowance(address owner, address spender) external view returns (uint256);
-        function name() external view returns (string);
-        function symbol() external view returns (string);
+        function totalSupply() external view returns (uint256);
+    }
+
+    /// nad.fun router buy, encoded here when a buy goes through a `TxSubmitter`
+    /// instead of the SDK. `msg.value` is the MON spent.
+    interface INadRouter {
+        struct BuyParams {
+            uint256 amountOutMin;
+            address token;
+            address to;
+            uint256 deadline;
+        }
+
+        function buy(BuyParams params) external payable;
+    }
+}
+
+/// SDK network for `chain_id`. The SDK only knows Monad mainnet and testnet.
+pub fn sdk_network(chain_id: u64) -> Result<Network, TradeError> {
+    match chain_id {
+        MONAD_MAINNET_CHAIN_ID => Ok(Network::Mainnet),
+        MONAD_TESTNET_CHAIN_ID => Ok(Network::Testnet),
+        _ => Err(TradeError::Config(format!(
+            "nad.fun SDK has no network for chain id {} (expected {} or {})",
+            chain_id, MONAD_MAINNET_CHAIN_ID, MONAD_TESTNET_CHAIN_ID
+        ))),
     }
 }
 
 /// Trade executor using official nad.fun SDK.
 pub struct SdkExecutor {
     core: Core,
-    slippage_pct: f64,
+    /// Slippage for `buy_token` (copy buys pass their own to `buy_token_with_retry`).
+    buy_slippage_pct: f64,
+    /// Sell slippage (bonding curve tokens are volatile).
+    sell_slippage_pct: f64,
+    /// Gas tier for organic buys (front-runs price their own gas).
+    gas_strategy: GasStrategy,
+    /// Fee model for organic buys.
+    gas_mode: GasMode,
+    /// Random spread on organic buy gas prices / priority fees.
+    gas_jitter: Jitter,
+    /// `amount_out_min` floor for front-run buys.
+    min_out_floor: MinOutFloor,
+    /// Allowance granted to the router on sells.
+    approval_mode: ApprovalMode,
+    /// Quote but never send (see `paper`).
+    paper_trading: bool,
+    /// How long to wait for a receipt before giving up on it.
+    receipt_timeout: Duration,
+    /// Router deadline, in seconds after sending.
+    deadline_secs: u64,
+    /// Resending organic buys that sit unconfirmed.
+    gas_bump: GasBump,
+    /// Private relay for organic buys (None = sent by the SDK through the public RPC).
+    submitter: Option<Arc<dyn TxSubmitter>>,
+    /// Name / symbol / decimals of tokens already looked up.
+    metadata: TokenMetadataCache,
 }
 
 impl SdkExecutor {
-    /// Create new SDK executor.
-    pub async fn new(
-        rpc_url: String,
-        private_key: String,
-        slippage_pct: f64,
-    ) -> Result<Self, String> {
-        let core = Core::new(rpc_url, private_key, Network::Mainnet)
+    /// Create new SDK executor from `config`; organic buys pay `gas_strategy`.
+    pub async fn new(config: &Config, gas_strategy: GasStrategy) -> Result<Self, TradeError> {
+        let core = Core::new(config.rpc_url.clone(), config.private_key.clone(), sdk_network(config.chain_id)?)
             .await
-            .map_err(|e| format!("Failed to create Core: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to create Core", e))?;
 
         info!("✅ SDK Executor initialized: wallet {:?}", core.wallet_address());
 
         Ok(Self {
             core,
-            slippage_pct,
+            buy_slippage_pct: config.buy_slippage_pct,
+            sell_slippage_pct: config.sell_slippage_pct,
+            gas_strategy,
+            gas_mode: config.gas_mode,
+            gas_jitter: config.gas_jitter(),
+            min_out_floor: MinOutFloor::from_config(config),
+            approval_mode: config.approval_mode,
+            paper_trading: config.paper_trading,
+            receipt_timeout: config.receipt_timeout(),
+            deadline_secs: config.tx_deadline_secs,
+            gas_bump: GasBump::from_config(config),
+            submitter: None,
+            metadata: TokenMetadataCache::new(),
         })
     }
 
+    /// Send organic buys through `submitter` (the private relay) instead of the SDK.
+    /// Front-runs stay public: they have to land next to the tx they chase.
+    pub fn with_submitter(mut self, submitter: Arc<dyn TxSubmitter>) -> Self {
+        self.submitter = Some(submitter);
+        self
+    }
+
     /// Get wallet address.
     pub fn wallet_address(&self) -> Address {
         self.core.wallet_address()
     }
 
+    /// Deadline for a trade sent now, so a tx stuck in the mempool reverts
+    /// instead of filling at a stale price later.
+    fn deadline(&self) -> U256 {
+        U256::from(chrono::Utc::now().timestamp() as u64 + self.deadline_secs)
+    }
+
+    /// Fees for an organic buy under the configured `GasMode` (see `buy_gas_fees`).
+    async fn buy_gas_pricing(&self) -> Result<(u128, Option<u128>), TradeError> {
+        buy_gas_fees(self.core.provider(), self.gas_mode, self.gas_strategy, &self.gas_jitter).await
+    }
+
     /// Buy tokens on bonding curve (official SDK method).
     pub async fn buy_token(
         &self,
         token: Address,
         amount_mon: U256,
-    ) -> Result<String, String> {
+    ) -> Result<TokenReceived, TradeError> {
+        self.buy_token_with_slippage(token, amount_mon, self.buy_slippage_pct)
+            .await
+            .map(|(received, _)| received)
+    }
+
+    /// Buy tokens, escalating slippage once when the fill can't meet `amount_out_min`.
+    /// The first attempt uses `slippage_pct`; if it misses on slippage, a single
+    /// re-quoted retry goes out at `slippage_pct + step_pct` (capped at `max_slippage_pct`).
+    pub async fn buy_token_with_retry(
+        &self,
+        token: Address,
+        amount_mon: U256,
+        slippage_pct: f64,
+        step_pct: f64,
+        max_slippage_pct: f64,
+    ) -> Result<TokenReceived, TradeError> {
+        let slippage = match self.buy_token_with_slippage(token, amount_mon, slippage_pct).await {
+            Ok((received, expected_tokens)) => {
+                log_buy_fill(received.amount, expected_tokens, slippage_pct);
+                return Ok(received);
+            }
+            Err(e) if e.is_slippage() => match escalated_slippage(slippage_pct, step_pct, max_slippage_pct) {
+                Some(next) => {
+                    warn!(
+                        "📈 Buy missed amount_out_min at {}% slippage ({}), retrying once at {}%",
+                        slippage_pct, e, next
+                    );
+                    next
+                }
+                None => return Err(e),
+            },
+            Err(e) => return Err(e),
+        };
+
+        let (received, expected_tokens) = self.buy_token_with_slippage(token, amount_mon, slippage).await?;
+        log_buy_fill(received.amount, expected_tokens, slippage);
+        Ok(received)
+    }
+
+    /// Buy tokens with an explicit slippage tolerance.
+    /// Returns the tokens received and the quoted token amount.
+    async fn buy_token_with_slippage(
+        &self,
+        token: Address,
+        amount_mon: U256,
+        slippage_pct: f64,
+    ) -> Result<(TokenReceived, U256), TradeError> {
         let wallet = self.core.wallet_address();
         
         info!(
             "🛒 Buying token {:?} with {} MON (slippage: {}%)",
-            token, amount_mon, self.slippage_pct
+            token, amount_mon, slippage_pct
         );
 
         // 1. Check token status before buying
         let is_locked = self.core.is_locked(token).await
-            .map_err(|e| format!("Failed to check locked: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to check locked", e))?;
         let is_graduated = self.core.is_graduated(token).await
-            .map_err(|e| format!("Failed to check graduated: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to check graduated", e))?;
         
         if is_locked {
             warn!("⚠️ Token is locked!");
@@ -84,10 +226,10 @@ impl SdkExecutor {
         let (router, expected_tokens) = self.core
             .get_amount_out(token, amount_mon, true)
             .await
-            .map_err(|e| format!("Failed to get quote: {}", e))?;
+            .map_err(|e| TradeError::quote("Failed to get quote", e))?;
 
         if expected_tokens == U256::ZERO {
-            return Err("Invalid quote: amount_out is zero".to_string());
+            return Err(TradeError::QuoteFailed("Invalid quote: amount_out is zero".to_string()));
         }
 
         info!("📊 Quote: {} tokens expected via {:?}", expected_tokens, router);
@@ -95,27 +237,28 @@ impl SdkExecutor {
         // 3. Apply slippage protection
         let amount_out_min = SlippageUtils::calculate_amount_out_min(
             expected_tokens,
-            self.slippage_pct,
+            slippage_pct,
         );
 
-        info!("🛡️ Min tokens with {}% slippage: {}", self.slippage_pct, amount_out_min);
+        info!("🛡️ Min tokens with {}% slippage: {}", slippage_pct, amount_out_min);
+
+        if self.paper_trading {
+            let tx_hash = paper::paper_fill("BUY", token, amount_mon, expected_tokens);
+            return Ok((TokenReceived { tx_hash, amount: expected_tokens }, expected_tokens));
+        }
 
         // 4. Get nonce
         let current_nonce = self.core.provider()
             .get_transaction_count(wallet)
             .block_id(BlockId::latest())
             .await
-            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to get nonce", e))?;
 
-        // 5. Get gas price
-        let network_gas_price = self.core.provider()
-            .get_gas_price()
-            .await
-            .map_err(|e| format!("Failed to get gas price: {}", e))?;
-        let recommended_gas_price = (network_gas_price * 300) / 100; // 3x network price
+        // 5. Price gas
+        let (max_gas_price, priority_fee) = self.buy_gas_pricing().await?;
 
         // 6. Estimate gas
-        let deadline = U256::from(9999999999999999u64);
+        let deadline = self.deadline();
         let gas_params = GasEstimationParams::Buy {
             token,
             amount_in: amount_mon,
@@ -137,6 +280,10 @@ impl SdkExecutor {
 
         let gas_with_buffer = estimated_gas * 120 / 100;
 
+        // Fail clearly on a low balance instead of burning a nonce on a doomed tx
+        let gas_cost = U256::from(gas_with_buffer) * U256::from(max_gas_price);
+        check_affordable(self.core.provider(), wallet, amount_mon, gas_cost).await?;
+
         // 7. Execute buy
         let buy_params = BuyParams {
             token,
@@ -145,47 +292,272 @@ impl SdkExecutor {
             to: wallet,
             deadline,
             gas_limit: Some(gas_with_buffer),
-            gas_price: Some(GasPricing::LegacyWithPrice {
-                gas_price: recommended_gas_price,
-            }),
+            gas_price: Some(gas_pricing(max_gas_price, priority_fee)),
             nonce: Some(current_nonce),
         };
 
-        let tx_hash = self.core
-            .buy(buy_params, router)
-            .await
-            .map_err(|e| format!("Buy failed: {}", e))?;
+        let tx_hash = self
+            .send_buy(buy_params, router, max_gas_price, priority_fee, "Buy failed")
+            .await?;
 
         info!("📤 TX submitted: {}", tx_hash);
 
+        // 8a. Resend at the same nonce with more gas while it sits unconfirmed
+        if self.gas_bump.enabled() {
+            let resent = BuyParams {
+                token,
+                amount_in: amount_mon,
+                amount_out_min,
+                to: wallet,
+                deadline,
+                gas_limit: Some(gas_with_buffer),
+                gas_price: None,
+                nonce: Some(current_nonce),
+            };
+            let receipt = confirm_with_bumps(self.core.provider(), tx_hash, &self.gas_bump, self.receipt_timeout, |attempt| {
+                self.resend_buy(&resent, max_gas_price, priority_fee, attempt)
+            })
+            .await?;
+            let tx_hash = format!("{:?}", receipt.transaction_hash);
+            if !receipt.status() {
+                error!("❌ BUY REVERTED: {}", tx_hash);
+                return Err(TradeError::Reverted { hash: tx_hash });
+            }
+            let amount = self.tokens_received(receipt.logs(), &tx_hash, token, expected_tokens).await;
+            return Ok((TokenReceived { tx_hash, amount }, expected_tokens));
+        }
+
         // 8. Wait for receipt
-        match self.core.get_receipt(tx_hash).await {
+        match wait_receipt(tx_hash, self.receipt_timeout, "Receipt not available", self.core.get_receipt(tx_hash)).await {
             Ok(receipt) => {
                 if receipt.status {
                     info!(
                         "✅ BUY SUCCESS! TX: {:?}, Gas: {:?}",
                         receipt.transaction_hash, receipt.gas_used
                     );
-                    Ok(format!("{:?}", receipt.transaction_hash))
+                    let tx_hash = format!("{:?}", receipt.transaction_hash);
+                    let amount = self.tokens_received(&receipt.logs, &tx_hash, token, expected_tokens).await;
+                    Ok((TokenReceived { tx_hash, amount }, expected_tokens))
                 } else {
                     error!("❌ BUY REVERTED: {:?}", receipt.transaction_hash);
-                    Err("Transaction reverted".to_string())
+                    Err(TradeError::Reverted {
+                        hash: format!("{:?}", receipt.transaction_hash),
+                    })
+                }
+            }
+            Err(e @ TradeError::ReceiptTimeout { .. }) => Err(e),
+            Err(e) => {
+                warn!("⚠️ {}, waiting for confirmation", e);
+                let received = self.confirm_buy(&format!("{}", tx_hash), token).await?;
+                Ok((received, expected_tokens))
+            }
+        }
+    }
+
+    /// Resend a stuck buy with `params` (same nonce) and fees bumped for `attempt`.
+    /// `Ok(None)` if the bumped fee would pass the ceiling.
+    async fn resend_buy(
+        &self,
+        params: &BuyParams,
+        max_gas_price: u128,
+        priority_fee: Option<u128>,
+        attempt: u32,
+    ) -> Result<Option<B256>, TradeError> {
+        let max_gas_price = self.gas_bump.scale(max_gas_price, attempt);
+        if !self.gas_bump.allows(max_gas_price) {
+            return Ok(None);
+        }
+        let priority_fee = priority_fee.map(|fee| self.gas_bump.scale(fee, attempt));
+
+        // The router isn't kept from the first send; the quote picks the same one
+        let (router, _) = self.core
+            .get_amount_out(params.token, params.amount_in, true)
+            .await
+            .map_err(|e| TradeError::quote("Failed to get router", e))?;
+        let params = BuyParams {
+            token: params.token,
+            amount_in: params.amount_in,
+            amount_out_min: params.amount_out_min,
+            to: params.to,
+            deadline: params.deadline,
+            gas_limit: params.gas_limit,
+            gas_price: Some(gas_pricing(max_gas_price, priority_fee)),
+            nonce: params.nonce,
+        };
+
+        let tx_hash = self
+            .send_buy(params, router, max_gas_price, priority_fee, "Buy resend failed")
+            .await?;
+        Ok(Some(tx_hash))
+    }
+
+    /// Send an organic buy: through the submitter when one is set (it falls
+    /// back to the public mempool itself), otherwise through the SDK.
+    /// `max_gas_price` / `priority_fee` are the fees already in `params.gas_price`;
+    /// `context` prefixes SDK errors.
+    async fn send_buy(
+        &self,
+        params: BuyParams,
+        router: Router,
+        max_gas_price: u128,
+        priority_fee: Option<u128>,
+        context: &str,
+    ) -> Result<B256, TradeError> {
+        let Some(submitter) = &self.submitter else {
+            return self
+                .core
+                .buy(params, router)
+                .await
+                .map_err(|e| TradeError::classify(context, e));
+        };
+
+        let call = INadRouter::buyCall {
+            params: INadRouter::BuyParams {
+                amountOutMin: params.amount_out_min,
+                token: params.token,
+                to: params.to,
+                deadline: params.deadline,
+            },
+        };
+        let mut tx = TransactionRequest::default()
+            .from(self.core.wallet_address())
+            .to(router.address())
+            .value(params.amount_in)
+            .input(call.abi_encode().into());
+        tx.nonce = params.nonce;
+        tx.gas = params.gas_limit;
+        match priority_fee {
+            Some(priority_fee) => {
+                tx.max_fee_per_gas = Some(max_gas_price);
+                tx.max_priority_fee_per_gas = Some(priority_fee);
+            }
+            None => tx.gas_price = Some(max_gas_price),
+        }
+
+        let pending = submitter.submit(tx).await?;
+        Ok(*pending.tx_hash())
+    }
+
+    /// Wait for a submitted buy to be mined and return the tokens it delivered.
+    /// Errors if the transaction reverted or no receipt shows up within the
+    /// receipt timeout (`TX_RECEIPT_TIMEOUT_SEC`, the same budget sells get).
+    pub async fn confirm_buy(&self, tx_hash: &str, token: Address) -> Result<TokenReceived, TradeError> {
+        let hash: B256 = tx_hash
+            .parse()
+            .map_err(|e| TradeError::RpcError(format!("Invalid tx hash {}: {}", tx_hash, e)))?;
+
+        let deadline = tokio::time::Instant::now() + self.receipt_timeout;
+        loop {
+            let receipt = match self.core.provider().get_transaction_receipt(hash).await {
+                Ok(Some(receipt)) => receipt,
+                result => {
+                    if let Err(e) = result {
+                        warn!("⚠️ Receipt query failed for {}: {}", tx_hash, e);
+                    }
+                    let now = tokio::time::Instant::now();
+                    if now >= deadline {
+                        return Err(TradeError::ReceiptTimeout {
+                            hash: tx_hash.to_string(),
+                        });
+                    }
+                    tokio::time::sleep(Duration::from_millis(RECEIPT_POLL_INTERVAL_MS).min(deadline - now)).await;
+                    continue;
                 }
+            };
+
+            if !receipt.status() {
+                error!("❌ BUY REVERTED: {}", tx_hash);
+                return Err(TradeError::Reverted {
+                    hash: tx_hash.to_string(),
+                });
             }
+
+            let amount = match tokens_received(receipt.logs(), token, self.core.wallet_address()) {
+                Some(amount) => amount,
+                None => {
+                    warn!("⚠️ No Transfer log found for {}, falling back to balance query", tx_hash);
+                    self.get_token_balance(token).await?
+                }
+            };
+
+            info!("✅ BUY CONFIRMED: {} - received {} tokens", tx_hash, amount);
+            return Ok(TokenReceived {
+                tx_hash: tx_hash.to_string(),
+                amount,
+            });
+        }
+    }
+
+    /// Tokens our wallet received in a buy, decoded from the Transfer `logs` of
+    /// the receipt the send path already holds. Falls back to a balance query,
+    /// then to the quote if both are unavailable.
+    async fn tokens_received(&self, logs: &[Log], tx_hash: &str, token: Address, quoted: U256) -> U256 {
+        if let Some(amount) = tokens_received(logs, token, self.core.wallet_address()) {
+            info!("📊 Received {} tokens", amount);
+            return amount;
+        }
+
+        warn!("⚠️ No Transfer log found for {}, falling back to balance query", tx_hash);
+        match self.get_token_balance(token).await {
+            Ok(balance) => balance,
             Err(e) => {
-                warn!("⚠️ Receipt not available: {}", e);
-                Ok(format!("{}", tx_hash))
+                warn!("⚠️ Couldn't get token balance: {}, using quote", e);
+                quoted
             }
         }
     }
 
+    /// MON our wallet received from a sell, from its balance change across the block.
+    /// Falls back to the quote if the receipt or historical balances are unavailable.
+    async fn mon_received(&self, tx_hash: &str, quoted: U256) -> U256 {
+        let Ok(hash) = tx_hash.parse::<B256>() else {
+            return quoted;
+        };
+
+        match mon_received(self.core.provider(), self.core.wallet_address(), hash).await {
+            Ok(amount) => {
+                info!("📊 Received {} MON wei", amount);
+                amount
+            }
+            Err(e) => {
+                warn!("⚠️ Couldn't measure MON received for {}: {}, using quote", tx_hash, e);
+                quoted
+            }
+        }
+    }
+
+    /// Simulate a buy (gas estimation is an `eth_call` against the router) without sending it.
+    /// Returns the revert error if the buy would fail.
+    pub async fn simulate_buy(&self, token: Address, amount_mon: U256) -> Result<(), TradeError> {
+        let (router, _) = self.core
+            .get_amount_out(token, amount_mon, true)
+            .await
+            .map_err(|e| TradeError::quote("Failed to get quote", e))?;
+
+        let gas_params = GasEstimationParams::Buy {
+            token,
+            amount_in: amount_mon,
+            amount_out_min: U256::ZERO,
+            to: self.core.wallet_address(),
+            deadline: self.deadline(),
+        };
+
+        self.core
+            .estimate_gas(&router, gas_params)
+            .await
+            .map(|_| ())
+            .map_err(|e| TradeError::classify("Buy simulation reverted", e))
+    }
+
     /// Buy tokens with custom gas price (for front-running).
+    /// Returns as soon as the tx is sent: the tx hash and the quoted token amount.
+    /// The buy is unconfirmed; use `confirm_buy` to learn whether it landed.
     pub async fn buy_token_with_gas(
         &self,
         token: Address,
         amount_mon: U256,
         priority_gas_price: u128,
-    ) -> Result<String, String> {
+    ) -> Result<(String, U256), TradeError> {
         let wallet = self.core.wallet_address();
         
         info!(
@@ -194,27 +566,36 @@ impl SdkExecutor {
         );
 
         // Get quote and router
-        let (router, _) = self.core
+        let (router, expected_tokens) = self.core
             .get_amount_out(token, amount_mon, true)
             .await
-            .map_err(|e| format!("Failed to get router: {}", e))?;
+            .map_err(|e| TradeError::quote("Failed to get router", e))?;
+
+        if self.paper_trading {
+            let tx_hash = paper::paper_fill("FRONT-RUN", token, amount_mon, expected_tokens);
+            return Ok((tx_hash, expected_tokens));
+        }
+
+        // The gas limit is reserved up front at the bid price; a wallet that can't cover it loses the race anyway
+        let gas_cost = U256::from(FRONTRUN_GAS_LIMIT) * U256::from(priority_gas_price);
+        check_affordable(self.core.provider(), wallet, amount_mon, gas_cost).await?;
 
         // 1. Get nonce
         let current_nonce = self.core.provider()
             .get_transaction_count(wallet)
             .block_id(BlockId::latest())
             .await
-            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to get nonce", e))?;
 
         // 2. Execute buy with explicit gas price
-        let deadline = U256::from(9999999999999999u64);
+        let deadline = self.deadline();
         let buy_params = BuyParams {
             token,
             amount_in: amount_mon,
-            amount_out_min: U256::ZERO, // Accept high slippage for sniping
+            amount_out_min: self.min_out_floor.amount_out_min(token, expected_tokens, "Front-run"),
             to: wallet,
             deadline,
-            gas_limit: Some(8_000_000), 
+            gas_limit: Some(FRONTRUN_GAS_LIMIT),
             gas_price: Some(GasPricing::LegacyWithPrice {
                 gas_price: priority_gas_price,
             }),
@@ -224,23 +605,22 @@ impl SdkExecutor {
         let tx_hash = self.core
             .buy(buy_params, router)
             .await
-            .map_err(|e| format!("Front-run failed: {}", e))?;
+            .map_err(|e| TradeError::classify("Front-run failed", e))?;
 
         info!("🔫 Front-run TX sent: {}", tx_hash);
-        Ok(format!("{}", tx_hash))
+        Ok((format!("{}", tx_hash), expected_tokens))
     }
 
     /// Sell tokens on bonding curve with automatic approve.
-    /// Uses 15% slippage for sells (more volatile than buys).
+    /// Uses the executor's sell slippage (wider than buys).
     pub async fn sell_token(
         &self,
         token: Address,
         amount_tokens: U256,
-    ) -> Result<String, String> {
+    ) -> Result<MonReceived, TradeError> {
         let wallet = self.core.wallet_address();
         
-        // Use higher slippage for sells (15%) - bonding curve tokens are volatile
-        let sell_slippage = 15.0;
+        let sell_slippage = self.sell_slippage_pct;
         
         info!(
             "💰 Selling {} tokens of {:?} (slippage: {}%)",
@@ -251,10 +631,15 @@ impl SdkExecutor {
         let (router, expected_mon) = self.core
             .get_amount_out(token, amount_tokens, false)
             .await
-            .map_err(|e| format!("Failed to get sell quote: {}", e))?;
+            .map_err(|e| TradeError::quote("Failed to get sell quote", e))?;
 
         info!("📊 Quote: {} MON expected via {:?}", expected_mon, router);
 
+        if self.paper_trading {
+            let tx_hash = paper::paper_fill("SELL", token, amount_tokens, expected_mon);
+            return Ok(MonReceived { tx_hash, amount: expected_mon, sold: amount_tokens });
+        }
+
         // 2. Get router address for approval
         let router_address = router.address();
 
@@ -265,16 +650,17 @@ impl SdkExecutor {
             .allowance(wallet, router_address)
             .call()
             .await
-            .map_err(|e| format!("Failed to check allowance: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to check allowance", e))?;
 
         if current_allowance < amount_tokens {
-            info!("🔐 Approving {} tokens for router {:?}", amount_tokens, router_address);
-            
-            // Approve max amount to avoid future approvals
-            let max_approval = U256::MAX;
+            let allowance = self.approval_mode.allowance_for(amount_tokens);
+            info!(
+                "🔐 Approving {} tokens for router {:?} ({:?} mode)",
+                allowance, router_address, self.approval_mode
+            );
             
             let approve_tx = token_contract
-                .approve(router_address, max_approval);
+                .approve(router_address, allowance);
             
             let pending = self.core.provider()
                 .send_transaction(
@@ -283,18 +669,24 @@ impl SdkExecutor {
                         .input(approve_tx.calldata().clone().into())
                 )
                 .await
-                .map_err(|e| format!("Approve TX failed: {}", e))?;
+                .map_err(|e| TradeError::classify("Approve TX failed", e))?;
             
             info!("📤 Approve TX submitted: {:?}", pending.tx_hash());
             
             // Wait for approval confirmation
-            let receipt = pending
-                .get_receipt()
-                .await
-                .map_err(|e| format!("Approve receipt failed: {}", e))?;
+            let approve_hash = *pending.tx_hash();
+            let receipt = wait_receipt(
+                approve_hash,
+                self.receipt_timeout,
+                "Approve receipt failed",
+                pending.get_receipt(),
+            )
+            .await?;
             
             if !receipt.status() {
-                return Err("Approve transaction reverted".to_string());
+                return Err(TradeError::Reverted {
+                    hash: format!("{:?}", receipt.transaction_hash),
+                });
             }
             
             info!("✅ Approval confirmed");
@@ -316,7 +708,7 @@ impl SdkExecutor {
             amount_in: amount_tokens,
             amount_out_min: min_mon,
             to: wallet,
-            deadline: U256::from(9999999999999999u64),
+            deadline: self.deadline(),
             gas_limit: Some(500000), // Explicit gas limit
             gas_price: None,
             nonce: None,
@@ -325,27 +717,34 @@ impl SdkExecutor {
         let tx_hash = self.core
             .sell(sell_params, router)
             .await
-            .map_err(|e| format!("Sell failed: {}", e))?;
+            .map_err(|e| TradeError::classify("Sell failed", e))?;
 
         info!("📤 Sell TX submitted: {}", tx_hash);
 
         // 6. Wait for receipt
-        match self.core.get_receipt(tx_hash).await {
+        match wait_receipt(tx_hash, self.receipt_timeout, "Receipt not available", self.core.get_receipt(tx_hash)).await {
             Ok(receipt) => {
                 if receipt.status {
                     info!(
                         "✅ SELL SUCCESS! TX: {:?}, Gas: {:?}",
                         receipt.transaction_hash, receipt.gas_used
                     );
-                    Ok(format!("{:?}", receipt.transaction_hash))
+                    let tx_hash = format!("{:?}", receipt.transaction_hash);
+                    let amount = self.mon_received(&tx_hash, expected_mon).await;
+                    Ok(MonReceived { tx_hash, amount, sold: amount_tokens })
                 } else {
                     error!("❌ SELL REVERTED: {:?}", receipt.transaction_hash);
-                    Err("Sell transaction reverted".to_string())
+                    Err(TradeError::Reverted {
+                        hash: format!("{:?}", receipt.transaction_hash),
+                    })
                 }
             }
+            Err(e @ TradeError::ReceiptTimeout { .. }) => Err(e),
             Err(e) => {
-                warn!("⚠️ Receipt not available: {}", e);
-                Ok(format!("{}", tx_hash))
+                warn!("⚠️ {}", e);
+                let tx_hash = format!("{}", tx_hash);
+                let amount = self.mon_received(&tx_hash, expected_mon).await;
+                Ok(MonReceived { tx_hash, amount, sold: amount_tokens })
             }
         }
     }
@@ -356,9 +755,17 @@ impl SdkExecutor {
         token: Address,
         amount_tokens: U256,
         slippage_pct: f64,
-    ) -> Result<String, String> {
+    ) -> Result<MonReceived, TradeError> {
         let wallet = self.core.wallet_address();
-        
+
+        // A failed first attempt may still have sold part of the bag; re-selling the
+        // full amount would revert, so clamp to what the wallet holds now
+        let amount_tokens = if self.paper_trading {
+            clamp_to_balance(amount_tokens, amount_tokens)?
+        } else {
+            clamp_to_balance(amount_tokens, self.get_token_balance(token).await?)?
+        };
+
         info!(
             "💰 Selling {} tokens of {:?} (custom slippage: {}%)",
             amount_tokens, token, slippage_pct
@@ -368,10 +775,15 @@ impl SdkExecutor {
         let (router, expected_mon) = self.core
             .get_amount_out(token, amount_tokens, false)
             .await
-            .map_err(|e| format!("Failed to get sell quote: {}", e))?;
+            .map_err(|e| TradeError::quote("Failed to get sell quote", e))?;
 
         info!("📊 Quote: {} MON expected via {:?}", expected_mon, router);
 
+        if self.paper_trading {
+            let tx_hash = paper::paper_fill("SELL", token, amount_tokens, expected_mon);
+            return Ok(MonReceived { tx_hash, amount: expected_mon, sold: amount_tokens });
+        }
+
         // Already approved from previous attempt, skip approval check
         
         // Apply custom slippage
@@ -385,7 +797,7 @@ impl SdkExecutor {
             amount_in: amount_tokens,
             amount_out_min: min_mon,
             to: wallet,
-            deadline: U256::from(9999999999999999u64),
+            deadline: self.deadline(),
             gas_limit: Some(500000),
             gas_price: None,
             nonce: None,
@@ -394,23 +806,30 @@ impl SdkExecutor {
         let tx_hash = self.core
             .sell(sell_params, router)
             .await
-            .map_err(|e| format!("Sell failed: {}", e))?;
+            .map_err(|e| TradeError::classify("Sell failed", e))?;
 
         info!("📤 Sell TX submitted: {}", tx_hash);
 
         // Wait for receipt
-        match self.core.get_receipt(tx_hash).await {
+        match wait_receipt(tx_hash, self.receipt_timeout, "Receipt not available", self.core.get_receipt(tx_hash)).await {
             Ok(receipt) => {
                 if receipt.status {
                     info!("✅ SELL SUCCESS! TX: {:?}", receipt.transaction_hash);
-                    Ok(format!("{:?}", receipt.transaction_hash))
+                    let tx_hash = format!("{:?}", receipt.transaction_hash);
+                    let amount = self.mon_received(&tx_hash, expected_mon).await;
+                    Ok(MonReceived { tx_hash, amount, sold: amount_tokens })
                 } else {
-                    Err("Sell transaction reverted".to_string())
+                    Err(TradeError::Reverted {
+                        hash: format!("{:?}", receipt.transaction_hash),
+                    })
                 }
             }
+            Err(e @ TradeError::ReceiptTimeout { .. }) => Err(e),
             Err(e) => {
-                warn!("⚠️ Receipt not available: {}", e);
-                Ok(format!("{}", tx_hash))
+                warn!("⚠️ {}", e);
+                let tx_hash = format!("{}", tx_hash);
+                let amount = self.mon_received(&tx_hash, expected_mon).await;
+                Ok(MonReceived { tx_hash, amount, sold: amount_tokens })
             }
         }
     }
@@ -421,24 +840,75 @@ impl SdkExecutor {
         &self,
         token: Address,
         amount_tokens: U256,
-    ) -> Result<f64, String> {
+    ) -> Result<f64, TradeError> {
         // Use SDK's get_amount_out with is_buy=false to get sell quote
         let (_router, expected_mon) = self.core
             .get_amount_out(token, amount_tokens, false)
             .await
-            .map_err(|e| format!("Failed to get price: {}", e))?;
+            .map_err(|e| TradeError::quote("Failed to get price", e))?;
 
         // Convert wei to MON
-        let mon = expected_mon.to::<u128>() as f64 / 1e18;
+        let mon = wei_to_mon(expected_mon);
         Ok(mon)
     }
 
     /// Check if token has graduated from bonding curve.
-    pub async fn is_graduated(&self, token: Address) -> Result<bool, String> {
+    pub async fn is_graduated(&self, token: Address) -> Result<bool, TradeError> {
         self.core
             .is_graduated(token)
             .await
-            .map_err(|e| format!("Failed to check graduated: {}", e))
+            .map_err(|e| TradeError::classify("Failed to check graduated", e))
+    }
+
+    /// Router the SDK would currently sell `token` through (bonding curve or DEX).
+    pub async fn sell_router(&self, token: Address) -> Result<Address, TradeError> {
+        let one_token = U256::from(1_000_000_000_000_000_000u128);
+        let (router, _) = self.core
+            .get_amount_out(token, one_token, false)
+            .await
+            .map_err(|e| TradeError::quote("Failed to get sell quote", e))?;
+        Ok(router.address())
+    }
+
+    /// Set `spender`'s allowance for `token` back to zero.
+    /// Returns `None` if there was no allowance to revoke.
+    pub async fn revoke_approval(&self, token: Address, spender: Address) -> Result<Option<B256>, TradeError> {
+        let token_contract = IERC20::new(token, self.core.provider());
+
+        let allowance = token_contract
+            .allowance(self.core.wallet_address(), spender)
+            .call()
+            .await
+            .map_err(|e| TradeError::classify("Failed to check allowance", e))?;
+        if allowance == U256::ZERO {
+            return Ok(None);
+        }
+
+        info!("🔓 Revoking {:?} allowance of {} for {:?}", token, allowance, spender);
+        let revoke_tx = token_contract.approve(spender, U256::ZERO);
+        let pending = self.core.provider()
+            .send_transaction(
+                alloy::rpc::types::TransactionRequest::default()
+                    .to(token)
+                    .input(revoke_tx.calldata().clone().into())
+            )
+            .await
+            .map_err(|e| TradeError::classify("Revoke TX failed", e))?;
+        let revoke_hash = *pending.tx_hash();
+        let receipt = wait_receipt(
+            revoke_hash,
+            self.receipt_timeout,
+            "Revoke receipt failed",
+            pending.get_receipt(),
+        )
+        .await?;
+
+        if !receipt.status() {
+            return Err(TradeError::Reverted {
+                hash: format!("{:?}", receipt.transaction_hash),
+            });
+        }
+        Ok(Some(receipt.transaction_hash))
     }
 
     /// Get a reference to the SDK Core for direct access.
@@ -447,7 +917,7 @@ impl SdkExecutor {
     }
 
     /// Get token balance for wallet using ERC20 interface.
-    pub async fn get_token_balance(&self, token: Address) -> Result<U256, String> {
+    pub async fn get_token_balance(&self, token: Address) -> Result<U256, TradeError> {
         let wallet = self.core.wallet_address();
         let token_contract = IERC20::new(token, self.core.provider());
         
@@ -455,25 +925,249 @@ impl SdkExecutor {
             .balanceOf(wallet)
             .call()
             .await
-            .map_err(|e| format!("Failed to get balance: {}", e))
+            .map_err(|e| TradeError::classify("Failed to get balance", e))
     }
 
-    /// Get token name and symbol from chain.
-    pub async fn get_token_info(&self, token: Address) -> Result<(String, String), String> {
+    /// Get token total supply using ERC20 interface.
+    pub async fn get_total_supply(&self, token: Address) -> Result<U256, TradeError> {
         let token_contract = IERC20::new(token, self.core.provider());
-        
-        let name = token_contract
-            .name()
+
+        token_contract
+            .totalSupply()
             .call()
             .await
-            .map_or_else(|_| "Unknown".to_string(), |s| s.to_string());
-        
-        let symbol = token_contract
-            .symbol()
-            .call()
+            .map_err(|e| TradeError::classify("Failed to get total supply", e))
+    }
+
+    /// Token name, symbol and decimals (cached after the first lookup).
+    pub async fn token_meta(&self, token: Address) -> TokenMeta {
+        self.metadata.get(self.core.provider(), token).await
+    }
+}
+
+/// Slippage for the single escalated retry, or `None` when there is no room to widen.
+fn escalated_slippage(slippage_pct: f64, step_pct: f64, max_slippage_pct: f64) -> Option<f64> {
+    (step_pct > 0.0 && slippage_pct < max_slippage_pct).then(|| (slippage_pct + step_pct).min(max_slippage_pct))
+}
+
+/// Log what the buy actually filled at versus the quote it was sent with.
+fn log_buy_fill(received: U256, expected_tokens: U256, slippage_pct: f64) {
+    if received.is_zero() || expected_tokens.is_zero() {
+        info!("📊 Buy filled with {}% slippage tolerance", slippage_pct);
+        return;
+    }
+
+    // Only the ratio matters, so the token's decimals cancel out
+    let expected = token_units_to_f64(expected_tokens, 18);
+    let paid_pct = ((expected - token_units_to_f64(received, 18)) / expected * 100.0).max(0.0);
+    info!(
+        "📊 Buy filled: {} tokens (quoted {}) - paid {:.2}% slippage (tolerance {}%)",
+        received, expected_tokens, paid_pct, slippage_pct
+    );
+}
+
+/// SDK gas settings for a buy: EIP-1559 when there is a priority fee, else a legacy gas price.
+fn gas_pricing(max_gas_price: u128, priority_fee: Option<u128>) -> GasPricing {
+    match priority_fee {
+        Some(max_priority_fee_per_gas) => GasPricing::Eip1559 {
+            max_fee_per_gas: max_gas_price,
+            max_priority_fee_per_gas,
+        },
+        None => GasPricing::LegacyWithPrice { gas_price: max_gas_price },
+    }
+}
+
+/// Tokens a sell retry can still sell: `requested`, capped at the wallet's `balance`.
+fn clamp_to_balance(requested: U256, balance: U256) -> Result<U256, TradeError> {
+    if balance < requested {
+        warn!("⚠️ Balance {} below requested {}, selling what's left", balance, requested);
+    }
+    let amount = requested.min(balance);
+    if amount.is_zero() {
+        return Err(TradeError::InsufficientFunds("No tokens to sell".to_string()));
+    }
+    Ok(amount)
+}
+
+/// Fees for an organic buy under `mode`: the most it can cost per gas unit
+/// (gas price or max fee), and the priority fee under EIP-1559.
+async fn buy_gas_fees<P: Provider + ?Sized>(
+    provider: &P,
+    mode: GasMode,
+    strategy: GasStrategy,
+    jitter: &Jitter,
+) -> Result<(u128, Option<u128>), TradeError> {
+    match mode {
+        GasMode::Legacy => {
+            let network_gas_price = provider
+                .get_gas_price()
+                .await
+                .map_err(|e| TradeError::classify("Failed to get gas price", e))?;
+            let gas_price = jitter.apply_fee(strategy.legacy_gas_price(network_gas_price));
+            Ok((gas_price, None))
+        }
+        GasMode::Eip1559 => {
+            let block = provider
+                .get_block_by_number(alloy::eips::BlockNumberOrTag::Latest)
+                .await
+                .map_err(|e| TradeError::classify("Failed to get block", e))?
+                .ok_or_else(|| TradeError::RpcError("No block found".to_string()))?;
+            let base_fee = block
+                .header
+                .base_fee_per_gas
+                .ok_or_else(|| TradeError::RpcError("No base fee".to_string()))?;
+            let (max_fee_per_gas, max_priority_fee_per_gas) =
+                jitter_priority(jitter, strategy.calculate(base_fee as u128));
+            debug!(
+                "Gas: base_fee={}, max_fee={}, priority={}",
+                base_fee, max_fee_per_gas, max_priority_fee_per_gas
+            );
+            Ok((max_fee_per_gas, Some(max_priority_fee_per_gas)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, offline_sdk_executor, push_block, push_quantity, MockProvider};
+    use crate::executor::submit::SubmitFuture;
+    use alloy::providers::PendingTransactionBuilder;
+
+    #[test]
+    fn escalates_by_one_step() {
+        assert_eq!(escalated_slippage(10.0, 5.0, 20.0), Some(15.0));
+    }
+
+    #[test]
+    fn escalation_is_capped_at_max() {
+        assert_eq!(escalated_slippage(18.0, 5.0, 20.0), Some(20.0));
+    }
+
+    #[test]
+    fn no_escalation_without_room_or_step() {
+        assert_eq!(escalated_slippage(20.0, 5.0, 20.0), None);
+        assert_eq!(escalated_slippage(10.0, 0.0, 20.0), None);
+    }
+
+    #[test]
+    fn only_slippage_errors_escalate() {
+        assert!(TradeError::SlippageExceeded("amount_out_min".to_string()).is_slippage());
+        assert!(!TradeError::Reverted { hash: "0xabc".to_string() }.is_slippage());
+        assert!(!TradeError::RpcError("connection reset".to_string()).is_slippage());
+    }
+
+    #[tokio::test]
+    async fn legacy_mode_scales_the_network_gas_price() {
+        let (provider, asserter) = mock_provider();
+        push_quantity(&asserter, 100_000_000_000);
+        let fees = buy_gas_fees(&provider, GasMode::Legacy, GasStrategy::Aggressive, &Jitter::new(0.0, None)).await;
+        assert_eq!(fees, Ok((300_000_000_000, None)));
+    }
+
+    #[tokio::test]
+    async fn eip1559_mode_prices_from_the_base_fee() {
+        let (provider, asserter) = mock_provider();
+        push_block(&asserter, 50_000_000_000);
+        let fees = buy_gas_fees(&provider, GasMode::Eip1559, GasStrategy::Aggressive, &Jitter::new(0.0, None)).await;
+        // 1.5x base fee + 10 gwei tip
+        assert_eq!(fees, Ok((85_000_000_000, Some(10_000_000_000))));
+    }
+
+    #[tokio::test]
+    async fn gas_price_rpc_failure_is_reported() {
+        let (provider, asserter) = mock_provider();
+        asserter.push_failure_msg("connection reset");
+        let fees = buy_gas_fees(&provider, GasMode::Legacy, GasStrategy::Normal, &Jitter::new(0.0, None)).await;
+        assert!(matches!(fees, Err(TradeError::RpcError(_))));
+    }
+
+    #[test]
+    fn retry_sells_no_more_than_the_wallet_holds() {
+        assert_eq!(clamp_to_balance(U256::from(100u64), U256::from(500u64)), Ok(U256::from(100u64)));
+        // A first attempt already sold 60
+        assert_eq!(clamp_to_balance(U256::from(100u64), U256::from(40u64)), Ok(U256::from(40u64)));
+        assert!(matches!(
+            clamp_to_balance(U256::from(100u64), U256::ZERO),
+            Err(TradeError::InsufficientFunds(_))
+        ));
+        assert!(clamp_to_balance(U256::ZERO, U256::from(1u64)).is_err());
+    }
+
+    #[test]
+    fn sdk_network_knows_mainnet_and_testnet_only() {
+        assert!(matches!(sdk_network(MONAD_MAINNET_CHAIN_ID), Ok(Network::Mainnet)));
+        assert!(matches!(sdk_network(MONAD_TESTNET_CHAIN_ID), Ok(Network::Testnet)));
+        assert!(matches!(sdk_network(1), Err(TradeError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn deadline_is_the_configured_window_from_now() {
+        let executor = offline_sdk_executor().await;
+        let before = chrono::Utc::now().timestamp() as u64;
+        let deadline = executor.deadline();
+        let after = chrono::Utc::now().timestamp() as u64;
+        assert!(deadline >= U256::from(before + 120) && deadline <= U256::from(after + 120));
+    }
+
+    #[tokio::test]
+    async fn confirm_buy_gives_up_after_the_receipt_timeout() {
+        // The offline node never answers; the configured timeout is 1s
+        let executor = offline_sdk_executor().await;
+        let started = std::time::Instant::now();
+        let err = executor
+            .confirm_buy(&format!("{:?}", B256::repeat_byte(7)), Address::repeat_byte(1))
             .await
-            .map_or_else(|_| "???".to_string(), |s| s.to_string());
-        
-        Ok((name, symbol))
+            .unwrap_err();
+        assert!(err.is_receipt_timeout(), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    /// Keeps every tx it is handed instead of sending it.
+    struct Recording {
+        provider: MockProvider,
+        sent: std::sync::Mutex<Vec<TransactionRequest>>,
+    }
+
+    impl TxSubmitter for Recording {
+        fn submit(&self, tx: TransactionRequest) -> SubmitFuture<'_> {
+            self.sent.lock().unwrap().push(tx);
+            Box::pin(async move { Ok(PendingTransactionBuilder::new(self.provider.clone(), B256::repeat_byte(7))) })
+        }
+    }
+
+    #[tokio::test]
+    async fn submitter_gets_the_encoded_router_buy() {
+        let recording = Arc::new(Recording { provider: mock_provider().0, sent: Default::default() });
+        let executor = offline_sdk_executor().await.with_submitter(Arc::clone(&recording) as Arc<dyn TxSubmitter>);
+        let (token, router) = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+        let params = BuyParams {
+            token,
+            amount_in: U256::from(5u64),
+            amount_out_min: U256::from(100u64),
+            to: executor.wallet_address(),
+            deadline: U256::from(1_000u64),
+            gas_limit: Some(300_000),
+            gas_price: None,
+            nonce: Some(9),
+        };
+
+        let hash = executor
+            .send_buy(params, Router::BondingCurve(router), 80, Some(2), "Buy failed")
+            .await
+            .unwrap();
+        assert_eq!(hash, B256::repeat_byte(7));
+
+        let sent = recording.sent.lock().unwrap();
+        let tx = &sent[0];
+        assert_eq!(tx.to, Some(router.into()));
+        assert_eq!(tx.value, Some(U256::from(5u64)));
+        assert_eq!((tx.nonce, tx.gas), (Some(9), Some(300_000)));
+        assert_eq!((tx.max_fee_per_gas, tx.max_priority_fee_per_gas, tx.gas_price), (Some(80), Some(2), None));
+
+        let call = INadRouter::buyCall::abi_decode(tx.input.input().unwrap()).unwrap();
+        assert_eq!(call.params.amountOutMin, U256::from(100u64));
+        assert_eq!((call.params.token, call.params.to), (token, executor.wallet_address()));
+        assert_eq!(call.params.deadline, U256::from(1_000u64));
     }
 }
@@ -4,15 +4,20 @@
 //! Sell execution for closing positions.
 
 use crate::config::Config;
-use crate::executor::GasStrategy;
+use crate::executor::receipt::{mon_received, wait_receipt, MonReceived};
+use crate::executor::routes::{select_route, DexRoute};
+use crate::executor::swap::{final_amount_out, min_amount_out};
+use crate::executor::{build_submitter, nonce, paper, wmon, ApprovalMode, GasStrategy, TradeError, TxSubmitter};
 use crate::position::SellDecision;
 use alloy::network::EthereumWallet;
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{Address, B256, U256};
 use alloy::providers::Provider;
 use alloy::rpc::types::TransactionRequest;
 use alloy::sol;
 use std::sync::atomic::{AtomicU64, Ordering};
-use tracing::{debug, error, info};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
 // Router interface for swaps
 sol! {
@@ -36,6 +41,7 @@ sol! {
     #[sol(rpc)]
     interface IERC20 {
         function approve(address spender, uint256 amount) external returns (bool);
+        function allowance(address owner, address spender) external view returns (uint256);
         function balanceOf(address account) external view returns (uint256);
     }
 }
@@ -44,58 +50,84 @@ sol! {
 pub struct SellExecutor<P: Provider + Clone> {
     provider: P,
     wallet: EthereumWallet,
-    router: Address,
+    /// Graduated-token DEXes, the primary router first.
+    routes: Vec<DexRoute>,
     wmon: Address,
     wallet_address: Address,
     gas_limit: u64,
     gas_strategy: GasStrategy,
     nonce: AtomicU64,
+    auto_wrap: bool,
+    submitter: Arc<dyn TxSubmitter>,
+    slippage_pct: f64,
+    deadline_secs: u64,
+    approve_gas_limit: u64,
+    approval_mode: ApprovalMode,
+    /// Quote but never send (see `paper`).
+    paper_trading: bool,
+    /// How long to wait for a receipt before giving up on it.
+    receipt_timeout: Duration,
 }
 
-impl<P: Provider + Clone> SellExecutor<P> {
+impl<P: Provider + Clone + 'static> SellExecutor<P> {
     /// Create a new sell executor.
     pub async fn new(
         provider: P,
         wallet: EthereumWallet,
         config: &Config,
-    ) -> Result<Self, String> {
-        let nonce = provider
-            .get_transaction_count(config.wallet_address)
-            .await
-            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+    ) -> Result<Self, TradeError> {
+        let nonce = nonce::pending_nonce(&provider, config.wallet_address).await?;
+
+        let submitter = build_submitter(provider.clone(), wallet.clone(), config, true)?;
 
         Ok(Self {
             provider,
             wallet,
-            router: config.router_address,
+            routes: config.dex_routes(),
             wmon: config.wmon_address,
             wallet_address: config.wallet_address,
             gas_limit: config.gas_limit,
             gas_strategy: GasStrategy::Normal, // Use normal for sells, not aggressive
             nonce: AtomicU64::new(nonce),
+            auto_wrap: config.auto_wrap,
+            submitter,
+            slippage_pct: config.dex_sell_slippage_pct,
+            deadline_secs: config.dex_sell_deadline_secs,
+            approve_gas_limit: config.approve_gas_limit,
+            approval_mode: config.approval_mode,
+            paper_trading: config.paper_trading,
+            receipt_timeout: config.receipt_timeout(),
         })
     }
 
-    /// Execute a sell transaction.
+    /// Execute a sell transaction. Returns the tx hash and the MON received.
+    /// `preferred_router` is the router the SDK reports for the token; when it is a
+    /// configured DEX it is used, otherwise the best-quoting configured DEX is.
     pub async fn sell(
         &self,
         token: Address,
         amount: U256,
         decision: &SellDecision,
-    ) -> Result<alloy::primitives::B256, String> {
+        preferred_router: Option<Address>,
+    ) -> Result<MonReceived, TradeError> {
         info!(
             "🔴 Executing SELL: {:?} - {:?}",
             token, decision
         );
 
         // Calculate sell amount based on decision
-        let sell_amount = match decision {
-            SellDecision::SecureProfit { portion, .. } => {
-                // Partial sell
-                amount * U256::from((*portion * 100.0) as u64) / U256::from(100)
-            }
-            _ => amount, // Full sell for other cases
-        };
+        let sell_amount = decision.sell_amount(amount);
+
+        // Graduated tokens may trade on any configured DEX
+        let (route, quoted_out) =
+            select_route(&self.provider, &self.routes, token, sell_amount, preferred_router).await?;
+        info!("🔀 Selling via {} router {:?}", route.name, route.router);
+
+        // Paper positions hold no real tokens: skip balance and approval, just quote
+        if self.paper_trading {
+            let tx_hash = paper::paper_fill("SELL", token, sell_amount, quoted_out);
+            return Ok(MonReceived { tx_hash, amount: quoted_out, sold: sell_amount });
+        }
 
         // Get token balance to verify
         let token_contract = IERC20::new(token, &self.provider);
@@ -103,7 +135,7 @@ impl<P: Provider + Clone> SellExecutor<P> {
             .balanceOf(self.wallet_address)
             .call()
             .await
-            .map_err(|e| format!("Failed to get balance: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to get balance", e))?;
 
         let actual_sell_amount = if sell_amount > balance {
             balance
@@ -112,62 +144,82 @@ impl<P: Provider + Clone> SellExecutor<P> {
         };
 
         if actual_sell_amount == U256::ZERO {
-            return Err("No tokens to sell".to_string());
+            return Err(TradeError::InsufficientFunds("No tokens to sell".to_string()));
         }
 
         info!("Selling {} tokens", actual_sell_amount);
 
-        // Approve router
-        let approve_call = token_contract.approve(self.router, actual_sell_amount);
-        let approve_nonce = self.nonce.fetch_add(1, Ordering::SeqCst);
-
-        let approve_tx = TransactionRequest::default()
-            .to(token)
-            .input(approve_call.calldata().clone().into())
-            .nonce(approve_nonce)
-            .gas_limit(100_000);
-
-        let pending_approve = self
-            .provider
-            .send_transaction(approve_tx)
-            .await
-            .map_err(|e| {
-                self.nonce.fetch_sub(1, Ordering::SeqCst);
-                format!("Approve failed: {}", e)
-            })?;
-
-        let approve_receipt = pending_approve
-            .get_receipt()
+        // Approve router (skipped while an earlier approval still covers the sell)
+        let current_allowance = token_contract
+            .allowance(self.wallet_address, route.router)
+            .call()
             .await
-            .map_err(|e| format!("Approve receipt failed: {}", e))?;
+            .map_err(|e| TradeError::classify("Failed to check allowance", e))?;
+
+        if current_allowance < actual_sell_amount {
+            let allowance = self.approval_mode.allowance_for(actual_sell_amount);
+            info!("🔐 Approving {} tokens for router ({:?} mode)", allowance, self.approval_mode);
+            let approve_call = token_contract.approve(route.router, allowance);
+            let approve_nonce = self.nonce.fetch_add(1, Ordering::SeqCst);
+
+            let approve_tx = TransactionRequest::default()
+                .to(token)
+                .input(approve_call.calldata().clone().into())
+                .nonce(approve_nonce)
+                .gas_limit(self.approve_gas_limit);
+
+            let pending_approve = match self.submitter.submit(approve_tx).await {
+                Ok(pending) => pending,
+                Err(e) => {
+                    let _ = self.resync_nonce().await;
+                    return Err(e);
+                }
+            };
+
+            let approve_hash = *pending_approve.tx_hash();
+            let approve_receipt = wait_receipt(
+                approve_hash,
+                self.receipt_timeout,
+                "Approve receipt failed",
+                pending_approve.get_receipt(),
+            )
+            .await?;
+
+            if !approve_receipt.status() {
+                return Err(TradeError::Reverted {
+                    hash: format!("{:?}", approve_receipt.transaction_hash),
+                });
+            }
 
-        if !approve_receipt.status() {
-            return Err("Approve transaction failed".to_string());
+            info!("✅ Approval confirmed");
+        } else {
+            info!("✅ Already approved for router");
         }
 
-        info!("✅ Approval confirmed");
-
         // Get base fee
         let base_fee = self.get_base_fee().await?;
         let (max_fee, priority_fee) = self.gas_strategy.calculate(base_fee);
 
         // Build swap path: Token -> WMON
-        let path = vec![token, self.wmon];
+        let path = vec![token, route.wmon];
 
         // Get expected output
-        let router = IRouter::new(self.router, &self.provider);
+        let router = IRouter::new(route.router, &self.provider);
         let amounts_out = router
             .getAmountsOut(actual_sell_amount, path.clone())
             .call()
             .await
-            .map_err(|e| format!("getAmountsOut failed: {}", e))?;
+            .map_err(|e| TradeError::quote("getAmountsOut failed", e))?;
 
-        // 5% slippage
-        let min_out = amounts_out[1] * U256::from(95) / U256::from(100);
-        debug!("Expected MON out: {}, Min: {}", amounts_out[1], min_out);
+        let expected_out = final_amount_out(&amounts_out, path.len())?;
+        let min_out = min_amount_out(expected_out, self.slippage_pct);
+        debug!(
+            "Expected MON out: {}, Min ({}% slippage): {}",
+            expected_out, self.slippage_pct, min_out
+        );
 
         // Build swap
-        let deadline = U256::from(chrono::Utc::now().timestamp() as u64 + 300);
+        let deadline = U256::from(chrono::Utc::now().timestamp() as u64 + self.deadline_secs);
 
         let swap_call = router.swapExactTokensForETH(
             actual_sell_amount,
@@ -180,56 +232,98 @@ impl<P: Provider + Clone> SellExecutor<P> {
         let swap_nonce = self.nonce.fetch_add(1, Ordering::SeqCst);
 
         let swap_tx = TransactionRequest::default()
-            .to(self.router)
+            .to(route.router)
             .input(swap_call.calldata().clone().into())
             .nonce(swap_nonce)
             .gas_limit(self.gas_limit)
             .max_fee_per_gas(max_fee)
             .max_priority_fee_per_gas(priority_fee);
 
-        let pending_swap = self
-            .provider
-            .send_transaction(swap_tx)
-            .await
-            .map_err(|e| {
-                self.nonce.fetch_sub(1, Ordering::SeqCst);
-                format!("Swap failed: {}", e)
-            })?;
+        let pending_swap = match self.submitter.submit(swap_tx).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                let _ = self.resync_nonce().await;
+                return Err(e);
+            }
+        };
 
         info!("📤 Sell transaction sent: {:?}", pending_swap.tx_hash());
 
-        let receipt = pending_swap
-            .get_receipt()
-            .await
-            .map_err(|e| format!("Sell receipt failed: {}", e))?;
+        let swap_hash = *pending_swap.tx_hash();
+        let receipt = wait_receipt(swap_hash, self.receipt_timeout, "Sell receipt failed", pending_swap.get_receipt()).await?;
 
-        if receipt.status() {
-            info!(
-                "✅ SELL SUCCESS: {:?} - tx: {:?}",
-                token, receipt.transaction_hash
-            );
-        } else {
+        if !receipt.status() {
             error!(
                 "❌ SELL FAILED: {:?} - tx: {:?}",
                 token, receipt.transaction_hash
             );
+            return Err(TradeError::Reverted {
+                hash: format!("{:?}", receipt.transaction_hash),
+            });
+        }
+
+        info!(
+            "✅ SELL SUCCESS: {:?} - tx: {:?}",
+            token, receipt.transaction_hash
+        );
+
+        // Measure before unwrapping: the unwrap is our own tx in a later block
+        let amount = match mon_received(&self.provider, self.wallet_address, receipt.transaction_hash).await {
+            Ok(amount) => amount,
+            Err(e) => {
+                warn!("⚠️ Couldn't measure MON received: {}, using quote", e);
+                expected_out
+            }
+        };
+
+        // Return any accumulated WMON to spendable native MON
+        if self.auto_wrap {
+            if let Err(e) = wmon::unwrap_all(&self.provider, self.wmon, self.wallet_address, &self.nonce, self.receipt_timeout).await {
+                warn!("⚠️ Failed to unwrap WMON after sell: {}", e);
+            }
+        }
+
+        Ok(MonReceived {
+            tx_hash: format!("{:?}", receipt.transaction_hash),
+            amount,
+            sold: actual_sell_amount,
+        })
+    }
+
+    /// Unwrap the wallet's WMON back to native MON if the balance is above
+    /// `threshold`. Paper trading never sends, so it never unwraps.
+    pub async fn unwrap_wmon_above(&self, threshold: U256) -> Result<Option<B256>, TradeError> {
+        if self.paper_trading {
+            return Ok(None);
         }
+        wmon::unwrap_above(
+            &self.provider,
+            self.wmon,
+            self.wallet_address,
+            threshold,
+            &self.nonce,
+            self.receipt_timeout,
+        )
+        .await
+    }
 
-        Ok(receipt.transaction_hash)
+    /// Re-read the wallet nonce from the chain (after a failed or stuck tx).
+    pub async fn resync_nonce(&self) -> Result<u64, TradeError> {
+        nonce::resync_nonce(&self.provider, self.wallet_address, &self.nonce).await
     }
 
-    async fn get_base_fee(&self) -> Result<u128, String> {
+    async fn get_base_fee(&self) -> Result<u128, TradeError> {
         let block = self
             .provider
             .get_block_by_number(alloy::eips::BlockNumberOrTag::Latest)
             .await
-            .map_err(|e| format!("Failed to get block: {}", e))?
-            .ok_or("No block found")?;
+            .map_err(|e| TradeError::classify("Failed to get block", e))?
+            .ok_or_else(|| TradeError::RpcError("No block found".to_string()))?;
 
         block
             .header
             .base_fee_per_gas
             .map(|fee| fee as u128)
-            .ok_or("No base fee".to_string())
+            .ok_or_else(|| TradeError::RpcError("No base fee".to_string()))
     }
 }
@@ -0,0 +1,174 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Transaction submission: public mempool or a private relay.
+//!
+//! Public trades are easy to sandwich. With `PRIVATE_TX_URL` set, signed
+//! transactions go to the relay instead and only fall back to the public
+//! mempool when the relay rejects them. Sells always use it; DEX and
+//! bonding-curve buys do with `PRIVATE_TX_BUYS`.
+
+use crate::config::Config;
+use crate::executor::TradeError;
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::{Ethereum, EthereumWallet, TransactionBuilder};
+use alloy::primitives::Address;
+use alloy::providers::{DynProvider, PendingTransactionBuilder, Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use alloy::transports::http::reqwest::Url;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Future returned by `TxSubmitter::submit`.
+pub type SubmitFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<PendingTransactionBuilder<Ethereum>, TradeError>> + Send + 'a>>;
+
+/// Sends a transaction somewhere it can be mined.
+/// The returned pending tx watches the main RPC, whichever path was used.
+pub trait TxSubmitter: Send + Sync {
+    fn submit(&self, tx: TransactionRequest) -> SubmitFuture<'_>;
+}
+
+/// Submitter for an executor: the private relay if configured and `private` is set,
+/// otherwise the public mempool.
+pub fn build_submitter<P: Provider + Clone + 'static>(
+    provider: P,
+    wallet: EthereumWallet,
+    config: &Config,
+    private: bool,
+) -> Result<Arc<dyn TxSubmitter>, TradeError> {
+    match (&config.private_tx_url, private) {
+        (Some(url), true) => Ok(Arc::new(PrivateRelay::new(
+            provider,
+            wallet,
+            url,
+            config.wallet_address,
+            config.chain_id,
+        )?)),
+        _ => Ok(Arc::new(PublicMempool::new(provider))),
+    }
+}
+
+/// Broadcast through the main RPC (public mempool).
+pub struct PublicMempool<P> {
+    provider: P,
+}
+
+impl<P> PublicMempool<P> {
+    pub fn new(provider: P) -> Self {
+        Self { provider }
+    }
+}
+
+impl<P: Provider + Clone> TxSubmitter for PublicMempool<P> {
+    fn submit(&self, tx: TransactionRequest) -> SubmitFuture<'_> {
+        Box::pin(async move {
+            let pending = self
+                .provider
+                .send_transaction(tx)
+                .await
+                .map_err(|e| TradeError::classify("Failed to send tx", e))?;
+            info!("📡 TX {:?} sent via public mempool", pending.tx_hash());
+            Ok(pending)
+        })
+    }
+}
+
+/// Sign locally and send the raw tx to a private relay, falling back to public.
+pub struct PrivateRelay<P> {
+    relay: DynProvider,
+    public: PublicMempool<P>,
+    wallet: EthereumWallet,
+    wallet_address: Address,
+    chain_id: u64,
+}
+
+impl<P: Provider + Clone> PrivateRelay<P> {
+    pub fn new(
+        provider: P,
+        wallet: EthereumWallet,
+        relay_url: &str,
+        wallet_address: Address,
+        chain_id: u64,
+    ) -> Result<Self, TradeError> {
+        let url: Url = relay_url
+            .parse()
+            .map_err(|e| TradeError::Config(format!("Invalid private relay URL: {}", e)))?;
+
+        info!("🕶️ Private relay enabled: {}", relay_url);
+
+        Ok(Self {
+            relay: ProviderBuilder::new().connect_http(url).erased(),
+            public: PublicMempool { provider },
+            wallet,
+            wallet_address,
+            chain_id,
+        })
+    }
+
+    /// Fill whatever the executor left to the provider's fillers, sign, and send raw.
+    async fn send_private(
+        &self,
+        mut tx: TransactionRequest,
+    ) -> Result<PendingTransactionBuilder<Ethereum>, TradeError> {
+        let provider = &self.public.provider;
+
+        tx.from = Some(self.wallet_address);
+        tx.chain_id.get_or_insert(self.chain_id);
+        if tx.nonce.is_none() {
+            let nonce = provider
+                .get_transaction_count(self.wallet_address)
+                .await
+                .map_err(|e| TradeError::classify("Failed to get nonce", e))?;
+            tx.nonce = Some(nonce);
+        }
+        if tx.gas.is_none() {
+            let gas = provider
+                .estimate_gas(tx.clone())
+                .await
+                .map_err(|e| TradeError::classify("Failed to estimate gas", e))?;
+            tx.gas = Some(gas);
+        }
+        if tx.gas_price.is_none() && tx.max_fee_per_gas.is_none() {
+            let fees = provider
+                .estimate_eip1559_fees()
+                .await
+                .map_err(|e| TradeError::classify("Failed to estimate fees", e))?;
+            tx.max_fee_per_gas = Some(fees.max_fee_per_gas);
+            tx.max_priority_fee_per_gas = Some(fees.max_priority_fee_per_gas);
+        }
+
+        let envelope = tx
+            .build(&self.wallet)
+            .await
+            .map_err(|e| TradeError::RpcError(format!("Failed to sign tx: {}", e)))?;
+
+        let pending = self
+            .relay
+            .send_raw_transaction(&envelope.encoded_2718())
+            .await
+            .map_err(|e| TradeError::classify("Private relay rejected tx", e))?;
+
+        // Watch for the receipt on the main RPC; the relay may not serve it
+        Ok(PendingTransactionBuilder::new(provider.root().clone(), *pending.tx_hash()))
+    }
+}
+
+impl<P: Provider + Clone> TxSubmitter for PrivateRelay<P> {
+    fn submit(&self, tx: TransactionRequest) -> SubmitFuture<'_> {
+        Box::pin(async move {
+            match self.send_private(tx.clone()).await {
+                Ok(pending) => {
+                    info!("🕶️ TX {:?} sent via private relay", pending.tx_hash());
+                    Ok(pending)
+                }
+                Err(e) => {
+                    warn!("⚠️ Private relay failed ({}), falling back to public mempool", e);
+                    self.public.submit(tx).await
+                }
+            }
+        })
+    }
+}
@@ -4,15 +4,26 @@
 //! Swap execution for buying tokens.
 
 use crate::config::Config;
-use crate::executor::GasStrategy;
+use crate::executor::bump::{confirm_with_bumps, GasBump};
+use crate::executor::funds::check_affordable;
+use crate::executor::receipt::{tokens_received, wait_receipt, TokenReceived};
+use crate::executor::{nonce, paper, wmon};
+use crate::executor::gas::jitter_priority;
+use crate::executor::{build_submitter, GasStrategy, TradeError, TxSubmitter};
+use crate::jitter::Jitter;
+use crate::token_meta::{TokenMeta, TokenMetadataCache};
+use crate::units::wei_to_mon;
 use crate::strategies::BuyDecision;
+use alloy::eips::BlockId;
 use alloy::network::EthereumWallet;
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{Address, B256, U256};
 use alloy::providers::Provider;
 use alloy::rpc::types::TransactionRequest;
 use alloy::sol;
 use std::sync::atomic::{AtomicU64, Ordering};
-use tracing::{debug, error, info};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
 // Router interface for swaps
 sol! {
@@ -30,6 +41,15 @@ sol! {
     }
 }
 
+// ERC20 for balance fallback
+sol! {
+    #[sol(rpc)]
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+        function totalSupply() external view returns (uint256);
+    }
+}
+
 /// Swap executor for buying tokens.
 pub struct SwapExecutor<P: Provider + Clone> {
     provider: P,
@@ -39,21 +59,33 @@ pub struct SwapExecutor<P: Provider + Clone> {
     wallet_address: Address,
     gas_limit: u64,
     gas_strategy: GasStrategy,
+    /// Random spread on the priority fee.
+    gas_jitter: Jitter,
     nonce: AtomicU64,
+    auto_wrap: bool,
+    submitter: Arc<dyn TxSubmitter>,
+    slippage_pct: f64,
+    /// Quote but never send (see `paper`).
+    paper_trading: bool,
+    /// How long to wait for a receipt before giving up on it.
+    receipt_timeout: Duration,
+    /// Resending buys that sit unconfirmed.
+    gas_bump: GasBump,
+    /// Name / symbol / decimals of tokens already looked up.
+    metadata: TokenMetadataCache,
 }
 
-impl<P: Provider + Clone> SwapExecutor<P> {
+impl<P: Provider + Clone + 'static> SwapExecutor<P> {
     /// Create a new swap executor.
     pub async fn new(
         provider: P,
         wallet: EthereumWallet,
         config: &Config,
-    ) -> Result<Self, String> {
-        // Get current nonce
-        let nonce = provider
-            .get_transaction_count(config.wallet_address)
-            .await
-            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+    ) -> Result<Self, TradeError> {
+        // Get current nonce (counting our own txs still in the mempool)
+        let nonce = nonce::pending_nonce(&provider, config.wallet_address).await?;
+
+        let submitter = build_submitter(provider.clone(), wallet.clone(), config, config.private_tx_buys)?;
 
         Ok(Self {
             provider,
@@ -62,21 +94,43 @@ impl<P: Provider + Clone> SwapExecutor<P> {
             wmon: config.wmon_address,
             wallet_address: config.wallet_address,
             gas_limit: config.gas_limit,
-            gas_strategy: GasStrategy::from_multiplier(config.gas_multiplier),
+            gas_strategy: GasStrategy::from_multiplier(config.snipe_gas_multiplier),
+            gas_jitter: config.gas_jitter(),
             nonce: AtomicU64::new(nonce),
+            auto_wrap: config.auto_wrap,
+            submitter,
+            slippage_pct: config.buy_slippage_pct,
+            paper_trading: config.paper_trading,
+            receipt_timeout: config.receipt_timeout(),
+            gas_bump: GasBump::from_config(config),
+            metadata: TokenMetadataCache::new(),
         })
     }
 
     /// Execute a buy transaction.
-    pub async fn buy(&self, decision: &BuyDecision) -> Result<alloy::primitives::B256, String> {
+    /// Returns the tx hash and the tokens received (decoded from the receipt).
+    pub async fn buy(&self, decision: &BuyDecision) -> Result<TokenReceived, TradeError> {
         info!(
             "🚀 Executing BUY: {} ({}) for {} wei",
             decision.name, decision.symbol, decision.amount_wei
         );
 
+        // The router takes native MON; cover a native shortfall from idle WMON
+        if self.auto_wrap && !self.paper_trading {
+            wmon::unwrap_shortfall(
+                &self.provider,
+                self.wmon,
+                self.wallet_address,
+                decision.amount_wei,
+                &self.nonce,
+                self.receipt_timeout,
+            )
+            .await?;
+        }
+
         // Get current base fee
         let base_fee = self.get_base_fee().await?;
-        let (max_fee, priority_fee) = self.gas_strategy.calculate(base_fee);
+        let (max_fee, priority_fee) = jitter_priority(&self.gas_jitter, self.gas_strategy.calculate(base_fee));
 
         debug!(
             "Gas: base_fee={}, max_fee={}, priority={}",
@@ -92,12 +146,25 @@ impl<P: Provider + Clone> SwapExecutor<P> {
             .getAmountsOut(decision.amount_wei, path.clone())
             .call()
             .await
-            .map_err(|e| format!("getAmountsOut failed: {}", e))?;
+            .map_err(|e| TradeError::quote("getAmountsOut failed", e))?;
+
+        let expected_out = final_amount_out(&amounts_out, path.len())?;
+        let min_out = min_amount_out(expected_out, self.slippage_pct);
+        debug!(
+            "Expected out: {}, Min out ({}% slippage): {}",
+            expected_out, self.slippage_pct, min_out
+        );
+
+        if self.paper_trading {
+            return Ok(TokenReceived {
+                tx_hash: paper::paper_fill("BUY", decision.token, decision.amount_wei, expected_out),
+                amount: expected_out,
+            });
+        }
 
-        // 5% slippage tolerance
-        let amounts = amounts_out;
-        let min_out = amounts[1] * U256::from(95) / U256::from(100);
-        debug!("Expected out: {}, Min out (5% slippage): {}", amounts[1], min_out);
+        // Fail clearly on a low balance instead of burning a nonce on a doomed tx
+        let gas_cost = U256::from(self.gas_limit) * U256::from(max_fee);
+        check_affordable(&self.provider, self.wallet_address, decision.amount_wei, gas_cost).await?;
 
         // Build swap calldata
         let deadline = U256::from(chrono::Utc::now().timestamp() as u64 + 300); // 5 min deadline
@@ -124,23 +191,27 @@ impl<P: Provider + Clone> SwapExecutor<P> {
             .max_priority_fee_per_gas(priority_fee);
 
         // Send transaction
-        let pending = self
-            .provider
-            .send_transaction(tx)
-            .await
-            .map_err(|e| {
-                // Rollback nonce on failure
-                self.nonce.fetch_sub(1, Ordering::SeqCst);
-                format!("Failed to send tx: {}", e)
-            })?;
+        let pending = match self.submitter.submit(tx.clone()).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                // The tx may still have reached the node; re-read the nonce instead of guessing
+                let _ = self.resync_nonce().await;
+                return Err(e);
+            }
+        };
 
         info!("📤 Transaction sent: {:?}", pending.tx_hash());
 
-        // Wait for receipt
-        let receipt = pending
-            .get_receipt()
-            .await
-            .map_err(|e| format!("Failed to get receipt: {}", e))?;
+        // Wait for receipt, resending at the same nonce with more gas while it sits unconfirmed
+        let tx_hash = *pending.tx_hash();
+        let receipt = if self.gas_bump.enabled() {
+            confirm_with_bumps(&self.provider, tx_hash, &self.gas_bump, self.receipt_timeout, |attempt| {
+                self.resend_buy(tx.clone(), max_fee, priority_fee, attempt)
+            })
+            .await?
+        } else {
+            wait_receipt(tx_hash, self.receipt_timeout, "Failed to get receipt", pending.get_receipt()).await?
+        };
 
         if receipt.status() {
             info!(
@@ -152,23 +223,321 @@ impl<P: Provider + Clone> SwapExecutor<P> {
                 "❌ BUY FAILED: {} ({}) - tx: {:?}",
                 decision.name, decision.symbol, receipt.transaction_hash
             );
+            return Err(TradeError::Reverted {
+                hash: format!("{:?}", receipt.transaction_hash),
+            });
+        }
+
+        let amount = match tokens_received(receipt.logs(), decision.token, self.wallet_address) {
+            Some(amount) => amount,
+            None => {
+                warn!("⚠️ No Transfer log in receipt, falling back to balance change");
+                self.balance_change(decision.token, receipt.block_number).await?
+            }
+        };
+        info!("📊 Received {} tokens", amount);
+
+        Ok(TokenReceived {
+            tx_hash: format!("{:?}", receipt.transaction_hash),
+            amount,
+        })
+    }
+
+    /// Resend a stuck buy (same nonce and calldata) with fees bumped for `attempt`.
+    /// `Ok(None)` if the bumped max fee would pass the ceiling.
+    async fn resend_buy(
+        &self,
+        tx: TransactionRequest,
+        max_fee: u128,
+        priority_fee: u128,
+        attempt: u32,
+    ) -> Result<Option<B256>, TradeError> {
+        let max_fee = self.gas_bump.scale(max_fee, attempt);
+        if !self.gas_bump.allows(max_fee) {
+            return Ok(None);
         }
+        let tx = tx
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(self.gas_bump.scale(priority_fee, attempt));
+        let pending = self.submitter.submit(tx).await?;
+        Ok(Some(*pending.tx_hash()))
+    }
+
+    /// Tokens gained across the buy's block: balance at `block` minus the block before.
+    /// Without a block number, the whole current balance (may include earlier holdings).
+    async fn balance_change(&self, token: Address, block: Option<u64>) -> Result<U256, TradeError> {
+        let contract = IERC20::new(token, &self.provider);
+        let balance_at = |block: Option<u64>| {
+            let call = contract.balanceOf(self.wallet_address);
+            async move {
+                let balance = match block {
+                    Some(number) => call.block(BlockId::number(number)).call().await,
+                    None => call.call().await,
+                };
+                balance.map_err(|e| TradeError::classify("Failed to get balance", e))
+            }
+        };
+
+        let after = balance_at(block).await?;
+        let before = match block {
+            Some(number) if number > 0 => balance_at(Some(number - 1)).await?,
+            _ => U256::ZERO,
+        };
+        Ok(after.saturating_sub(before))
+    }
+
+    /// Token name, symbol and decimals (cached after the first lookup).
+    pub async fn token_meta(&self, token: Address) -> TokenMeta {
+        self.metadata.get(&self.provider, token).await
+    }
+
+    /// Live MCap in USD on the venue this executor buys through: the router's
+    /// MON price for one whole token times the total supply.
+    pub async fn market_cap_usd(&self, token: Address, mon_price_usd: f64) -> Result<f64, TradeError> {
+        let meta = self.token_meta(token).await;
+        let path = vec![token, self.wmon];
+        let amounts = IRouter::new(self.router, &self.provider)
+            .getAmountsOut(meta.one_token(), path.clone())
+            .call()
+            .await
+            .map_err(|e| TradeError::quote("getAmountsOut failed", e))?;
+        let price_mon = wei_to_mon(final_amount_out(&amounts, path.len())?);
+
+        let supply = IERC20::new(token, &self.provider)
+            .totalSupply()
+            .call()
+            .await
+            .map_err(|e| TradeError::classify("Failed to get total supply", e))?;
+
+        Ok(price_mon * meta.to_tokens(supply) * mon_price_usd)
+    }
 
-        Ok(receipt.transaction_hash)
+    /// Re-read the wallet nonce from the chain (after a failed or stuck tx).
+    pub async fn resync_nonce(&self) -> Result<u64, TradeError> {
+        nonce::resync_nonce(&self.provider, self.wallet_address, &self.nonce).await
     }
 
-    async fn get_base_fee(&self) -> Result<u128, String> {
+    /// Cancel the oldest stuck tx, if any, then resync the nonce.
+    pub async fn unstick(&self) -> Result<Option<B256>, TradeError> {
+        let cancelled = nonce::cancel_pending_tx(&self.provider, self.wallet_address, self.receipt_timeout).await?;
+        self.resync_nonce().await?;
+        Ok(cancelled)
+    }
+
+    async fn get_base_fee(&self) -> Result<u128, TradeError> {
         let block = self
             .provider
             .get_block_by_number(alloy::eips::BlockNumberOrTag::Latest)
             .await
-            .map_err(|e| format!("Failed to get block: {}", e))?
-            .ok_or("No block found")?;
+            .map_err(|e| TradeError::classify("Failed to get block", e))?
+            .ok_or_else(|| TradeError::RpcError("No block found".to_string()))?;
 
         block
             .header
             .base_fee_per_gas
             .map(|fee| fee as u128)
-            .ok_or("No base fee".to_string())
+            .ok_or_else(|| TradeError::RpcError("No base fee".to_string()))
+    }
+}
+
+/// Output of the last hop of a `getAmountsOut` result for a `path_len`-hop path.
+/// A short vector (malformed router, empty revert data) is a failed quote, not a panic.
+pub(crate) fn final_amount_out(amounts: &[U256], path_len: usize) -> Result<U256, TradeError> {
+    if path_len < 2 || amounts.len() < path_len {
+        return Err(TradeError::QuoteFailed(format!(
+            "getAmountsOut returned {} amounts for a {}-token path",
+            amounts.len(),
+            path_len
+        )));
+    }
+    Ok(amounts[path_len - 1])
+}
+
+/// Minimum acceptable output for `expected` at `slippage_pct` tolerance.
+/// Integer math in basis points, so the result never exceeds `expected`.
+pub(crate) fn min_amount_out(expected: U256, slippage_pct: f64) -> U256 {
+    let slippage_bps = (slippage_pct.clamp(0.0, 100.0) * 100.0).round() as u64;
+    expected * U256::from(10_000 - slippage_bps) / U256::from(10_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_block, push_call, push_quantity, MockProvider};
+    use crate::executor::is_paper_tx;
+    use crate::executor::submit::PublicMempool;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::transports::mock::Asserter;
+    use proptest::prelude::*;
+
+    const START_NONCE: u64 = 5;
+
+    fn executor(provider: MockProvider, paper_trading: bool) -> SwapExecutor<MockProvider> {
+        let signer = PrivateKeySigner::from_bytes(&B256::repeat_byte(1)).unwrap();
+        SwapExecutor {
+            submitter: Arc::new(PublicMempool::new(provider.clone())),
+            provider,
+            wallet_address: signer.address(),
+            wallet: EthereumWallet::from(signer),
+            router: Address::repeat_byte(0xaa),
+            wmon: Address::repeat_byte(0xbb),
+            gas_limit: 300_000,
+            gas_strategy: GasStrategy::Normal,
+            gas_jitter: Jitter::new(0.0, Some(0)),
+            nonce: AtomicU64::new(START_NONCE),
+            auto_wrap: false,
+            slippage_pct: 10.0,
+            paper_trading,
+            receipt_timeout: Duration::from_secs(1),
+            gas_bump: GasBump {
+                timeout: Duration::ZERO,
+                bump_pct: 0.0,
+                max_attempts: 0,
+                max_fee_per_gas: 0,
+            },
+            metadata: TokenMetadataCache::new(),
+        }
+    }
+
+    fn decision() -> BuyDecision {
+        BuyDecision {
+            token: Address::repeat_byte(0xcc),
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            amount_wei: U256::from(10u64).pow(U256::from(18)),
+            reason: "test".to_string(),
+            priority: false,
+        }
+    }
+
+    /// Script the calls a live buy makes before sending: base fee, quote, balance.
+    fn push_pre_send(asserter: &Asserter, quoted_out: u64, balance_wei: u64) {
+        push_block(asserter, 1_000_000_000);
+        let amounts = vec![U256::from(10u64).pow(U256::from(18)), U256::from(quoted_out)];
+        push_call::<IRouter::getAmountsOutCall>(asserter, &amounts);
+        push_quantity(asserter, balance_wei);
+    }
+
+    #[tokio::test]
+    async fn failed_send_resyncs_the_nonce_from_the_chain() {
+        let (provider, asserter) = mock_provider();
+        let executor = executor(provider, false);
+        push_pre_send(&asserter, 1_000, u64::MAX);
+        asserter.push_failure_msg("nonce too low");
+        push_quantity(&asserter, 9);
+
+        let err = executor.buy(&decision()).await.unwrap_err();
+        assert!(matches!(err, TradeError::NonceError(_)), "{err}");
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), 9);
+    }
+
+    #[tokio::test]
+    async fn a_sent_tx_consumes_its_nonce_when_resync_fails() {
+        let (provider, asserter) = mock_provider();
+        let executor = executor(provider, false);
+        push_pre_send(&asserter, 1_000, u64::MAX);
+        asserter.push_failure_msg("connection reset");
+        asserter.push_failure_msg("connection reset");
+
+        assert!(executor.buy(&decision()).await.is_err());
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), START_NONCE + 1);
+    }
+
+    #[tokio::test]
+    async fn low_balance_fails_before_taking_a_nonce() {
+        let (provider, asserter) = mock_provider();
+        let executor = executor(provider, false);
+        push_pre_send(&asserter, 1_000, 1);
+
+        let err = executor.buy(&decision()).await.unwrap_err();
+        assert!(matches!(err, TradeError::InsufficientFunds(_)), "{err}");
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), START_NONCE);
+    }
+
+    #[tokio::test]
+    async fn short_quote_fails_the_buy_before_sending() {
+        let (provider, asserter) = mock_provider();
+        let executor = executor(provider, false);
+        push_block(&asserter, 1_000_000_000);
+        push_call::<IRouter::getAmountsOutCall>(&asserter, &vec![U256::from(1u64)]);
+
+        let err = executor.buy(&decision()).await.unwrap_err();
+        assert!(matches!(err, TradeError::QuoteFailed(_)), "{err}");
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), START_NONCE);
+    }
+
+    #[tokio::test]
+    async fn resync_nonce_moves_the_counter_either_way() {
+        let (provider, asserter) = mock_provider();
+        let executor = executor(provider, false);
+        push_quantity(&asserter, 2);
+        assert_eq!(executor.resync_nonce().await.unwrap(), 2);
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), 2);
+
+        asserter.push_failure_msg("request timed out");
+        assert!(matches!(executor.resync_nonce().await, Err(TradeError::Timeout(_))));
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn paper_buy_fills_at_the_quote_without_a_nonce() {
+        let (provider, asserter) = mock_provider();
+        let executor = executor(provider, true);
+        push_pre_send(&asserter, 4_242, 0);
+
+        let fill = executor.buy(&decision()).await.unwrap();
+        assert_eq!(fill.amount, U256::from(4_242u64));
+        assert!(is_paper_tx(&fill.tx_hash));
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), START_NONCE);
+    }
+
+    #[tokio::test]
+    async fn balance_change_spans_the_buy_block() {
+        let (provider, asserter) = mock_provider();
+        let executor = executor(provider, false);
+        let token = Address::repeat_byte(0xcc);
+
+        push_call::<IERC20::balanceOfCall>(&asserter, &U256::from(500u64));
+        push_call::<IERC20::balanceOfCall>(&asserter, &U256::from(200u64));
+        assert_eq!(executor.balance_change(token, Some(100)).await.unwrap(), U256::from(300u64));
+
+        // No block number: the whole current balance
+        push_call::<IERC20::balanceOfCall>(&asserter, &U256::from(700u64));
+        assert_eq!(executor.balance_change(token, None).await.unwrap(), U256::from(700u64));
+
+        // A balance that went down (sold in the same block) is no gain, not an underflow
+        push_call::<IERC20::balanceOfCall>(&asserter, &U256::from(100u64));
+        push_call::<IERC20::balanceOfCall>(&asserter, &U256::from(200u64));
+        assert_eq!(executor.balance_change(token, Some(100)).await.unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn min_amount_out_applies_and_clamps_the_tolerance() {
+        let expected = U256::from(1_000_000u64);
+        assert_eq!(min_amount_out(expected, 10.0), U256::from(900_000u64));
+        assert_eq!(min_amount_out(expected, 0.5), U256::from(995_000u64));
+        assert_eq!(min_amount_out(expected, 0.0), expected);
+        assert_eq!(min_amount_out(expected, -5.0), expected);
+        assert_eq!(min_amount_out(expected, 150.0), U256::ZERO);
+    }
+
+    #[test]
+    fn short_quote_is_a_failed_quote() {
+        let amounts = [U256::from(1u64), U256::from(2u64)];
+        assert_eq!(final_amount_out(&amounts, 2).unwrap(), U256::from(2u64));
+        assert!(matches!(final_amount_out(&amounts[..1], 2), Err(TradeError::QuoteFailed(_))));
+        assert!(final_amount_out(&amounts, 1).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn min_amount_out_stays_within_the_tolerance(expected in any::<u128>(), slippage_pct in 0.0..100.0f64) {
+            let expected = U256::from(expected);
+            let min_out = min_amount_out(expected, slippage_pct);
+            prop_assert!(min_out <= expected);
+            // At most half a basis point of rounding past the requested tolerance
+            let floor = expected * U256::from(10_000 - (slippage_pct * 100.0).ceil() as u64) / U256::from(10_000);
+            prop_assert!(min_out >= floor);
+        }
     }
 }
@@ -0,0 +1,228 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! WMON -> native MON unwrapping helpers.
+//!
+//! The router's ETH-style entrypoints move native MON, while WMON-based pairs
+//! and arbitrage legs leave WMON behind. These helpers unwrap it so it stays
+//! spendable on buys.
+
+use crate::executor::receipt::wait_receipt;
+use crate::executor::{SellExecutor, TradeError};
+use crate::units::{mon_to_wei, wei_to_mon};
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Gas limit for a WMON withdraw (a simple WETH9-style call).
+const WRAP_GAS_LIMIT: u64 = 100_000;
+
+sol! {
+    #[sol(rpc)]
+    interface IWMON {
+        function withdraw(uint256 amount) external;
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+/// Unwrap `amount` WMON back to native MON.
+pub async fn unwrap_wmon<P: Provider>(
+    provider: &P,
+    wmon: Address,
+    amount: U256,
+    nonce: &AtomicU64,
+    receipt_timeout: Duration,
+) -> Result<B256, TradeError> {
+    let input = IWMON::new(wmon, provider).withdraw(amount).calldata().clone();
+    info!("📦 Unwrapping {} WMON -> MON", amount);
+    send(provider, wmon, input, U256::ZERO, nonce, receipt_timeout, "Unwrap").await
+}
+
+/// WMON balance of `wallet`.
+pub async fn wmon_balance<P: Provider>(
+    provider: &P,
+    wmon: Address,
+    wallet: Address,
+) -> Result<U256, TradeError> {
+    IWMON::new(wmon, provider)
+        .balanceOf(wallet)
+        .call()
+        .await
+        .map_err(|e| TradeError::classify("Failed to get WMON balance", e))
+}
+
+/// Unwrap only what's missing (from WMON, as far as it goes) for `wallet`
+/// to hold `target` native MON. Returns `None` if nothing needed unwrapping.
+pub async fn unwrap_shortfall<P: Provider>(
+    provider: &P,
+    wmon: Address,
+    wallet: Address,
+    target: U256,
+    nonce: &AtomicU64,
+    receipt_timeout: Duration,
+) -> Result<Option<B256>, TradeError> {
+    let native = provider
+        .get_balance(wallet)
+        .await
+        .map_err(|e| TradeError::classify("Failed to get MON balance", e))?;
+    if native >= target {
+        return Ok(None);
+    }
+
+    let wrapped = wmon_balance(provider, wmon, wallet).await?;
+    let amount = (target - native).min(wrapped);
+    if amount == U256::ZERO {
+        return Ok(None);
+    }
+    unwrap_wmon(provider, wmon, amount, nonce, receipt_timeout).await.map(Some)
+}
+
+/// Unwrap the whole WMON balance of `wallet`. Returns `None` if there was none.
+pub async fn unwrap_all<P: Provider>(
+    provider: &P,
+    wmon: Address,
+    wallet: Address,
+    nonce: &AtomicU64,
+    receipt_timeout: Duration,
+) -> Result<Option<B256>, TradeError> {
+    unwrap_above(provider, wmon, wallet, U256::ZERO, nonce, receipt_timeout).await
+}
+
+/// Unwrap the whole WMON balance of `wallet` if it is above `threshold`.
+/// Returns `None` if it wasn't.
+pub async fn unwrap_above<P: Provider>(
+    provider: &P,
+    wmon: Address,
+    wallet: Address,
+    threshold: U256,
+    nonce: &AtomicU64,
+    receipt_timeout: Duration,
+) -> Result<Option<B256>, TradeError> {
+    let balance = wmon_balance(provider, wmon, wallet).await?;
+    if balance == U256::ZERO || balance <= threshold {
+        return Ok(None);
+    }
+    unwrap_wmon(provider, wmon, balance, nonce, receipt_timeout).await.map(Some)
+}
+
+/// Periodically unwrap WMON left behind by SDK trades and WMON pairs, so it
+/// is spendable on buys again. The first sweep runs at startup.
+pub fn spawn_wmon_sweeper<P: Provider + Clone + Send + Sync + 'static>(
+    sell_executor: Arc<SellExecutor<P>>,
+    threshold_mon: f64,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    info!("📦 WMON sweep: unwrapping balances above {} WMON every {}s", threshold_mon, interval_secs);
+
+    tokio::spawn(async move {
+        let threshold = mon_to_wei(threshold_mon);
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            match sell_executor.unwrap_wmon_above(threshold).await {
+                Ok(Some(tx_hash)) => info!("📦 Swept WMON back to MON: {:?}", tx_hash),
+                Ok(None) => debug!("WMON sweep: balance at or below {} WMON", wei_to_mon(threshold)),
+                Err(e) => warn!("⚠️ WMON sweep failed: {}", e),
+            }
+        }
+    })
+}
+
+/// Send a WMON call and wait for it to be mined.
+async fn send<P: Provider>(
+    provider: &P,
+    wmon: Address,
+    input: Bytes,
+    value: U256,
+    nonce: &AtomicU64,
+    receipt_timeout: Duration,
+    label: &str,
+) -> Result<B256, TradeError> {
+    let tx_nonce = nonce.fetch_add(1, Ordering::SeqCst);
+
+    let tx = TransactionRequest::default()
+        .to(wmon)
+        .value(value)
+        .input(input.into())
+        .nonce(tx_nonce)
+        .gas_limit(WRAP_GAS_LIMIT);
+
+    let pending = provider.send_transaction(tx).await.map_err(|e| {
+        nonce.fetch_sub(1, Ordering::SeqCst);
+        TradeError::classify(&format!("{} failed", label), e)
+    })?;
+
+    let tx_hash = *pending.tx_hash();
+    let receipt = wait_receipt(
+        tx_hash,
+        receipt_timeout,
+        &format!("{} receipt failed", label),
+        pending.get_receipt(),
+    )
+    .await?;
+
+    if !receipt.status() {
+        return Err(TradeError::Reverted {
+            hash: format!("{:?}", receipt.transaction_hash),
+        });
+    }
+
+    info!("✅ {} confirmed: {:?}", label, receipt.transaction_hash);
+    Ok(receipt.transaction_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_call, push_quantity};
+
+    const WMON: Address = Address::new([0xee; 20]);
+    const WALLET: Address = Address::new([0xaa; 20]);
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    #[tokio::test]
+    async fn balance_at_or_below_the_threshold_is_left_wrapped() {
+        let (provider, asserter) = mock_provider();
+        let nonce = AtomicU64::new(7);
+        push_call::<IWMON::balanceOfCall>(&asserter, &U256::from(100u64));
+        let swept = unwrap_above(&provider, WMON, WALLET, U256::from(100u64), &nonce, TIMEOUT).await;
+        assert_eq!(swept.unwrap(), None);
+
+        push_call::<IWMON::balanceOfCall>(&asserter, &U256::ZERO);
+        assert_eq!(unwrap_all(&provider, WMON, WALLET, &nonce, TIMEOUT).await.unwrap(), None);
+        assert_eq!(nonce.load(Ordering::SeqCst), 7);
+    }
+
+    #[tokio::test]
+    async fn failed_unwrap_hands_its_nonce_back() {
+        let (provider, asserter) = mock_provider();
+        let nonce = AtomicU64::new(7);
+        push_call::<IWMON::balanceOfCall>(&asserter, &U256::from(500u64));
+        asserter.push_failure_msg("insufficient funds for gas");
+
+        let err = unwrap_above(&provider, WMON, WALLET, U256::from(100u64), &nonce, TIMEOUT).await.unwrap_err();
+        assert!(err.to_string().contains("Unwrap failed"), "{}", err);
+        assert_eq!(nonce.load(Ordering::SeqCst), 7);
+    }
+
+    #[tokio::test]
+    async fn shortfall_unwraps_nothing_when_native_mon_covers_it() {
+        let (provider, asserter) = mock_provider();
+        let nonce = AtomicU64::new(0);
+        push_quantity(&asserter, 1_000);
+        let target = U256::from(1_000u64);
+        assert_eq!(unwrap_shortfall(&provider, WMON, WALLET, target, &nonce, TIMEOUT).await.unwrap(), None);
+
+        // Short, but there's no WMON to cover it
+        push_quantity(&asserter, 400);
+        push_call::<IWMON::balanceOfCall>(&asserter, &U256::ZERO);
+        assert_eq!(unwrap_shortfall(&provider, WMON, WALLET, target, &nonce, TIMEOUT).await.unwrap(), None);
+    }
+}
@@ -5,4 +5,4 @@
 
 pub mod sell_handler;
 
-pub use sell_handler::spawn_sell_handler;
+pub use sell_handler::{emergency_slippage, sell_all, spawn_sell_handler, SellContext, SellRoute};
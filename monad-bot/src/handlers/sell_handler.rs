@@ -2,40 +2,154 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Sell signal handler - processes trailing stop-loss and other sell signals.
-//! Uses SDK for bonding curve tokens, DEX router for graduated tokens.
-//! Features: rate limiting (30s cooldown), retry with higher slippage.
+//! Uses SDK for bonding curve tokens, DEX router for graduated tokens, in the
+//! order set by `SELL_ROUTE_PREFERENCE`.
+//! Features: rate limiting (30s cooldown), retry with higher slippage,
+//! short delayed retries while a token is unsellable mid-graduation, and
+//! flagging positions stuck after repeated total failures.
 
-use crate::executor::{SdkExecutor, SellExecutor};
-use crate::position::{PositionTracker, SellDecision};
+use crate::config::Config;
+use crate::executor::{MonReceived, SdkExecutor, SellExecutor, TradeError};
+use crate::position::{cost_basis, PositionTracker, SellDecision};
+use crate::notify::{Notifications, NotifyEvent};
+use crate::shutdown::InFlightTrades;
+use crate::trade_history::{TradeHistory, TradeRecord, TradeType};
+use crate::units::wei_to_mon;
 use alloy::primitives::{Address, U256};
 use alloy::providers::Provider;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Mutex};
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 /// Cooldown between sell attempts for the same token (prevents spam).
 const SELL_COOLDOWN_SECS: u64 = 30;
 
+/// Delay before retrying a sell that failed on every venue for lack of liquidity.
+const NO_LIQUIDITY_RETRY_DELAY_SECS: u64 = 5;
+
+/// Maximum delayed retries for no-liquidity failures (graduation window is short).
+const NO_LIQUIDITY_MAX_RETRIES: u32 = 3;
+
+/// Where a sell is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Venue {
+    /// nad.fun bonding curve, through the SDK.
+    Sdk,
+    /// DEX router, for graduated tokens.
+    Dex,
+}
+
+impl std::fmt::Display for Venue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sdk => write!(f, "SDK"),
+            Self::Dex => write!(f, "DEX"),
+        }
+    }
+}
+
+/// Order in which sells try the venues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SellRoute {
+    /// Bonding curve, then DEX, whatever the position's graduated flag says.
+    SdkFirst,
+    /// DEX, then bonding curve.
+    DexFirst,
+    /// Graduated positions go to the DEX only; the rest try the curve first.
+    Auto,
+}
+
+impl SellRoute {
+    /// Parse `sdk_first` / `dex_first` / `auto` (case-insensitive). Anything else
+    /// is an error: a typo must not silently change where sells go.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "sdk_first" | "sdk" => Ok(Self::SdkFirst),
+            "dex_first" | "dex" => Ok(Self::DexFirst),
+            "auto" => Ok(Self::Auto),
+            _ => Err(format!("unknown sell route {:?} (expected sdk_first, dex_first or auto)", value)),
+        }
+    }
+
+    /// Venues to try, in order, for a position.
+    pub fn venues(self, graduated: bool) -> &'static [Venue] {
+        match self {
+            Self::SdkFirst => &[Venue::Sdk, Venue::Dex],
+            Self::DexFirst => &[Venue::Dex, Venue::Sdk],
+            Self::Auto if graduated => &[Venue::Dex],
+            Self::Auto => &[Venue::Sdk, Venue::Dex],
+        }
+    }
+}
+
+/// What the sell handler sells with, and the state it updates.
+pub struct SellContext<P: Provider + Clone> {
+    pub sdk_executor: Arc<SdkExecutor>,
+    pub dex_sell_executor: Arc<SellExecutor<P>>,
+    pub positions: Arc<Mutex<PositionTracker>>,
+    pub trade_history: Arc<Mutex<TradeHistory>>,
+    pub in_flight: Arc<InFlightTrades>,
+    pub notifier: Arc<Notifications>,
+    /// Slippage for the second SDK attempt (and emergency exits).
+    pub retry_slippage_pct: f64,
+    /// Consecutive total failures before a position is marked stuck (0 = never).
+    pub max_sell_failures: u32,
+    /// Venue order for sells.
+    pub sell_route: SellRoute,
+}
+
 /// Spawn a background task to handle sell signals from the position monitor.
 /// Uses SDK for bonding curve tokens, falls back to DEX router for graduated tokens.
 /// Includes rate limiting (30s cooldown per token) and retry with higher slippage.
+/// After `max_sell_failures` consecutive total failures (0 = never) the position is
+/// marked stuck, the monitor stops signalling it, and an alert is sent.
 pub fn spawn_sell_handler<P: Provider + Clone + Send + Sync + 'static>(
-    sdk_executor: Arc<SdkExecutor>,
-    dex_sell_executor: Arc<SellExecutor<P>>,
-    positions: Arc<Mutex<PositionTracker>>,
+    context: SellContext<P>,
+    sell_signal_tx: mpsc::Sender<(Address, SellDecision)>,
     mut sell_signal_rx: mpsc::Receiver<(Address, SellDecision)>,
 ) -> tokio::task::JoinHandle<()> {
+    let SellContext {
+        sdk_executor,
+        dex_sell_executor,
+        positions,
+        trade_history,
+        in_flight,
+        notifier,
+        retry_slippage_pct,
+        max_sell_failures,
+        sell_route,
+    } = context;
+
     tokio::spawn(async move {
-        info!("🔔 Sell signal handler started (SDK + DEX fallback, 30s cooldown)");
+        info!("🔔 Sell signal handler started ({:?} routing, 30s cooldown)", sell_route);
         
         // Track last sell attempt per token for rate limiting
         let mut last_sell_attempt: HashMap<Address, Instant> = HashMap::new();
+        // Delayed retries already scheduled per token for no-liquidity failures
+        let mut no_liquidity_retries: HashMap<Address, u32> = HashMap::new();
         
         while let Some((token, decision)) = sell_signal_rx.recv().await {
-            // Rate limiting: check if we've tried selling this token recently
-            let is_force_sell = matches!(decision, SellDecision::CopySell { .. } | SellDecision::HardStopLoss { .. });
+            // The monitor keeps raising a take-profit tier or recoup until its sell fills;
+            // copies queued behind that sell are dropped here
+            if positions.lock().await.get(&token).is_some_and(|position| decision.already_taken(position)) {
+                debug!("Ladder step for {:?} already taken, dropping {:?}", token, decision);
+                continue;
+            }
+
+            // Rate limiting: check if we've tried selling this token recently.
+            // A skipped take-profit tier or recoup isn't lost: it is only recorded
+            // once sold, so the monitor raises it again after the cooldown.
+            let is_force_sell = matches!(
+                decision,
+                SellDecision::CopySell { .. }
+                    | SellDecision::PartialManual { .. }
+                    | SellDecision::HardStopLoss { .. }
+                    | SellDecision::Rug { .. }
+                    | SellDecision::Emergency { .. }
+            );
             
             if !is_force_sell {
                 if let Some(last_attempt) = last_sell_attempt.get(&token) {
@@ -61,60 +175,95 @@ pub fn spawn_sell_handler<P: Provider + Clone + Send + Sync + 'static>(
             let pos_guard = positions.lock().await;
             if let Some(position) = pos_guard.get(&token) {
                 let amount = position.amount;
+                let graduated = position.graduated;
                 let name = position.name.clone();
                 let symbol = position.symbol.clone();
                 drop(pos_guard); // Release lock before async operation
+
+                // Held until this sell settles, so shutdown waits for it
+                let Some(_trade) = in_flight.start() else {
+                    info!("🛑 Shutting down, not selling {} ({})", name, symbol);
+                    continue;
+                };
                 
                 info!(
                     "🔴 Executing SELL: {} ({}) - {:?}",
                     name, symbol, decision
                 );
                 
-                // Calculate sell amount based on decision
-                let sell_amount = match &decision {
-                    SellDecision::SecureProfit { portion, .. } => {
-                        // Partial sell
-                        amount * U256::from((*portion * 100.0) as u64) / U256::from(100)
-                    }
-                    _ => amount, // Full sell
-                };
+                // Calculate sell amount based on decision (partial or full)
+                let sell_amount = decision.sell_amount(amount);
                 
-                // Try SDK first (for bonding curve tokens)
-                let sdk_result = sdk_executor.sell_token(token, sell_amount).await;
-                
-                match sdk_result {
-                    Ok(tx_hash) => {
-                        info!("✅ SDK Sell executed: {}", tx_hash);
-                        update_position_after_sell(&positions, token, &decision, amount).await;
+                // Emergency exits can't afford a failed first attempt; sell at the retry slippage outright
+                let emergency = matches!(decision, SellDecision::Emergency { .. });
+
+                // Try each venue in the configured order until one fills
+                let (sdk, dex, reason) = (&*sdk_executor, &*dex_sell_executor, &decision);
+                let sold = sell_across(sell_route.venues(graduated), move |venue| async move {
+                    match venue {
+                        Venue::Sdk => sell_on_sdk(sdk, token, sell_amount, emergency, retry_slippage_pct).await,
+                        Venue::Dex => sell_on_dex(sdk, dex, token, sell_amount, reason).await,
                     }
-                    Err(sdk_error) => {
-                        warn!("⚠️ SDK sell failed: {}", sdk_error);
-                        
-                        // Retry with higher slippage (25%) - will be implemented in SDK executor
-                        info!("🔄 Retrying SDK sell with higher slippage...");
-                        match sdk_executor.sell_token_with_slippage(token, sell_amount, 25.0).await {
-                            Ok(tx_hash) => {
-                                info!("✅ SDK Sell (retry 25% slippage) executed: {}", tx_hash);
-                                update_position_after_sell(&positions, token, &decision, amount).await;
-                            }
-                            Err(retry_error) => {
-                                warn!("⚠️ SDK retry failed: {}, trying DEX...", retry_error);
-                                
-                                // Fallback to DEX router for graduated tokens
-                                match dex_sell_executor.sell(token, sell_amount, &decision).await {
-                                    Ok(tx_hash) => {
-                                        info!("✅ DEX Sell executed: {:?}", tx_hash);
-                                        update_position_after_sell(&positions, token, &decision, amount).await;
-                                    }
-                                    Err(dex_error) => {
-                                        error!("❌ All sell attempts failed!");
-                                        error!("   SDK (15%): {}", sdk_error);
-                                        error!("   SDK (25%): {}", retry_error);
-                                        error!("   DEX: {}", dex_error);
-                                    }
-                                }
-                            }
-                        }
+                })
+                .await;
+                let errors = match sold {
+                    Ok(fill) => {
+                        no_liquidity_retries.remove(&token);
+                        update_position_after_sell(&positions, &trade_history, token, &decision, amount, &fill).await;
+                        continue;
+                    }
+                    Err(errors) => errors,
+                };
+
+                // The sell may still land; retrying now could sell twice.
+                // The cooldown lets the position settle before the next attempt.
+                if errors.iter().any(|(_, e)| e.is_receipt_timeout()) {
+                    warn!("⏳ Not retrying sell of {:?} until the pending tx settles", token);
+                    continue;
+                }
+
+                error!(token = %token, "❌ All sell attempts failed!");
+                for (venue, e) in &errors {
+                    error!("   {}: {}", venue, e);
+                }
+                let last_error = errors
+                    .last()
+                    .map(|(_, e)| e.to_string())
+                    .unwrap_or_else(|| "no venue tried".to_string());
+
+                if positions.lock().await.record_sell_failure(&token, max_sell_failures) {
+                    error!(
+                        "🧱 {} ({}) failed to sell {} times in a row - marked stuck",
+                        name, symbol, max_sell_failures
+                    );
+                    notifier.notify(NotifyEvent::Error, || format!(
+                        "🧱 *Position Stuck*\n{} ({})\n`{:?}`\nSell failed {} times in a row on every venue; automatic sells stopped.\nSell manually, or clear `stuck` in positions.json to resume.\nLast error: {}",
+                        name, symbol, token, max_sell_failures, last_error
+                    ));
+                }
+
+                // Mid-graduation neither venue can route the sell; the
+                // window is transient, so retry soon instead of waiting
+                // out the spam cooldown.
+                let no_liquidity = !errors.is_empty() && errors.iter().all(|(_, e)| e.is_no_liquidity());
+                if no_liquidity {
+                    let attempts = no_liquidity_retries.entry(token).or_insert(0);
+                    *attempts += 1;
+                    if *attempts <= NO_LIQUIDITY_MAX_RETRIES {
+                        warn!(
+                            "🎓 No liquidity on any venue for {:?} (graduating?) - retry {}/{} in {}s",
+                            token, attempts, NO_LIQUIDITY_MAX_RETRIES, NO_LIQUIDITY_RETRY_DELAY_SECS
+                        );
+                        last_sell_attempt.remove(&token);
+                        let retry_tx = sell_signal_tx.clone();
+                        let retry_decision = decision.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(NO_LIQUIDITY_RETRY_DELAY_SECS)).await;
+                            let _ = retry_tx.send((token, retry_decision)).await;
+                        });
+                    } else {
+                        warn!("⚠️ Giving up no-liquidity retries for {:?}", token);
+                        no_liquidity_retries.remove(&token);
                     }
                 }
             }
@@ -124,21 +273,200 @@ pub fn spawn_sell_handler<P: Provider + Clone + Send + Sync + 'static>(
     })
 }
 
+/// Sell on each of `venues` in turn until one fills. A receipt timeout stops
+/// the walk, since that sell may still land. Errors are every attempt's, in order.
+async fn sell_across<F, Fut>(venues: &[Venue], mut sell: F) -> Result<MonReceived, Vec<(Venue, TradeError)>>
+where
+    F: FnMut(Venue) -> Fut,
+    Fut: Future<Output = Result<MonReceived, Vec<TradeError>>>,
+{
+    let mut errors: Vec<(Venue, TradeError)> = Vec::new();
+    for &venue in venues {
+        match sell(venue).await {
+            Ok(fill) => return Ok(fill),
+            Err(venue_errors) => {
+                let pending = venue_errors.iter().any(TradeError::is_receipt_timeout);
+                errors.extend(venue_errors.into_iter().map(|e| (venue, e)));
+                if pending {
+                    break;
+                }
+                debug!("{} sell failed, trying the next venue", venue);
+            }
+        }
+    }
+    Err(errors)
+}
+
+/// Bonding curve sell, retried once at `retry_slippage_pct` when the failure
+/// is a transient one more slippage could fix. Errors are every attempt's, in order.
+async fn sell_on_sdk(
+    sdk_executor: &SdkExecutor,
+    token: Address,
+    sell_amount: U256,
+    emergency: bool,
+    retry_slippage_pct: f64,
+) -> Result<MonReceived, Vec<TradeError>> {
+    let first = if emergency {
+        sdk_executor.sell_token_with_slippage(token, sell_amount, retry_slippage_pct).await
+    } else {
+        sdk_executor.sell_token(token, sell_amount).await
+    };
+    let sdk_error = match first {
+        Ok(fill) => {
+            info!(token = %token, tx_hash = %fill.tx_hash, venue = "sdk", "✅ SDK Sell executed: {}", fill.tx_hash);
+            return Ok(fill);
+        }
+        Err(e) => e,
+    };
+    warn!("⚠️ SDK sell failed: {}", sdk_error);
+
+    if let Some(reason) = skip_slippage_retry(&sdk_error, emergency) {
+        info!("{}, skipping slippage retry", reason);
+        return Err(vec![sdk_error]);
+    }
+
+    info!("🔄 Retrying SDK sell with higher slippage...");
+    match sdk_executor.sell_token_with_slippage(token, sell_amount, retry_slippage_pct).await {
+        Ok(fill) => {
+            info!(
+                token = %token,
+                tx_hash = %fill.tx_hash,
+                venue = "sdk",
+                slippage_pct = retry_slippage_pct,
+                "✅ SDK Sell (retry {}% slippage) executed: {}",
+                retry_slippage_pct,
+                fill.tx_hash
+            );
+            Ok(fill)
+        }
+        Err(retry_error) => {
+            warn!("⚠️ SDK retry failed: {}", retry_error);
+            Err(vec![sdk_error, retry_error])
+        }
+    }
+}
+
+/// Why a failed curve sell shouldn't be retried at more slippage, if it shouldn't.
+fn skip_slippage_retry(sdk_error: &TradeError, emergency: bool) -> Option<&'static str> {
+    if sdk_error.is_receipt_timeout() {
+        Some("⏳ SDK sell may still land")
+    } else if sdk_error.is_no_liquidity() {
+        // More slippage can't help when the bonding curve has no route (usually graduated)
+        Some("🎓 No bonding curve route")
+    } else if !sdk_error.is_transient() {
+        // e.g. no tokens or no gas: the same sell would fail again
+        Some("🚫 SDK sell failure isn't transient")
+    } else if emergency {
+        Some("🔥 Emergency sell already went out at the retry slippage")
+    } else {
+        None
+    }
+}
+
+/// DEX router sell, on the DEX the SDK routes the token to when it knows.
+async fn sell_on_dex<P: Provider + Clone + Send + Sync + 'static>(
+    sdk_executor: &SdkExecutor,
+    dex_sell_executor: &SellExecutor<P>,
+    token: Address,
+    sell_amount: U256,
+    decision: &SellDecision,
+) -> Result<MonReceived, Vec<TradeError>> {
+    let sdk_router = sdk_executor.sell_router(token).await.ok();
+    match dex_sell_executor.sell(token, sell_amount, decision, sdk_router).await {
+        Ok(fill) => {
+            info!(token = %token, tx_hash = %fill.tx_hash, venue = "dex", "✅ DEX Sell executed: {}", fill.tx_hash);
+            Ok(fill)
+        }
+        Err(dex_error) => {
+            warn!("⚠️ DEX sell failed: {}", dex_error);
+            Err(vec![dex_error])
+        }
+    }
+}
+
+/// Slippage emergency sells go out at, for the messages announcing a panic sell:
+/// the curve sells at `SELL_RETRY_SLIPPAGE_PCT` outright, the DEX at its usual slippage.
+pub fn emergency_slippage(config: &Config) -> String {
+    format!(
+        "{}% slippage on the curve, {}% on the DEX",
+        config.sell_retry_slippage_pct, config.dex_sell_slippage_pct
+    )
+}
+
+/// Queue a forced full sell of every open position. Returns how many were queued.
+pub async fn sell_all(
+    positions: &Mutex<PositionTracker>,
+    sell_signal_tx: &mpsc::Sender<(Address, SellDecision)>,
+    reason: &str,
+) -> usize {
+    let tokens: Vec<Address> = positions.lock().await.all().iter().map(|p| p.token).collect();
+
+    warn!("🚨 SELL ALL: {} positions ({})", tokens.len(), reason);
+    let mut queued = 0;
+    for token in tokens {
+        let decision = SellDecision::Emergency {
+            reason: reason.to_string(),
+        };
+        match sell_signal_tx.send((token, decision)).await {
+            Ok(()) => queued += 1,
+            Err(e) => error!("❌ Failed to queue emergency sell for {:?}: {}", token, e),
+        }
+    }
+    queued
+}
+
+/// Apply a confirmed sell: shrink or close the position and record the sell
+/// with its realized PnL against the position's cost basis.
 async fn update_position_after_sell(
     positions: &Arc<Mutex<PositionTracker>>,
+    trade_history: &Mutex<TradeHistory>,
     token: Address,
     decision: &SellDecision,
     original_amount: U256,
+    fill: &MonReceived,
 ) {
+    let requested = decision.sell_amount(original_amount);
+    // The executors clamp to the wallet balance, so trust what they report as sold
+    let sold = fill.sold.min(requested);
+    let mon_received = wei_to_mon(fill.amount);
+
     let mut pos_guard = positions.lock().await;
-    match decision {
-        SellDecision::SecureProfit { portion, .. } => {
-            // Partial sell - update amount
+    pos_guard.reset_sell_failures(&token);
+    let Some(pos) = pos_guard.get(&token) else {
+        return;
+    };
+
+    let cost = cost_basis(pos.buy_price_mon, sold, pos.amount);
+    let record = TradeRecord {
+        token,
+        token_name: pos.name.clone(),
+        token_symbol: pos.symbol.clone(),
+        trade_type: TradeType::Sell,
+        amount_tokens: sold.to_string(),
+        amount_mon: mon_received,
+        timestamp: chrono::Utc::now().timestamp() as u64,
+        tx_hash: fill.tx_hash.clone(),
+        realized_pnl_mon: Some(mon_received - cost),
+    };
+
+    // A partial that rounds up to the whole position (e.g. 100%) closes it like a full sell
+    match decision.portion() {
+        Some(_) if sold < pos.amount => {
+            // Partial sell - keep the remainder's basis and high in line with its size
             if let Some(pos) = pos_guard.get_mut(&token) {
-                let sold = original_amount * U256::from((*portion * 100.0) as u64) / U256::from(100);
-                pos.amount -= sold;
-                info!("📊 Updated position: {} tokens remaining", pos.amount);
+                decision.record_taken(pos);
+                let remaining = pos.amount.saturating_sub(sold);
+                let kept = if pos.buy_price_mon > 0.0 {
+                    (pos.buy_price_mon - cost) / pos.buy_price_mon
+                } else {
+                    0.0
+                };
+                pos.amount = remaining;
+                pos.buy_price_mon -= cost;
+                pos.highest_price *= kept;
+                info!("📊 Updated position: {:.4} tokens remaining", pos.amount_tokens());
             }
+            let _ = pos_guard.save();
         }
         _ => {
             // Full sell - remove position
@@ -146,4 +474,236 @@ async fn update_position_after_sell(
             info!("📊 Position closed");
         }
     }
+    drop(pos_guard);
+
+    trade_history.lock().await.record(record);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::Position;
+    use crate::units::mon_to_wei;
+
+    const TOKEN: Address = Address::repeat_byte(1);
+
+    /// 1,000 tokens bought for 2 MON.
+    fn tracker() -> Arc<Mutex<PositionTracker>> {
+        let mut tracker = PositionTracker::in_memory();
+        tracker.add(Position {
+            token: TOKEN,
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            amount: U256::from(1_000u64),
+            buy_price_mon: 2.0,
+            buy_time: 1_700_000_000,
+            highest_price: 4.0,
+            tx_hash: "0xa".to_string(),
+            provisional: false,
+            fired_tp_levels: Vec::new(),
+            sell_failures: 2,
+            stuck: false,
+            graduated: false,
+            peak_liquidity_mon: 0.0,
+            in_runner_mode: false,
+            decimals: 18,
+        });
+        Arc::new(Mutex::new(tracker))
+    }
+
+    fn fill(mon: f64, sold: u64) -> MonReceived {
+        MonReceived {
+            tx_hash: "0xsell".to_string(),
+            amount: mon_to_wei(mon),
+            sold: U256::from(sold),
+        }
+    }
+
+    #[test]
+    fn auto_route_sends_graduated_positions_to_the_dex_only() {
+        assert_eq!(SellRoute::Auto.venues(true), [Venue::Dex]);
+        assert_eq!(SellRoute::Auto.venues(false), [Venue::Sdk, Venue::Dex]);
+        // Explicit orders ignore the flag
+        assert_eq!(SellRoute::SdkFirst.venues(true), [Venue::Sdk, Venue::Dex]);
+        assert_eq!(SellRoute::DexFirst.venues(false), [Venue::Dex, Venue::Sdk]);
+    }
+
+    #[test]
+    fn sell_route_parse_rejects_unknown_routes() {
+        assert_eq!(SellRoute::parse(" DEX_first "), Ok(SellRoute::DexFirst));
+        assert_eq!(SellRoute::parse("sdk"), Ok(SellRoute::SdkFirst));
+        assert_eq!(SellRoute::parse("Auto"), Ok(SellRoute::Auto));
+        assert!(SellRoute::parse("dex_frist").is_err_and(|e| e.contains("dex_frist")));
+    }
+
+    #[test]
+    fn emergency_slippage_names_what_each_venue_uses() {
+        let config = crate::config::tests::config_with(&[
+            ("SELL_RETRY_SLIPPAGE_PCT", "25"),
+            ("DEX_SELL_SLIPPAGE_PCT", "12.5"),
+        ])
+        .unwrap();
+        assert_eq!(emergency_slippage(&config), "25% slippage on the curve, 12.5% on the DEX");
+    }
+
+    #[tokio::test]
+    async fn partial_sell_records_pnl_against_its_share_of_the_basis() {
+        let positions = tracker();
+        let history = Mutex::new(TradeHistory::in_memory());
+        let decision = SellDecision::SecureProfit { level: 50.0, portion: 0.25, current_pnl: 100.0 };
+        assert!(!decision.already_taken(positions.lock().await.get(&TOKEN).unwrap()));
+
+        update_position_after_sell(&positions, &history, TOKEN, &decision, U256::from(1_000u64), &fill(1.5, 250))
+            .await;
+
+        let guard = positions.lock().await;
+        let pos = guard.get(&TOKEN).unwrap();
+        assert_eq!(pos.amount, U256::from(750u64));
+        // The tier counts as taken only now, so copies queued behind this sell are dropped
+        assert_eq!(pos.fired_tp_levels, vec![50.0]);
+        assert!(decision.already_taken(pos));
+        assert!((pos.buy_price_mon - 1.5).abs() < 1e-9);
+        // The high shrinks with the position so the remainder isn't read as a drawdown
+        assert!((pos.highest_price - 3.0).abs() < 1e-9);
+        assert_eq!(pos.sell_failures, 0);
+
+        // Sold a quarter (0.5 MON of basis) for 1.5 MON
+        let history = history.lock().await;
+        assert!((history.realized_pnl_mon() - 1.0).abs() < 1e-9);
+        assert_eq!(history.win_rate(), Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn filled_recoup_turns_the_rest_into_a_runner() {
+        let positions = tracker();
+        let history = Mutex::new(TradeHistory::in_memory());
+        let decision = SellDecision::Recoup { portion: 0.5, current_pnl: 100.0 };
+
+        update_position_after_sell(&positions, &history, TOKEN, &decision, U256::from(1_000u64), &fill(2.0, 500))
+            .await;
+
+        let guard = positions.lock().await;
+        let pos = guard.get(&TOKEN).unwrap();
+        assert_eq!(pos.amount, U256::from(500u64));
+        assert!(pos.in_runner_mode);
+        assert!(decision.already_taken(pos));
+    }
+
+    #[tokio::test]
+    async fn full_sell_closes_the_position_at_a_loss() {
+        let positions = tracker();
+        let history = Mutex::new(TradeHistory::in_memory());
+        let decision = SellDecision::HardStopLoss { current_pnl: -60.0 };
+
+        update_position_after_sell(&positions, &history, TOKEN, &decision, U256::from(1_000u64), &fill(0.8, 1_000))
+            .await;
+
+        assert!(positions.lock().await.get(&TOKEN).is_none());
+        let history = history.lock().await;
+        assert!((history.realized_pnl_mon() + 1.2).abs() < 1e-9);
+        assert_eq!(history.win_rate(), Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn short_fill_is_charged_only_for_what_was_sold() {
+        let positions = tracker();
+        let history = Mutex::new(TradeHistory::in_memory());
+        let decision = SellDecision::PartialManual { portion: 0.5 };
+
+        // Asked for 500 but the wallet only had 100 to sell
+        update_position_after_sell(&positions, &history, TOKEN, &decision, U256::from(1_000u64), &fill(0.3, 100))
+            .await;
+
+        assert_eq!(positions.lock().await.get(&TOKEN).unwrap().amount, U256::from(900u64));
+        assert!((history.lock().await.realized_pnl_mon() - 0.1).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn sell_all_queues_an_emergency_exit_per_position() {
+        let positions = tracker();
+        {
+            let mut positions = positions.lock().await;
+            let second = Position { token: Address::repeat_byte(2), ..positions.get(&TOKEN).unwrap().clone() };
+            positions.add(second);
+        }
+        let (tx, mut rx) = mpsc::channel(4);
+
+        assert_eq!(sell_all(&positions, &tx, "test").await, 2);
+        for _ in 0..2 {
+            let (_, decision) = rx.recv().await.unwrap();
+            assert!(matches!(decision, SellDecision::Emergency { reason } if reason == "test"));
+        }
+    }
+
+    #[tokio::test]
+    async fn sell_all_counts_only_what_was_queued() {
+        let (tx, rx) = mpsc::channel(4);
+        drop(rx);
+        assert_eq!(sell_all(&tracker(), &tx, "test").await, 0);
+    }
+
+    #[test]
+    fn slippage_retry_only_follows_transient_failures() {
+        let slippage = TradeError::SlippageExceeded("amountOutMin".to_string());
+        assert_eq!(skip_slippage_retry(&slippage, false), None);
+        assert_eq!(skip_slippage_retry(&TradeError::RpcError("reset".to_string()), false), None);
+        // Emergency sells start at the retry slippage
+        assert!(skip_slippage_retry(&slippage, true).is_some());
+
+        for err in [
+            TradeError::InsufficientFunds("No tokens to sell".to_string()),
+            TradeError::Config("bad chain id".to_string()),
+            TradeError::NoLiquidity("graduated".to_string()),
+            TradeError::ReceiptTimeout { hash: "0xa".to_string() },
+        ] {
+            assert!(skip_slippage_retry(&err, false).is_some(), "{:?}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn venues_are_tried_in_order_until_one_fills() {
+        let mut tried = Vec::new();
+        let sold = sell_across(SellRoute::SdkFirst.venues(false), |venue| {
+            tried.push(venue);
+            async move {
+                match venue {
+                    Venue::Sdk => Err(vec![TradeError::NoLiquidity("graduated".to_string())]),
+                    Venue::Dex => Ok(fill(1.0, 1_000)),
+                }
+            }
+        })
+        .await;
+        assert_eq!(sold.unwrap().sold, U256::from(1_000u64));
+        assert_eq!(tried, [Venue::Sdk, Venue::Dex]);
+    }
+
+    #[tokio::test]
+    async fn pending_sell_stops_the_walk_before_the_next_venue() {
+        let mut tried = Vec::new();
+        let sold = sell_across(SellRoute::DexFirst.venues(false), |venue| {
+            tried.push(venue);
+            async { Err(vec![TradeError::ReceiptTimeout { hash: "0xa".to_string() }]) }
+        })
+        .await;
+        let errors = sold.unwrap_err();
+        assert_eq!(tried, [Venue::Dex]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].1.is_receipt_timeout());
+    }
+
+    #[tokio::test]
+    async fn every_venue_error_is_kept_in_order() {
+        let sold = sell_across(SellRoute::SdkFirst.venues(false), |venue| async move {
+            match venue {
+                Venue::Sdk => Err(vec![
+                    TradeError::SlippageExceeded("first".to_string()),
+                    TradeError::SlippageExceeded("retry".to_string()),
+                ]),
+                Venue::Dex => Err(vec![TradeError::NoLiquidity("no pair".to_string())]),
+            }
+        })
+        .await;
+        let venues: Vec<Venue> = sold.unwrap_err().into_iter().map(|(venue, _)| venue).collect();
+        assert_eq!(venues, [Venue::Sdk, Venue::Sdk, Venue::Dex]);
+    }
 }
@@ -0,0 +1,406 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Liveness tracking, the periodic heartbeat notification, the dead-man's switch
+//! and the watchdog that restarts dead background tasks.
+
+use crate::handlers::sell_all;
+use crate::position::{PositionTracker, SellDecision};
+use crate::price::MonPriceOracle;
+use crate::notify::{Notifications, NotifyEvent};
+use crate::trade_history::TradeHistory;
+use alloy::primitives::Address;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Watchdog name of the CurveStream listener task.
+pub const LISTENER_TASK: &str = "listener";
+/// Watchdog name of the position monitor task.
+pub const MONITOR_TASK: &str = "monitor";
+/// Watchdog name of the mempool monitor task.
+pub const MEMPOOL_TASK: &str = "mempool";
+
+/// Connection flags and event counters, updated by the listeners and the position monitor.
+#[derive(Debug)]
+pub struct HealthStatus {
+    started_at: Instant,
+    /// Unix timestamp of startup.
+    started_unix: u64,
+    stream_up: AtomicBool,
+    mempool_up: AtomicBool,
+    /// Events received since the last heartbeat.
+    events: AtomicU64,
+    /// Unix timestamp of the last event (0 = none yet).
+    last_event_at: AtomicU64,
+    /// Unix timestamp of the last successful position price check (0 = none yet).
+    last_price_check_at: AtomicU64,
+    /// Unix timestamp of each supervised task's last heartbeat.
+    task_beats: std::sync::Mutex<HashMap<&'static str, u64>>,
+}
+
+impl HealthStatus {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            started_unix: chrono::Utc::now().timestamp() as u64,
+            stream_up: AtomicBool::new(false),
+            mempool_up: AtomicBool::new(false),
+            events: AtomicU64::new(0),
+            last_event_at: AtomicU64::new(0),
+            last_price_check_at: AtomicU64::new(0),
+            task_beats: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Note that the task named `task` is still making progress.
+    pub fn beat(&self, task: &'static str) {
+        if let Ok(mut beats) = self.task_beats.lock() {
+            beats.insert(task, chrono::Utc::now().timestamp() as u64);
+        }
+    }
+
+    /// Unix timestamp of `task`'s last heartbeat, if it sent one.
+    pub fn last_beat(&self, task: &str) -> Option<u64> {
+        self.task_beats.lock().ok()?.get(task).copied()
+    }
+
+    pub fn set_stream_up(&self, up: bool) {
+        self.stream_up.store(up, Ordering::Relaxed);
+    }
+
+    pub fn set_mempool_up(&self, up: bool) {
+        self.mempool_up.store(up, Ordering::Relaxed);
+    }
+
+    /// Note that a stream event arrived.
+    pub fn record_event(&self) {
+        self.events.fetch_add(1, Ordering::Relaxed);
+        self.last_event_at
+            .store(chrono::Utc::now().timestamp() as u64, Ordering::Relaxed);
+    }
+
+    /// Note that a position price check succeeded.
+    pub fn record_price_check(&self) {
+        self.last_price_check_at
+            .store(chrono::Utc::now().timestamp() as u64, Ordering::Relaxed);
+    }
+
+    pub fn stream_up(&self) -> bool {
+        self.stream_up.load(Ordering::Relaxed)
+    }
+
+    pub fn mempool_up(&self) -> bool {
+        self.mempool_up.load(Ordering::Relaxed)
+    }
+
+    /// Unix timestamp of the last event, if any arrived.
+    pub fn last_event_at(&self) -> Option<u64> {
+        match self.last_event_at.load(Ordering::Relaxed) {
+            0 => None,
+            ts => Some(ts),
+        }
+    }
+
+    /// Unix timestamp of the most recent sign of connectivity
+    /// (event or price check), or startup if there was none.
+    pub fn last_activity_at(&self) -> u64 {
+        self.started_unix
+            .max(self.last_event_at.load(Ordering::Relaxed))
+            .max(self.last_price_check_at.load(Ordering::Relaxed))
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Events since the previous call (resets the window).
+    fn take_events(&self) -> u64 {
+        self.events.swap(0, Ordering::Relaxed)
+    }
+}
+
+impl Default for HealthStatus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Periodically push a status summary to the notifiers.
+/// Not spawned when heartbeats are disabled (interval 0 or notifications off).
+pub fn spawn_heartbeat(
+    health: Arc<HealthStatus>,
+    positions: Arc<Mutex<PositionTracker>>,
+    trade_history: Arc<Mutex<TradeHistory>>,
+    notifier: Arc<Notifications>,
+    mon_price: MonPriceOracle,
+    mempool_enabled: bool,
+    interval_secs: u64,
+) -> Option<tokio::task::JoinHandle<()>> {
+    if interval_secs == 0 || !notifier.enabled(NotifyEvent::Heartbeat) {
+        return None;
+    }
+
+    info!("💓 Heartbeat every {}s", interval_secs);
+
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+        ticker.tick().await; // First tick is immediate; the launch message covers it
+
+        loop {
+            ticker.tick().await;
+
+            let open_positions = positions.lock().await.len();
+            let pnl_today = trade_history.lock().await.summary_since(start_of_day()).net_pnl;
+            let events = health.take_events();
+            let now = chrono::Utc::now().timestamp() as u64;
+            let last_event = match health.last_event_at() {
+                Some(ts) => format!("{} ago", format_duration(now.saturating_sub(ts))),
+                None => "never".to_string(),
+            };
+            let mempool = if !mempool_enabled {
+                "off"
+            } else if health.mempool_up() {
+                "up"
+            } else {
+                "DOWN"
+            };
+
+            notifier.notify(NotifyEvent::Heartbeat, || format!(
+                "💓 *Heartbeat*\nUptime: {}\nEvents (last {}): {}\nLast event: {}\nStream: {} | Mempool: {}\nOpen positions: {}\nRealized PnL today: {:+.4} MON{}",
+                format_duration(health.uptime().as_secs()),
+                format_duration(interval_secs),
+                events,
+                last_event,
+                if health.stream_up() { "up" } else { "DOWN" },
+                mempool,
+                open_positions,
+                pnl_today,
+                mon_price.format_usd(pnl_today)
+            ));
+        }
+    }))
+}
+
+/// Dead-man's switch: if neither an event nor a price check has succeeded for
+/// `after_secs`, force-sell every open position and send an alert.
+/// Fires once per outage; re-arms when activity resumes.
+pub fn spawn_deadman(
+    health: Arc<HealthStatus>,
+    positions: Arc<Mutex<PositionTracker>>,
+    sell_signal_tx: mpsc::Sender<(Address, SellDecision)>,
+    notifier: Arc<Notifications>,
+    after_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    info!("☠️ Dead-man's switch armed: sell all after {}s without connectivity", after_secs);
+
+    tokio::spawn(async move {
+        let check_every = Duration::from_secs((after_secs / 4).clamp(1, DEADMAN_MAX_CHECK_SECS));
+        let mut fired_at: Option<u64> = None;
+
+        loop {
+            tokio::time::sleep(check_every).await;
+
+            let last_activity = health.last_activity_at();
+            if fired_at.is_some_and(|fired| last_activity > fired) {
+                info!("☠️ Connectivity restored, dead-man's switch re-armed");
+                fired_at = None;
+            }
+
+            let now = chrono::Utc::now().timestamp() as u64;
+            let silent_for = now.saturating_sub(last_activity);
+            if fired_at.is_some() || silent_for < after_secs {
+                continue;
+            }
+
+            fired_at = Some(now);
+            if positions.lock().await.is_empty() {
+                continue;
+            }
+
+            error!("☠️ No events or price checks for {}s - panic-selling all positions", silent_for);
+            let reason = format!("Dead-man's switch: no connectivity for {}", format_duration(silent_for));
+            let queued = sell_all(&positions, &sell_signal_tx, &reason).await;
+
+            notifier.notify(NotifyEvent::Error, || format!(
+                "☠️ *DEAD-MAN'S SWITCH*\nNo events or price checks for {}\nPanic-selling {} positions",
+                format_duration(silent_for),
+                queued
+            ));
+        }
+    })
+}
+
+/// How often the watchdog checks its tasks.
+const WATCHDOG_CHECK_SECS: u64 = 15;
+
+/// A background task the watchdog keeps running.
+pub struct SupervisedTask {
+    name: &'static str,
+    /// Restart after this long without a heartbeat; `None` only restarts on exit.
+    stale_after: Option<Duration>,
+    spawn: Box<dyn Fn() -> JoinHandle<()> + Send + Sync>,
+    handle: JoinHandle<()>,
+    /// Unix timestamp of the latest (re)start; a fresh task gets a full window to beat.
+    started_at: u64,
+}
+
+impl SupervisedTask {
+    /// Spawn the task now; the watchdog calls `spawn` again to restart it.
+    pub fn start<F>(name: &'static str, stale_after: Option<Duration>, spawn: F) -> Self
+    where
+        F: Fn() -> JoinHandle<()> + Send + Sync + 'static,
+    {
+        let handle = spawn();
+        Self {
+            name,
+            stale_after,
+            spawn: Box::new(spawn),
+            handle,
+            started_at: chrono::Utc::now().timestamp() as u64,
+        }
+    }
+
+    /// Why the task needs a restart, if it does.
+    fn failure(&self, health: &HealthStatus, now: u64) -> Option<String> {
+        if self.handle.is_finished() {
+            return Some("task exited".to_string());
+        }
+        let stale_after = self.stale_after?.as_secs();
+        let last_beat = health.last_beat(self.name).unwrap_or(0).max(self.started_at);
+        let silent_for = now.saturating_sub(last_beat);
+        (silent_for >= stale_after).then(|| format!("no heartbeat for {}", format_duration(silent_for)))
+    }
+
+    fn restart(&mut self) {
+        self.handle.abort();
+        self.handle = (self.spawn)();
+        self.started_at = chrono::Utc::now().timestamp() as u64;
+    }
+}
+
+/// Watchdog: restarts supervised tasks that exited (returned or panicked) or
+/// stopped sending heartbeats, and sends an alert for each restart.
+pub fn spawn_watchdog(
+    mut tasks: Vec<SupervisedTask>,
+    health: Arc<HealthStatus>,
+    notifier: Arc<Notifications>,
+) -> JoinHandle<()> {
+    let names: Vec<&str> = tasks.iter().map(|task| task.name).collect();
+    info!("🐕 Watchdog supervising: {}", names.join(", "));
+
+    tokio::spawn(async move {
+        let mut restarts: HashMap<&'static str, u32> = HashMap::new();
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(WATCHDOG_CHECK_SECS)).await;
+
+            let now = chrono::Utc::now().timestamp() as u64;
+            for task in &mut tasks {
+                let Some(reason) = task.failure(&health, now) else {
+                    continue;
+                };
+
+                let count = restarts.entry(task.name).or_insert(0);
+                *count += 1;
+                warn!("🐕 Restarting {} task ({}), restart #{}", task.name, reason, count);
+                task.restart();
+
+                let (name, count) = (task.name, *count);
+                notifier.notify(NotifyEvent::Error, || format!(
+                    "🐕 *Task Restarted*\n{}: {}\nRestart #{}",
+                    name, reason, count
+                ));
+            }
+        }
+    })
+}
+
+/// Upper bound on the dead-man's switch polling interval.
+const DEADMAN_MAX_CHECK_SECS: u64 = 30;
+
+/// Unix timestamp of today's 00:00 UTC.
+fn start_of_day() -> u64 {
+    let now = chrono::Utc::now().timestamp() as u64;
+    now - now % 86_400
+}
+
+/// Compact duration, e.g. `2d 3h`, `3h 12m`, `45s`.
+fn format_duration(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// A task that never finishes, counting how often it was spawned.
+    fn forever(spawns: &Arc<AtomicUsize>, stale_after: Option<Duration>) -> SupervisedTask {
+        let spawns = Arc::clone(spawns);
+        SupervisedTask::start(MONITOR_TASK, stale_after, move || {
+            spawns.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(std::future::pending())
+        })
+    }
+
+    #[tokio::test]
+    async fn exited_task_is_restarted() {
+        let task = SupervisedTask::start(LISTENER_TASK, None, || tokio::spawn(async {}));
+        while !task.handle.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(task.failure(&HealthStatus::new(), task.started_at).as_deref(), Some("task exited"));
+    }
+
+    #[tokio::test]
+    async fn silent_task_is_stale_only_with_a_window() {
+        let spawns = Arc::new(AtomicUsize::new(0));
+        let health = HealthStatus::new();
+
+        let task = forever(&spawns, Some(Duration::from_secs(300)));
+        assert_eq!(task.failure(&health, task.started_at + 299), None);
+        assert_eq!(task.failure(&health, task.started_at + 300).as_deref(), Some("no heartbeat for 5m 0s"));
+
+        // Without a window only an exit counts
+        let task = forever(&spawns, None);
+        assert_eq!(task.failure(&health, task.started_at + 86_400), None);
+    }
+
+    #[tokio::test]
+    async fn heartbeat_resets_the_window_and_restart_respawns() {
+        let spawns = Arc::new(AtomicUsize::new(0));
+        let health = HealthStatus::new();
+        let mut task = forever(&spawns, Some(Duration::from_secs(60)));
+
+        health.beat(MONITOR_TASK);
+        let beat = health.last_beat(MONITOR_TASK).unwrap();
+        assert_eq!(task.failure(&health, beat + 59), None);
+        assert!(task.failure(&health, beat + 60).is_some());
+
+        task.restart();
+        assert_eq!(spawns.load(Ordering::SeqCst), 2);
+        assert_eq!(health.last_beat(LISTENER_TASK), None);
+    }
+
+    #[test]
+    fn durations_show_the_two_largest_units() {
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(125), "2m 5s");
+        assert_eq!(format_duration(3 * 3600 + 12 * 60), "3h 12m");
+        assert_eq!(format_duration(2 * 86_400 + 3 * 3600), "2d 3h");
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Random spread on buy amounts and priority fees.
+//!
+//! Buying the same round amount at the same gas tier every time makes the
+//! wallet easy to spot and to front-run. `Jitter` scales values by a random
+//! factor within ±pct. The generator is seeded so a run can be reproduced
+//! (`JITTER_SEED`).
+
+use alloy::primitives::U256;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Resolution of the random factor applied to integer amounts (parts per million).
+const PPM: u64 = 1_000_000;
+
+/// Scales values by a random factor in `[1 - pct/100, 1 + pct/100]`.
+#[derive(Debug)]
+pub struct Jitter {
+    pct: f64,
+    /// SplitMix64 state; shared across tasks, so advanced atomically.
+    state: AtomicU64,
+}
+
+impl Jitter {
+    /// `pct` is clamped to 0..=100; zero disables the jitter. Without a
+    /// `seed` the generator is seeded from the clock.
+    pub fn new(pct: f64, seed: Option<u64>) -> Self {
+        let pct = if pct.is_finite() { pct.clamp(0.0, 100.0) } else { 0.0 };
+        let seed = seed.unwrap_or_else(|| chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0) as u64);
+        Self {
+            pct,
+            state: AtomicU64::new(seed),
+        }
+    }
+
+    /// Whether values are left untouched.
+    pub fn is_off(&self) -> bool {
+        self.pct == 0.0
+    }
+
+    /// `value` scaled by a random factor within ±pct.
+    pub fn apply(&self, value: f64) -> f64 {
+        if self.is_off() {
+            return value;
+        }
+        value * (1.0 + self.next_unit() * self.pct / 100.0)
+    }
+
+    /// `amount` (wei) scaled by a random factor within ±pct.
+    pub fn apply_wei(&self, amount: U256) -> U256 {
+        if self.is_off() {
+            return amount;
+        }
+        amount * U256::from(self.next_factor_ppm()) / U256::from(PPM)
+    }
+
+    /// Gas price or fee (wei) scaled by a random factor within ±pct.
+    pub fn apply_fee(&self, fee: u128) -> u128 {
+        if self.is_off() {
+            return fee;
+        }
+        fee.saturating_mul(self.next_factor_ppm() as u128) / PPM as u128
+    }
+
+    /// Random factor in parts per million, within ±pct of `PPM`.
+    fn next_factor_ppm(&self) -> u64 {
+        let spread = self.next_unit() * self.pct / 100.0;
+        ((1.0 + spread) * PPM as f64).round().max(0.0) as u64
+    }
+
+    /// Uniform sample in `[-1, 1]`.
+    fn next_unit(&self) -> f64 {
+        // Top 53 bits give a uniform f64 in [0, 1]
+        let bits = self.next_u64() >> 11;
+        bits as f64 / ((1u64 << 53) - 1) as f64 * 2.0 - 1.0
+    }
+
+    /// SplitMix64.
+    fn next_u64(&self) -> u64 {
+        let mut z = self
+            .state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn zero_pct_leaves_values_untouched() {
+        let jitter = Jitter::new(0.0, Some(1));
+        assert!(jitter.is_off());
+        assert_eq!(jitter.apply(2.5), 2.5);
+        assert_eq!(jitter.apply_wei(U256::from(1_000u64)), U256::from(1_000u64));
+        assert_eq!(jitter.apply_fee(1_000), 1_000);
+        assert!(Jitter::new(f64::NAN, None).is_off());
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_sequence() {
+        let a = Jitter::new(10.0, Some(42));
+        let b = Jitter::new(10.0, Some(42));
+        let c = Jitter::new(10.0, Some(43));
+        let run = |jitter: &Jitter| (0..5).map(|_| jitter.apply_fee(1_000_000)).collect::<Vec<_>>();
+        assert_eq!(run(&a), run(&b));
+        assert_ne!(run(&a), run(&c));
+    }
+
+    #[test]
+    fn values_actually_spread() {
+        let jitter = Jitter::new(10.0, Some(7));
+        let fees: std::collections::HashSet<u128> = (0..20).map(|_| jitter.apply_fee(1_000_000)).collect();
+        assert!(fees.len() > 1);
+    }
+
+    proptest! {
+        #[test]
+        fn jitter_stays_within_pct(pct in 0.0f64..=100.0, seed: u64, value in 1u64..u64::MAX / 4) {
+            let jitter = Jitter::new(pct, Some(seed));
+            let spread = value as f64 * pct / 100.0;
+            // One ppm of rounding on the integer paths
+            let slack = value as f64 / PPM as f64 + 1.0;
+
+            let scaled = jitter.apply(value as f64);
+            prop_assert!((scaled - value as f64).abs() <= spread * (1.0 + 1e-9));
+
+            let fee = jitter.apply_fee(value as u128) as f64;
+            prop_assert!((fee - value as f64).abs() <= spread + slack);
+
+            let wei: f64 = jitter.apply_wei(U256::from(value)).to::<u64>() as f64;
+            prop_assert!((wei - value as f64).abs() <= spread + slack);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Reconnect backoff shared by the WebSocket listeners.
+//!
+//! Fixed-interval retries make every connection hit a struggling endpoint in
+//! lockstep. Delays here double from 1s up to 60s, carry ±20% jitter, and
+//! reset once a connection has stayed up for 30s.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+/// A connection that lasted this long counts as healthy and resets the backoff.
+pub const STABLE_AFTER: Duration = Duration::from_secs(30);
+/// Jitter as a fraction of the delay (±).
+const JITTER: f64 = 0.2;
+
+/// Exponential reconnect delay for one listener.
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    current: Duration,
+}
+
+impl ReconnectBackoff {
+    pub fn new() -> Self {
+        Self { current: BASE_DELAY }
+    }
+
+    /// Delay before the next attempt, given how long the last connection stayed up.
+    pub fn next_delay(&mut self, uptime: Duration) -> Duration {
+        if uptime >= STABLE_AFTER {
+            self.current = BASE_DELAY;
+        }
+        let delay = self.current;
+        self.current = (self.current * 2).min(MAX_DELAY);
+        with_jitter(delay)
+    }
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scale `delay` by a factor in [1 - JITTER, 1 + JITTER].
+/// Sub-second clock noise is random enough to de-synchronize listeners.
+fn with_jitter(delay: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let unit = (nanos % 10_000) as f64 / 10_000.0; // [0, 1)
+    delay.mul_f64(1.0 - JITTER + 2.0 * JITTER * unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn within_jitter(delay: Duration, expected: Duration) -> bool {
+        delay >= expected.mul_f64(1.0 - JITTER) && delay <= expected.mul_f64(1.0 + JITTER)
+    }
+
+    #[test]
+    fn delays_double_up_to_the_cap() {
+        let mut backoff = ReconnectBackoff::new();
+        for secs in [1, 2, 4, 8, 16, 32, 60, 60] {
+            let delay = backoff.next_delay(Duration::ZERO);
+            assert!(within_jitter(delay, Duration::from_secs(secs)), "{delay:?} vs {secs}s");
+        }
+    }
+
+    #[test]
+    fn a_stable_connection_resets_the_backoff() {
+        let mut backoff = ReconnectBackoff::new();
+        for _ in 0..5 {
+            backoff.next_delay(Duration::ZERO);
+        }
+        assert!(within_jitter(backoff.next_delay(STABLE_AFTER), BASE_DELAY));
+        assert!(within_jitter(backoff.next_delay(STABLE_AFTER - Duration::from_secs(1)), BASE_DELAY * 2));
+    }
+}
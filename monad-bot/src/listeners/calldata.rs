@@ -0,0 +1,110 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Router buy calldata seen in the mempool, decoded to the token being bought.
+//!
+//! The token's position depends on the function: a field of the nad.fun
+//! router's params struct, the last hop of a V2-style `path`, or the only
+//! argument of `deposit`. Anything else (sells, approvals, unknown
+//! selectors) decodes to `None`.
+
+use alloy::primitives::Address;
+use alloy::sol;
+use alloy::sol_types::SolInterface;
+
+sol! {
+    interface IBuyRouter {
+        struct BuyParams {
+            uint256 amountOutMin;
+            address token;
+            address to;
+            uint256 deadline;
+        }
+
+        /// nad.fun bonding-curve / DEX router (0x6df9e92b).
+        function buy(BuyParams params) external payable;
+
+        /// V2-style routers: the token bought is the last entry of `path`.
+        function swapExactETHForTokens(uint256 amountOutMin, address[] path, address to, uint256 deadline) external payable;
+        function swapETHForExactTokens(uint256 amountOut, address[] path, address to, uint256 deadline) external payable;
+        function swapExactETHForTokensSupportingFeeOnTransferTokens(uint256 amountOutMin, address[] path, address to, uint256 deadline) external payable;
+
+        /// 0xf340fa01, the selector the monitor originally matched.
+        function deposit(address token) external payable;
+    }
+}
+
+/// Token bought by a router call with calldata `input`, or `None` if the
+/// call isn't a supported buy or doesn't decode.
+pub fn bought_token(input: &[u8]) -> Option<Address> {
+    use IBuyRouter::IBuyRouterCalls as Call;
+
+    let token = match Call::abi_decode(input).ok()? {
+        Call::buy(call) => call.params.token,
+        Call::swapExactETHForTokens(call) => *call.path.last()?,
+        Call::swapETHForExactTokens(call) => *call.path.last()?,
+        Call::swapExactETHForTokensSupportingFeeOnTransferTokens(call) => *call.path.last()?,
+        Call::deposit(call) => call.token,
+    };
+    (!token.is_zero()).then_some(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+    use alloy::sol_types::SolCall;
+
+    const TOKEN: Address = Address::new([0x11; 20]);
+
+    #[test]
+    fn nad_router_buy_names_its_token() {
+        let call = IBuyRouter::buyCall {
+            params: IBuyRouter::BuyParams {
+                amountOutMin: U256::from(1u64),
+                token: TOKEN,
+                to: Address::repeat_byte(0x22),
+                deadline: U256::from(1_000u64),
+            },
+        };
+        assert_eq!(IBuyRouter::buyCall::SELECTOR, [0x6d, 0xf9, 0xe9, 0x2b]);
+        assert_eq!(bought_token(&call.abi_encode()), Some(TOKEN));
+    }
+
+    #[test]
+    fn v2_swaps_buy_the_last_hop() {
+        let wmon = Address::repeat_byte(0x33);
+        let call = IBuyRouter::swapExactETHForTokensCall {
+            amountOutMin: U256::ZERO,
+            path: vec![wmon, TOKEN],
+            to: Address::repeat_byte(0x22),
+            deadline: U256::from(1_000u64),
+        };
+        assert_eq!(bought_token(&call.abi_encode()), Some(TOKEN));
+
+        let empty = IBuyRouter::swapETHForExactTokensCall {
+            amountOut: U256::from(1u64),
+            path: Vec::new(),
+            to: Address::repeat_byte(0x22),
+            deadline: U256::from(1_000u64),
+        };
+        assert_eq!(bought_token(&empty.abi_encode()), None);
+    }
+
+    #[test]
+    fn deposit_keeps_the_original_selector() {
+        let call = IBuyRouter::depositCall { token: TOKEN };
+        assert_eq!(IBuyRouter::depositCall::SELECTOR, [0xf3, 0x40, 0xfa, 0x01]);
+        assert_eq!(bought_token(&call.abi_encode()), Some(TOKEN));
+        assert_eq!(bought_token(&IBuyRouter::depositCall { token: Address::ZERO }.abi_encode()), None);
+    }
+
+    #[test]
+    fn other_and_truncated_calls_are_not_buys() {
+        // approve(address,uint256)
+        assert_eq!(bought_token(&[0x09, 0x5e, 0xa7, 0xb3, 0, 0]), None);
+        let call = IBuyRouter::depositCall { token: TOKEN }.abi_encode();
+        assert_eq!(bought_token(&call[..20]), None);
+        assert_eq!(bought_token(&[]), None);
+    }
+}
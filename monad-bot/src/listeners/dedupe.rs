@@ -0,0 +1,160 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Short-lived caches of seen copy-trade events and launches.
+//!
+//! CurveStream can replay recent events after a reconnect, and acting on a
+//! replayed smart-wallet buy would copy the same trade twice; a launch seen
+//! twice (replay, or both listeners) would be analyzed and bought twice.
+
+use crate::listeners::CopyTradeEvent;
+use alloy::primitives::{Address, B256, U256};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long an event is remembered. Replays arrive within seconds of a reconnect.
+const DEDUPE_TTL: Duration = Duration::from_secs(300);
+
+/// Identity of a copy-trade event.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum EventKey {
+    /// Exact, when the stream provides the transaction hash.
+    Tx(B256, Address),
+    /// Best effort otherwise: the same wallet trading the same amounts of the same token.
+    Trade {
+        token: Address,
+        wallet: Address,
+        amount_in: U256,
+        amount_out: U256,
+        is_buy: bool,
+    },
+}
+
+impl EventKey {
+    fn of(event: &CopyTradeEvent) -> Self {
+        match event.tx_hash {
+            Some(hash) => Self::Tx(hash, event.token),
+            None => Self::Trade {
+                token: event.token,
+                wallet: event.smart_wallet,
+                amount_in: event.amount_in,
+                amount_out: event.amount_out,
+                is_buy: event.is_buy,
+            },
+        }
+    }
+}
+
+/// Remembers copy-trade events for `DEDUPE_TTL` so replays can be dropped.
+#[derive(Debug, Default)]
+pub struct CopyTradeDedupe {
+    seen: HashMap<EventKey, Instant>,
+}
+
+impl CopyTradeDedupe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the event; returns `true` if it was already seen within the TTL.
+    pub fn is_duplicate(&mut self, event: &CopyTradeEvent) -> bool {
+        self.is_duplicate_at(event, Instant::now())
+    }
+
+    fn is_duplicate_at(&mut self, event: &CopyTradeEvent, now: Instant) -> bool {
+        self.seen.retain(|_, seen_at| now.duration_since(*seen_at) < DEDUPE_TTL);
+        self.seen.insert(EventKey::of(event), now).is_some()
+    }
+}
+
+/// Remembers launched tokens for a window so each is processed once.
+#[derive(Debug)]
+pub struct NewTokenDedupe {
+    seen: HashMap<Address, Instant>,
+    ttl: Duration,
+}
+
+impl NewTokenDedupe {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            seen: HashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Record the token; returns `true` if it was already seen within the window.
+    pub fn is_duplicate(&mut self, token: Address) -> bool {
+        self.is_duplicate_at(token, Instant::now())
+    }
+
+    fn is_duplicate_at(&mut self, token: Address, now: Instant) -> bool {
+        let ttl = self.ttl;
+        self.seen.retain(|_, seen_at| now.duration_since(*seen_at) < ttl);
+        self.seen.insert(token, now).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(tx_hash: Option<B256>, amount_in: u64) -> CopyTradeEvent {
+        CopyTradeEvent {
+            token: Address::repeat_byte(1),
+            smart_wallet: Address::repeat_byte(2),
+            amount_in: U256::from(amount_in),
+            amount_out: U256::from(500u64),
+            is_buy: true,
+            is_scout_only: false,
+            tx_hash,
+            timestamp_ms: 1_700_000_000_000,
+        }
+    }
+
+    #[test]
+    fn replayed_trade_is_a_duplicate() {
+        let mut dedupe = CopyTradeDedupe::new();
+        let now = Instant::now();
+        assert!(!dedupe.is_duplicate_at(&event(None, 100), now));
+        assert!(dedupe.is_duplicate_at(&event(None, 100), now + Duration::from_secs(5)));
+        // A different amount is a different trade
+        assert!(!dedupe.is_duplicate_at(&event(None, 101), now));
+    }
+
+    #[test]
+    fn tx_hash_identifies_the_event_when_present() {
+        let mut dedupe = CopyTradeDedupe::new();
+        let now = Instant::now();
+        assert!(!dedupe.is_duplicate_at(&event(Some(B256::repeat_byte(7)), 100), now));
+        assert!(dedupe.is_duplicate_at(&event(Some(B256::repeat_byte(7)), 200), now));
+        // Same amounts in another tx is a genuine repeat trade
+        assert!(!dedupe.is_duplicate_at(&event(Some(B256::repeat_byte(8)), 100), now));
+    }
+
+    #[test]
+    fn events_are_forgotten_after_the_ttl() {
+        let mut dedupe = CopyTradeDedupe::new();
+        let now = Instant::now();
+        assert!(!dedupe.is_duplicate_at(&event(None, 100), now));
+        assert!(!dedupe.is_duplicate_at(&event(None, 100), now + DEDUPE_TTL));
+    }
+
+    #[test]
+    fn launches_are_remembered_for_the_window() {
+        let mut dedupe = NewTokenDedupe::new(Duration::from_secs(60));
+        let token = Address::repeat_byte(1);
+        let now = Instant::now();
+        assert!(!dedupe.is_duplicate_at(token, now));
+        assert!(dedupe.is_duplicate_at(token, now + Duration::from_secs(59)));
+        assert!(!dedupe.is_duplicate_at(Address::repeat_byte(2), now));
+        assert!(!dedupe.is_duplicate_at(token, now + Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn zero_window_remembers_nothing() {
+        let mut dedupe = NewTokenDedupe::new(Duration::ZERO);
+        let token = Address::repeat_byte(1);
+        assert!(!dedupe.is_duplicate(token));
+        assert!(!dedupe.is_duplicate(token));
+    }
+}
@@ -6,25 +6,76 @@
 use futures_util::{StreamExt, SinkExt};
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use serde_json::{json, Value};
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
 use std::sync::Arc;
 use crate::config::Config;
-use crate::executor::SdkExecutor;
-use alloy::primitives::Address;
+use crate::executor::{is_paper_tx, SdkExecutor};
+use crate::health::HealthStatus;
+use crate::listeners::backoff::{ReconnectBackoff, STABLE_AFTER};
+use crate::listeners::calldata::bought_token;
+use crate::listeners::nadfun::BONDING_CURVE_ROUTER;
+use crate::position::{Position, PositionTracker};
+use crate::rpc::EndpointRotation;
+use crate::shutdown::InFlightTrades;
+use crate::trade_history::{TradeHistory, TradeRecord};
+use alloy::primitives::{hex, Address, U256};
 use std::str::FromStr;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+/// Front-run bid when the victim's gas price is missing (50 gwei).
+const FALLBACK_FRONTRUN_GAS_PRICE: u128 = 50_000_000_000;
 
 pub struct MempoolMonitor {
     config: Config,
     sdk: Arc<SdkExecutor>,
+    positions: Arc<Mutex<PositionTracker>>,
+    trade_history: Arc<Mutex<TradeHistory>>,
+    health: Arc<HealthStatus>,
+    in_flight: Arc<InFlightTrades>,
 }
 
 impl MempoolMonitor {
-    pub fn new(config: Config, sdk: Arc<SdkExecutor>) -> Self {
-        Self { config, sdk }
+    pub fn new(
+        config: Config,
+        sdk: Arc<SdkExecutor>,
+        positions: Arc<Mutex<PositionTracker>>,
+        trade_history: Arc<Mutex<TradeHistory>>,
+        health: Arc<HealthStatus>,
+        in_flight: Arc<InFlightTrades>,
+    ) -> Self {
+        Self { config, sdk, positions, trade_history, health, in_flight }
     }
 
+    /// Run the monitor, reconnecting with backoff whenever the stream drops.
+    /// Connections that keep dropping early move it to the next WS endpoint.
     pub async fn start(&self) {
-        let ws_url = &self.config.ws_url;
+        let mut backoff = ReconnectBackoff::new();
+        let mut endpoints = EndpointRotation::new(
+            "Mempool",
+            self.config.ws_urls.clone(),
+            self.config.rpc_failover_errors,
+        );
+
+        loop {
+            let connected_at = Instant::now();
+            let endpoint = endpoints.index();
+            self.connect_and_listen(endpoints.current()).await;
+
+            let uptime = connected_at.elapsed();
+            if uptime >= STABLE_AFTER {
+                endpoints.record_success(endpoint);
+            } else {
+                endpoints.record_failure(endpoint);
+            }
+
+            let delay = backoff.next_delay(uptime);
+            warn!("🔌 Reconnecting to mempool stream in {:.1}s...", delay.as_secs_f64());
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn connect_and_listen(&self, ws_url: &str) {
         info!("🔌 Connecting to Mempool stream: {}", ws_url);
 
         let (ws_stream, _) = match connect_async(ws_url).await {
@@ -58,6 +109,7 @@ impl MempoolMonitor {
             error!("❌ Failed to subscribe to mempool: {}", e);
             return;
         }
+        self.health.set_mempool_up(true);
 
         while let Some(msg) = read.next().await {
             match msg {
@@ -76,65 +128,263 @@ impl MempoolMonitor {
                 _ => {}
             }
         }
-        
+
+        self.health.set_mempool_up(false);
         warn!("⚠️ Mempool monitor disconnected");
     }
 
     async fn handle_message(&self, msg: Value) {
-        if let Some(params) = msg.get("params") {
-            if let Some(result) = params.get("result") {
-                // info!("🔍 Mempool Event: {:?}", result); // Debug all events
-                
-                let to_addr_str = result.get("to").and_then(|v| v.as_str());
-                let input_str = result.get("input").and_then(|v| v.as_str());
-                let from_addr = result.get("from").and_then(|v| v.as_str()).unwrap_or("unknown");
-                
-                if let (Some(to), Some(input)) = (to_addr_str, input_str) {
-                    // Check if target is Router
-                    let router_addr = format!("{:?}", self.config.router_address).to_lowercase();
-                    
-                    if to.to_lowercase() == router_addr {
-                        // Check for 'buy' selector: 0xf340fa01
-                        if input.starts_with("0xf340fa01") {
-                            // Extract token address (param 1)
-                            // "0x" (2) + selector (8) + padding (24) + address (40)
-                            // Offset: 2 + 8 + 24 = 34
-                            if input.len() >= 74 {
-                                let token_hex = &input[34..74]; 
-                                if let Ok(token_address) = Address::from_str(&format!("0x{}", token_hex)) {
-                                    info!("🚨 MEMPOOL SNIPE DETECTED! Smart Wallet {} buying {:?}", from_addr, token_address);
-                                    
-                                    // Calculate front-run gas
-                                    let victim_gas_price_hex = result.get("gasPrice").and_then(|v| v.as_str()).unwrap_or("0x0");
-                                    let victim_gas_price = u128::from_str_radix(victim_gas_price_hex.trim_start_matches("0x"), 16).unwrap_or(0);
-                                    
-                                    // Gas War: Victim + 25% or at least +2 gwei
-                                    let my_gas_price = if victim_gas_price > 0 {
-                                        victim_gas_price + (victim_gas_price / 4)
-                                    } else {
-                                        50_000_000_000 // Fallback 50 gwei
-                                    };
-                                    
-                                    info!("🔥 Front-running with gas: {} wei (Victim: {})", my_gas_price, victim_gas_price);
-                                    
-                                    // Execute Buy
-                                    let amount = self.config.mon_to_wei(self.config.snipe_amount_mon);
-                                    
-                                    // Trigger buy in background
-                                    let sdk = self.sdk.clone();
-                                    let token = token_address;
-                                    tokio::spawn(async move {
-                                        match sdk.buy_token_with_gas(token, amount, my_gas_price).await {
-                                            Ok(_) => info!("✅ Front-run transaction successful!"),
-                                            Err(e) => error!("❌ Front-run failed: {}", e),
-                                        }
-                                    });
-                                }
-                            }
-                        }
-                    }
+        let Some(result) = msg.get("params").and_then(|params| params.get("result")) else {
+            return;
+        };
+
+        let to_addr_str = result.get("to").and_then(|v| v.as_str());
+        let input_str = result.get("input").and_then(|v| v.as_str());
+        let from_addr = result.get("from").and_then(|v| v.as_str()).unwrap_or("unknown");
+
+        let (Some(to), Some(input)) = (to_addr_str, input_str) else {
+            return;
+        };
+
+        // Only buys sent to the DEX router or the nad.fun bonding-curve router
+        let Ok(to) = Address::from_str(to) else {
+            return;
+        };
+        let curve_router = Address::from_str(BONDING_CURVE_ROUTER).ok();
+        if to != self.config.router_address && Some(to) != curve_router {
+            return;
+        }
+
+        let Ok(calldata) = hex::decode(input) else {
+            debug!("Undecodable calldata from {}", from_addr);
+            return;
+        };
+        let Some(token_address) = bought_token(&calldata) else {
+            debug!("Router call from {} is not a supported buy", from_addr);
+            return;
+        };
+
+        info!("🚨 MEMPOOL SNIPE DETECTED! Smart Wallet {} buying {:?}", from_addr, token_address);
+
+        // Calculate front-run gas
+        let victim_gas_price_hex = result.get("gasPrice").and_then(|v| v.as_str()).unwrap_or("0x0");
+        let victim_gas_price = u128::from_str_radix(victim_gas_price_hex.trim_start_matches("0x"), 16).unwrap_or(0);
+
+        // Gas War: outbid the victim by the front-run tier, up to the ceiling
+        let max_gas_price = (self.config.max_frontrun_gas_gwei * 1e9) as u128;
+        let Some(my_gas_price) = frontrun_gas_price(
+            victim_gas_price,
+            self.config.frontrun_gas_multiplier,
+            max_gas_price,
+        ) else {
+            warn!(
+                "⛽ Skipping front-run of {:?}: victim gas {} wei is at or above the {} gwei ceiling",
+                token_address, victim_gas_price, self.config.max_frontrun_gas_gwei
+            );
+            return;
+        };
+
+        info!("🔥 Front-running with gas: {} wei (Victim: {})", my_gas_price, victim_gas_price);
+
+        // Execute Buy
+        let amount = self.config.mon_to_wei(self.config.snipe_amount_mon);
+
+        let Some(trade) = self.in_flight.start() else {
+            info!("🛑 Shutting down, not front-running {:?}", token_address);
+            return;
+        };
+
+        // Trigger buy in background
+        let sdk = self.sdk.clone();
+        let positions = self.positions.clone();
+        let trade_history = self.trade_history.clone();
+        let buy_price = self.config.snipe_amount_mon;
+        let token = token_address;
+        tokio::spawn(async move {
+            match sdk.buy_token_with_gas(token, amount, my_gas_price).await {
+                Ok((tx_hash, expected_tokens)) => {
+                    track_front_run(&sdk, &positions, &trade_history, token, tx_hash, expected_tokens, buy_price).await;
                 }
+                Err(e) => error!("❌ Front-run failed: {}", e),
+            }
+            // Moved in so the front-run counts as in flight until it settles
+            drop(trade);
+        });
+    }
+}
+
+/// Gas price (wei) that outbids `victim_gas_price` by `multiplier`, capped at
+/// `max_gas_price`. 50 gwei (or the cap) when the victim's price is unknown;
+/// `None` when even the cap wouldn't beat the victim.
+pub fn frontrun_gas_price(victim_gas_price: u128, multiplier: f64, max_gas_price: u128) -> Option<u128> {
+    if victim_gas_price == 0 {
+        return Some(FALLBACK_FRONTRUN_GAS_PRICE.min(max_gas_price));
+    }
+    if victim_gas_price >= max_gas_price {
+        return None;
+    }
+
+    let bid = ((victim_gas_price as f64 * multiplier) as u128).max(victim_gas_price + 1);
+    if bid > max_gas_price {
+        warn!(
+            "⛽ Front-run bid {} wei clamped to the {} wei ceiling (victim: {})",
+            bid, max_gas_price, victim_gas_price
+        );
+        return Some(max_gas_price);
+    }
+    Some(bid)
+}
+
+/// Open a provisional position for a sent front-run, then reconcile it with the receipt:
+/// confirmed buys get the real token amount, reverted ones are removed.
+async fn track_front_run(
+    sdk: &SdkExecutor,
+    positions: &Mutex<PositionTracker>,
+    trade_history: &Mutex<TradeHistory>,
+    token: Address,
+    tx_hash: String,
+    expected_tokens: U256,
+    buy_price_mon: f64,
+) {
+    // Don't clobber a position we already hold
+    let provisional = positions.lock().await.get(&token).is_none();
+    let meta = sdk.token_meta(token).await;
+    let (name, symbol) = (meta.name, meta.symbol);
+
+    if provisional {
+        positions.lock().await.add(Position {
+            token,
+            name: name.clone(),
+            symbol: symbol.clone(),
+            amount: expected_tokens,
+            buy_price_mon,
+            buy_time: chrono::Utc::now().timestamp() as u64,
+            highest_price: buy_price_mon,
+            tx_hash: tx_hash.clone(),
+            provisional: true,
+            fired_tp_levels: Vec::new(),
+            sell_failures: 0,
+            stuck: false,
+            graduated: false,
+            peak_liquidity_mon: 0.0,
+            in_runner_mode: false,
+            decimals: meta.decimals,
+        });
+    }
+
+    let buy_record = |amount: U256, tx_hash: String| {
+        TradeRecord::buy(token, name, symbol, amount, buy_price_mon, tx_hash)
+    };
+
+    // A paper fill never lands on chain; the quote is the fill
+    if is_paper_tx(&tx_hash) {
+        trade_history.lock().await.record(buy_record(expected_tokens, tx_hash.clone()));
+        if provisional {
+            positions.lock().await.confirm(&token, expected_tokens, expected_tokens, tx_hash);
+        }
+        return;
+    }
+
+    match sdk.confirm_buy(&tx_hash, token).await {
+        Ok(received) => {
+            info!("✅ Front-run confirmed: {} tokens", received.amount);
+            trade_history.lock().await.record(buy_record(received.amount, received.tx_hash.clone()));
+            if provisional {
+                positions.lock().await.confirm(&token, expected_tokens, received.amount, received.tx_hash);
+            }
+        }
+        Err(e) => {
+            error!("❌ Front-run not confirmed: {}", e);
+            if provisional {
+                warn!("🗑️ Removing provisional position for {:?}", token);
+                positions.lock().await.remove(&token);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::offline_sdk_executor;
+    use crate::executor::paper::paper_tx_hash;
+
+    const TOKEN: Address = Address::new([0x11; 20]);
+
+    #[tokio::test]
+    async fn paper_front_run_is_confirmed_and_recorded() {
+        let sdk = offline_sdk_executor().await;
+        let positions = Mutex::new(PositionTracker::in_memory());
+        let history = Mutex::new(TradeHistory::in_memory());
+
+        let tx_hash = paper_tx_hash();
+        track_front_run(&sdk, &positions, &history, TOKEN, tx_hash.clone(), U256::from(1_000u64), 0.5).await;
+
+        let positions = positions.lock().await;
+        let position = positions.get(&TOKEN).unwrap();
+        assert!(!position.provisional);
+        assert_eq!(position.amount, U256::from(1_000u64));
+        assert_eq!(position.tx_hash, tx_hash);
+
+        let summary = history.lock().await.summary_since(0);
+        assert_eq!(summary.buy_count, 1);
+        assert_eq!(summary.total_bought, 0.5);
+    }
+
+    #[tokio::test]
+    async fn front_run_into_a_held_token_keeps_the_position() {
+        let sdk = offline_sdk_executor().await;
+        let positions = Mutex::new(PositionTracker::in_memory());
+        let history = Mutex::new(TradeHistory::in_memory());
+        let held = Position {
+            token: TOKEN,
+            name: "Held".to_string(),
+            symbol: "HLD".to_string(),
+            amount: U256::from(7u64),
+            buy_price_mon: 2.0,
+            buy_time: 1,
+            highest_price: 2.0,
+            tx_hash: "0xheld".to_string(),
+            provisional: false,
+            fired_tp_levels: Vec::new(),
+            sell_failures: 0,
+            stuck: false,
+            graduated: false,
+            peak_liquidity_mon: 0.0,
+            in_runner_mode: false,
+            decimals: 18,
+        };
+        positions.lock().await.add(held);
+
+        track_front_run(&sdk, &positions, &history, TOKEN, paper_tx_hash(), U256::from(1_000u64), 0.5).await;
+
+        let positions = positions.lock().await;
+        let position = positions.get(&TOKEN).unwrap();
+        assert_eq!(position.amount, U256::from(7u64));
+        assert_eq!(position.tx_hash, "0xheld");
+        // The buy still happened, so it's still recorded
+        assert_eq!(history.lock().await.summary_since(0).buy_count, 1);
+    }
+
+    const GWEI: u128 = 1_000_000_000;
+
+    #[test]
+    fn front_run_outbids_the_victim_by_the_multiplier() {
+        assert_eq!(frontrun_gas_price(100 * GWEI, 1.25, 200 * GWEI), Some(125 * GWEI));
+        // A multiplier that rounds down to the victim's price still outbids by a wei
+        assert_eq!(frontrun_gas_price(100 * GWEI, 1.0, 200 * GWEI), Some(100 * GWEI + 1));
+    }
+
+    #[test]
+    fn front_run_bid_is_clamped_to_the_ceiling() {
+        assert_eq!(frontrun_gas_price(180 * GWEI, 1.25, 200 * GWEI), Some(200 * GWEI));
+        assert_eq!(frontrun_gas_price(200 * GWEI, 1.25, 200 * GWEI), None);
+        assert_eq!(frontrun_gas_price(500 * GWEI, 1.25, 200 * GWEI), None);
+    }
+
+    #[test]
+    fn unknown_victim_gas_bids_the_fallback_under_the_ceiling() {
+        assert_eq!(frontrun_gas_price(0, 1.25, 200 * GWEI), Some(FALLBACK_FRONTRUN_GAS_PRICE));
+        assert_eq!(frontrun_gas_price(0, 1.25, 20 * GWEI), Some(20 * GWEI));
+    }
+}
@@ -3,8 +3,13 @@
 
 //! Event listeners for detecting new tokens.
 
+pub mod backoff;
+pub mod calldata;
+pub mod dedupe;
 pub mod nadfun;
 pub mod sdk_stream;
 pub mod mempool;
+pub mod replay;
 
-pub use sdk_stream::{spawn_listener, NewTokenEvent, CopyTradeEvent};
+pub use dedupe::{CopyTradeDedupe, NewTokenDedupe};
+pub use sdk_stream::{spawn_listener, NewTokenEvent, CopyTradeEvent, StreamConfig};
@@ -4,18 +4,20 @@
 
 //! Blockchain event listener for new token events via QuickNode WebSocket.
 
+use crate::listeners::backoff::ReconnectBackoff;
 use alloy::primitives::{Address, B256, U256};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::time::Instant;
 use tokio::sync::mpsc;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{debug, error, info, warn};
 
 /// nad.fun Bonding Curve Router contract address on Monad.
-const BONDING_CURVE_ROUTER: &str = "0x4F5A3518F082275edf59026f72B66AC2838c0414";
+pub(crate) const BONDING_CURVE_ROUTER: &str = "0x4F5A3518F082275edf59026f72B66AC2838c0414";
 
 /// Bonding Curve contract address.
-const BONDING_CURVE: &str = "0x52D34d8536350Cd997bCBD0b9E9d722452f341F5";
+pub(crate) const BONDING_CURVE: &str = "0x52D34d8536350Cd997bCBD0b9E9d722452f341F5";
 
 /// Event emitted when a new token is created.
 #[derive(Debug, Clone)]
@@ -103,16 +105,21 @@ impl NadFunListener {
 
     /// Start listening for new token events.
     pub async fn run(&self) {
+        let mut backoff = ReconnectBackoff::new();
+
         loop {
-            match self.connect_and_listen().await {
+            let connected_at = Instant::now();
+            let result = self.connect_and_listen().await;
+            let delay = backoff.next_delay(connected_at.elapsed());
+            match result {
                 Ok(_) => {
-                    warn!("WebSocket disconnected, reconnecting in 5s...");
+                    warn!("WebSocket disconnected, reconnecting in {:.1}s...", delay.as_secs_f64());
                 }
                 Err(e) => {
-                    error!("WebSocket error: {}, reconnecting in 5s...", e);
+                    error!("WebSocket error: {}, reconnecting in {:.1}s...", e, delay.as_secs_f64());
                 }
             }
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            tokio::time::sleep(delay).await;
         }
     }
 
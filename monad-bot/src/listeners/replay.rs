@@ -0,0 +1,412 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Replay of bonding curve events missed while CurveStream was disconnected.
+//!
+//! The stream only delivers events emitted while it is connected, so a
+//! reconnect gap can swallow a launch or a smart-wallet exit. The listener
+//! keeps a rough cursor of the chain head it was live at; after resubscribing
+//! it pulls the curve's logs for the gap and feeds them through the same path
+//! as live events.
+
+use crate::listeners::nadfun::BONDING_CURVE;
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log};
+use alloy::sol;
+use alloy::sol_types::SolEvent;
+use nadfun_sdk::types::EventType;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+sol! {
+    event CurveCreate(
+        address indexed creator,
+        address indexed token,
+        address indexed pool,
+        string name,
+        string symbol,
+        string tokenURI,
+        uint256 virtualMon,
+        uint256 virtualToken,
+        uint256 targetTokenAmount
+    );
+    event CurveBuy(address indexed sender, address indexed token, uint256 amountIn, uint256 amountOut);
+    event CurveSell(address indexed sender, address indexed token, uint256 amountIn, uint256 amountOut);
+    event CurveGraduate(address indexed token, address indexed pool);
+}
+
+/// Blocks re-read before the cursor, covering events the stream hadn't
+/// delivered yet when it dropped. Anything seen twice is deduplicated.
+const REPLAY_OVERLAP_BLOCKS: u64 = 10;
+
+/// Block span per `eth_getLogs` call (RPCs cap the range).
+const REPLAY_CHUNK_BLOCKS: u64 = 100;
+
+/// Blocks searched back from the head for a live launch's Create log.
+pub const CREATE_LOOKBACK_BLOCKS: u64 = REPLAY_CHUNK_BLOCKS;
+
+/// Minimum time between head-block samples while events are flowing.
+const CURSOR_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A bonding curve event, from the live stream or a replayed log.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CurveEvent {
+    Create {
+        token: Address,
+        creator: Address,
+        pool: Address,
+        name: String,
+        symbol: String,
+    },
+    Trade {
+        token: Address,
+        sender: Address,
+        amount_in: U256,
+        amount_out: U256,
+        is_buy: bool,
+    },
+    Graduate {
+        token: Address,
+        pool: Address,
+    },
+}
+
+/// A curve event recovered from logs.
+#[derive(Debug, Clone)]
+pub struct MissedEvent {
+    pub event: CurveEvent,
+    pub block_number: Option<u64>,
+    /// Unix time of the event's block, when the RPC includes it in logs.
+    pub block_timestamp: Option<u64>,
+}
+
+/// Latest chain head the stream is known to have been live at.
+#[derive(Debug, Default)]
+pub struct BlockCursor {
+    block: Option<u64>,
+    sampled_at: Option<Instant>,
+}
+
+impl BlockCursor {
+    pub fn block(&self) -> Option<u64> {
+        self.block
+    }
+
+    /// Move the cursor to `block`; it never goes backwards.
+    pub fn advance(&mut self, block: u64) {
+        self.block = Some(self.block.map_or(block, |current| current.max(block)));
+        self.sampled_at = Some(Instant::now());
+    }
+
+    /// Whether the head should be re-sampled (at most every `CURSOR_SAMPLE_INTERVAL`).
+    pub fn needs_sample(&self) -> bool {
+        self.sampled_at.is_none_or(|at| at.elapsed() >= CURSOR_SAMPLE_INTERVAL)
+    }
+}
+
+/// Block range to replay after a reconnect: from just before the cursor to
+/// `head`, trimmed to the newest `max_blocks`. `None` when there is no gap.
+pub fn replay_range(cursor: Option<u64>, head: u64, max_blocks: u64) -> Option<(u64, u64)> {
+    let cursor = cursor?;
+    if max_blocks == 0 {
+        return None;
+    }
+    let from = cursor
+        .saturating_sub(REPLAY_OVERLAP_BLOCKS)
+        .max(head.saturating_sub(max_blocks - 1));
+    (from <= head).then_some((from, head))
+}
+
+/// Curve events of `types` emitted in `from..=to`, oldest first.
+pub async fn fetch_missed<P: Provider>(
+    provider: &P,
+    from: u64,
+    to: u64,
+    types: &[EventType],
+) -> Result<Vec<MissedEvent>, String> {
+    let curve = Address::from_str(BONDING_CURVE).map_err(|e| format!("Invalid curve address: {}", e))?;
+    let signatures: Vec<B256> = types.iter().filter_map(signature).collect();
+    if signatures.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut events = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let end = to.min(start + REPLAY_CHUNK_BLOCKS - 1);
+        let filter = Filter::new()
+            .address(curve)
+            .event_signature(signatures.clone())
+            .from_block(start)
+            .to_block(end);
+        let logs = provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| format!("eth_getLogs {}..={} failed: {}", start, end, e))?;
+        debug!("Replay: {} curve logs in blocks {}..={}", logs.len(), start, end);
+        events.extend(logs.iter().filter_map(|log| {
+            decode(log).map(|event| MissedEvent {
+                event,
+                block_number: log.block_number,
+                block_timestamp: log.block_timestamp,
+            })
+        }));
+        start = end + 1;
+    }
+
+    // Most RPCs leave blockTimestamp out of logs; the replayed events still
+    // need it for launch age and copy-trade staleness
+    let mut block_times: HashMap<u64, u64> = HashMap::new();
+    for missed in events.iter_mut().filter(|missed| missed.block_timestamp.is_none()) {
+        let Some(number) = missed.block_number else {
+            continue;
+        };
+        if let Some(timestamp) = block_times.get(&number) {
+            missed.block_timestamp = Some(*timestamp);
+            continue;
+        }
+        match block_timestamp(provider, number).await {
+            Ok(timestamp) => {
+                block_times.insert(number, timestamp);
+                missed.block_timestamp = Some(timestamp);
+            }
+            Err(e) => debug!("Replay: no timestamp for block {}: {}", number, e),
+        }
+    }
+
+    info!("⏪ Replayed {} curve events from blocks {}..={}", events.len(), from, to);
+    Ok(events)
+}
+
+/// Unix time of block `number`.
+pub async fn block_timestamp<P: Provider>(provider: &P, number: u64) -> Result<u64, String> {
+    provider
+        .get_block_by_number(BlockNumberOrTag::Number(number))
+        .await
+        .map_err(|e| format!("Failed to get block {}: {}", number, e))?
+        .map(|block| block.header.timestamp)
+        .ok_or_else(|| format!("Block {} not found", number))
+}
+
+/// When `token` was launched: the timestamp of the block holding its curve
+/// Create log, searched back from the head over `lookback_blocks`.
+/// `None` if no Create log is in that range.
+pub async fn creation_time<P: Provider>(
+    provider: &P,
+    token: Address,
+    lookback_blocks: u64,
+) -> Result<Option<u64>, String> {
+    let curve = Address::from_str(BONDING_CURVE).map_err(|e| format!("Invalid curve address: {}", e))?;
+    let head = provider
+        .get_block_number()
+        .await
+        .map_err(|e| format!("Failed to get block number: {}", e))?;
+    let oldest = head.saturating_sub(lookback_blocks);
+
+    // Newest chunk first: a live launch is a block or two behind the head
+    let mut end = head;
+    loop {
+        let start = end.saturating_sub(REPLAY_CHUNK_BLOCKS - 1).max(oldest);
+        let filter = Filter::new()
+            .address(curve)
+            .event_signature(CurveCreate::SIGNATURE_HASH)
+            .topic2(token.into_word())
+            .from_block(start)
+            .to_block(end);
+        let logs = provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| format!("eth_getLogs {}..={} failed: {}", start, end, e))?;
+
+        if let Some(log) = logs.first() {
+            if let Some(timestamp) = log.block_timestamp {
+                return Ok(Some(timestamp));
+            }
+            return match log.block_number {
+                Some(number) => block_timestamp(provider, number).await.map(Some),
+                None => Ok(None),
+            };
+        }
+        if start == oldest {
+            return Ok(None);
+        }
+        end = start - 1;
+    }
+}
+
+/// Topic 0 of the log behind a stream event type.
+fn signature(kind: &EventType) -> Option<B256> {
+    match kind {
+        EventType::Create => Some(CurveCreate::SIGNATURE_HASH),
+        EventType::Buy => Some(CurveBuy::SIGNATURE_HASH),
+        EventType::Sell => Some(CurveSell::SIGNATURE_HASH),
+        EventType::Graduate => Some(CurveGraduate::SIGNATURE_HASH),
+        _ => None,
+    }
+}
+
+/// Decode one curve log; logs that don't match are skipped.
+fn decode(log: &Log) -> Option<CurveEvent> {
+    match *log.topic0()? {
+        CurveCreate::SIGNATURE_HASH => log.log_decode::<CurveCreate>().ok().map(|log| {
+            let e = log.inner.data;
+            CurveEvent::Create {
+                token: e.token,
+                creator: e.creator,
+                pool: e.pool,
+                name: e.name,
+                symbol: e.symbol,
+            }
+        }),
+        CurveBuy::SIGNATURE_HASH => log.log_decode::<CurveBuy>().ok().map(|log| {
+            let e = log.inner.data;
+            CurveEvent::Trade {
+                token: e.token,
+                sender: e.sender,
+                amount_in: e.amountIn,
+                amount_out: e.amountOut,
+                is_buy: true,
+            }
+        }),
+        CurveSell::SIGNATURE_HASH => log.log_decode::<CurveSell>().ok().map(|log| {
+            let e = log.inner.data;
+            CurveEvent::Trade {
+                token: e.token,
+                sender: e.sender,
+                amount_in: e.amountIn,
+                amount_out: e.amountOut,
+                is_buy: false,
+            }
+        }),
+        CurveGraduate::SIGNATURE_HASH => log.log_decode::<CurveGraduate>().ok().map(|log| {
+            let e = log.inner.data;
+            CurveEvent::Graduate { token: e.token, pool: e.pool }
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_block, push_quantity};
+    use alloy::sol_types::SolEvent;
+
+    /// A curve log for `event` in block `number`, with its timestamp when `timestamp` is set.
+    pub(crate) fn curve_log<E: SolEvent>(event: &E, number: u64, timestamp: Option<u64>) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address: Address::from_str(BONDING_CURVE).unwrap(),
+                data: event.encode_log_data(),
+            },
+            block_number: Some(number),
+            block_timestamp: timestamp,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn buy(sender: Address, amount_in: u64) -> CurveBuy {
+        CurveBuy {
+            sender,
+            token: Address::repeat_byte(0x11),
+            amountIn: U256::from(amount_in),
+            amountOut: U256::from(1_000u64),
+        }
+    }
+
+    #[test]
+    fn no_cursor_or_no_budget_means_no_replay() {
+        assert_eq!(replay_range(None, 500, 1000), None);
+        assert_eq!(replay_range(Some(400), 500, 0), None);
+    }
+
+    #[test]
+    fn replay_starts_just_before_the_cursor_and_is_capped() {
+        assert_eq!(replay_range(Some(400), 500, 1000), Some((390, 500)));
+        // Down for longer than the budget: only the newest blocks
+        assert_eq!(replay_range(Some(100), 500, 50), Some((451, 500)));
+        assert_eq!(replay_range(Some(5), 8, 1000), Some((0, 8)));
+    }
+
+    #[test]
+    fn cursor_never_goes_backwards() {
+        let mut cursor = BlockCursor::default();
+        assert!(cursor.needs_sample());
+        cursor.advance(10);
+        cursor.advance(7);
+        assert_eq!(cursor.block(), Some(10));
+        assert!(!cursor.needs_sample());
+    }
+
+    #[test]
+    fn logs_decode_to_curve_events() {
+        let sender = Address::repeat_byte(0xaa);
+        let log = curve_log(&buy(sender, 5), 1, None);
+        assert!(matches!(decode(&log), Some(CurveEvent::Trade { sender: s, is_buy: true, .. }) if s == sender));
+
+        let graduate = CurveGraduate { token: Address::repeat_byte(1), pool: Address::repeat_byte(2) };
+        assert_eq!(
+            decode(&curve_log(&graduate, 1, None)),
+            Some(CurveEvent::Graduate { token: Address::repeat_byte(1), pool: Address::repeat_byte(2) })
+        );
+
+        let mut unknown = log.clone();
+        unknown.inner.data = alloy::primitives::LogData::new_unchecked(vec![B256::ZERO], Default::default());
+        assert_eq!(decode(&unknown), None);
+    }
+
+    #[tokio::test]
+    async fn fetch_walks_the_range_in_chunks_and_fills_block_times() {
+        let (provider, asserter) = mock_provider();
+        let sender = Address::repeat_byte(0xaa);
+        // 150 blocks: two eth_getLogs calls
+        asserter.push_success(&vec![curve_log(&buy(sender, 1), 10, Some(1_000))]);
+        asserter.push_success(&vec![curve_log(&buy(sender, 2), 120, None), curve_log(&buy(sender, 3), 120, None)]);
+        // One block fetch for both logs in block 120
+        push_block(&asserter, 0);
+
+        let missed = fetch_missed(&provider, 0, 149, &[EventType::Buy]).await.unwrap();
+        assert_eq!(missed.len(), 3);
+        assert_eq!(missed[0].block_timestamp, Some(1_000));
+        assert_eq!(missed[1].block_number, Some(120));
+        assert!(missed[1..].iter().all(|missed| missed.block_timestamp.is_some()));
+    }
+
+    #[tokio::test]
+    async fn fetch_failure_names_the_chunk() {
+        let (provider, asserter) = mock_provider();
+        asserter.push_failure_msg("range too large");
+        let err = fetch_missed(&provider, 0, 10, &[EventType::Create]).await.unwrap_err();
+        assert!(err.starts_with("eth_getLogs 0..=10 failed"), "{}", err);
+
+        // Nothing to ask for, nothing sent
+        assert_eq!(fetch_missed(&provider, 0, 10, &[]).await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn creation_time_comes_from_the_create_log_block() {
+        let (provider, asserter) = mock_provider();
+        push_quantity(&asserter, 500);
+        let create = CurveCreate {
+            creator: Address::repeat_byte(1),
+            token: Address::repeat_byte(0x11),
+            pool: Address::repeat_byte(2),
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            tokenURI: String::new(),
+            virtualMon: U256::ZERO,
+            virtualToken: U256::ZERO,
+            targetTokenAmount: U256::ZERO,
+        };
+        asserter.push_success(&vec![curve_log(&create, 499, Some(1_234))]);
+        assert_eq!(creation_time(&provider, Address::repeat_byte(0x11), 100).await, Ok(Some(1_234)));
+
+        push_quantity(&asserter, 500);
+        asserter.push_success(&Vec::<Log>::new());
+        assert_eq!(creation_time(&provider, Address::repeat_byte(0x11), 50).await, Ok(None));
+    }
+}
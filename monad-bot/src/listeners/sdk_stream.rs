@@ -4,11 +4,24 @@
 
 //! nad.fun SDK-based event listener using official CurveStream.
 
+use crate::config::Config;
+use crate::health::{HealthStatus, LISTENER_TASK};
+use crate::listeners::backoff::{ReconnectBackoff, STABLE_AFTER};
+use crate::listeners::replay::{
+    creation_time, fetch_missed, replay_range, BlockCursor, CurveEvent, CREATE_LOOKBACK_BLOCKS,
+};
+use crate::rpc::{failover_client, EndpointRotation, FailoverPolicy};
+use crate::units::wei_to_mon;
 use alloy::primitives::{Address, B256, U256};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
 use futures_util::{pin_mut, StreamExt};
 use nadfun_sdk::stream::CurveStream;
 use nadfun_sdk::types::{BondingCurveEvent, EventType};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
 use tracing::{debug, error, info, warn};
 
 /// Event emitted when a new token is created.
@@ -34,143 +47,137 @@ pub struct CopyTradeEvent {
     pub amount_out: U256,
     pub is_buy: bool, // true = buy, false = sell
     pub is_scout_only: bool, // true = observe only, do not copy
+    pub tx_hash: Option<B256>,
+    /// Unix ms of the trade: when the stream delivered it, or its block time if replayed
+    pub timestamp_ms: u64,
+}
+
+impl CopyTradeEvent {
+    /// How long ago the trade happened, in ms.
+    pub fn age_ms(&self) -> u64 {
+        (chrono::Utc::now().timestamp_millis() as u64).saturating_sub(self.timestamp_ms)
+    }
+
+    /// The trade's age if it is older than `max_delay_ms` (0 = never stale).
+    pub fn stale_age_ms(&self, max_delay_ms: u64) -> Option<u64> {
+        let age_ms = self.age_ms();
+        (max_delay_ms > 0 && age_ms > max_delay_ms).then_some(age_ms)
+    }
+}
+
+/// Listener settings, taken from the bot config.
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    /// WebSocket URLs for nad.fun CurveStream, in order of preference
+    pub ws_urls: Vec<String>,
+    /// HTTP RPCs used to replay events missed while disconnected
+    pub rpc_urls: Vec<String>,
+    /// When to move on to the next WS or RPC endpoint
+    pub failover: FailoverPolicy,
+    /// Wallet addresses to track as "smart money"
+    pub smart_wallets: Vec<String>,
+    /// Skip Buy/Sell events entirely when `smart_wallets` is empty
+    pub create_only_without_wallets: bool,
+    /// Most blocks replayed after a reconnect (0 = no replay)
+    pub replay_max_blocks: u64,
+}
+
+impl StreamConfig {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            ws_urls: config.ws_urls.clone(),
+            rpc_urls: config.rpc_urls.clone(),
+            failover: FailoverPolicy::from_config(config),
+            smart_wallets: config.smart_wallets.clone(),
+            create_only_without_wallets: config.create_only_without_wallets,
+            replay_max_blocks: config.stream_replay_max_blocks,
+        }
+    }
 }
 
 /// Spawn the CurveStream listener as a background task.
 /// This replaces the legacy `nadfun::spawn_listener`.
-/// 
+///
+/// After a reconnect, events emitted while the stream was down are fetched
+/// from the curve's logs and forwarded before live ones.
+///
 /// # Arguments
-/// * `ws_url` - WebSocket URL for nad.fun CurveStream
+/// * `config` - Stream URL, smart wallets and replay settings
 /// * `tx` - Channel to send new token events
 /// * `copy_tx` - Channel to send copy trade events when smart wallets trade
-/// * `smart_wallets` - List of wallet addresses to track as "smart money"
+/// * `graduated_tx` - Channel to send tokens that graduated to a DEX
+/// * `health` - Connection flag and event counters for the heartbeat
 pub fn spawn_listener(
-    ws_url: String,
+    config: StreamConfig,
     tx: mpsc::Sender<NewTokenEvent>,
     copy_tx: mpsc::Sender<CopyTradeEvent>,
-    smart_wallets: Vec<String>,
+    graduated_tx: mpsc::Sender<Address>,
+    health: Arc<HealthStatus>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         info!("🔌 Connecting to nad.fun CurveStream...");
-        if !smart_wallets.is_empty() {
-            info!("👀 Tracking {} smart wallets for copy trading", smart_wallets.len());
+        if !config.smart_wallets.is_empty() {
+            info!("👀 Tracking {} smart wallets for copy trading", config.smart_wallets.len());
         }
 
+        let has_smart_wallets = !config.smart_wallets.is_empty();
+        if !has_smart_wallets && config.create_only_without_wallets {
+            info!("🔕 No smart wallets configured - skipping Buy/Sell events");
+        }
+        let event_types = stream_event_types(has_smart_wallets, config.create_only_without_wallets);
 
+        let replay_provider = if config.replay_max_blocks == 0 {
+            None
+        } else {
+            match failover_client(&config.rpc_urls, config.failover) {
+                Ok(client) => Some(ProviderBuilder::new().connect_client(client).erased()),
+                Err(e) => {
+                    warn!("⚠️ Invalid RPC URL, missed events won't be replayed: {}", e);
+                    None
+                }
+            }
+        };
+
+        let mut dispatcher = Dispatcher::new(tx, copy_tx, graduated_tx, config.smart_wallets, replay_provider.clone());
+        let mut cursor = BlockCursor::default();
+        let mut backoff = ReconnectBackoff::new();
+        let mut endpoints = EndpointRotation::new("CurveStream", config.ws_urls.clone(), config.failover.max_failures);
 
         loop {
-            match CurveStream::new(ws_url.clone()).await {
+            health.beat(LISTENER_TASK);
+            let connected_at = Instant::now();
+            let endpoint = endpoints.index();
+            match CurveStream::new(endpoints.current().to_string()).await {
                 Ok(curve_stream) => {
                     info!("✅ Connected to nad.fun CurveStream");
 
-                    // Subscribe to Create events for new tokens
-                    // We also subscribe to Buy/Sell for logging/debugging, but main loop only cares about Create for now
-                    let curve_stream = curve_stream
-                        .subscribe_events(vec![EventType::Create, EventType::Buy, EventType::Sell]);
+                    // Create for new tokens; Buy/Sell feed copy trading and the scout;
+                    // Graduate moves held tokens to DEX pricing.
+                    // Smart-wallet filtering stays in Rust: the SDK only filters by token
+                    // address, and the scout needs trades from unknown wallets anyway.
+                    let curve_stream = curve_stream.subscribe_events(event_types.clone());
 
                     match curve_stream.subscribe().await {
                         Ok(stream) => {
                             pin_mut!(stream);
+                            health.set_stream_up(true);
+
+                            // Subscribed first, so nothing falls between the replay and the stream
+                            if let Some(provider) = &replay_provider {
+                                replay_gap(provider, &mut cursor, config.replay_max_blocks, &event_types, &mut dispatcher)
+                                    .await;
+                            }
 
                             while let Some(event_result) = stream.next().await {
                                 match event_result {
                                     Ok(event) => {
-                                        match event {
-                                            BondingCurveEvent::Create(e) => {
-                                                info!(
-                                                    "🆕 NEW TOKEN: {} ({}) at {:?}",
-                                                    e.name, e.symbol, e.token
-                                                );
-                                                
-                                                let event = NewTokenEvent {
-                                                    token_address: e.token,
-                                                    name: e.name,
-                                                    symbol: e.symbol,
-                                                    creator: Some(e.creator),
-                                                    bonding_curve: Some(e.pool),
-                                                    initial_liquidity: None, // SDK create event might not have this, strategy handles None or fetching
-                                                    timestamp: Some(chrono::Utc::now().timestamp() as u64),
-                                                    tx_hash: None, // Stream might not provide tx hash directly in event struct yet
-                                                };
-
-                                                // Send to channel
-                                                if let Err(e) = tx.send(event).await {
-                                                    warn!("Failed to send token event: {}", e);
-                                                }
-                                            }
-                                            BondingCurveEvent::Buy(e) => {
-                                                let sender = e.sender;
-                                                let sender_lower = format!("{:?}", sender).to_lowercase();
-                                                
-                                                let is_target = smart_wallets.iter().any(|w| sender_lower.contains(w));
-                                                
-                                                // Calculate value roughly (amount_in is MON for Buy)
-                                                // Note: U256 to f64 helper needed or simple conversion
-                                                let val_str = e.amount_in.to_string();
-                                                let val_f64: f64 = val_str.parse().unwrap_or(0.0) / 1e18;
-
-                                                // Scout Filter: Ignore small unknown trades (< 5.0 MON)
-                                                if !is_target && val_f64 < 5.0 {
-                                                    continue;
-                                                }
-
-                                                if is_target {
-                                                    info!("🚨 SMART MONEY BUY: {:?} | Amount: {} | Sender: {:?}", e.token, e.amount_in, sender);
-                                                }
-
-                                                // Send event
-                                                let copy_event = CopyTradeEvent {
-                                                    token: e.token,
-                                                    smart_wallet: sender,
-                                                    amount_in: e.amount_in,
-                                                    amount_out: e.amount_out,
-                                                    is_buy: true,
-                                                    is_scout_only: !is_target,
-                                                };
-                                                if let Err(err) = copy_tx.send(copy_event).await {
-                                                    warn!("Failed to send event: {}", err);
-                                                }
-                                                debug!("📈 BUY: {:?} | In: {} | Out: {}", e.token, e.amount_in, e.amount_out);
-                                            }
-                                            BondingCurveEvent::Sell(e) => {
-                                                let sender = e.sender;
-                                                let sender_lower = format!("{:?}", sender).to_lowercase();
-
-
-                                                let is_target = smart_wallets.iter().any(|w| sender_lower.contains(w));
-
-                                                // Calculate value roughly (amount_out is MON for Sell)
-                                                let val_str = e.amount_out.to_string();
-                                                let val_f64: f64 = val_str.parse().unwrap_or(0.0) / 1e18;
-
-                                                // Scout Filter: Ignore small unrecgonized sells
-                                                if !is_target && val_f64 < 5.0 {
-                                                    continue;
-                                                }
-
-                                                if is_target {
-                                                    info!("🚨 SMART MONEY SELL: {:?} | Amount: {} | Sender: {:?}", e.token, e.amount_in, sender);
-                                                }
-
-                                                // Send copy trade event for sells too!
-                                                let copy_event = CopyTradeEvent {
-                                                    token: e.token,
-                                                    smart_wallet: sender,
-                                                    amount_in: e.amount_in,
-                                                    amount_out: e.amount_out,
-                                                    is_buy: false,
-                                                    is_scout_only: !is_target,
-                                                };
-                                                if let Err(err) = copy_tx.send(copy_event).await {
-                                                    warn!("Failed to send event: {}", err);
-                                                }
-
-                                                debug!("📉 SELL: {:?} | In: {} | Out: {}", e.token, e.amount_in, e.amount_out);
-                                            }
-                                            BondingCurveEvent::Graduate(e) => {
-                                                info!("🎓 GRADUATED: {:?} -> Pool: {:?}", e.token, e.pool);
-                                            }
-                                            _ => {}
+                                        health.record_event();
+                                        health.beat(LISTENER_TASK);
+                                        if let Some(event) = curve_event(event) {
+                                            dispatcher.dispatch(event, None).await;
+                                        }
+                                        if let Some(provider) = &replay_provider {
+                                            sample_head(provider, &mut cursor).await;
                                         }
                                     }
                                     Err(e) => {
@@ -179,7 +186,8 @@ pub fn spawn_listener(
                                 }
                             }
 
-                            warn!("CurveStream ended, reconnecting...");
+                            health.set_stream_up(false);
+                            warn!("CurveStream ended");
                         }
                         Err(e) => {
                             error!("Failed to subscribe to CurveStream: {}", e);
@@ -187,12 +195,492 @@ pub fn spawn_listener(
                     }
                 }
                 Err(e) => {
-                    error!("Failed to connect to CurveStream ({}). Retrying...", e);
+                    error!("Failed to connect to CurveStream ({})", e);
                 }
             }
 
-            // Reconnect delay
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            // A connection that didn't stay up counts against the endpoint
+            let uptime = connected_at.elapsed();
+            if uptime >= STABLE_AFTER {
+                endpoints.record_success(endpoint);
+            } else {
+                endpoints.record_failure(endpoint);
+            }
+
+            let delay = backoff.next_delay(uptime);
+            warn!("🔌 Reconnecting to CurveStream in {:.1}s...", delay.as_secs_f64());
+            tokio::time::sleep(delay).await;
         }
     })
 }
+
+/// Forward the events emitted between the cursor and the current head, then
+/// move the cursor to the head. The first connection has no cursor and
+/// replays nothing.
+async fn replay_gap<P: Provider>(
+    provider: &P,
+    cursor: &mut BlockCursor,
+    max_blocks: u64,
+    event_types: &[EventType],
+    dispatcher: &mut Dispatcher,
+) {
+    let head = match provider.get_block_number().await {
+        Ok(head) => head,
+        Err(e) => {
+            warn!("⚠️ Can't read head block, missed events not replayed: {}", e);
+            return;
+        }
+    };
+
+    if let Some((from, to)) = replay_range(cursor.block(), head, max_blocks) {
+        let gap = head.saturating_sub(cursor.block().unwrap_or(head));
+        if gap > max_blocks {
+            warn!(
+                "⚠️ Stream was down for {} blocks, replaying only the last {}",
+                gap, max_blocks
+            );
+        }
+        match fetch_missed(provider, from, to, event_types).await {
+            Ok(missed) => {
+                for missed in missed {
+                    dispatcher.dispatch(missed.event, missed.block_timestamp).await;
+                }
+            }
+            Err(e) => warn!("⚠️ Failed to replay missed events: {}", e),
+        }
+    }
+
+    cursor.advance(head);
+}
+
+/// Keep the cursor near the head while the stream is live.
+async fn sample_head<P: Provider>(provider: &P, cursor: &mut BlockCursor) {
+    if !cursor.needs_sample() {
+        return;
+    }
+    match provider.get_block_number().await {
+        Ok(head) => cursor.advance(head),
+        Err(e) => debug!("Head block sample failed: {}", e),
+    }
+}
+
+/// The curve event behind a stream event, if it's one the bot handles.
+fn curve_event(event: BondingCurveEvent) -> Option<CurveEvent> {
+    match event {
+        BondingCurveEvent::Create(e) => Some(CurveEvent::Create {
+            token: e.token,
+            creator: e.creator,
+            pool: e.pool,
+            name: e.name,
+            symbol: e.symbol,
+        }),
+        BondingCurveEvent::Buy(e) => Some(CurveEvent::Trade {
+            token: e.token,
+            sender: e.sender,
+            amount_in: e.amount_in,
+            amount_out: e.amount_out,
+            is_buy: true,
+        }),
+        BondingCurveEvent::Sell(e) => Some(CurveEvent::Trade {
+            token: e.token,
+            sender: e.sender,
+            amount_in: e.amount_in,
+            amount_out: e.amount_out,
+            is_buy: false,
+        }),
+        BondingCurveEvent::Graduate(e) => Some(CurveEvent::Graduate {
+            token: e.token,
+            pool: e.pool,
+        }),
+        _ => None,
+    }
+}
+
+/// How long a forwarded event is remembered. Covers the longest reconnect
+/// backoff plus the replay overlap.
+const SEEN_TTL: Duration = Duration::from_secs(300);
+
+/// How often expired entries are dropped from the seen set.
+const SEEN_PRUNE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Turns curve events into main-loop events. Replayed logs overlap what the
+/// stream already delivered, so events forwarded within `SEEN_TTL` are dropped.
+struct Dispatcher {
+    tx: mpsc::Sender<NewTokenEvent>,
+    copy_tx: mpsc::Sender<CopyTradeEvent>,
+    graduated_tx: mpsc::Sender<Address>,
+    smart_wallets: Vec<String>,
+    /// Looks up launch times; without it launches are stamped with the receipt time.
+    provider: Option<DynProvider>,
+    seen: HashMap<CurveEvent, Instant>,
+    pruned_at: Instant,
+}
+
+impl Dispatcher {
+    fn new(
+        tx: mpsc::Sender<NewTokenEvent>,
+        copy_tx: mpsc::Sender<CopyTradeEvent>,
+        graduated_tx: mpsc::Sender<Address>,
+        smart_wallets: Vec<String>,
+        provider: Option<DynProvider>,
+    ) -> Self {
+        Self {
+            tx,
+            copy_tx,
+            graduated_tx,
+            smart_wallets,
+            provider,
+            seen: HashMap::new(),
+            pruned_at: Instant::now(),
+        }
+    }
+
+    /// Forward `event` unless it was already forwarded. `block_timestamp` is
+    /// set for replayed events whose block time is known.
+    async fn dispatch(&mut self, event: CurveEvent, block_timestamp: Option<u64>) {
+        // Scout filter first, so ignored trades don't fill the seen set
+        if let CurveEvent::Trade { sender, amount_in, amount_out, is_buy, .. } = &event {
+            // MON is amount_in for a Buy, amount_out for a Sell
+            let mon = wei_to_mon(if *is_buy { *amount_in } else { *amount_out });
+            // Scout Filter: Ignore small unknown trades (< 5.0 MON)
+            if !self.is_target(sender) && mon < 5.0 {
+                return;
+            }
+        }
+
+        if self.already_seen(&event) {
+            debug!("Skipping already forwarded event: {:?}", event);
+            return;
+        }
+
+        match event {
+            CurveEvent::Create { token, creator, pool, name, symbol } => {
+                info!("🆕 NEW TOKEN: {} ({}) at {:?}", name, symbol, token);
+                let created_at = match block_timestamp {
+                    Some(timestamp) => timestamp,
+                    None => self.creation_time(token).await,
+                };
+
+                let event = NewTokenEvent {
+                    token_address: token,
+                    name,
+                    symbol,
+                    creator: Some(creator),
+                    bonding_curve: Some(pool),
+                    initial_liquidity: None, // SDK create event might not have this, strategy handles None or fetching
+                    timestamp: Some(created_at),
+                    tx_hash: None, // Stream might not provide tx hash directly in event struct yet
+                };
+                forward(&self.tx, event, "token").await;
+            }
+            CurveEvent::Trade { token, sender, amount_in, amount_out, is_buy } => {
+                let is_target = self.is_target(&sender);
+                if is_target {
+                    let side = if is_buy { "BUY" } else { "SELL" };
+                    info!("🚨 SMART MONEY {}: {:?} | Amount: {} | Sender: {:?}", side, token, amount_in, sender);
+                }
+
+                let copy_event = CopyTradeEvent {
+                    token,
+                    smart_wallet: sender,
+                    amount_in,
+                    amount_out,
+                    is_buy,
+                    is_scout_only: !is_target,
+                    tx_hash: None, // Not exposed by the SDK event; dedupe falls back to trade fields
+                    timestamp_ms: block_timestamp
+                        .map(|secs| secs * 1000)
+                        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64),
+                };
+                forward(&self.copy_tx, copy_event, "copy trade").await;
+
+                if is_buy {
+                    debug!("📈 BUY: {:?} | In: {} | Out: {}", token, amount_in, amount_out);
+                } else {
+                    debug!("📉 SELL: {:?} | In: {} | Out: {}", token, amount_in, amount_out);
+                }
+            }
+            CurveEvent::Graduate { token, pool } => {
+                info!("🎓 GRADUATED: {:?} -> Pool: {:?}", token, pool);
+                forward(&self.graduated_tx, token, "graduation").await;
+            }
+        }
+    }
+
+    /// Launch time of a live token from its Create log's block. Costs an
+    /// eth_getLogs and a block fetch; falls back to now, which is within a
+    /// block or two for a live event.
+    async fn creation_time(&self, token: Address) -> u64 {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let Some(provider) = &self.provider else {
+            return now;
+        };
+        match creation_time(provider, token, CREATE_LOOKBACK_BLOCKS).await {
+            Ok(Some(timestamp)) => timestamp,
+            Ok(None) => {
+                debug!("No Create log for {:?} in the last {} blocks, using now", token, CREATE_LOOKBACK_BLOCKS);
+                now
+            }
+            Err(e) => {
+                debug!("Creation time lookup for {:?} failed, using now: {}", token, e);
+                now
+            }
+        }
+    }
+
+    fn is_target(&self, sender: &Address) -> bool {
+        let sender_lower = format!("{:?}", sender).to_lowercase();
+        self.smart_wallets.iter().any(|w| sender_lower.contains(w))
+    }
+
+    /// Record the event; returns `true` if it was forwarded within the TTL.
+    fn already_seen(&mut self, event: &CurveEvent) -> bool {
+        let now = Instant::now();
+        if now.duration_since(self.pruned_at) >= SEEN_PRUNE_INTERVAL {
+            self.seen.retain(|_, seen_at| now.duration_since(*seen_at) < SEEN_TTL);
+            self.pruned_at = now;
+        }
+        match self.seen.insert(event.clone(), now) {
+            Some(seen_at) => now.duration_since(seen_at) < SEEN_TTL,
+            None => false,
+        }
+    }
+}
+
+/// CurveStream event types to subscribe to.
+/// Buy/Sell are dropped only when there are no smart wallets and the config opts out of them.
+pub fn stream_event_types(has_smart_wallets: bool, create_only_without_wallets: bool) -> Vec<EventType> {
+    if !has_smart_wallets && create_only_without_wallets {
+        vec![EventType::Create, EventType::Graduate]
+    } else {
+        vec![EventType::Create, EventType::Buy, EventType::Sell, EventType::Graduate]
+    }
+}
+
+/// Send an event to the main loop, warning when the buffer is full
+/// (the stream reader then waits for space instead of dropping the event).
+async fn forward<T>(tx: &mpsc::Sender<T>, event: T, kind: &str) {
+    let event = match tx.try_send(event) {
+        Ok(()) => return,
+        Err(TrySendError::Full(event)) => {
+            warn!("⚠️ {} channel full ({} slots) - stream is backing up", kind, tx.max_capacity());
+            event
+        }
+        Err(TrySendError::Closed(_)) => {
+            warn!("Failed to send {} event: channel closed", kind);
+            return;
+        }
+    };
+
+    if let Err(e) = tx.send(event).await {
+        warn!("Failed to send {} event: {}", kind, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn buy_and_sell_are_dropped_only_when_opted_out_without_wallets() {
+        let all = vec![EventType::Create, EventType::Buy, EventType::Sell, EventType::Graduate];
+        assert_eq!(stream_event_types(false, true), vec![EventType::Create, EventType::Graduate]);
+        assert_eq!(stream_event_types(false, false), all);
+        assert_eq!(stream_event_types(true, true), all);
+    }
+
+    #[tokio::test]
+    async fn full_channel_waits_for_space_instead_of_dropping() {
+        let (tx, mut rx) = mpsc::channel(1);
+        forward(&tx, 1, "test").await;
+
+        let pending = tokio::spawn(async move { forward(&tx, 2, "test").await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!pending.is_finished());
+
+        assert_eq!(rx.recv().await, Some(1));
+        pending.await.unwrap();
+        assert_eq!(rx.recv().await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn closed_channel_does_not_block() {
+        let (tx, rx) = mpsc::channel::<u32>(1);
+        drop(rx);
+        tokio::time::timeout(Duration::from_secs(1), forward(&tx, 1, "test")).await.unwrap();
+    }
+
+    use crate::executor::mock::{mock_provider, push_quantity};
+    use crate::listeners::replay::tests::{buy, curve_log};
+
+    struct Channels {
+        tokens: mpsc::Receiver<NewTokenEvent>,
+        trades: mpsc::Receiver<CopyTradeEvent>,
+        _graduated: mpsc::Receiver<Address>,
+    }
+
+    fn dispatcher(wallet: Address) -> (Dispatcher, Channels) {
+        let (tx, tokens) = mpsc::channel(16);
+        let (copy_tx, trades) = mpsc::channel(16);
+        let (graduated_tx, _graduated) = mpsc::channel(16);
+        let wallets = vec![format!("{:?}", wallet).to_lowercase()];
+        let dispatcher = Dispatcher::new(tx, copy_tx, graduated_tx, wallets, None);
+        (dispatcher, Channels { tokens, trades, _graduated })
+    }
+
+    #[tokio::test]
+    async fn disconnect_gap_is_replayed_exactly_once() {
+        let wallet = Address::repeat_byte(0xaa);
+        let (mut dispatcher, mut channels) = dispatcher(wallet);
+        let (provider, asserter) = mock_provider();
+        let types = stream_event_types(true, false);
+
+        // Live at block 100; the stream delivered the first trade before dropping
+        let mut cursor = BlockCursor::default();
+        cursor.advance(100);
+        let seen_live = buy(wallet, 1);
+        dispatcher.dispatch(curve_event_of(&seen_live), None).await;
+        assert!(channels.trades.try_recv().is_ok());
+
+        // Back at block 105: the replay reads 90..=105 and holds the live trade again plus two missed ones
+        push_quantity(&asserter, 105);
+        asserter.push_success(&vec![
+            curve_log(&seen_live, 99, Some(1_000)),
+            curve_log(&buy(wallet, 2), 102, Some(1_010)),
+            curve_log(&buy(wallet, 3), 104, Some(1_020)),
+        ]);
+        replay_gap(&provider, &mut cursor, 1000, &types, &mut dispatcher).await;
+
+        let replayed: Vec<CopyTradeEvent> = std::iter::from_fn(|| channels.trades.try_recv().ok()).collect();
+        assert_eq!(replayed.iter().map(|t| t.amount_in).collect::<Vec<_>>(), vec![U256::from(2u64), U256::from(3u64)]);
+        assert_eq!(replayed[0].timestamp_ms, 1_010_000);
+        assert_eq!(cursor.block(), Some(105));
+
+        // A second reconnect overlapping the same blocks forwards nothing new
+        push_quantity(&asserter, 106);
+        asserter.push_success(&vec![curve_log(&buy(wallet, 3), 104, Some(1_020))]);
+        replay_gap(&provider, &mut cursor, 1000, &types, &mut dispatcher).await;
+        assert!(channels.trades.try_recv().is_err());
+        assert!(channels.tokens.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn first_connection_replays_nothing() {
+        let (mut dispatcher, mut channels) = dispatcher(Address::repeat_byte(0xaa));
+        let (provider, asserter) = mock_provider();
+        let mut cursor = BlockCursor::default();
+        push_quantity(&asserter, 500);
+
+        replay_gap(&provider, &mut cursor, 1000, &stream_event_types(true, false), &mut dispatcher).await;
+        assert_eq!(cursor.block(), Some(500));
+        assert!(channels.trades.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn small_trades_from_unknown_wallets_are_not_forwarded() {
+        let (mut dispatcher, mut channels) = dispatcher(Address::repeat_byte(0xaa));
+        let stranger = Address::repeat_byte(0xbb);
+        dispatcher.dispatch(curve_event_of(&buy(stranger, 1)), None).await;
+        assert!(channels.trades.try_recv().is_err());
+
+        // Five MON or more is scouted
+        let whale = buy(stranger, 5_000_000_000_000_000_000);
+        dispatcher.dispatch(curve_event_of(&whale), None).await;
+        assert!(channels.trades.try_recv().unwrap().is_scout_only);
+    }
+
+    /// The curve event a live stream would deliver for a replay-log buy.
+    fn curve_event_of(buy: &crate::listeners::replay::CurveBuy) -> CurveEvent {
+        CurveEvent::Trade {
+            token: buy.token,
+            sender: buy.sender,
+            amount_in: buy.amountIn,
+            amount_out: buy.amountOut,
+            is_buy: true,
+        }
+    }
+
+    fn copy_trade(timestamp_ms: u64) -> CopyTradeEvent {
+        CopyTradeEvent {
+            token: Address::repeat_byte(0x11),
+            smart_wallet: Address::repeat_byte(0xaa),
+            amount_in: U256::from(1u64),
+            amount_out: U256::from(1u64),
+            is_buy: true,
+            is_scout_only: false,
+            tx_hash: None,
+            timestamp_ms,
+        }
+    }
+
+    #[test]
+    fn copy_trades_go_stale_past_the_max_delay() {
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        assert_eq!(copy_trade(now_ms + 1_000).stale_age_ms(3_000), None);
+        assert_eq!(copy_trade(now_ms).stale_age_ms(3_000), None);
+
+        let old = copy_trade(now_ms - 10_000);
+        assert!(old.stale_age_ms(3_000).is_some_and(|age| age >= 10_000));
+        // Zero turns the guard off
+        assert_eq!(old.stale_age_ms(0), None);
+    }
+
+    #[tokio::test]
+    async fn live_trades_are_stamped_on_arrival() {
+        let wallet = Address::repeat_byte(0xaa);
+        let (mut dispatcher, mut channels) = dispatcher(wallet);
+        dispatcher.dispatch(curve_event_of(&buy(wallet, 1)), None).await;
+        assert_eq!(channels.trades.try_recv().unwrap().stale_age_ms(3_000), None);
+    }
+
+    fn create(token: Address) -> CurveEvent {
+        CurveEvent::Create {
+            token,
+            creator: Address::repeat_byte(1),
+            pool: Address::repeat_byte(2),
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn launches_are_dated_from_their_create_block() {
+        let (mut dispatcher, mut channels) = dispatcher(Address::repeat_byte(0xaa));
+
+        // Replayed: the log's block time
+        dispatcher.dispatch(create(Address::repeat_byte(0x11)), Some(1_234)).await;
+        assert_eq!(channels.tokens.try_recv().unwrap().timestamp, Some(1_234));
+
+        // Live: looked up from the Create log
+        let (provider, asserter) = mock_provider();
+        dispatcher.provider = Some(provider.erased());
+        push_quantity(&asserter, 500);
+        let log = crate::listeners::replay::CurveCreate {
+            creator: Address::repeat_byte(1),
+            token: Address::repeat_byte(0x12),
+            pool: Address::repeat_byte(2),
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            tokenURI: String::new(),
+            virtualMon: U256::ZERO,
+            virtualToken: U256::ZERO,
+            targetTokenAmount: U256::ZERO,
+        };
+        asserter.push_success(&vec![curve_log(&log, 499, Some(5_678))]);
+        dispatcher.dispatch(create(Address::repeat_byte(0x12)), None).await;
+        assert_eq!(channels.tokens.try_recv().unwrap().timestamp, Some(5_678));
+    }
+
+    #[tokio::test]
+    async fn failed_lookup_dates_a_live_launch_now() {
+        let (mut dispatcher, mut channels) = dispatcher(Address::repeat_byte(0xaa));
+        let (provider, asserter) = mock_provider();
+        dispatcher.provider = Some(provider.erased());
+        asserter.push_failure_msg("connection reset");
+
+        let before = chrono::Utc::now().timestamp() as u64;
+        dispatcher.dispatch(create(Address::repeat_byte(0x11)), None).await;
+        assert!(channels.tokens.try_recv().unwrap().timestamp.is_some_and(|ts| ts >= before));
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Log output setup: human-readable text or JSON lines for log aggregators.
+
+use tracing::{Level, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::FmtSubscriber;
+
+/// How log lines are written to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Compact human output (local use).
+    #[default]
+    Text,
+    /// One JSON object per line, with event fields as keys (Loki, Datadog, ...).
+    Json,
+}
+
+impl LogFormat {
+    /// Parse `text` / `json` (case-insensitive). Anything else falls back to `Text`.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "json" => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Install the global tracing subscriber in the given format.
+pub fn init_logging(format: LogFormat) -> Result<(), tracing::subscriber::SetGlobalDefaultError> {
+    tracing::subscriber::set_global_default(subscriber(format, std::io::stdout))
+}
+
+/// Subscriber writing `format` lines to `writer`.
+fn subscriber<W>(format: LogFormat, writer: W) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let builder = FmtSubscriber::builder()
+        .with_writer(writer)
+        .with_max_level(Level::INFO)
+        .with_target(false)
+        .with_thread_ids(false)
+        .with_file(false)
+        .with_line_number(false);
+
+    match format {
+        LogFormat::Text => Box::new(builder.finish()),
+        LogFormat::Json => Box::new(builder.json().flatten_event(true).with_current_span(false).finish()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects everything written by a subscriber.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Captured {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    fn log_line(format: LogFormat) -> String {
+        let captured = Captured::default();
+        tracing::subscriber::with_default(subscriber(format, captured.clone()), || {
+            tracing::info!(token = "0xabc", venue = "dex", "✅ DEX Sell executed");
+            tracing::debug!("below the level");
+        });
+        let bytes = captured.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn json_lines_carry_event_fields_as_keys() {
+        let output = log_line(LogFormat::Json);
+        assert_eq!(output.lines().count(), 1);
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["token"], "0xabc");
+        assert_eq!(line["venue"], "dex");
+        assert_eq!(line["message"], "✅ DEX Sell executed");
+        assert_eq!(line["level"], "INFO");
+    }
+
+    #[test]
+    fn text_lines_are_not_json() {
+        let output = log_line(LogFormat::Text);
+        assert!(output.contains("DEX Sell executed") && output.contains("\"dex\""));
+        assert!(serde_json::from_str::<serde_json::Value>(output.trim()).is_err());
+    }
+
+    #[test]
+    fn format_parse_falls_back_to_text() {
+        assert_eq!(LogFormat::parse(" JSON "), LogFormat::Json);
+        assert_eq!(LogFormat::parse("pretty"), LogFormat::Text);
+    }
+}
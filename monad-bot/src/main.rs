@@ -3,52 +3,74 @@
 
 //! Monad Sniper Bot - Fast token sniping for nad.fun
 
+mod api;
 mod arbitrage;
+mod backtest;
 mod config;
+mod discord;
+mod error;
 mod executor;
 mod handlers;
+mod health;
+mod jitter;
 mod listeners;
+mod logging;
+mod notify;
 mod position;
+mod persist;
+mod price;
+mod price_feed;
+mod reject_log;
 mod rpc;
+mod shutdown;
 mod strategies;
 mod streams;
 mod trade_history;
+mod units;
 mod validators;
 mod telegram;
+mod token_meta;
 
 use config::Config;
-use executor::{SdkExecutor, SellExecutor, SwapExecutor};
-use handlers::spawn_sell_handler;
-use listeners::{spawn_listener, NewTokenEvent, CopyTradeEvent};
-use telegram::TelegramNotifier;
-use position::{spawn_monitor, Position, PositionTracker, SellDecision, TrailingStopLossConfig};
-use rpc::create_provider;
-use strategies::SniperStrategy;
+use logging::init_logging;
+use executor::{build_submitter, GasStrategy, SdkExecutor, SellExecutor, SwapExecutor, TradeError};
+use executor::wmon::spawn_wmon_sweeper;
+use handlers::{emergency_slippage, sell_all, spawn_sell_handler, SellContext};
+use health::{spawn_deadman, spawn_heartbeat, spawn_watchdog, HealthStatus, SupervisedTask, LISTENER_TASK, MEMPOOL_TASK, MONITOR_TASK};
+use listeners::{spawn_listener, CopyTradeDedupe, NewTokenDedupe, NewTokenEvent, CopyTradeEvent, StreamConfig};
+use notify::{Notifications, NotifyEvent};
+use telegram::spawn_command_listener;
+use position::{spawn_monitor, LivePriceSource, Position, PositionMonitor, PositionTracker, SellDecision, TrailingStopLossConfig};
+use price::MonPriceOracle;
+use price_feed::{fetch_mon_price_usd, spawn_price_feed};
+use reject_log::{count_by_reason, RejectLog, RejectReason};
+use shutdown::InFlightTrades;
+use trade_history::{TradeHistory, TradeRecord};
+use units::wei_to_mon;
+use rpc::{create_provider, network_name, verify_chain_id};
+use strategies::{get_market_cap_usd, spawn_limit_buyer, spawn_order_watcher, BuyDecision, BuyThrottle, LimitBuyConfig, PendingOrderConfig, SmartWallets, SniperStrategy};
 use validators::wallet_tracker::WalletTracker;
-use validators::{TokenAnalyzer, FilterConfig};
+use validators::{TokenAnalyzer, FilterConfig, TradingCheck};
 
+use alloy::providers::Provider;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, Mutex};
 use tokio::signal;
-use tracing::{info, warn, error, debug, Level};
+use tracing::{info, warn, error, debug};
 use std::collections::{HashMap, HashSet};
-use tracing_subscriber::FmtSubscriber;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load configuration first (it also loads .env), so LOG_FORMAT applies from the first line
+    let config_for_test = Config::from_env();
+
     // Initialize logging
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_file(false)
-        .with_line_number(false)
-        .finish();
-
-    tracing::subscriber::set_global_default(subscriber)?;
-
-    // Load configuration for potential test mode
-    let config_for_test = Config::from_env().map_err(|e| {
+    init_logging(config_for_test.as_ref().map(|c| c.log_format).unwrap_or_default())?;
+    config::warn_deprecated_vars();
+
+    let config_for_test = config_for_test.map_err(|e| {
         error!("Failed to load config for test mode: {}", e);
         e
     });
@@ -64,13 +86,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             
         info!("🧪 Testing analysis for {:?}", token_addr);
         
-        let (provider, _) = create_provider(&rpc::RpcConfig {
-            rpc_url: config.rpc_url.clone(),
-            private_key: config.private_key.clone(),
-            chain_id: config.chain_id,
-        })?;
-        let filter_config = FilterConfig::default(); // Changed to use the imported FilterConfig
-        let analyzer = TokenAnalyzer::new(provider, filter_config, 0.50); // Changed to use the imported TokenAnalyzer
+        let (provider, _) = create_provider(&rpc::RpcConfig::from_config(&config))?;
+        let filter_config = FilterConfig::from_config(&config);
+        let analyzer = TokenAnalyzer::new(provider, filter_config, MonPriceOracle::new(config.mon_price_usd)); // Changed to use the imported TokenAnalyzer
         
         let analysis = analyzer.analyze(token_addr, None, 0, 1000.0).await;
         info!("📊 Results: {:?}", analysis);
@@ -82,95 +100,257 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    if args.len() > 1 && args[1] == "--revoke-approvals" {
+        let config = config_for_test?;
+        return revoke_approvals(&config, &args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "--backtest" {
+        let config = config_for_test?;
+        let path = args.get(2).ok_or("Usage: --backtest <events.jsonl>")?;
+        backtest::run_backtest(&config, path).await?;
+        return Ok(());
+    }
+
+    if args.len() > 1 && args[1] == "--review-rejects" {
+        let config = config_for_test?;
+        let with_prices = args[2..].iter().any(|arg| arg == "--prices");
+        return review_rejects(&config, with_prices).await;
+    }
+
     info!("🚀 Monad Sniper Bot starting...");
 
     // Load configuration (main execution)
-    let config = config_for_test?; // Use the already loaded config
+    let mut config = config_for_test?; // Use the already loaded config
+
+    info!("📡 RPC: {}", config.rpc_urls.join(", "));
+    info!("📡 WS:  {}", config.ws_urls.join(", "));
+    // MON/USD price used for display (USD omitted while unknown)
+    let mon_price = MonPriceOracle::new(config.mon_price_usd);
 
-    info!("📡 RPC: {}", config.rpc_url);
-    info!("📡 WS:  {}", config.ws_url);
     info!("👛 Wallet: {:?}", config.wallet_address);
-    info!("💰 Snipe amount: {} MON", config.snipe_amount_mon);
+    if config.paper_trading {
+        warn!("📝 PAPER TRADING: trades are quoted and logged, no transactions are sent");
+    }
+    info!("💰 Snipe amount: {} MON{}", config.snipe_amount_mon, mon_price.format_usd(config.snipe_amount_mon));
+    if config.snipe_amount_jitter_pct > 0.0 || config.gas_jitter_pct > 0.0 {
+        info!(
+            "🎲 Jitter: amount ±{}%, priority fee ±{}%",
+            config.snipe_amount_jitter_pct, config.gas_jitter_pct
+        );
+    }
     info!("📉 Trailing SL: {}% drop, {}% min profit", config.trailing_drop_pct, config.trailing_min_profit);
 
+    // Copy targets and their sizing; the listeners watch every listed wallet, enabled or not
+    let mut smart_wallets = SmartWallets::from_config(&config);
+    config.smart_wallets = smart_wallets.addresses();
+
     // Create provider and wallet
-    let (provider, wallet) = create_provider(&rpc::RpcConfig {
-        rpc_url: config.rpc_url.clone(),
-        private_key: config.private_key.clone(),
-        chain_id: config.chain_id,
-    })?;
+    let (provider, wallet) = create_provider(&rpc::RpcConfig::from_config(&config))?;
+    verify_chain_id(&provider, config.chain_id).await?;
 
-    info!("✅ Connected to Monad RPC");
+    info!("✅ Connected to {} RPC (chain {})", network_name(config.chain_id), config.chain_id);
+
+    // Keep the MON price live so USD filters and displays track the market
+    if config.mon_price_ttl_secs > 0 {
+        let _price_feed_handle = spawn_price_feed(
+            provider.clone(),
+            mon_price.clone(),
+            config.wmon_address,
+            config.usdc_address,
+            config.mon_price_ttl_secs,
+        );
+    }
 
     // Create swap executor (for buying new tokens via DEX)
-    let buy_executor = SwapExecutor::new(provider.clone(), wallet.clone(), &config).await?;
+    let buy_executor = Arc::new(SwapExecutor::new(provider.clone(), wallet.clone(), &config).await?);
 
     // Create SDK executor (for bonding curve trades - copy trading)
-    let sdk_executor = Arc::new(
-        SdkExecutor::new(
-            config.rpc_url.clone(),
-            config.private_key.clone(),
-            5.0, // 5% slippage for copy trades
-        ).await?
-    );
+    let mut sdk_executor = SdkExecutor::new(&config, GasStrategy::from_multiplier(config.snipe_gas_multiplier)).await?;
+    // Bonding-curve buys use the private relay too when PRIVATE_TX_BUYS is set
+    if config.private_tx_buys && config.private_tx_url.is_some() {
+        sdk_executor = sdk_executor.with_submitter(build_submitter(provider.clone(), wallet.clone(), &config, true)?);
+    }
+    let sdk_executor = Arc::new(sdk_executor);
 
     // Create sell executor
     let sell_executor = Arc::new(SellExecutor::new(provider.clone(), wallet, &config).await?);
 
+    // Return stranded WMON to native MON so it funds buys
+    if config.wmon_sweep_interval_secs > 0 && !config.paper_trading {
+        let _wmon_sweep_handle = spawn_wmon_sweeper(
+            Arc::clone(&sell_executor),
+            config.wmon_sweep_threshold_mon,
+            config.wmon_sweep_interval_secs,
+        );
+    }
+
     // Create strategy
-    let strategy = SniperStrategy::from_config(&config);
+    let mut strategy = SniperStrategy::from_config(&config);
+    strategy.mon_price = mon_price.clone();
+
+    // Simulated-buy precheck shared by the strategy and the limit-buy poller
+    let trading_check = Arc::new(TradingCheck::new(Arc::clone(&sdk_executor)));
+    strategy.trading_check = Some(Arc::clone(&trading_check));
 
     // Create token analyzer
     let analyzer = TokenAnalyzer::new(
         provider.clone(),
-        FilterConfig::default(),
-        0.50, // TODO: Fetch price dynamically or from config
+        FilterConfig::from_config(&config),
+        mon_price.clone(),
     );
 
     // Load existing positions into Arc<Mutex<>>
-    let positions = Arc::new(Mutex::new(PositionTracker::load()));
+    let mut position_tracker = PositionTracker::load();
+    position_tracker.averaging_enabled = config.position_averaging;
+    let positions = Arc::new(Mutex::new(position_tracker));
     {
         let pos_guard = positions.lock().await;
         info!("📊 Loaded {} existing positions", pos_guard.len());
     }
 
+    // Load trade history
+    let trade_history = Arc::new(Mutex::new(TradeHistory::load()));
+    trade_history.lock().await.log_summary(&mon_price);
+
+    // Connection flags / event counters shared by listeners and the heartbeat
+    let health = Arc::new(HealthStatus::new());
+
     // Load Wallet Tracker
     let wallet_tracker = Arc::new(Mutex::new(WalletTracker::load()));
     info!("📊 Wallet Tracker loaded");
 
+    // Read-only status API over the shared trackers
+    if let Some(token) = config.api_token.clone() {
+        let _api_handle = api::spawn_api_server(
+            config.api_port,
+            api::ApiState {
+                token,
+                health: Arc::clone(&health),
+                positions: Arc::clone(&positions),
+                wallet_tracker: Arc::clone(&wallet_tracker),
+                trade_history: Arc::clone(&trade_history),
+            },
+        );
+    }
+
     // Create channels
-    let (new_token_tx, mut new_token_rx) = mpsc::channel::<NewTokenEvent>(100);
-    let (sell_signal_tx, sell_signal_rx) = mpsc::channel::<(alloy::primitives::Address, SellDecision)>(100);
-    let (copy_trade_tx, mut copy_trade_rx) = mpsc::channel::<CopyTradeEvent>(100);
+    let (new_token_tx, mut new_token_rx) = mpsc::channel::<NewTokenEvent>(config.channel_capacity);
+    let (sell_signal_tx, sell_signal_rx) = mpsc::channel::<(alloy::primitives::Address, SellDecision)>(config.channel_capacity);
+    let (copy_trade_tx, mut copy_trade_rx) = mpsc::channel::<CopyTradeEvent>(config.channel_capacity);
+    let (limit_buy_tx, mut limit_buy_rx) = mpsc::channel::<BuyDecision>(config.channel_capacity);
+    let (graduated_tx, graduated_rx) = mpsc::channel::<alloy::primitives::Address>(config.channel_capacity);
+    let (unstick_tx, mut unstick_rx) = mpsc::channel::<()>(1);
+    let (unwrap_tx, mut unwrap_rx) = mpsc::channel::<()>(1);
+
+    // Start limit-buy poller (parks safe tokens below the entry zone)
+    if config.limit_buy_enabled {
+        let (register_tx, register_rx) = mpsc::channel::<NewTokenEvent>(config.channel_capacity);
+        strategy.limit_buy_tx = Some(register_tx);
+        let _limit_buy_handle = spawn_limit_buyer(
+            Arc::clone(&buy_executor),
+            Some(Arc::clone(&trading_check)),
+            LimitBuyConfig::from_config(&config, &strategy),
+            register_rx,
+            limit_buy_tx.clone(),
+        );
+    }
+
+    // Start pending-order watcher (parks safe tokens above the entry zone until they dip)
+    if config.pending_orders_enabled {
+        let (register_tx, register_rx) = mpsc::channel::<NewTokenEvent>(config.channel_capacity);
+        strategy.pending_order_tx = Some(register_tx);
+        let _pending_orders_handle = spawn_order_watcher(
+            Arc::clone(&buy_executor),
+            Some(Arc::clone(&trading_check)),
+            PendingOrderConfig::from_config(&config, &strategy),
+            register_rx,
+            limit_buy_tx,
+        );
+    }
+
+    // Sells and front-runs awaiting receipts; shutdown waits for these
+    let in_flight = InFlightTrades::new();
+
+    // Tasks the watchdog restarts if they die or go silent
+    let stale_after = (config.watchdog_stale_secs > 0).then(|| Duration::from_secs(config.watchdog_stale_secs));
+    let mut supervised = Vec::new();
 
     // Start blockchain event listener
     info!("🔌 Connecting to Monad WebSocket for events...");
-    let _listener_handle = spawn_listener(
-        config.ws_url.clone(), 
-        new_token_tx,
-        copy_trade_tx,
-        config.smart_wallets.clone(),
-    );
+    supervised.push({
+        let stream_config = StreamConfig::from_config(&config);
+        let health = Arc::clone(&health);
+        SupervisedTask::start(LISTENER_TASK, stale_after, move || {
+            spawn_listener(
+                stream_config.clone(),
+                new_token_tx.clone(),
+                copy_trade_tx.clone(),
+                graduated_tx.clone(),
+                Arc::clone(&health),
+            )
+        })
+    });
 
     // Start position monitor (trailing stop-loss) with SDK pricing
-    let tsl_config = TrailingStopLossConfig::from_config(&config);
-    let _monitor_handle = spawn_monitor(
-        provider.clone(),
-        config.router_address,
-        config.wmon_address,
-        Arc::clone(&sdk_executor),
-        tsl_config,
+    supervised.push({
+        let tsl_config = TrailingStopLossConfig::from_config(&config);
+        let routes = config.dex_routes();
+        let (provider, sdk_executor, positions) = (provider.clone(), Arc::clone(&sdk_executor), Arc::clone(&positions));
+        let (sell_signal_tx, filters, mon_price) = (sell_signal_tx.clone(), strategy.filters.clone(), mon_price.clone());
+        let graduated_rx = Arc::new(Mutex::new(graduated_rx));
+        let health = Arc::clone(&health);
+        SupervisedTask::start(MONITOR_TASK, stale_after, move || {
+            let monitor = PositionMonitor::new(
+                Box::new(LivePriceSource::new(provider.clone(), routes.clone(), Arc::clone(&sdk_executor))),
+                tsl_config.clone(),
+                filters.clone(),
+                mon_price.clone(),
+                Arc::clone(&health),
+            );
+            spawn_monitor(monitor, Arc::clone(&positions), sell_signal_tx.clone(), Arc::clone(&graduated_rx))
+        })
+    });
+
+    // Initialize notifiers (Telegram and/or Discord)
+    let notifier = Arc::new(Notifications::from_config(&config));
+
+    notifier.send_message("🚀 Monad Sniper Bot launching...");
+
+    // Periodic status push so a silent bot can be told apart from a hung one
+    let _heartbeat_handle = spawn_heartbeat(
+        Arc::clone(&health),
         Arc::clone(&positions),
-        sell_signal_tx.clone(),
+        Arc::clone(&trade_history),
+        Arc::clone(&notifier),
+        mon_price.clone(),
+        !config.smart_wallets.is_empty(),
+        config.heartbeat_interval_secs,
     );
 
-    // Initialize Telegram notifier
-    let telegram = Arc::new(TelegramNotifier::new(
-        config.telegram_token.clone(),
-        config.telegram_chat_id.clone(),
-    ));
+    // Dead-man's switch: exit everything if the bot goes blind for too long
+    if config.deadman_enabled && config.deadman_sell_after_secs > 0 {
+        let _deadman_handle = spawn_deadman(
+            Arc::clone(&health),
+            Arc::clone(&positions),
+            sell_signal_tx.clone(),
+            Arc::clone(&notifier),
+            config.deadman_sell_after_secs,
+        );
+    }
 
-    telegram.send_message("🚀 Monad Sniper Bot launching...").await;
+    // Telegram commands: manual sells and pausing new buys from the phone
+    if config.telegram_commands_enabled {
+        let _commands_handle = spawn_command_listener(
+            &config,
+            Arc::clone(&positions),
+            sell_signal_tx.clone(),
+            Arc::clone(&strategy.paused),
+            unstick_tx,
+            unwrap_tx,
+            Arc::clone(&trade_history),
+        );
+    }
 
     // Start arbitrage scanner
     let (arb_tx, _) = mpsc::channel::<arbitrage::ArbitrageOpportunity>(100);
@@ -179,12 +359,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let pairs = vec![
             arbitrage::TokenPair {
                 token_a: config.wmon_address,
-                token_b: "0x0F0BDEbF0F83cD1EE3974779Bcb7315f9808c714".parse().unwrap(), // USDC
+                token_b: config.usdc_address,
                 name: "WMON/USDC".to_string(),
             },
             arbitrage::TokenPair {
                 token_a: config.wmon_address,
-                token_b: "0xf817257fed379853cDe0fa4F97AB987181B1E5Ea".parse().unwrap(), // USDT  
+                token_b: config.usdt_address,
                 name: "WMON/USDT".to_string(),
             },
         ];
@@ -192,9 +372,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let scan_amount = config.mon_to_wei(config.arb_amount_mon);
         let _arb_handle = arbitrage::spawn_scanner(
             provider.clone(),
+            arbitrage::default_quoters(provider.clone(), config.kuru_router),
             pairs,
             scan_amount,
             config.arb_scan_interval_ms,
+            arbitrage::ArbCosts::from_config(&config),
             arb_tx,
         );
         info!("🔍 Arbitrage scanner enabled ({}ms interval, {} MON)", 
@@ -207,20 +389,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Start Mempool Monitor (Front-running)
     if !config.smart_wallets.is_empty() {
-        let mempool = listeners::mempool::MempoolMonitor::new(config.clone(), Arc::clone(&sdk_executor));
-        tokio::spawn(async move {
-            mempool.start().await;
-        });
+        let mempool_config = config.clone();
+        let (sdk_executor, positions) = (Arc::clone(&sdk_executor), Arc::clone(&positions));
+        let (trade_history, health) = (Arc::clone(&trade_history), Arc::clone(&health));
+        let in_flight = Arc::clone(&in_flight);
+        // Filtered to a few wallets, so silence is normal: restarted only if it exits
+        supervised.push(SupervisedTask::start(MEMPOOL_TASK, None, move || {
+            let mempool = listeners::mempool::MempoolMonitor::new(
+                mempool_config.clone(),
+                Arc::clone(&sdk_executor),
+                Arc::clone(&positions),
+                Arc::clone(&trade_history),
+                Arc::clone(&health),
+                Arc::clone(&in_flight),
+            );
+            tokio::spawn(async move {
+                mempool.start().await;
+            })
+        }));
         info!("🦈 Mempool Monitor started (Front-running enabled)");
     }
 
+    // Without the watchdog the tasks run detached, as before
+    if config.watchdog_enabled {
+        let _watchdog_handle = spawn_watchdog(supervised, Arc::clone(&health), Arc::clone(&notifier));
+    }
+
     // Spawn sell signal handler (SDK for bonding curve, DEX fallback)
-    let _sell_handler = spawn_sell_handler(
-        Arc::clone(&sdk_executor),
-        Arc::clone(&sell_executor),
-        Arc::clone(&positions),
-        sell_signal_rx,
-    );
+    let sell_context = SellContext {
+        sdk_executor: Arc::clone(&sdk_executor),
+        dex_sell_executor: Arc::clone(&sell_executor),
+        positions: Arc::clone(&positions),
+        trade_history: Arc::clone(&trade_history),
+        in_flight: Arc::clone(&in_flight),
+        notifier: Arc::clone(&notifier),
+        retry_slippage_pct: config.sell_retry_slippage_pct,
+        max_sell_failures: config.max_sell_failures,
+        sell_route: config.sell_route,
+    };
+    let _sell_handler = spawn_sell_handler(sell_context, sell_signal_tx.clone(), sell_signal_rx);
 
     // Clone positions for shutdown handler
     let positions_for_shutdown = Arc::clone(&positions);
@@ -228,24 +435,117 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Dynamic Smart Wallets (found by Scout)
     let mut dynamic_smart_wallets: HashSet<alloy::primitives::Address> = HashSet::new();
 
+    // Copy-trade events seen recently, so stream replays aren't copied twice
+    let mut copy_dedupe = CopyTradeDedupe::new();
+    let mut launch_dedupe = NewTokenDedupe::new(Duration::from_secs(config.new_token_dedupe_secs));
+
+    // Set once a buy is skipped for lack of MON, so the alert goes out once
+    let low_balance = AtomicBool::new(false);
+
+    // Spacing between buys and the open-position cap, across every buy path
+    let mut buy_throttle = BuyThrottle::from_config(&config);
+
+    // SIGUSR1 panic-sells like Telegram /panic, without stopping the bot.
+    // SIGTERM (docker stop, systemd) shuts down gracefully like Ctrl-C and never dumps the book
+    let mut terminate_signal = signal::unix::signal(signal::unix::SignalKind::terminate())?;
+    let mut panic_signal = signal::unix::signal(signal::unix::SignalKind::user_defined1())?;
+    // SIGHUP re-reads SMART_WALLETS_FILE
+    let mut reload_signal = signal::unix::signal(signal::unix::SignalKind::hangup())?;
+
     // Main event loop with graceful shutdown
     loop {
         tokio::select! {
             // Handle shutdown signal
-            _ = signal::ctrl_c() => {
-                info!("🛑 Shutdown signal received, saving positions...");
+            _ = shutdown_signal(&mut terminate_signal) => {
+                let in_flight_count = in_flight.count();
+                info!("🛑 Shutdown signal received, waiting for {} in-flight trades...", in_flight_count);
+                let unsettled = in_flight.drain(Duration::from_secs(config.shutdown_drain_secs)).await;
+                if unsettled > 0 {
+                    warn!(
+                        "⚠️ {} of {} trades still in flight after {}s; positions.json may not reflect them",
+                        unsettled, in_flight_count, config.shutdown_drain_secs
+                    );
+                }
+                info!("💾 Saving positions...");
                 let pos_guard = positions_for_shutdown.lock().await;
                 if let Err(e) = pos_guard.save() {
                     error!("❌ Failed to save positions: {}", e);
                 } else {
                     info!("✅ Positions saved successfully ({} positions)", pos_guard.len());
                 }
-                telegram.send_message("🛑 Bot shutting down gracefully...").await;
+                let summary = trade_history.lock().await.get_summary();
+                notifier.notify(NotifyEvent::PnlSummary, || format!(
+                    "📊 *PnL Summary*\nBuys: {} ({:.4} MON)\nSells: {} ({:.4} MON)\nNet: {:+.4} MON{}\nRealized: {:+.4} MON | Win rate: {}",
+                    summary.buy_count, summary.total_bought,
+                    summary.sell_count, summary.total_sold,
+                    summary.net_pnl, mon_price.format_usd(summary.net_pnl),
+                    summary.realized_pnl,
+                    summary.win_rate.map_or("n/a".to_string(), |rate| format!("{:.0}%", rate * 100.0))
+                ));
+                notifier.send_message(&format!(
+                    "🛑 Bot shutting down gracefully... ({} trades in flight, {} unsettled)",
+                    in_flight_count, unsettled
+                ));
+                notifier.flush().await;
                 break;
             }
             
+            // `kill -USR1 <pid>`: pause sniping and emergency-sell everything
+            Some(()) = panic_signal.recv() => {
+                strategy.paused.store(true, Ordering::Relaxed);
+                let queued = sell_all(&positions, &sell_signal_tx, "SIGUSR1 panic sell").await;
+                notifier.notify(NotifyEvent::Error, || format!(
+                    "🚨 *PANIC SELL* (SIGUSR1)\nSelling {} positions at {}\nSniping paused",
+                    queued, emergency_slippage(&config)
+                ));
+            }
+
+            // `kill -HUP <pid>`: pick up edits to the smart wallets file
+            Some(()) = reload_signal.recv() => {
+                match smart_wallets.reload() {
+                    Ok(count) => info!("🔄 Reloaded {} smart wallets", count),
+                    Err(e) => warn!("⚠️ Smart wallets not reloaded: {}", e),
+                }
+            }
+
+            // Telegram /unstick: clear a stuck tx and resync both DEX executors
+            Some(()) = unstick_rx.recv() => {
+                let reply = match buy_executor.unstick().await {
+                    Ok(cancelled) => {
+                        let _ = sell_executor.resync_nonce().await;
+                        match cancelled {
+                            Some(tx_hash) => format!("✅ Cancelled stuck tx: `{:?}`\nNonces resynced.", tx_hash),
+                            None => "✅ No stuck transactions. Nonces resynced.".to_string(),
+                        }
+                    }
+                    Err(e) => format!("❌ Unstick failed: {}", e),
+                };
+                notifier.send_message(&reply);
+            }
+
+            // Telegram /unwrap: turn the whole WMON balance back into spendable MON
+            Some(()) = unwrap_rx.recv() => {
+                let reply = match sell_executor.unwrap_wmon_above(alloy::primitives::U256::ZERO).await {
+                    Ok(Some(tx_hash)) => format!("✅ Unwrapped WMON: `{:?}`", tx_hash),
+                    Ok(None) if config.paper_trading => "📝 Paper trading, nothing unwrapped.".to_string(),
+                    Ok(None) => "✅ No WMON to unwrap.".to_string(),
+                    Err(e) => format!("❌ Unwrap failed: {}", e),
+                };
+                notifier.send_message(&reply);
+            }
+
             // Handle new token events
             Some(token_event) = new_token_rx.recv() => {
+                if launch_dedupe.is_duplicate(token_event.token_address) {
+                    debug!("🔁 Skipping repeated launch of {:?}", token_event.token_address);
+                    continue;
+                }
+                // A launch of a token we already hold is a replay; skip it before the analysis RPCs
+                if positions.lock().await.get(&token_event.token_address).is_some() {
+                    debug!("⏭️ Skipping launch of {:?}: already held", token_event.token_address);
+                    continue;
+                }
+
                 info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
                 let name = token_event.name.clone();
                 let symbol = token_event.symbol.clone();
@@ -257,7 +557,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                 // Analyze token
                 let liquidity_mon = token_event.initial_liquidity
-                    .map(|l| l.to::<u128>() as f64 / 1e18)
+                    .map(wei_to_mon)
                     .unwrap_or(0.0);
 
                 let analysis = analyzer.analyze(
@@ -282,42 +582,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 };
 
                 // Send Telegram notification for new token
-                telegram.send_message(&format!(
+                notifier.notify(NotifyEvent::NewToken, || format!(
                     "🆕 *New Token Detected*\nName: {}\nSymbol: {}\nAddress: `{:?}`", 
                     name, symbol, token_event.token_address
-                )).await;
-
-                // Check if we should buy
-                match strategy.should_buy(&strategy_event, &analysis).await {
-                    Some(decision) => {
-                        // Execute buy
-                        match buy_executor.buy(&decision).await {
-                            Ok(tx_hash) => {
-                                let msg = format!("🟢 *BUY EXECUTED*\nToken: {}\nHash: `{:?}`", decision.symbol, tx_hash);
-                                telegram.send_message(&msg).await;
-                                
-                                // Calculate buy price (amount in MON)
-                                let buy_price = decision.amount_wei.to::<u128>() as f64 / 1e18;
-                                
-                                // Add to positions
-                                let position = Position {
-                                    token: decision.token,
-                                    name: decision.name,
-                                    symbol: decision.symbol,
-                                    amount: decision.amount_wei, // This will be updated with actual token amount
-                                    buy_price_mon: buy_price,
-                                    buy_time: chrono::Utc::now().timestamp() as u64,
-                                    highest_price: buy_price,
-                                    tx_hash: format!("{:?}", tx_hash),
-                                };
-                                
-                                let mut pos_guard = positions.lock().await;
-                                pos_guard.add(position);
-                            }
-                            Err(e) => {
-                                error!("❌ Buy failed: {}", e);
-                                telegram.send_message(&format!("❌ *Buy Failed*\nError: {}", e)).await;
-                            }
+                ));
+
+                // Check if we should buy (held launches were skipped above; the cap is
+                // re-checked against whatever a copy buy added during the analysis)
+                match strategy.should_buy(&strategy_event, &analysis, None).await {
+                    Some(mut decision) => {
+                        let Some(amount) = strategy.per_token_amount(&*positions.lock().await, decision.token, decision.amount_wei) else {
+                            continue;
+                        };
+                        decision.amount_wei = amount;
+                        if throttle_buy(&mut buy_throttle, &positions, decision.token).await {
+                            execute_buy(&buy_executor, decision, &positions, &trade_history, &notifier, &mon_price, &low_balance).await;
                         }
                     }
                     None => {
@@ -325,31 +604,62 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
                 }
             }
+
+            // Handle limit buys that reached the entry zone, and filled dip orders
+            Some(mut decision) = limit_buy_rx.recv() => {
+                if strategy.paused.load(Ordering::Relaxed) {
+                    info!("⏸️ Sniping paused, skipping limit buy of {} ({})", decision.name, decision.symbol);
+                    continue;
+                }
+                let Some(amount) = strategy.per_token_amount(&*positions.lock().await, decision.token, decision.amount_wei) else {
+                    continue;
+                };
+                decision.amount_wei = amount;
+                if !throttle_buy(&mut buy_throttle, &positions, decision.token).await {
+                    continue;
+                }
+                info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+                info!("🎯 Executing limit buy: {} ({}) - {}", decision.name, decision.symbol, decision.reason);
+                execute_buy(&buy_executor, decision, &positions, &trade_history, &notifier, &mon_price, &low_balance).await;
+            }
             
             // Handle copy trade events from smart wallets
             Some(copy_event) = copy_trade_rx.recv() => {
-                // Determine if we should execute (Configured or Promoted)
+                if copy_dedupe.is_duplicate(&copy_event) {
+                    debug!("🔁 Skipping replayed copy event: {:?} by {:?}", copy_event.token, copy_event.smart_wallet);
+                    continue;
+                }
+
+                // Determine if we should execute (Configured or Promoted). The configured list
+                // is checked here rather than trusting the listener, so reloads apply immediately
                 let is_dynamic_target = dynamic_smart_wallets.contains(&copy_event.smart_wallet);
-                let should_execute = !copy_event.is_scout_only || is_dynamic_target;
+                let should_execute = smart_wallets.contains(&copy_event.smart_wallet) || is_dynamic_target;
 
                 if !should_execute {
                     // SCOUT MODE: Track silent wallet performance
                     if copy_event.is_buy {
-                        let val_mon = copy_event.amount_in.to::<u128>() as f64 / 1e18;
+                        let val_mon = wei_to_mon(copy_event.amount_in);
                         wallet_tracker.lock().await.record_buy(copy_event.smart_wallet, copy_event.token, val_mon);
                     } else {
-                        let val_mon = copy_event.amount_out.to::<u128>() as f64 / 1e18;
+                        let val_mon = wei_to_mon(copy_event.amount_out);
                         // Record sell returns PnL if trade closed
                         if let Some(pnl) = wallet_tracker.lock().await.record_sell(copy_event.smart_wallet, copy_event.token, val_mon) {
                             // Check for promotion
                             let score = wallet_tracker.lock().await.get_score(&copy_event.smart_wallet);
                             if score > 80.0 {
-                                info!("👑 NEW WHALE PROMOTED: {:?} (Score: {:.1})", copy_event.smart_wallet, score);
+                                info!(
+                                    wallet = %copy_event.smart_wallet,
+                                    score,
+                                    pnl_mon = pnl,
+                                    "👑 NEW WHALE PROMOTED: {:?} (Score: {:.1})",
+                                    copy_event.smart_wallet,
+                                    score
+                                );
                                 dynamic_smart_wallets.insert(copy_event.smart_wallet);
-                                telegram.send_message(&format!(
-                                    "👑 *NEW WHALE DISCOVERED*\nAddress: `{:?}`\nScore: {:.1}\nPnL: {:.2} MON\nAdded to Copy List! 🚀", 
-                                    copy_event.smart_wallet, score, pnl
-                                )).await;
+                                notifier.notify(NotifyEvent::ScoutPromotion, || format!(
+                                    "👑 *NEW WHALE DISCOVERED*\nAddress: `{:?}`\nScore: {:.1}\nPnL: {:.2} MON{}\nAdded to Copy List! 🚀", 
+                                    copy_event.smart_wallet, score, pnl, mon_price.format_usd(pnl)
+                                ));
                             }
                         }
                     }
@@ -364,35 +674,79 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         copy_event.token, copy_event.smart_wallet, copy_event.amount_in
                     );
 
+                    if strategy.paused.load(Ordering::Relaxed) {
+                        info!("⏸️ Sniping paused, skipping copy buy of {:?}", copy_event.token);
+                        continue;
+                    }
+
+                    if !smart_wallets.is_enabled(&copy_event.smart_wallet) {
+                        info!("⏸️ Copying disabled for {:?}, skipping buy of {:?}", copy_event.smart_wallet, copy_event.token);
+                        continue;
+                    }
+
+                    // A backed-up channel or a replay delivers the trade late; by then the whale has pumped the price
+                    if let Some(age_ms) = copy_event.stale_age_ms(config.max_copy_delay_ms) {
+                        warn!(
+                            "⏰ Skipping stale copy buy of {:?}: trade is {}ms old (max {}ms)",
+                            copy_event.token, age_ms, config.max_copy_delay_ms
+                        );
+                        continue;
+                    }
+
                     // Check Wallet Score
                     let score = wallet_tracker.lock().await.get_score(&copy_event.smart_wallet);
                     if score < 40.0 {
                         warn!("🚫 Ignoring Copy Buy from {:?} - Score too low: {:.2}", copy_event.smart_wallet, score);
                         continue;
                     }
+
+                    if !throttle_buy(&mut buy_throttle, &positions, copy_event.token).await {
+                        continue;
+                    }
                     
                     // Send Telegram notification
-                    telegram.send_message(&format!(
+                    notifier.notify(NotifyEvent::Copy, || format!(
                         "📋 *COPY TRADE*\nSmart wallet `{:?}` bought token\nToken: `{:?}`\nExecuting copy buy via SDK...", 
                         copy_event.smart_wallet, copy_event.token
-                    )).await;
+                    ));
                     
                     // Use SDK executor for bonding curve trades
                     // WHALE MODE: Calculate buy amount based on whale's input
                     let base_amount_mon = config.snipe_amount_mon;
-                    let whale_input_mon = copy_event.amount_in.to::<u128>() as f64 / 1e18;
+                    let whale_input_mon = wei_to_mon(copy_event.amount_in);
+                    // Per-wallet settings from SMART_WALLETS_FILE, else the global ones
+                    let copy_pct = smart_wallets.copy_pct(&copy_event.smart_wallet);
+                    let max_amount_mon = smart_wallets.max_mon(&copy_event.smart_wallet).min(config.max_snipe_amount);
                     
-                    let target_amount_mon = if whale_input_mon > 0.5 {
-                        let scaled = whale_input_mon * (config.whale_copy_pct / 100.0);
-                        // Buy at least base_amount, up to max_snipe_amount
-                        f64::max(base_amount_mon, scaled).min(config.max_snipe_amount)
+                    let sized_amount_mon = if whale_input_mon > 0.5 {
+                        let scaled = whale_input_mon * (copy_pct / 100.0);
+                        // Buy at least base_amount, up to the wallet's cap
+                        f64::max(base_amount_mon, scaled).min(max_amount_mon)
                     } else {
                         base_amount_mon
                     };
+
+                    // Lean into proven wallets, hedge on unproven ones; the cap still applies
+                    let score_multiplier = if config.score_sizing_enabled {
+                        wallet_tracker.lock().await.size_multiplier(&copy_event.smart_wallet, &config.score_sizing_tiers)
+                    } else {
+                        1.0
+                    };
+                    let jittered_mon = strategy
+                        .amount_jitter
+                        .apply(sized_amount_mon * score_multiplier)
+                        .min(max_amount_mon);
+                    // Copies of repeat whale buys top up the position; keep it under MAX_PER_TOKEN_MON
+                    let Some(buy_amount) = strategy.per_token_amount(&*positions.lock().await, copy_event.token, config.mon_to_wei(jittered_mon)) else {
+                        continue;
+                    };
+                    let target_amount_mon = jittered_mon.min(wei_to_mon(buy_amount));
                     
                     info!(
-                        "🐳 WHALE MODE: Smart Wallet committed {:.2} MON -> We commit {:.2} MON (Base: {}, Cap: {})", 
-                        whale_input_mon, target_amount_mon, base_amount_mon, config.max_snipe_amount
+                        "🐳 WHALE MODE: Smart Wallet committed {:.2} MON{} -> We commit {:.2} MON{} (Base: {}, Copy {}%, Score x{:.2}, Cap: {})", 
+                        whale_input_mon, mon_price.format_usd(whale_input_mon),
+                        target_amount_mon, mon_price.format_usd(target_amount_mon),
+                        base_amount_mon, copy_pct, score_multiplier, max_amount_mon
                     );
 
                     // Track smart wallet entry
@@ -402,61 +756,76 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         whale_input_mon
                     );
                     
-                    let buy_amount = config.mon_to_wei(target_amount_mon);
-                    
-                    match sdk_executor.buy_token(copy_event.token, buy_amount).await {
-                        Ok(tx_hash) => {
-                            let msg = format!("🟢 *COPY BUY EXECUTED*\nToken: `{:?}`\nHash: `{}`", copy_event.token, tx_hash);
-                            telegram.send_message(&msg).await;
-                            info!("✅ Copy trade executed via SDK: {}", tx_hash);
+                    match sdk_executor.buy_token_with_retry(
+                        copy_event.token,
+                        buy_amount,
+                        config.copy_trade_slippage_pct,
+                        config.buy_slippage_step_pct,
+                        config.buy_max_slippage_pct,
+                    ).await {
+                        Ok(received) => {
+                            low_balance.store(false, Ordering::Relaxed);
+                            notifier.notify(NotifyEvent::Buy, || format!(
+                                "🟢 *COPY BUY EXECUTED*\nToken: `{:?}`\nAmount: {:.2} MON{}\nHash: `{}`",
+                                copy_event.token, target_amount_mon, mon_price.format_usd(target_amount_mon), received.tx_hash
+                            ));
+                            info!(
+                                token = %copy_event.token,
+                                tx_hash = %received.tx_hash,
+                                amount_mon = target_amount_mon,
+                                "✅ Copy trade executed via SDK: {}",
+                                received.tx_hash
+                            );
                             
-                            // Get actual token balance received
-                            let token_balance = match sdk_executor.get_token_balance(copy_event.token).await {
-                                Ok(balance) => {
-                                    info!("📊 Received {} tokens", balance);
-                                    balance
-                                }
-                                Err(e) => {
-                                    warn!("⚠️ Couldn't get token balance: {}, using estimate", e);
-                                    buy_amount // Fallback to buy amount if balance check fails
-                                }
-                            };
-                            
-                            // Fetch real token name and symbol from chain
-                            let (token_name, token_symbol) = match sdk_executor.get_token_info(copy_event.token).await {
-                                Ok((name, symbol)) => {
-                                    info!("📝 Token info: {} ({})", name, symbol);
-                                    (name, symbol)
-                                }
-                                Err(_) => {
-                                    (format!("CopyTrade-{:?}", copy_event.token), "COPY".to_string())
-                                }
-                            };
+                            // Fetch real token name, symbol and decimals from chain
+                            let meta = sdk_executor.token_meta(copy_event.token).await;
+                            info!("📝 Token info: {} ({}), {} decimals", meta.name, meta.symbol, meta.decimals);
+                            let (token_name, token_symbol) = (meta.name, meta.symbol);
                             
                             // Add to positions with actual token info
                             let buy_price = target_amount_mon;
+                            trade_history.lock().await.record(TradeRecord::buy(
+                                copy_event.token,
+                                token_name.clone(),
+                                token_symbol.clone(),
+                                received.amount,
+                                buy_price,
+                                received.tx_hash.clone(),
+                            ));
                             let position = Position {
                                 token: copy_event.token,
                                 name: token_name,
                                 symbol: token_symbol,
-                                amount: token_balance, // Actual tokens received!
+                                amount: received.amount, // Actual tokens received!
                                 buy_price_mon: buy_price,
                                 buy_time: chrono::Utc::now().timestamp() as u64,
                                 highest_price: buy_price,
-                                tx_hash: tx_hash.clone(),
+                                tx_hash: received.tx_hash,
+                                provisional: false,
+                                fired_tp_levels: Vec::new(),
+                                sell_failures: 0,
+                                stuck: false,
+                                graduated: false,
+                                peak_liquidity_mon: 0.0,
+                                in_runner_mode: false,
+                                decimals: meta.decimals,
                             };
                             
                             let mut pos_guard = positions.lock().await;
                             pos_guard.add(position);
                         }
+                        Err(TradeError::InsufficientFunds(msg)) => {
+                            warn!("💸 Skipping copy buy of {:?}: {}", copy_event.token, msg);
+                            notify_low_balance(&low_balance, &notifier, &msg);
+                        }
                         Err(e) => {
                             error!("❌ Copy trade buy failed: {}", e);
-                            telegram.send_message(&format!("❌ *Copy Trade Failed*\nError: {}", e)).await;
+                            notifier.notify(NotifyEvent::Error, || format!("❌ *Copy Trade Failed*\nError: {}", e));
                         }
                     }
                 } else {
                     // Smart wallet selling - track performance and consider selling
-                    let output_mon = copy_event.amount_out.to::<u128>() as f64 / 1e18;
+                    let output_mon = wei_to_mon(copy_event.amount_out);
                     wallet_tracker.lock().await.record_sell(
                         copy_event.smart_wallet, 
                         copy_event.token, 
@@ -484,10 +853,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         if let Err(e) = sell_signal_tx.send((token, decision)).await {
                             error!("❌ Failed to send copy sell signal: {}", e);
                         } else {
-                            telegram.send_message(&format!(
+                            notifier.notify(NotifyEvent::Sell, || format!(
                                 "🚨 *COPY SELL EXECUTED*\nSmart wallet `{:?}` dumped token `{:?}`\nSelling our bag!", 
                                 wallet, token
-                            )).await;
+                            ));
                         }
                     } else {
                         drop(pos_guard);
@@ -500,3 +869,253 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// `--revoke-approvals [token...]`: zero router allowances for tokens we no longer hold.
+/// Without arguments, checks every token in the trade history that has no open position.
+async fn revoke_approvals(config: &Config, token_args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let sdk_executor = SdkExecutor::new(config, GasStrategy::Normal).await?;
+
+    let tokens: Vec<alloy::primitives::Address> = if token_args.is_empty() {
+        let positions = PositionTracker::load();
+        TradeHistory::load()
+            .traded_tokens()
+            .into_iter()
+            .filter(|token| positions.get(token).is_none())
+            .collect()
+    } else {
+        token_args
+            .iter()
+            .filter_map(|arg| match arg.parse() {
+                Ok(token) => Some(token),
+                Err(_) => {
+                    warn!("⚠️ Skipping invalid token address: {}", arg);
+                    None
+                }
+            })
+            .collect()
+    };
+
+    info!("🔓 Checking approvals for {} tokens", tokens.len());
+    let mut revoked = 0;
+
+    for token in tokens {
+        match sdk_executor.get_token_balance(token).await {
+            Ok(balance) if balance > alloy::primitives::U256::ZERO => {
+                info!("⏭️ Still holding {:?}, keeping its approvals", token);
+                continue;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("⚠️ Couldn't check balance of {:?}: {}, skipping", token, e);
+                continue;
+            }
+        }
+
+        // DEX router plus whichever router the SDK currently routes this token through
+        let mut spenders = vec![config.router_address];
+        if let Ok(router) = sdk_executor.sell_router(token).await {
+            if !spenders.contains(&router) {
+                spenders.push(router);
+            }
+        }
+
+        for spender in spenders {
+            match sdk_executor.revoke_approval(token, spender).await {
+                Ok(Some(tx_hash)) => {
+                    info!("✅ Revoked {:?} for {:?}: {:?}", token, spender, tx_hash);
+                    revoked += 1;
+                }
+                Ok(None) => debug!("No allowance on {:?} for {:?}", token, spender),
+                Err(e) => error!("❌ Failed to revoke {:?} for {:?}: {}", token, spender, e),
+            }
+        }
+    }
+
+    info!("🔓 Revoked {} approvals", revoked);
+    Ok(())
+}
+
+/// `--review-rejects [--prices]`: summarize rejects.jsonl by filter.
+/// With `--prices`, re-quote each rejected token and count how many have since
+/// pumped above their market cap at rejection (i.e. what each filter cost us).
+async fn review_rejects(config: &Config, with_prices: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let records = RejectLog::load_all();
+    if records.is_empty() {
+        info!("📭 No rejected tokens logged yet");
+        return Ok(());
+    }
+
+    info!("📋 {} rejections logged", records.len());
+    for (reason, count) in count_by_reason(&records) {
+        info!("   {:<10} {:>6} ({:.1}%)", reason, count, count as f64 / records.len() as f64 * 100.0);
+    }
+
+    if !with_prices {
+        return Ok(());
+    }
+
+    let sdk_executor = SdkExecutor::new(config, GasStrategy::Normal).await?;
+    let (provider, _) = create_provider(&rpc::RpcConfig::from_config(config))?;
+    let mon_price = MonPriceOracle::new(config.mon_price_usd);
+    match fetch_mon_price_usd(&provider, config.wmon_address, config.usdc_address).await {
+        Ok(price) => mon_price.set_price_usd(price),
+        Err(e) => warn!("⚠️ Couldn't fetch live MON price: {}", e),
+    }
+    let mon_price_usd = mon_price.price_usd_or_fallback();
+
+    // First rejection per token is the baseline; tokens without a known MCap can't be compared
+    let mut seen = HashSet::new();
+    let mut checked: HashMap<RejectReason, usize> = HashMap::new();
+    let mut pumped: HashMap<RejectReason, usize> = HashMap::new();
+
+    for record in records.iter().filter(|r| r.market_cap_usd > 0.0) {
+        if !seen.insert(record.token) {
+            continue;
+        }
+        let current = match get_market_cap_usd(&sdk_executor, record.token, mon_price_usd).await {
+            Ok(mcap) => mcap,
+            Err(e) => {
+                debug!("Couldn't price {:?}: {}", record.token, e);
+                continue;
+            }
+        };
+
+        *checked.entry(record.reason).or_insert(0) += 1;
+        if current > record.market_cap_usd {
+            *pumped.entry(record.reason).or_insert(0) += 1;
+            info!(
+                "📈 {} ({}) [{}]: ${:.0}k -> ${:.0}k",
+                record.name, record.symbol, record.reason,
+                record.market_cap_usd / 1000.0, current / 1000.0
+            );
+        }
+    }
+
+    info!("📊 Now above rejection MCap, per filter:");
+    let mut reasons: Vec<_> = checked.into_iter().collect();
+    reasons.sort_by_key(|&(_, total)| std::cmp::Reverse(total));
+    for (reason, total) in reasons {
+        let up = pumped.get(&reason).copied().unwrap_or(0);
+        info!("   {:<10} {:>4}/{:<4} ({:.1}%)", reason, up, total, up as f64 / total as f64 * 100.0);
+    }
+
+    Ok(())
+}
+
+/// Resolves on Ctrl-C or SIGTERM; both shut the bot down gracefully.
+async fn shutdown_signal(terminate: &mut signal::unix::Signal) {
+    tokio::select! {
+        _ = signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+/// Run a buy of `token` past the global throttle. Starts the spacing window
+/// when it passes; logs and returns false when the buy must be skipped.
+async fn throttle_buy(
+    throttle: &mut BuyThrottle,
+    positions: &Mutex<PositionTracker>,
+    token: alloy::primitives::Address,
+) -> bool {
+    let (open_positions, held) = {
+        let positions = positions.lock().await;
+        (positions.len(), positions.get(&token).is_some())
+    };
+    match throttle.check(open_positions, held) {
+        Ok(()) => {
+            throttle.record_buy();
+            true
+        }
+        Err(reason) => {
+            warn!("🚦 Skipping buy of {:?}: {}", token, reason);
+            false
+        }
+    }
+}
+
+/// Execute a strategy buy via the DEX and open a position on success.
+async fn execute_buy<P: Provider + Clone + 'static>(
+    buy_executor: &SwapExecutor<P>,
+    decision: BuyDecision,
+    positions: &Arc<Mutex<PositionTracker>>,
+    trade_history: &Mutex<TradeHistory>,
+    notifier: &Notifications,
+    mon_price: &MonPriceOracle,
+    low_balance: &AtomicBool,
+) {
+    match buy_executor.buy(&decision).await {
+        Ok(received) => {
+            low_balance.store(false, Ordering::Relaxed);
+
+            // Calculate buy price (amount in MON)
+            let buy_price = wei_to_mon(decision.amount_wei);
+
+            info!(
+                token = %decision.token,
+                symbol = %decision.symbol,
+                tx_hash = %received.tx_hash,
+                amount_mon = buy_price,
+                priority = decision.priority,
+                "🟢 Buy executed: {} for {:.2} MON",
+                decision.symbol,
+                buy_price
+            );
+
+            notifier.notify(NotifyEvent::Buy, || format!(
+                "🟢 *BUY EXECUTED*\nToken: {}\nAmount: {:.2} MON{}\nHash: `{}`",
+                decision.symbol, buy_price, mon_price.format_usd(buy_price), received.tx_hash
+            ));
+            
+            trade_history.lock().await.record(TradeRecord::buy(
+                decision.token,
+                decision.name.clone(),
+                decision.symbol.clone(),
+                received.amount,
+                buy_price,
+                received.tx_hash.clone(),
+            ));
+
+            // Add to positions
+            let decimals = buy_executor.token_meta(decision.token).await.decimals;
+            let position = Position {
+                token: decision.token,
+                name: decision.name,
+                symbol: decision.symbol,
+                amount: received.amount,
+                buy_price_mon: buy_price,
+                buy_time: chrono::Utc::now().timestamp() as u64,
+                highest_price: buy_price,
+                tx_hash: received.tx_hash,
+                provisional: false,
+                fired_tp_levels: Vec::new(),
+                sell_failures: 0,
+                stuck: false,
+                graduated: false,
+                peak_liquidity_mon: 0.0,
+                in_runner_mode: false,
+                decimals,
+            };
+            
+            let mut pos_guard = positions.lock().await;
+            pos_guard.add(position);
+        }
+        Err(TradeError::InsufficientFunds(msg)) => {
+            warn!("💸 Skipping {}: {}", decision.symbol, msg);
+            notify_low_balance(low_balance, notifier, &msg);
+        }
+        Err(e) => {
+            error!("❌ Buy failed: {}", e);
+            notifier.notify(NotifyEvent::Error, || format!("❌ *Buy Failed*\nError: {}", e));
+        }
+    }
+}
+
+/// Alert Telegram the first time buys fail for lack of MON; re-armed by the next successful buy.
+fn notify_low_balance(low_balance: &AtomicBool, notifier: &Notifications, msg: &str) {
+    if !low_balance.swap(true, Ordering::Relaxed) {
+        notifier.notify(NotifyEvent::Error, || format!(
+            "💸 *Low Balance*\n{}\nBuys are skipped until the wallet is topped up.",
+            msg
+        ));
+    }
+}
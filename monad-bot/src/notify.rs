@@ -0,0 +1,361 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Outgoing notifications: the per-event toggles, the `Notifier` trait the
+//! Telegram and Discord senders implement, and `Notifications`, which fans a
+//! message out to every configured sender.
+
+use crate::config::Config;
+use crate::discord::DiscordNotifier;
+use crate::telegram::TelegramNotifier;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{error, info, warn};
+
+/// Kinds of notification that can be toggled individually.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyEvent {
+    NewToken,
+    Buy,
+    Sell,
+    Copy,
+    ScoutPromotion,
+    Error,
+    PnlSummary,
+    Heartbeat,
+}
+
+/// Per-event notification toggles.
+#[derive(Debug, Clone)]
+pub struct NotifyConfig {
+    pub new_token: bool,
+    pub buy: bool,
+    pub sell: bool,
+    pub copy: bool,
+    pub scout_promotion: bool,
+    pub errors: bool,
+    pub pnl_summary: bool,
+    pub heartbeat: bool,
+}
+
+impl NotifyConfig {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            new_token: config.notify_new_token,
+            buy: config.notify_buy,
+            sell: config.notify_sell,
+            copy: config.notify_copy,
+            scout_promotion: config.notify_scout_promotion,
+            errors: config.notify_errors,
+            pnl_summary: config.notify_pnl_summary,
+            heartbeat: config.notify_heartbeat,
+        }
+    }
+
+    pub fn allows(&self, event: NotifyEvent) -> bool {
+        match event {
+            NotifyEvent::NewToken => self.new_token,
+            NotifyEvent::Buy => self.buy,
+            NotifyEvent::Sell => self.sell,
+            NotifyEvent::Copy => self.copy,
+            NotifyEvent::ScoutPromotion => self.scout_promotion,
+            NotifyEvent::Error => self.errors,
+            NotifyEvent::PnlSummary => self.pnl_summary,
+            NotifyEvent::Heartbeat => self.heartbeat,
+        }
+    }
+}
+
+/// Future returned by `Notifier::flush`.
+pub type FlushFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// A destination for notification messages.
+/// Messages are written in Telegram's Markdown; senders adapt them as needed.
+pub trait Notifier: Send + Sync {
+    /// Queue `message`. Never blocks.
+    fn send(&self, message: &str);
+
+    /// Wait until every message queued so far has been delivered (used on shutdown).
+    fn flush(&self) -> FlushFuture<'_>;
+}
+
+/// Every configured notifier, behind the per-event toggles.
+pub struct Notifications {
+    notifiers: Vec<Box<dyn Notifier>>,
+    notify: NotifyConfig,
+}
+
+impl Notifications {
+    pub fn new(notifiers: Vec<Box<dyn Notifier>>, notify: NotifyConfig) -> Self {
+        Self { notifiers, notify }
+    }
+
+    /// Telegram and/or Discord, whichever are configured.
+    pub fn from_config(config: &Config) -> Self {
+        let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+        if let Some(telegram) = TelegramNotifier::new(config.telegram_token.clone(), config.telegram_chat_id.clone()) {
+            notifiers.push(Box::new(telegram));
+        }
+        if let Some(url) = config.discord_webhook_url.clone() {
+            notifiers.push(Box::new(DiscordNotifier::new(url)));
+        }
+        info!("📣 {} notifier(s) configured", notifiers.len());
+        Self::new(notifiers, NotifyConfig::from_config(config))
+    }
+
+    /// Whether a message for `event` would actually be sent.
+    pub fn enabled(&self, event: NotifyEvent) -> bool {
+        !self.notifiers.is_empty() && self.notify.allows(event)
+    }
+
+    /// Queue a message for `event` if enabled.
+    /// The message is only built when it will be sent.
+    pub fn notify<F: FnOnce() -> String>(&self, event: NotifyEvent, build: F) {
+        if self.enabled(event) {
+            self.send_message(&build());
+        }
+    }
+
+    /// Queue a message on every notifier, regardless of the toggles.
+    pub fn send_message(&self, message: &str) {
+        for notifier in &self.notifiers {
+            notifier.send(message);
+        }
+    }
+
+    /// Wait for every notifier to drain its queue.
+    pub async fn flush(&self) {
+        for notifier in &self.notifiers {
+            notifier.flush().await;
+        }
+    }
+}
+
+/// How long `MessageQueue::flush` waits for the queue to drain.
+const FLUSH_TIMEOUT_SECS: u64 = 10;
+
+/// Work item for a queue's background sender.
+enum Outgoing {
+    Message(String),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Handle to a background sender that coalesces whatever queued up since the
+/// last send into as few messages as possible and paces delivery.
+#[derive(Clone)]
+pub(crate) struct MessageQueue {
+    tx: mpsc::UnboundedSender<Outgoing>,
+    name: &'static str,
+}
+
+impl MessageQueue {
+    /// Spawn the sender. `deliver` sends one batch of at most `max_chars`,
+    /// at least `interval` after the previous one.
+    pub(crate) fn spawn<F, Fut>(name: &'static str, interval: Duration, max_chars: usize, deliver: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_sender(rx, interval, max_chars, deliver));
+        Self { tx, name }
+    }
+
+    pub(crate) fn push(&self, message: String) {
+        if self.tx.send(Outgoing::Message(message)).is_err() {
+            error!("{} sender stopped, dropping message", self.name);
+        }
+    }
+
+    pub(crate) async fn flush(&self) {
+        let (done_tx, done_rx) = oneshot::channel();
+        if self.tx.send(Outgoing::Flush(done_tx)).is_err() {
+            return;
+        }
+        if tokio::time::timeout(Duration::from_secs(FLUSH_TIMEOUT_SECS), done_rx).await.is_err() {
+            warn!("⚠️ Timed out flushing {} messages", self.name);
+        }
+    }
+}
+
+async fn run_sender<F, Fut>(mut rx: mpsc::UnboundedReceiver<Outgoing>, interval: Duration, max_chars: usize, deliver: F)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut last_sent: Option<Instant> = None;
+
+    while let Some(first) = rx.recv().await {
+        let mut pending = Vec::new();
+        let mut flush_waiters = Vec::new();
+
+        for item in std::iter::once(first).chain(std::iter::from_fn(|| rx.try_recv().ok())) {
+            match item {
+                Outgoing::Message(message) => pending.push(message),
+                Outgoing::Flush(done) => flush_waiters.push(done),
+            }
+        }
+
+        for batch in coalesce(pending, max_chars) {
+            if let Some(last) = last_sent {
+                let elapsed = last.elapsed();
+                if elapsed < interval {
+                    tokio::time::sleep(interval - elapsed).await;
+                }
+            }
+            deliver(batch).await;
+            last_sent = Some(Instant::now());
+        }
+
+        for done in flush_waiters {
+            let _ = done.send(());
+        }
+    }
+}
+
+/// Join messages into batches of at most `max_chars`. A single message longer
+/// than that is split first, so no batch goes over the limit.
+fn coalesce(messages: Vec<String>, max_chars: usize) -> Vec<String> {
+    let mut batches: Vec<String> = Vec::new();
+
+    for message in messages.into_iter().flat_map(|message| split_message(message, max_chars)) {
+        match batches.last_mut() {
+            Some(batch) if batch.len() + 2 + message.len() <= max_chars => {
+                batch.push_str("\n\n");
+                batch.push_str(&message);
+            }
+            _ => batches.push(message),
+        }
+    }
+
+    batches
+}
+
+/// Split `message` into pieces of at most `max_chars` bytes, cutting only on
+/// char boundaries so multi-byte characters (emoji) stay whole.
+fn split_message(message: String, max_chars: usize) -> Vec<String> {
+    if message.len() <= max_chars {
+        return vec![message];
+    }
+
+    let mut pieces = Vec::new();
+    let mut rest = message.as_str();
+    while rest.len() > max_chars {
+        let mut cut = max_chars;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        // A limit below one character's width still has to make progress
+        if cut == 0 {
+            cut = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        pieces.push(rest[..cut].to_string());
+        rest = &rest[cut..];
+    }
+    if !rest.is_empty() {
+        pieces.push(rest.to_string());
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_messages_share_a_batch() {
+        let batches = coalesce(vec!["a".to_string(), "b".to_string()], 10);
+        assert_eq!(batches, vec!["a\n\nb".to_string()]);
+    }
+
+    #[test]
+    fn batches_stop_at_the_limit() {
+        let batches = coalesce(vec!["aaaa".to_string(), "bbbb".to_string()], 9);
+        assert_eq!(batches, vec!["aaaa".to_string(), "bbbb".to_string()]);
+    }
+
+    #[test]
+    fn oversized_message_is_split_to_the_limit() {
+        let batches = coalesce(vec!["x".repeat(25)], 10);
+        assert_eq!(batches.len(), 3);
+        assert!(batches.iter().all(|batch| batch.len() <= 10));
+        assert_eq!(batches.concat(), "x".repeat(25));
+    }
+
+    #[test]
+    fn split_keeps_multibyte_chars_whole() {
+        // Each emoji is 4 bytes, so a 6-byte limit fits one per piece
+        let message = "🟢🔴🟡".to_string();
+        let pieces = split_message(message.clone(), 6);
+        assert_eq!(pieces, vec!["🟢", "🔴", "🟡"]);
+        assert_eq!(pieces.concat(), message);
+    }
+
+    #[test]
+    fn split_makes_progress_below_one_char() {
+        assert_eq!(split_message("🟢🔴".to_string(), 1), vec!["🟢", "🔴"]);
+    }
+
+    /// Records what it is sent.
+    #[derive(Clone, Default)]
+    struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl Notifier for Recorder {
+        fn send(&self, message: &str) {
+            self.0.lock().unwrap().push(message.to_string());
+        }
+
+        fn flush(&self) -> FlushFuture<'_> {
+            Box::pin(async {})
+        }
+    }
+
+    fn toggles(buy: bool) -> NotifyConfig {
+        NotifyConfig {
+            new_token: false,
+            buy,
+            sell: true,
+            copy: false,
+            scout_promotion: false,
+            errors: true,
+            pnl_summary: false,
+            heartbeat: false,
+        }
+    }
+
+    #[test]
+    fn every_notifier_gets_the_allowed_events() {
+        let (telegram, discord) = (Recorder::default(), Recorder::default());
+        let notifications = Notifications::new(vec![Box::new(telegram.clone()), Box::new(discord.clone())], toggles(false));
+
+        notifications.notify(NotifyEvent::Sell, || "sold".to_string());
+        notifications.notify(NotifyEvent::Buy, || unreachable!("disabled events aren't built"));
+
+        assert_eq!(*telegram.0.lock().unwrap(), ["sold"]);
+        assert_eq!(*discord.0.lock().unwrap(), ["sold"]);
+    }
+
+    #[test]
+    fn nothing_is_enabled_without_a_notifier() {
+        let notifications = Notifications::new(Vec::new(), toggles(true));
+        assert!(!notifications.enabled(NotifyEvent::Buy));
+    }
+
+    #[tokio::test]
+    async fn queue_coalesces_and_flush_waits_for_delivery() {
+        let delivered = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&delivered);
+        let queue = MessageQueue::spawn("Test", Duration::ZERO, 100, move |batch| {
+            let sink = std::sync::Arc::clone(&sink);
+            async move { sink.lock().unwrap().push(batch) }
+        });
+
+        queue.push("one".to_string());
+        queue.push("two".to_string());
+        queue.flush().await;
+
+        assert_eq!(delivered.lock().unwrap().join("\n\n"), "one\n\ntwo");
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Crash-safe writes for the JSON state files.
+
+use std::fs;
+use std::io;
+
+/// Write `contents` to `<path>.tmp`, then rename it over `path`.
+/// The rename is atomic on the same filesystem, so a crash or Ctrl-C mid-write
+/// leaves the previous file intact instead of a truncated one.
+pub fn write_atomic(path: &str, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let tmp_path = format!("{}.tmp", path);
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A fresh directory under the system temp dir, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("monad-bot-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn file(&self, name: &str) -> String {
+            self.0.join(name).to_string_lossy().into_owned()
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn replaces_the_file_and_leaves_no_temp() {
+        let dir = TempDir::new("persist-replace");
+        let path = dir.file("state.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!PathBuf::from(format!("{}.tmp", path)).exists());
+    }
+
+    #[test]
+    fn crash_before_the_rename_keeps_the_old_file() {
+        let dir = TempDir::new("persist-crash");
+        let path = dir.file("state.json");
+        fs::write(&path, r#"{"ok":true}"#).unwrap();
+
+        // Killed after writing part of the temp file, before the rename
+        fs::write(format!("{}.tmp", path), r#"{"ok":tr"#).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"ok":true}"#);
+
+        // The next save overwrites the leftover temp file
+        write_atomic(&path, r#"{"ok":false}"#).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"ok":false}"#);
+    }
+
+    #[test]
+    fn failed_write_keeps_the_old_file() {
+        let dir = TempDir::new("persist-fail");
+        let path = dir.file("state.json");
+        fs::write(&path, "old").unwrap();
+        // A directory where the temp file goes makes the write fail
+        fs::create_dir(format!("{}.tmp", path)).unwrap();
+
+        assert!(write_atomic(&path, "new").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    }
+}
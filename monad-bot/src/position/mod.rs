@@ -4,8 +4,10 @@
 
 //! Position management module.
 
+pub mod price_source;
 pub mod tracker;
 pub mod trailing_sl;
 
+pub use price_source::{LivePriceSource, PriceFuture, PriceSource};
 pub use tracker::{Position, PositionTracker};
-pub use trailing_sl::{spawn_monitor, SellDecision, TrailingStopLossConfig};
+pub use trailing_sl::{cost_basis, spawn_monitor, ExitMode, PositionMonitor, SellDecision, TrailingStopLossConfig};
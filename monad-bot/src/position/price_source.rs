@@ -0,0 +1,329 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Where the position monitor gets token prices from.
+//!
+//! Live trading prices on-chain, through the bonding curve or the DEX
+//! depending on whether the token has graduated; the backtester replays
+//! recorded prices.
+
+use crate::executor::routes::{pool_liquidity, select_route};
+use crate::executor::{DexRoute, SdkExecutor};
+use crate::listeners::nadfun::BONDING_CURVE;
+use crate::units::wei_to_mon;
+use alloy::primitives::{Address, U256};
+use alloy::providers::{DynProvider, Provider};
+use alloy::sol;
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+sol! {
+    #[sol(rpc)]
+    interface IBondingCurve {
+        function curves(address token) external view returns (
+            uint256 realMonReserve,
+            uint256 realTokenReserve,
+            uint256 virtualMonReserve,
+            uint256 virtualTokenReserve,
+            uint256 k,
+            uint256 targetTokenAmount,
+            uint256 initVirtualMonReserve,
+            uint256 initVirtualTokenReserve
+        );
+    }
+}
+
+/// Future returned by `PriceSource::price_mon`.
+pub type PriceFuture<'a> = Pin<Box<dyn Future<Output = Result<f64, String>> + Send + 'a>>;
+
+/// Values token holdings in MON.
+pub trait PriceSource: Send + Sync {
+    /// MON that selling `amount` of `token` would return.
+    fn price_mon(&self, token: Address, amount: U256) -> PriceFuture<'_>;
+
+    /// MON backing `token`'s market: the curve's real MON reserve or the DEX pair's WMON.
+    /// Sources without reserve data return an error and the rug check is skipped.
+    fn liquidity_mon(&self, token: Address) -> PriceFuture<'_> {
+        Box::pin(async move { Err(format!("No liquidity data for {:?}", token)) })
+    }
+
+    /// Fully diluted market cap of `token` in MON: one whole token's price times total supply.
+    /// Sources without supply data return an error and the migration exit is skipped.
+    fn market_cap_mon(&self, token: Address) -> PriceFuture<'_> {
+        Box::pin(async move { Err(format!("No market cap data for {:?}", token)) })
+    }
+
+    /// Current base fee in gwei, for pricing the gas of a round trip.
+    /// Sources without it return an error and `FEE_GAS_PRICE_GWEI` is assumed.
+    fn base_fee_gwei(&self) -> PriceFuture<'_> {
+        Box::pin(async move { Err("No base fee data".to_string()) })
+    }
+
+    /// `token` has left the bonding curve. Sources that route by venue price it on the DEX from now on.
+    fn mark_graduated(&self, _token: Address) {}
+}
+
+/// Bonding curve pricing through the nad.fun SDK.
+pub struct SdkPriceSource<P> {
+    provider: P,
+    sdk_executor: Arc<SdkExecutor>,
+}
+
+impl<P: Provider + Clone> SdkPriceSource<P> {
+    pub fn new(provider: P, sdk_executor: Arc<SdkExecutor>) -> Self {
+        Self { provider, sdk_executor }
+    }
+}
+
+impl<P: Provider + Clone + Send + Sync> PriceSource for SdkPriceSource<P> {
+    fn liquidity_mon(&self, token: Address) -> PriceFuture<'_> {
+        Box::pin(async move {
+            let curve = Address::from_str(BONDING_CURVE).map_err(|e| format!("Invalid curve address: {}", e))?;
+            let state = IBondingCurve::new(curve, &self.provider)
+                .curves(token)
+                .call()
+                .await
+                .map_err(|e| format!("Curve reserves failed: {}", e))?;
+            Ok(wei_to_mon(state.realMonReserve))
+        })
+    }
+
+    fn price_mon(&self, token: Address, amount: U256) -> PriceFuture<'_> {
+        Box::pin(async move {
+            let price = self
+                .sdk_executor
+                .get_token_price_mon(token, amount)
+                .await
+                .map_err(|e| e.to_string())?;
+            if price <= 0.0 {
+                return Err("SDK returned a zero price".to_string());
+            }
+            debug!("📊 SDK price for {:?}: {} MON", token, price);
+            Ok(price)
+        })
+    }
+}
+
+/// Graduated-token pricing: the best quote across the configured DEXes.
+pub struct DexPriceSource<P> {
+    provider: P,
+    routes: Vec<DexRoute>,
+}
+
+impl<P: Provider + Clone> DexPriceSource<P> {
+    pub fn new(provider: P, routes: Vec<DexRoute>) -> Self {
+        Self { provider, routes }
+    }
+}
+
+impl<P: Provider + Clone + Send + Sync> PriceSource for DexPriceSource<P> {
+    fn liquidity_mon(&self, token: Address) -> PriceFuture<'_> {
+        Box::pin(async move {
+            let wmon = pool_liquidity(&self.provider, &self.routes, token)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(wei_to_mon(wmon))
+        })
+    }
+
+    fn price_mon(&self, token: Address, amount: U256) -> PriceFuture<'_> {
+        Box::pin(async move {
+            let (route, mon_wei) = select_route(&self.provider, &self.routes, token, amount, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            let mon = wei_to_mon(mon_wei);
+            debug!("📊 DEX ({}) price for {:?}: {} MON", route.name, token, mon);
+            Ok(mon)
+        })
+    }
+}
+
+/// On-chain pricing that follows the token's venue: the bonding curve until
+/// it graduates, the DEX after. Curve misses (e.g. mid-graduation) fall back to the DEX.
+pub struct LivePriceSource {
+    curve: Box<dyn PriceSource>,
+    dex: Box<dyn PriceSource>,
+    /// For the base fee
+    provider: DynProvider,
+    sdk_executor: Arc<SdkExecutor>,
+    /// Tokens seen graduated; graduation is permanent, so they skip the check.
+    graduated: Mutex<HashSet<Address>>,
+}
+
+impl LivePriceSource {
+    pub fn new<P: Provider + Clone + Send + Sync + 'static>(
+        provider: P,
+        routes: Vec<DexRoute>,
+        sdk_executor: Arc<SdkExecutor>,
+    ) -> Self {
+        Self {
+            curve: Box::new(SdkPriceSource::new(provider.clone(), Arc::clone(&sdk_executor))),
+            dex: Box::new(DexPriceSource::new(provider.clone(), routes)),
+            provider: provider.erased(),
+            sdk_executor,
+            graduated: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Whether `token` trades on a DEX now. Unknown counts as not graduated.
+    async fn is_graduated(&self, token: Address) -> bool {
+        if self.graduated.lock().is_ok_and(|graduated| graduated.contains(&token)) {
+            return true;
+        }
+        match self.sdk_executor.is_graduated(token).await {
+            Ok(true) => {
+                self.mark_graduated(token);
+                true
+            }
+            Ok(false) => false,
+            Err(e) => {
+                debug!("Graduation check failed for {:?}: {}", token, e);
+                false
+            }
+        }
+    }
+}
+
+impl PriceSource for LivePriceSource {
+    fn liquidity_mon(&self, token: Address) -> PriceFuture<'_> {
+        Box::pin(async move {
+            if self.is_graduated(token).await {
+                self.dex.liquidity_mon(token).await
+            } else {
+                self.curve.liquidity_mon(token).await
+            }
+        })
+    }
+
+    fn market_cap_mon(&self, token: Address) -> PriceFuture<'_> {
+        Box::pin(async move {
+            let meta = self.sdk_executor.token_meta(token).await;
+            let price = self.price_mon(token, meta.one_token()).await?;
+            let supply = self
+                .sdk_executor
+                .get_total_supply(token)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(price * meta.to_tokens(supply))
+        })
+    }
+
+    fn base_fee_gwei(&self) -> PriceFuture<'_> {
+        Box::pin(async move {
+            let block = self
+                .provider
+                .get_block_by_number(alloy::eips::BlockNumberOrTag::Latest)
+                .await
+                .map_err(|e| format!("Failed to get block: {}", e))?
+                .ok_or_else(|| "No block found".to_string())?;
+            let base_fee = block.header.base_fee_per_gas.ok_or_else(|| "No base fee".to_string())?;
+            Ok(base_fee as f64 / 1e9)
+        })
+    }
+
+    fn mark_graduated(&self, token: Address) {
+        if let Ok(mut graduated) = self.graduated.lock() {
+            graduated.insert(token);
+        }
+    }
+
+    fn price_mon(&self, token: Address, amount: U256) -> PriceFuture<'_> {
+        Box::pin(async move {
+            if self.is_graduated(token).await {
+                return self.dex.price_mon(token, amount).await;
+            }
+
+            match self.curve.price_mon(token, amount).await {
+                Ok(price) => Ok(price),
+                Err(curve_error) => {
+                    debug!("Curve price failed for {:?}: {}, trying DEX...", token, curve_error);
+                    self.dex
+                        .price_mon(token, amount)
+                        .await
+                        .map_err(|e| format!("Both SDK and DEX failed: {} / {}", curve_error, e))
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, offline_sdk_executor, push_call};
+    use crate::executor::routes::IQuoteRouter;
+
+    const TOKEN: Address = Address::new([0x11; 20]);
+
+    /// Always returns `price`, or fails when it is `None`.
+    struct Fixed(Option<f64>);
+
+    impl PriceSource for Fixed {
+        fn price_mon(&self, _token: Address, _amount: U256) -> PriceFuture<'_> {
+            let price = self.0;
+            Box::pin(async move { price.ok_or_else(|| "no quote".to_string()) })
+        }
+    }
+
+    async fn live(curve: Option<f64>, dex: Option<f64>) -> LivePriceSource {
+        let (provider, _) = mock_provider();
+        LivePriceSource {
+            curve: Box::new(Fixed(curve)),
+            dex: Box::new(Fixed(dex)),
+            provider: provider.erased(),
+            // Its graduation check fails, which counts as not graduated
+            sdk_executor: Arc::new(offline_sdk_executor().await),
+            graduated: Mutex::new(HashSet::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn curve_prices_until_graduation_then_the_dex() {
+        let source = live(Some(1.0), Some(2.0)).await;
+        assert_eq!(source.price_mon(TOKEN, U256::from(1u64)).await, Ok(1.0));
+
+        source.mark_graduated(TOKEN);
+        assert_eq!(source.price_mon(TOKEN, U256::from(1u64)).await, Ok(2.0));
+        // Other tokens stay on the curve
+        assert_eq!(source.price_mon(Address::ZERO, U256::from(1u64)).await, Ok(1.0));
+    }
+
+    #[tokio::test]
+    async fn curve_miss_falls_back_to_the_dex() {
+        let source = live(None, Some(2.0)).await;
+        assert_eq!(source.price_mon(TOKEN, U256::from(1u64)).await, Ok(2.0));
+
+        let source = live(None, None).await;
+        let err = source.price_mon(TOKEN, U256::from(1u64)).await.unwrap_err();
+        assert!(err.starts_with("Both SDK and DEX failed"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn dex_source_prices_at_the_best_route() {
+        let (provider, asserter) = mock_provider();
+        let route = |byte: u8| DexRoute {
+            name: format!("dex{}", byte),
+            router: Address::repeat_byte(byte),
+            wmon: Address::repeat_byte(0xee),
+        };
+        let source = DexPriceSource::new(provider, vec![route(1), route(2)]);
+        let one_mon = U256::from(1_000_000_000_000_000_000u128);
+        push_call::<IQuoteRouter::getAmountsOutCall>(&asserter, &vec![U256::from(1u64), one_mon]);
+        push_call::<IQuoteRouter::getAmountsOutCall>(&asserter, &vec![U256::from(1u64), one_mon * U256::from(3u64)]);
+
+        assert_eq!(source.price_mon(TOKEN, U256::from(1u64)).await, Ok(3.0));
+    }
+
+    #[tokio::test]
+    async fn market_cap_needs_the_total_supply() {
+        // Priced, but the offline executor can't read the supply
+        let source = live(Some(1.0), Some(1.0)).await;
+        assert!(source.market_cap_mon(TOKEN).await.is_err());
+        // Sources without supply data opt out of the migration exit
+        assert!(Fixed(Some(1.0)).market_cap_mon(TOKEN).await.is_err());
+    }
+}
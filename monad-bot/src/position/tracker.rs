@@ -4,6 +4,8 @@
 
 //! Position tracking for open trades.
 
+use crate::persist::write_atomic;
+use crate::units::token_units_to_f64;
 use alloy::primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -24,12 +26,100 @@ pub struct Position {
     pub buy_time: u64,
     pub highest_price: f64,
     pub tx_hash: String,
+    /// Opened optimistically before the buy was confirmed (front-run).
+    #[serde(default)]
+    pub provisional: bool,
+    /// Take-profit tiers (profit %) already sold into.
+    #[serde(default)]
+    pub fired_tp_levels: Vec<f64>,
+    /// Consecutive sells that failed on every venue.
+    #[serde(default)]
+    pub sell_failures: u32,
+    /// Too many failed sells; the monitor leaves it for manual intervention.
+    #[serde(default)]
+    pub stuck: bool,
+    /// Left the bonding curve; priced and sold on the DEX.
+    #[serde(default)]
+    pub graduated: bool,
+    /// Highest pool liquidity (MON) seen while held, for the rug check.
+    #[serde(default)]
+    pub peak_liquidity_mon: f64,
+    /// Cost basis already recouped (`recoup_then_runner` exit mode); the rest
+    /// rides on the runner trailing stop.
+    #[serde(default)]
+    pub in_runner_mode: bool,
+    /// Token decimals. `amount` is in the token's smallest unit; MON values are always 18.
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+}
+
+/// Positions saved before decimals were tracked were all nad.fun (18-decimal) tokens.
+fn default_decimals() -> u8 {
+    18
+}
+
+impl Position {
+    /// `amount` in whole tokens.
+    pub fn amount_tokens(&self) -> f64 {
+        token_units_to_f64(self.amount, self.decimals)
+    }
+
+    /// Fold a repeat buy of the same token into this position.
+    /// `buy_price_mon` and `highest_price` are MON for the whole holding, so the
+    /// costs add up (the weighted-average entry price times the combined amount)
+    /// and the peak keeps the larger one, never below the combined cost.
+    ///
+    /// The exit state merges as follows:
+    /// - `fired_tp_levels` and `in_runner_mode` reset: the top-up changes the
+    ///   cost basis the ladder and the recoup are measured against, so they
+    ///   start over on the combined holding.
+    /// - `sell_failures` keeps the higher count and `stuck` stays set: a buy
+    ///   going through says nothing about whether the token can be sold.
+    /// - `peak_liquidity_mon` keeps the higher peak, so the rug check still
+    ///   compares against the deepest pool seen while holding.
+    /// - `graduated` and `provisional` are set if either side is.
+    pub fn merge(&mut self, other: Position) {
+        self.amount += other.amount;
+        self.buy_price_mon += other.buy_price_mon;
+        self.highest_price = self.highest_price.max(other.highest_price).max(self.buy_price_mon);
+        if self.buy_time == 0 || (other.buy_time > 0 && other.buy_time < self.buy_time) {
+            self.buy_time = other.buy_time;
+        }
+        self.tx_hash = other.tx_hash;
+        self.provisional |= other.provisional;
+        self.fired_tp_levels.clear();
+        self.in_runner_mode = false;
+        self.sell_failures = self.sell_failures.max(other.sell_failures);
+        self.stuck |= other.stuck;
+        self.peak_liquidity_mon = self.peak_liquidity_mon.max(other.peak_liquidity_mon);
+        self.graduated |= other.graduated;
+    }
+
+    /// Average MON paid per whole token.
+    pub fn entry_price_mon(&self) -> f64 {
+        let tokens = self.amount_tokens();
+        if tokens > 0.0 {
+            self.buy_price_mon / tokens
+        } else {
+            0.0
+        }
+    }
 }
 
 /// Manages all open positions.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct PositionTracker {
     positions: HashMap<Address, Position>,
+    /// Repeat buys of a held token merge into its position; off, the latest buy replaces it.
+    pub averaging_enabled: bool,
+    /// Write `positions.json` on every change (off for tests).
+    persist: bool,
+}
+
+impl Default for PositionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PositionTracker {
@@ -37,6 +127,16 @@ impl PositionTracker {
     pub fn new() -> Self {
         Self {
             positions: HashMap::new(),
+            averaging_enabled: true,
+            persist: true,
+        }
+    }
+
+    /// Empty tracker that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            persist: false,
+            ..Self::new()
         }
     }
 
@@ -52,7 +152,10 @@ impl PositionTracker {
             Ok(content) => match serde_json::from_str::<HashMap<Address, Position>>(&content) {
                 Ok(positions) => {
                     info!("Loaded {} positions from file", positions.len());
-                    Self { positions }
+                    Self {
+                        positions,
+                        ..Self::new()
+                    }
                 }
                 Err(e) => {
                     error!("Failed to parse positions file: {}", e);
@@ -68,23 +171,42 @@ impl PositionTracker {
 
     /// Save positions to file.
     pub fn save(&self) -> Result<(), String> {
+        if !self.persist {
+            return Ok(());
+        }
         let content = serde_json::to_string_pretty(&self.positions)
             .map_err(|e| format!("Failed to serialize positions: {}", e))?;
 
-        fs::write(POSITIONS_FILE, content)
+        write_atomic(POSITIONS_FILE, content)
             .map_err(|e| format!("Failed to write positions file: {}", e))?;
 
         debug!("Saved {} positions to file", self.positions.len());
         Ok(())
     }
 
-    /// Add a new position.
+    /// Add a position. A token already held is merged into (see `Position::merge`)
+    /// unless averaging is disabled, in which case it is replaced.
     pub fn add(&mut self, position: Position) {
-        info!(
-            "Adding position: {} ({}) - {} tokens",
-            position.name, position.symbol, position.amount
-        );
-        self.positions.insert(position.token, position);
+        match self.positions.get_mut(&position.token) {
+            Some(existing) if self.averaging_enabled => {
+                existing.merge(position);
+                info!(
+                    "Averaged into position: {} ({}) - {} tokens, {:.4} MON cost, {:.10} MON/token",
+                    existing.name,
+                    existing.symbol,
+                    existing.amount,
+                    existing.buy_price_mon,
+                    existing.entry_price_mon()
+                );
+            }
+            _ => {
+                info!(
+                    "Adding position: {} ({}) - {} tokens",
+                    position.name, position.symbol, position.amount
+                );
+                self.positions.insert(position.token, position);
+            }
+        }
         let _ = self.save();
     }
 
@@ -97,6 +219,38 @@ impl PositionTracker {
         position
     }
 
+    /// Reconcile a provisional position with its confirmed buy: the `expected`
+    /// tokens it was opened with become the `amount` actually received, leaving
+    /// any buys merged in meanwhile untouched.
+    pub fn confirm(&mut self, token: &Address, expected: U256, amount: U256, tx_hash: String) {
+        if let Some(pos) = self.positions.get_mut(token) {
+            info!(
+                "Confirmed position: {} ({}) - {} tokens",
+                pos.name, pos.symbol, amount
+            );
+            pos.amount = pos.amount.saturating_sub(expected) + amount;
+            pos.tx_hash = tx_hash;
+            pos.provisional = false;
+            let _ = self.save();
+        }
+    }
+
+    /// Flag a held token as graduated. Returns `false` if we don't hold it
+    /// or it was already flagged.
+    pub fn mark_graduated(&mut self, token: &Address) -> bool {
+        match self.positions.get_mut(token) {
+            Some(pos) if !pos.graduated => {
+                info!("🎓 Position graduated: {} ({})", pos.name, pos.symbol);
+                pos.graduated = true;
+                // Liquidity now lives in the DEX pair; start its peak afresh
+                pos.peak_liquidity_mon = 0.0;
+                let _ = self.save();
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Get a position by token address.
     pub fn get(&self, token: &Address) -> Option<&Position> {
         self.positions.get(token)
@@ -121,6 +275,32 @@ impl PositionTracker {
         }
     }
 
+    /// Count a sell that failed on every venue.
+    /// Returns true if this failure marks the position stuck (`max_failures` 0 never does).
+    pub fn record_sell_failure(&mut self, token: &Address, max_failures: u32) -> bool {
+        let Some(pos) = self.positions.get_mut(token) else {
+            return false;
+        };
+        pos.sell_failures += 1;
+        let newly_stuck = max_failures > 0 && !pos.stuck && pos.sell_failures >= max_failures;
+        if newly_stuck {
+            pos.stuck = true;
+        }
+        let _ = self.save();
+        newly_stuck
+    }
+
+    /// Clear the failure count (and stuck flag) after a successful sell.
+    pub fn reset_sell_failures(&mut self, token: &Address) {
+        if let Some(pos) = self.positions.get_mut(token) {
+            if pos.sell_failures > 0 || pos.stuck {
+                pos.sell_failures = 0;
+                pos.stuck = false;
+                let _ = self.save();
+            }
+        }
+    }
+
     /// Get all positions.
     pub fn all(&self) -> Vec<&Position> {
         self.positions.values().collect()
@@ -136,3 +316,189 @@ impl PositionTracker {
         self.positions.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(token: Address) -> Position {
+        Position {
+            token,
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            amount: U256::from(1_000u64),
+            buy_price_mon: 1.0,
+            buy_time: 1_700_000_000,
+            highest_price: 1.0,
+            tx_hash: "0xa".to_string(),
+            provisional: false,
+            fired_tp_levels: Vec::new(),
+            sell_failures: 0,
+            stuck: false,
+            graduated: false,
+            peak_liquidity_mon: 0.0,
+            in_runner_mode: false,
+            decimals: 18,
+        }
+    }
+
+    #[test]
+    fn position_is_stuck_once_after_max_failures() {
+        let token = Address::repeat_byte(1);
+        let mut tracker = PositionTracker::in_memory();
+        tracker.add(position(token));
+
+        assert!(!tracker.record_sell_failure(&token, 3));
+        assert!(!tracker.record_sell_failure(&token, 3));
+        assert!(tracker.record_sell_failure(&token, 3));
+        // Already stuck: not reported again
+        assert!(!tracker.record_sell_failure(&token, 3));
+        assert_eq!(tracker.get(&token).map(|p| (p.sell_failures, p.stuck)), Some((4, true)));
+
+        tracker.reset_sell_failures(&token);
+        assert_eq!(tracker.get(&token).map(|p| (p.sell_failures, p.stuck)), Some((0, false)));
+    }
+
+    #[test]
+    fn zero_max_failures_never_sticks() {
+        let token = Address::repeat_byte(1);
+        let mut tracker = PositionTracker::in_memory();
+        tracker.add(position(token));
+        assert!((0..10).all(|_| !tracker.record_sell_failure(&token, 0)));
+        assert!(!tracker.get(&token).unwrap().stuck);
+        assert!(!tracker.record_sell_failure(&Address::repeat_byte(2), 1));
+    }
+
+    #[test]
+    fn positions_saved_before_the_ladder_load_with_no_tiers_fired() {
+        let json = r#"{
+            "token": "0x1111111111111111111111111111111111111111",
+            "name": "Test",
+            "symbol": "TST",
+            "amount": "0x3e8",
+            "buy_price_mon": 1.0,
+            "buy_time": 1700000000,
+            "highest_price": 1.0,
+            "tx_hash": "0xabc"
+        }"#;
+        let position: Position = serde_json::from_str(json).unwrap();
+        assert!(position.fired_tp_levels.is_empty());
+        assert!(!position.graduated);
+        assert_eq!(position.amount, U256::from(1_000u64));
+        // Saved before decimals were tracked: a nad.fun token
+        assert_eq!(position.decimals, 18);
+    }
+
+    #[test]
+    fn whole_token_amounts_follow_the_position_decimals() {
+        let mut six = position(Address::repeat_byte(1));
+        six.decimals = 6;
+        six.amount = U256::from(2_500_000u64);
+        six.buy_price_mon = 5.0;
+        assert_eq!(six.amount_tokens(), 2.5);
+        assert_eq!(six.entry_price_mon(), 2.0);
+
+        let mut empty = six.clone();
+        empty.amount = U256::ZERO;
+        assert_eq!(empty.entry_price_mon(), 0.0);
+    }
+
+    #[test]
+    fn graduation_is_flagged_once_for_held_tokens() {
+        let token = Address::repeat_byte(1);
+        let mut tracker = PositionTracker::in_memory();
+        tracker.add(position(token));
+
+        assert!(tracker.mark_graduated(&token));
+        assert!(tracker.get(&token).unwrap().graduated);
+        assert!(!tracker.mark_graduated(&token));
+        assert!(!tracker.mark_graduated(&Address::repeat_byte(2)));
+    }
+
+    #[test]
+    fn merge_adds_cost_and_keeps_the_earliest_buy() {
+        let token = Address::repeat_byte(1);
+        let mut held = position(token);
+        let top_up = Position {
+            amount: U256::from(500u64),
+            buy_price_mon: 2.0,
+            buy_time: 1_700_000_100,
+            highest_price: 2.0,
+            tx_hash: "0xb".to_string(),
+            ..position(token)
+        };
+        held.merge(top_up);
+
+        assert_eq!(held.amount, U256::from(1_500u64));
+        assert_eq!(held.buy_price_mon, 3.0);
+        assert_eq!(held.highest_price, 3.0);
+        assert_eq!(held.buy_time, 1_700_000_000);
+        assert_eq!(held.tx_hash, "0xb");
+    }
+
+    #[test]
+    fn merge_restarts_the_ladder_and_keeps_sell_trouble_and_peaks() {
+        let token = Address::repeat_byte(1);
+        let mut held = Position {
+            fired_tp_levels: vec![50.0, 100.0],
+            in_runner_mode: true,
+            sell_failures: 2,
+            stuck: true,
+            peak_liquidity_mon: 80.0,
+            graduated: true,
+            ..position(token)
+        };
+        held.merge(Position { peak_liquidity_mon: 40.0, ..position(token) });
+
+        assert!(held.fired_tp_levels.is_empty());
+        assert!(!held.in_runner_mode);
+        assert_eq!((held.sell_failures, held.stuck), (2, true));
+        assert_eq!(held.peak_liquidity_mon, 80.0);
+        assert!(held.graduated);
+
+        // The other side's trouble and peaks carry over too
+        let mut fresh = position(token);
+        fresh.merge(Position { sell_failures: 1, peak_liquidity_mon: 90.0, provisional: true, ..position(token) });
+        assert_eq!((fresh.sell_failures, fresh.stuck), (1, false));
+        assert_eq!(fresh.peak_liquidity_mon, 90.0);
+        assert!(fresh.provisional);
+    }
+
+    #[test]
+    fn repeat_buy_merges_only_when_averaging() {
+        let token = Address::repeat_byte(1);
+        let mut tracker = PositionTracker::in_memory();
+        tracker.add(position(token));
+        tracker.add(position(token));
+        assert_eq!(tracker.get(&token).unwrap().amount, U256::from(2_000u64));
+
+        tracker.averaging_enabled = false;
+        tracker.add(position(token));
+        assert_eq!(tracker.get(&token).unwrap().amount, U256::from(1_000u64));
+    }
+
+    #[test]
+    fn entry_price_is_the_average_cost_per_whole_token() {
+        let token = Address::repeat_byte(1);
+        let one = U256::from(10u64).pow(U256::from(18));
+        let mut held = Position { amount: one * U256::from(100u64), buy_price_mon: 1.0, ..position(token) };
+        held.merge(Position { amount: one * U256::from(100u64), buy_price_mon: 3.0, ..position(token) });
+        assert!((held.entry_price_mon() - 0.02).abs() < 1e-12);
+        assert_eq!(Position { amount: U256::ZERO, ..position(token) }.entry_price_mon(), 0.0);
+    }
+
+    #[test]
+    fn confirm_swaps_only_the_provisional_amount() {
+        let token = Address::repeat_byte(1);
+        let mut tracker = PositionTracker::in_memory();
+        tracker.add(Position { provisional: true, ..position(token) });
+        // A copy buy lands before the front-run confirms
+        tracker.add(Position { amount: U256::from(500u64), ..position(token) });
+
+        tracker.confirm(&token, U256::from(1_000u64), U256::from(900u64), "0xc".to_string());
+        let held = tracker.get(&token).unwrap();
+        assert_eq!(held.amount, U256::from(1_400u64));
+        assert_eq!(held.tx_hash, "0xc");
+        assert!(!held.provisional);
+    }
+}
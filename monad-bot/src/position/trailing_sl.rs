@@ -3,56 +3,116 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Trailing stop-loss implementation.
-//! Updated to use SDK for bonding curve token pricing.
 
 use crate::config::Config;
-use crate::executor::SdkExecutor;
-use crate::position::{Position, PositionTracker};
+use crate::health::{HealthStatus, MONITOR_TASK};
+use crate::position::{Position, PositionTracker, PriceSource};
+use crate::price::MonPriceOracle;
+use crate::strategies::sniper::MonadFilters;
 use alloy::primitives::{Address, U256};
-use alloy::providers::Provider;
-use alloy::sol;
+use futures_util::stream::{self, StreamExt};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
-// Router interface for price queries (fallback for graduated tokens)
-sol! {
-    #[sol(rpc)]
-    interface IRouter {
-        function getAmountsOut(uint256 amountIn, address[] calldata path)
-            external view returns (uint256[] memory amounts);
-    }
-}
-
 /// Trailing stop-loss configuration.
 #[derive(Debug, Clone)]
 pub struct TrailingStopLossConfig {
     /// Percentage drop from highest to trigger sell.
     pub drop_pct: f64,
+    /// `drop_pct` by position age as (minutes held, drop %), ascending.
+    /// Interpolated linearly between points; empty keeps `drop_pct` constant.
+    pub drop_decay: Vec<(f64, f64)>,
     /// Minimum profit percentage before trailing activates.
     pub min_profit_pct: f64,
+    /// Fees charged against the PnL before a take-profit or trailing exit.
+    pub round_trip_cost: RoundTripCost,
     /// Hard stop-loss percentage (always triggers).
     pub hard_stop_loss_pct: f64,
-    /// Profit percentage to secure partial profits.
-    pub secure_profit_pct: f64,
-    /// Portion to sell when securing profits.
-    pub secure_sell_portion: f64,
+    /// Pool liquidity drop from its peak, in %, treated as a rug (0 = off).
+    pub rug_liquidity_drop_pct: f64,
+    /// Sell out once the market cap nears the migration cap.
+    pub sell_before_migration: bool,
+    /// Take-profit tiers: (profit %, portion of the remaining position), ascending.
+    /// Each tier fires once per position.
+    pub tp_ladder: Vec<(f64, f64)>,
+    /// Take-profit tiers, or recoup-then-runner.
+    pub exit_mode: ExitMode,
+    /// `RecoupThenRunner`: position value, as a multiple of its cost, that triggers the recoup.
+    pub recoup_multiple: f64,
+    /// `RecoupThenRunner`: trailing drop % for the runner left after the recoup.
+    pub runner_drop_pct: f64,
     /// Maximum hold time in hours.
     pub max_hold_hours: u64,
     /// Check interval in seconds.
     pub check_interval_sec: u64,
+    /// Positions priced at once per check.
+    pub max_concurrent_checks: usize,
 }
 
 impl TrailingStopLossConfig {
     pub fn from_config(config: &Config) -> Self {
         Self {
             drop_pct: config.trailing_drop_pct,
+            drop_decay: config.trailing_decay.clone(),
             min_profit_pct: config.trailing_min_profit,
+            round_trip_cost: RoundTripCost {
+                gas: config.round_trip_gas,
+                fallback_gas_price_gwei: config.fee_gas_price_gwei,
+                slippage_pct: config.fee_slippage_pct,
+            },
             hard_stop_loss_pct: config.hard_stop_loss_pct,
-            secure_profit_pct: config.secure_profit_pct,
-            secure_sell_portion: config.secure_sell_portion,
+            rug_liquidity_drop_pct: config.rug_liquidity_drop_pct,
+            sell_before_migration: config.sell_before_migration,
+            tp_ladder: config.tp_ladder.clone(),
+            exit_mode: config.exit_mode,
+            recoup_multiple: config.recoup_multiple,
+            runner_drop_pct: config.runner_trailing_drop_pct,
             max_hold_hours: config.max_hold_hours,
             check_interval_sec: config.check_interval_sec,
+            max_concurrent_checks: config.monitor_concurrency,
+        }
+    }
+
+    /// Trailing drop % for a position held `age_secs` (`None` = unknown buy time).
+    /// Before the first point and after the last, the nearest point applies.
+    pub fn effective_drop_pct(&self, age_secs: Option<u64>) -> f64 {
+        let (Some(age_secs), Some(&first)) = (age_secs, self.drop_decay.first()) else {
+            return self.drop_pct;
+        };
+        let minutes = age_secs as f64 / 60.0;
+
+        let mut prev = first;
+        for &(at, drop_pct) in &self.drop_decay {
+            if minutes <= at {
+                if at <= prev.0 {
+                    return drop_pct;
+                }
+                let t = (minutes - prev.0) / (at - prev.0);
+                return prev.1 + (drop_pct - prev.1) * t.clamp(0.0, 1.0);
+            }
+            prev = (at, drop_pct);
+        }
+        prev.1
+    }
+}
+
+/// How a profitable position is scaled out of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitMode {
+    /// Take-profit ladder, then the trailing stop.
+    Standard,
+    /// At `recoup_multiple`x, sell just enough to get the cost basis back and
+    /// let the rest run on the wider runner trailing stop. Replaces the ladder.
+    RecoupThenRunner,
+}
+
+impl ExitMode {
+    /// Parse `standard` / `recoup_then_runner` (case-insensitive). Anything else falls back to `Standard`.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "recoup_then_runner" | "runner" => Self::RecoupThenRunner,
+            _ => Self::Standard,
         }
     }
 }
@@ -66,198 +126,1110 @@ pub enum SellDecision {
     TrailingStop { current_pnl: f64 },
     /// Sell due to hard stop-loss.
     HardStopLoss { current_pnl: f64 },
-    /// Sell partial to secure profits.
-    SecureProfit { portion: f64, current_pnl: f64 },
+    /// Sell because the pool's liquidity collapsed (likely a rug).
+    Rug { liquidity_drop_pct: f64 },
+    /// Sell before the token graduates, its market cap being near the migration cap.
+    NearMigration { mcap_usd: f64 },
+    /// Sell partial to secure profits at the take-profit tier `level` (net PnL %).
+    SecureProfit { level: f64, portion: f64, current_pnl: f64 },
+    /// Sell the `portion` that returns the cost basis; the rest becomes a runner.
+    Recoup { portion: f64, current_pnl: f64 },
     /// Sell due to Copy Sell or external signal.
     CopySell { reason: String },
     /// Sell due to max hold time exceeded.
     MaxHoldTime { hours_held: u64 },
+    /// Forced full exit (dead-man's switch, manual sell-all).
+    Emergency { reason: String },
+    /// Manual scale-out of `portion` (0.0-1.0) of the position.
+    PartialManual { portion: f64 },
+}
+
+impl SellDecision {
+    /// Share of the position this decision sells, for partial exits.
+    pub fn portion(&self) -> Option<f64> {
+        match self {
+            Self::SecureProfit { portion, .. } | Self::Recoup { portion, .. } | Self::PartialManual { portion } => {
+                Some(*portion)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `position` already took this ladder step: a duplicate signal
+    /// raised while the first sell was still running.
+    pub fn already_taken(&self, position: &Position) -> bool {
+        match self {
+            Self::SecureProfit { level, .. } => position.fired_tp_levels.contains(level),
+            Self::Recoup { .. } => position.in_runner_mode,
+            _ => false,
+        }
+    }
+
+    /// Record the ladder step on `position` once its sell has filled, so a
+    /// skipped or failed sell is raised again on the next check.
+    pub fn record_taken(&self, position: &mut Position) {
+        match self {
+            Self::SecureProfit { level, .. } => position.fired_tp_levels.push(*level),
+            Self::Recoup { .. } => position.in_runner_mode = true,
+            _ => {}
+        }
+    }
+
+    /// Tokens to sell out of `held`: the decision's portion, or all of it.
+    pub fn sell_amount(&self, held: U256) -> U256 {
+        self.portion().map_or(held, |portion| portion_of(held, portion))
+    }
+}
+
+/// `portion` (0.0-1.0) of `amount`, in basis points so e.g. 0.335 keeps its half percent.
+/// Used through `SellDecision::sell_amount` by the sell executor and handler so
+/// the amount sold and the amount deducted from the position always agree.
+pub fn portion_of(amount: U256, portion: f64) -> U256 {
+    let bps = (portion.clamp(0.0, 1.0) * 10_000.0).round() as u64;
+    amount * U256::from(bps) / U256::from(10_000)
+}
+
+/// Share of `buy_price_mon` (MON paid for `held` tokens) attributable to `sold` of them.
+pub fn cost_basis(buy_price_mon: f64, sold: U256, held: U256) -> f64 {
+    if held.is_zero() || sold >= held {
+        return buy_price_mon;
+    }
+    buy_price_mon * (sold.to::<u128>() as f64 / held.to::<u128>() as f64)
+}
+
+/// Share of a position worth `value_mon` to sell to get `cost_mon` back,
+/// rounded up to the basis point `portion_of` sells in. `None` unless the
+/// position is worth more than it cost. `value_mon` is the quote for selling
+/// everything; a slice moves the curve less, so it fetches at least its share.
+pub fn recoup_portion(cost_mon: f64, value_mon: f64) -> Option<f64> {
+    if cost_mon <= 0.0 || value_mon <= cost_mon {
+        return None;
+    }
+    Some(((cost_mon / value_mon) * 10_000.0).ceil() / 10_000.0)
+}
+
+/// Estimated cost of getting into and out of a position.
+#[derive(Debug, Clone, Copy)]
+pub struct RoundTripCost {
+    /// Gas used by the buy and the sell together.
+    pub gas: u64,
+    /// Gas price assumed when the base fee is unknown, in gwei.
+    pub fallback_gas_price_gwei: f64,
+    /// Slippage allowance on the exit, in % of the position's value.
+    pub slippage_pct: f64,
+}
+
+impl RoundTripCost {
+    /// Cost in MON for a position worth `value_mon`, gas priced at `base_fee_gwei` when known.
+    pub fn cost_mon(&self, value_mon: f64, base_fee_gwei: Option<f64>) -> f64 {
+        let gas_price_gwei = base_fee_gwei.unwrap_or(self.fallback_gas_price_gwei);
+        self.gas as f64 * gas_price_gwei / 1e9 + value_mon * self.slippage_pct / 100.0
+    }
 }
 
 /// Position monitor that runs trailing stop-loss checks.
-/// Uses SDK for bonding curve tokens and DEX router for graduated tokens.
-pub struct PositionMonitor<P: Provider + Clone> {
-    provider: P,
-    router: Address,
-    wmon: Address,
-    sdk_executor: Arc<SdkExecutor>,
+pub struct PositionMonitor {
+    prices: Box<dyn PriceSource>,
     config: TrailingStopLossConfig,
+    /// Strategy filters, for the migration cap.
+    filters: MonadFilters,
+    mon_price: MonPriceOracle,
+    health: Arc<HealthStatus>,
 }
 
-impl<P: Provider + Clone + 'static> PositionMonitor<P> {
+impl PositionMonitor {
     pub fn new(
-        provider: P,
-        router: Address,
-        wmon: Address,
-        sdk_executor: Arc<SdkExecutor>,
+        prices: Box<dyn PriceSource>,
         config: TrailingStopLossConfig,
+        filters: MonadFilters,
+        mon_price: MonPriceOracle,
+        health: Arc<HealthStatus>,
     ) -> Self {
         Self {
-            provider,
-            router,
-            wmon,
-            sdk_executor,
+            prices,
             config,
+            filters,
+            mon_price,
+            health,
         }
     }
 
     /// Check a single position for sell conditions.
     pub async fn check_position(&self, position: &mut Position) -> SellDecision {
+        let now = chrono::Utc::now().timestamp() as u64;
+        self.check_position_at(position, now).await
+    }
+
+    /// Check every position in `snapshot`, pricing up to `concurrency` at once.
+    /// Results come back in completion order.
+    async fn check_all(&self, snapshot: Vec<Position>, concurrency: usize) -> Vec<(Position, SellDecision)> {
+        stream::iter(snapshot)
+            .map(|mut position| async move {
+                let decision = self.check_position(&mut position).await;
+                (position, decision)
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await
+    }
+
+    /// `check_position` as of `now` (unix secs), for replaying recorded history.
+    pub async fn check_position_at(&self, position: &mut Position, now: u64) -> SellDecision {
+        // Graduated tokens have no bonding curve price left
+        if position.graduated {
+            self.prices.mark_graduated(position.token);
+        }
+
         // Get current price
-        let current_price = match self.get_token_price_mon(position.token, position.amount).await {
-            Ok(price) => price,
+        let current_price = match self.prices.price_mon(position.token, position.amount).await {
+            Ok(price) => {
+                self.health.record_price_check();
+                price
+            }
             Err(e) => {
                 warn!("Failed to get price for {:?}: {}", position.token, e);
                 return SellDecision::Hold;
             }
         };
 
-        // Update highest price
-        if current_price > position.highest_price {
-            position.highest_price = current_price;
-            debug!(
-                "New high for {} ({}): {} MON",
-                position.name, position.symbol, current_price
-            );
+        if let Some(decision) = self.check_liquidity(position).await {
+            return decision;
         }
 
-        // Calculate P&L
-        let pnl_pct = if position.buy_price_mon > 0.0 {
-            ((current_price - position.buy_price_mon) / position.buy_price_mon) * 100.0
+        if let Some(decision) = self.check_migration(position).await {
+            return decision;
+        }
+
+        let fees_mon = self.round_trip_cost_mon(current_price).await;
+        evaluate_position(position, current_price, fees_mon, now, &self.config)
+    }
+
+    /// Round-trip fees for a position worth `value_mon`, at the live base fee if available.
+    async fn round_trip_cost_mon(&self, value_mon: f64) -> f64 {
+        let cost = &self.config.round_trip_cost;
+        let base_fee_gwei = if cost.gas == 0 {
+            None
         } else {
-            0.0
+            match self.prices.base_fee_gwei().await {
+                Ok(base_fee) => Some(base_fee),
+                Err(e) => {
+                    debug!("No base fee, assuming {} gwei: {}", cost.fallback_gas_price_gwei, e);
+                    None
+                }
+            }
+        };
+        cost.cost_mon(value_mon, base_fee_gwei)
+    }
+
+    /// Rug check: track the pool's peak liquidity and flag a collapse from it.
+    /// Skipped when disabled or when the price source has no reserve data.
+    async fn check_liquidity(&self, position: &mut Position) -> Option<SellDecision> {
+        if self.config.rug_liquidity_drop_pct <= 0.0 {
+            return None;
+        }
+        match self.prices.liquidity_mon(position.token).await {
+            Ok(liquidity) => evaluate_liquidity(position, liquidity, self.config.rug_liquidity_drop_pct),
+            Err(e) => {
+                debug!("No liquidity reading for {:?}: {}", position.token, e);
+                None
+            }
+        }
+    }
+
+    /// Migration exit: sell once the market cap reaches 80% of the migration cap.
+    /// Skipped when disabled, for graduated tokens, and when the price source has no supply data.
+    async fn check_migration(&self, position: &Position) -> Option<SellDecision> {
+        if !self.config.sell_before_migration || position.graduated {
+            return None;
+        }
+        let mcap_mon = match self.prices.market_cap_mon(position.token).await {
+            Ok(mcap_mon) => mcap_mon,
+            Err(e) => {
+                debug!("No market cap for {:?}: {}", position.token, e);
+                return None;
+            }
         };
+        evaluate_migration(position, mcap_mon * self.mon_price.price_usd_or_fallback(), &self.filters)
+    }
+}
+
+/// A migration exit if `mcap_usd` puts the position's token near the migration cap.
+pub fn evaluate_migration(position: &Position, mcap_usd: f64, filters: &MonadFilters) -> Option<SellDecision> {
+    if !filters.is_near_migration(mcap_usd) {
+        return None;
+    }
+    info!(
+        "🎓 {} ({}) near migration: ${:.0}k mcap (cap ${:.0}k) - exiting before graduation",
+        position.name,
+        position.symbol,
+        mcap_usd / 1000.0,
+        filters.migration_mcap_usd / 1000.0
+    );
+    Some(SellDecision::NearMigration { mcap_usd })
+}
+
+/// Record `liquidity_mon` against the position's peak; a rug sell if it sits
+/// `drop_pct` or more below that peak.
+pub fn evaluate_liquidity(position: &mut Position, liquidity_mon: f64, drop_pct: f64) -> Option<SellDecision> {
+    if liquidity_mon > position.peak_liquidity_mon || position.peak_liquidity_mon <= 0.0 {
+        position.peak_liquidity_mon = liquidity_mon;
+        return None;
+    }
+
+    let drop = (position.peak_liquidity_mon - liquidity_mon) / position.peak_liquidity_mon * 100.0;
+    if drop < drop_pct {
+        return None;
+    }
+    warn!(
+        "🪤 Liquidity collapse for {} ({}): {:.1} -> {:.1} MON (-{:.1}%) - likely rug",
+        position.name, position.symbol, position.peak_liquidity_mon, liquidity_mon, drop
+    );
+    Some(SellDecision::Rug { liquidity_drop_pct: drop })
+}
 
+/// Pure sell decision for a position at `current_price` (MON) and time `now` (unix secs).
+/// Precedence: max-hold > hard-stop > take-profit tier (or recoup) > trailing stop.
+/// Updates `highest_price` when a new high is seen. A take-profit tier keeps
+/// firing until its sell fills and `SellDecision::record_taken` marks it in
+/// `fired_tp_levels` (one tier per call).
+///
+/// In `RecoupThenRunner` mode the ladder is replaced by a single recoup sell
+/// at `recoup_multiple`x; once it fills, `in_runner_mode` is set and from then on the
+/// trailing stop allows at least `runner_drop_pct`.
+///
+/// Take-profit tiers and the trailing stop judge the PnL net of `fees_mon`
+/// (the round trip's gas and slippage) and never fire unless that net is
+/// positive; the stops and the hold-time limit use the gross PnL.
+pub fn evaluate_position(
+    position: &mut Position,
+    current_price: f64,
+    fees_mon: f64,
+    now: u64,
+    config: &TrailingStopLossConfig,
+) -> SellDecision {
+    // Update highest price
+    if current_price > position.highest_price {
+        position.highest_price = current_price;
         debug!(
-            "{} ({}) - Price: {} MON, P&L: {:.2}%, High: {} MON",
-            position.name, position.symbol, current_price, pnl_pct, position.highest_price
+            "New high for {} ({}): {} MON",
+            position.name, position.symbol, current_price
         );
+    }
 
-        // Check max hold time
-        let now = chrono::Utc::now().timestamp() as u64;
-        let hours_held = (now - position.buy_time) / 3600;
-        if hours_held >= self.config.max_hold_hours {
-            info!(
-                "⏰ Max hold time exceeded for {} ({}) - {} hours",
-                position.name, position.symbol, hours_held
-            );
-            return SellDecision::MaxHoldTime { hours_held };
-        }
+    // Calculate P&L
+    let pnl_pct = if position.buy_price_mon > 0.0 {
+        ((current_price - position.buy_price_mon) / position.buy_price_mon) * 100.0
+    } else {
+        0.0
+    };
 
-        // Check hard stop-loss (always active)
-        if pnl_pct <= self.config.hard_stop_loss_pct {
-            info!(
-                "🛑 Hard stop-loss triggered for {} ({}) at {:.2}%",
-                position.name, position.symbol, pnl_pct
-            );
-            return SellDecision::HardStopLoss { current_pnl: pnl_pct };
+    let net_pnl_pct = if position.buy_price_mon > 0.0 {
+        ((current_price - fees_mon - position.buy_price_mon) / position.buy_price_mon) * 100.0
+    } else {
+        0.0
+    };
+    let net_positive = net_pnl_pct > 0.0;
+
+    debug!(
+        "{} ({}) - Price: {} MON, P&L: {:.2}% ({:.2}% after fees), High: {} MON",
+        position.name, position.symbol, current_price, pnl_pct, net_pnl_pct, position.highest_price
+    );
+
+    // Check max hold time (unknown buy time never trips it)
+    let hours_held = if position.buy_time > 0 {
+        now.saturating_sub(position.buy_time) / 3600
+    } else {
+        0
+    };
+    if position.buy_time > 0 && hours_held >= config.max_hold_hours {
+        info!(
+            "⏰ Max hold time exceeded for {} ({}) - {} hours",
+            position.name, position.symbol, hours_held
+        );
+        return SellDecision::MaxHoldTime { hours_held };
+    }
+
+    // Check hard stop-loss (always active)
+    if pnl_pct <= config.hard_stop_loss_pct {
+        info!(
+            "🛑 Hard stop-loss triggered for {} ({}) at {:.2}%",
+            position.name, position.symbol, pnl_pct
+        );
+        return SellDecision::HardStopLoss { current_pnl: pnl_pct };
+    }
+
+    // Recoup the cost basis once, then let the remainder run
+    let recoup = if config.exit_mode == ExitMode::RecoupThenRunner
+        && !position.in_runner_mode
+        && net_positive
+        && current_price >= position.buy_price_mon * config.recoup_multiple
+    {
+        recoup_portion(position.buy_price_mon, current_price)
+    } else {
+        None
+    };
+    if let Some(portion) = recoup {
+        info!(
+            "🏃 Recouping {} ({}) at {:.2}x - selling {:.2}% to get {:.4} MON back, rest rides",
+            position.name,
+            position.symbol,
+            current_price / position.buy_price_mon,
+            portion * 100.0,
+            position.buy_price_mon
+        );
+        return SellDecision::Recoup {
+            portion,
+            current_pnl: pnl_pct,
+        };
+    }
+
+    // Check take-profit ladder (partial sell, lowest unfired tier first)
+    let next_tier = config
+        .tp_ladder
+        .iter()
+        .filter(|_| config.exit_mode == ExitMode::Standard)
+        .find(|(level, _)| net_positive && net_pnl_pct >= *level && !position.fired_tp_levels.contains(level));
+    if let Some(&(level, portion)) = next_tier {
+        info!(
+            "💰 Take-profit tier +{}% triggered for {} ({}) at {:.2}% after fees - selling {:.0}%",
+            level, position.name, position.symbol, net_pnl_pct, portion * 100.0
+        );
+        return SellDecision::SecureProfit {
+            level,
+            portion,
+            current_pnl: pnl_pct,
+        };
+    }
+
+    // Check trailing stop (only if in profit above minimum, after fees)
+    if net_positive && net_pnl_pct >= config.min_profit_pct && position.highest_price > 0.0 {
+        let drop_from_high = ((position.highest_price - current_price) / position.highest_price) * 100.0;
+        let age_secs = (position.buy_time > 0).then(|| now.saturating_sub(position.buy_time));
+        let mut drop_pct = config.effective_drop_pct(age_secs);
+        if position.in_runner_mode {
+            drop_pct = drop_pct.max(config.runner_drop_pct);
         }
 
-        // Check secure profit (partial sell)
-        if pnl_pct >= self.config.secure_profit_pct {
+        if drop_from_high >= drop_pct {
             info!(
-                "💰 Secure profit triggered for {} ({}) at {:.2}%",
-                position.name, position.symbol, pnl_pct
+                "📉 Trailing stop triggered for {} ({}){} - dropped {:.2}% from high (limit {:.1}%)",
+                position.name,
+                position.symbol,
+                if position.in_runner_mode { " runner" } else { "" },
+                drop_from_high,
+                drop_pct
             );
-            return SellDecision::SecureProfit {
-                portion: self.config.secure_sell_portion,
-                current_pnl: pnl_pct,
-            };
-        }
-
-        // Check trailing stop (only if in profit above minimum)
-        if pnl_pct >= self.config.min_profit_pct && position.highest_price > 0.0 {
-            let drop_from_high = ((position.highest_price - current_price) / position.highest_price) * 100.0;
-            
-            if drop_from_high >= self.config.drop_pct {
-                info!(
-                    "📉 Trailing stop triggered for {} ({}) - dropped {:.2}% from high",
-                    position.name, position.symbol, drop_from_high
-                );
-                return SellDecision::TrailingStop { current_pnl: pnl_pct };
-            }
+            return SellDecision::TrailingStop { current_pnl: pnl_pct };
         }
-
-        SellDecision::Hold
     }
 
-    /// Get token price in MON.
-    /// Uses SDK for bonding curve tokens, falls back to DEX router for graduated tokens.
-    async fn get_token_price_mon(&self, token: Address, amount: U256) -> Result<f64, String> {
-        // First try SDK (for bonding curve tokens)
-        match self.sdk_executor.get_token_price_mon(token, amount).await {
-            Ok(price) if price > 0.0 => {
-                debug!("📊 SDK price for {:?}: {} MON", token, price);
-                return Ok(price);
-            }
-            Ok(_) => {
-                debug!("SDK returned 0 price for {:?}, trying DEX...", token);
-            }
-            Err(e) => {
-                debug!("SDK price failed for {:?}: {}, trying DEX...", token, e);
-            }
-        }
+    SellDecision::Hold
+}
 
-        // Fallback to DEX router (for graduated tokens with liquidity pools)
-        let router = IRouter::new(self.router, &self.provider);
-        let path = vec![token, self.wmon];
+/// Write the monitor state priced off the lock back into the tracker and
+/// return the sell signals to send.
+///
+/// A result is dropped when its position was sold, resized or marked stuck
+/// while it was being priced; the next tick sees the new state.
+fn apply_checks(
+    tracker: &mut PositionTracker,
+    checked: Vec<(Position, SellDecision)>,
+) -> Vec<(Address, SellDecision)> {
+    let mut signals = Vec::new();
+    for (checked, decision) in checked {
+        let Some(position) = tracker.get_mut(&checked.token) else {
+            continue;
+        };
+        if position.amount != checked.amount || position.stuck {
+            continue;
+        }
 
-        let amounts = router
-            .getAmountsOut(amount, path)
-            .call()
-            .await
-            .map_err(|e| format!("Both SDK and DEX failed: {}", e))?;
+        position.highest_price = checked.highest_price;
+        position.peak_liquidity_mon = checked.peak_liquidity_mon;
 
-        // Convert wei to MON
-        let mon_wei = amounts[1];
-        let mon = mon_wei.to::<u128>() as f64 / 1e18;
-        
-        debug!("📊 DEX price for {:?}: {} MON", token, mon);
-        Ok(mon)
+        if !matches!(decision, SellDecision::Hold) {
+            info!(
+                "🔔 Sell signal for {} ({}): {:?}",
+                position.name, position.symbol, decision
+            );
+            signals.push((checked.token, decision));
+        }
     }
+    signals
 }
 
 /// Spawn position monitor background task.
-pub fn spawn_monitor<P: Provider + Clone + Send + Sync + 'static>(
-    provider: P,
-    router: Address,
-    wmon: Address,
-    sdk_executor: Arc<SdkExecutor>,
-    config: TrailingStopLossConfig,
+pub fn spawn_monitor(
+    monitor: PositionMonitor,
     positions: Arc<Mutex<PositionTracker>>,
     sell_tx: tokio::sync::mpsc::Sender<(Address, SellDecision)>,
+    graduated_rx: Arc<Mutex<tokio::sync::mpsc::Receiver<Address>>>,
 ) -> tokio::task::JoinHandle<()> {
-    let interval_sec = config.check_interval_sec;
-    let monitor = PositionMonitor::new(provider, router, wmon, sdk_executor, config);
+    let interval_sec = monitor.config.check_interval_sec;
+    let concurrency = monitor.config.max_concurrent_checks;
     
     tokio::spawn(async move {
-        info!("📊 Position monitor started (checking every {}s, using SDK for pricing)", interval_sec);
+        info!(
+            "📊 Position monitor started (checking every {}s, {} at a time)",
+            interval_sec, concurrency
+        );
         
         loop {
             tokio::time::sleep(tokio::time::Duration::from_secs(interval_sec)).await;
-            
-            let mut positions_guard = positions.lock().await;
-            let tokens: Vec<Address> = positions_guard.all().iter().map(|p| p.token).collect();
-            
-            for token in tokens {
-                if let Some(position) = positions_guard.get_mut(&token) {
-                    let decision = monitor.check_position(position).await;
-                    
-                    match &decision {
-                        SellDecision::Hold => {}
-                        _ => {
-                            info!(
-                                "🔔 Sell signal for {} ({}): {:?}",
-                                position.name, position.symbol, decision
-                            );
-                            let _ = sell_tx.send((token, decision.clone())).await;
-                        }
-                    }
+            monitor.health.beat(MONITOR_TASK);
+
+            // Snapshot under the lock, then price without it so buys and sells aren't starved
+            let snapshot: Vec<Position> = {
+                let mut positions_guard = positions.lock().await;
+
+                // Tokens that graduated since the last check switch to DEX pricing
+                // (the receiver is shared so a restarted monitor picks up where this one stopped)
+                let mut graduated_rx = graduated_rx.lock().await;
+                while let Ok(token) = graduated_rx.try_recv() {
+                    positions_guard.mark_graduated(&token);
                 }
+
+                // Provisional positions wait for their buy to confirm before being managed;
+                // stuck ones wait for manual intervention
+                positions_guard
+                    .all()
+                    .into_iter()
+                    .filter(|p| !p.provisional && !p.stuck)
+                    .cloned()
+                    .collect()
+            };
+
+            let checked = monitor.check_all(snapshot, concurrency).await;
+
+            let signals = {
+                let mut positions_guard = positions.lock().await;
+                let signals = apply_checks(&mut positions_guard, checked);
+                // Save updated positions (highest_price may have changed)
+                let _ = positions_guard.save();
+                signals
+            };
+
+            for signal in signals {
+                let _ = sell_tx.send(signal).await;
             }
-            
-            // Save updated positions (highest_price may have changed)
-            let _ = positions_guard.save();
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::PriceFuture;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn config() -> TrailingStopLossConfig {
+        TrailingStopLossConfig {
+            drop_pct: 20.0,
+            drop_decay: Vec::new(),
+            min_profit_pct: 10.0,
+            round_trip_cost: RoundTripCost {
+                gas: 0,
+                fallback_gas_price_gwei: 0.0,
+                slippage_pct: 0.0,
+            },
+            hard_stop_loss_pct: -30.0,
+            rug_liquidity_drop_pct: 50.0,
+            sell_before_migration: false,
+            tp_ladder: vec![(50.0, 0.25), (100.0, 0.5)],
+            exit_mode: ExitMode::Standard,
+            recoup_multiple: 2.0,
+            runner_drop_pct: 40.0,
+            max_hold_hours: 24,
+            check_interval_sec: 5,
+            max_concurrent_checks: 4,
+        }
+    }
+
+    /// A position that cost 1 MON, bought an hour before `NOW`.
+    fn position() -> Position {
+        Position {
+            token: Address::repeat_byte(1),
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            amount: U256::from(1_000u64),
+            buy_price_mon: 1.0,
+            buy_time: NOW - 3600,
+            highest_price: 1.0,
+            tx_hash: String::new(),
+            provisional: false,
+            fired_tp_levels: Vec::new(),
+            sell_failures: 0,
+            stuck: false,
+            graduated: false,
+            peak_liquidity_mon: 0.0,
+            in_runner_mode: false,
+            decimals: 18,
+        }
+    }
+
+    #[test]
+    fn max_hold_beats_every_other_exit() {
+        let mut pos = position();
+        pos.buy_time = NOW - 24 * 3600;
+        let decision = evaluate_position(&mut pos, 0.1, 0.0, NOW, &config());
+        assert!(matches!(decision, SellDecision::MaxHoldTime { hours_held: 24 }));
+    }
+
+    #[test]
+    fn unknown_buy_time_never_trips_max_hold() {
+        let mut pos = position();
+        pos.buy_time = 0;
+        assert!(matches!(evaluate_position(&mut pos, 1.0, 0.0, NOW, &config()), SellDecision::Hold));
+    }
+
+    #[test]
+    fn hard_stop_beats_take_profit_and_trailing() {
+        let mut pos = position();
+        pos.highest_price = 3.0;
+        let decision = evaluate_position(&mut pos, 0.7, 0.0, NOW, &config());
+        assert!(matches!(decision, SellDecision::HardStopLoss { current_pnl } if (current_pnl + 30.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn take_profit_beats_trailing_and_fires_each_tier_once() {
+        let config = config();
+        let mut pos = position();
+        // Far enough below the high to trail, but the +50% tier is unfired
+        pos.highest_price = 3.0;
+        let decision = evaluate_position(&mut pos, 1.6, 0.0, NOW, &config);
+        assert!(matches!(decision, SellDecision::SecureProfit { level, portion, .. } if level == 50.0 && portion == 0.25));
+        assert!(pos.fired_tp_levels.is_empty());
+
+        decision.record_taken(&mut pos);
+        assert_eq!(pos.fired_tp_levels, vec![50.0]);
+        let decision = evaluate_position(&mut pos, 1.6, 0.0, NOW, &config);
+        assert!(matches!(decision, SellDecision::TrailingStop { .. }));
+    }
+
+    #[test]
+    fn one_tier_fires_per_check_lowest_first() {
+        let config = config();
+        let mut pos = position();
+        let first = evaluate_position(&mut pos, 2.5, 0.0, NOW, &config);
+        first.record_taken(&mut pos);
+        let second = evaluate_position(&mut pos, 2.5, 0.0, NOW, &config);
+        second.record_taken(&mut pos);
+        assert_eq!(first.portion(), Some(0.25));
+        assert_eq!(second.portion(), Some(0.5));
+        assert_eq!(pos.fired_tp_levels, vec![50.0, 100.0]);
+        assert!(matches!(evaluate_position(&mut pos, 2.5, 0.0, NOW, &config), SellDecision::Hold));
+    }
+
+    #[test]
+    fn fees_hold_back_take_profit_and_trailing() {
+        let config = config();
+        let mut pos = position();
+        pos.highest_price = 2.0;
+        // +55% gross, but only +5% after 0.5 MON of fees: under the tier and the trailing minimum
+        assert!(matches!(evaluate_position(&mut pos, 1.55, 0.5, NOW, &config), SellDecision::Hold));
+        assert!(pos.fired_tp_levels.is_empty());
+    }
+
+    #[test]
+    fn trailing_needs_min_profit_and_the_drop() {
+        let mut config = config();
+        config.tp_ladder.clear();
+        let mut pos = position();
+
+        // New high, no drop yet
+        assert!(matches!(evaluate_position(&mut pos, 2.0, 0.0, NOW, &config), SellDecision::Hold));
+        assert_eq!(pos.highest_price, 2.0);
+        // -15% from the high is under the 20% drop
+        assert!(matches!(evaluate_position(&mut pos, 1.7, 0.0, NOW, &config), SellDecision::Hold));
+        // -25% from the high with +50% profit
+        assert!(matches!(evaluate_position(&mut pos, 1.5, 0.0, NOW, &config), SellDecision::TrailingStop { .. }));
+        // -47.5% from the high but only +5%: under the profit minimum
+        assert!(matches!(evaluate_position(&mut pos, 1.05, 0.0, NOW, &config), SellDecision::Hold));
+    }
+
+    #[test]
+    fn zero_cost_position_only_exits_on_time() {
+        let config = config();
+        let mut pos = position();
+        pos.buy_price_mon = 0.0;
+        pos.highest_price = 0.0;
+
+        assert!(matches!(evaluate_position(&mut pos, 5.0, 0.0, NOW, &config), SellDecision::Hold));
+        assert!(matches!(evaluate_position(&mut pos, 0.0, 0.0, NOW, &config), SellDecision::Hold));
+        assert!(pos.fired_tp_levels.is_empty());
+        assert_eq!(recoup_portion(0.0, 5.0), None);
+    }
+
+    #[test]
+    fn zero_amounts_sell_and_cost_nothing_extra() {
+        assert_eq!(portion_of(U256::ZERO, 0.5), U256::ZERO);
+        assert_eq!(SellDecision::Hold.sell_amount(U256::ZERO), U256::ZERO);
+        // Nothing held: the whole cost basis goes with the sale
+        assert_eq!(cost_basis(2.0, U256::ZERO, U256::ZERO), 2.0);
+        assert_eq!(cost_basis(2.0, U256::ZERO, U256::from(100)), 0.0);
+        assert_eq!(cost_basis(2.0, U256::from(25), U256::from(100)), 0.5);
+    }
+
+    #[test]
+    fn portion_of_clamps_and_keeps_basis_points() {
+        let amount = U256::from(1_000_000u64);
+        assert_eq!(portion_of(amount, 0.335), U256::from(335_000u64));
+        assert_eq!(portion_of(amount, 1.5), amount);
+        assert_eq!(portion_of(amount, -0.2), U256::ZERO);
+    }
+
+    #[test]
+    fn partial_decisions_sell_their_portion_and_the_rest_sell_everything() {
+        let held = U256::from(10u64).pow(U256::from(30)) + U256::from(7u64);
+        let secure = SellDecision::SecureProfit { level: 50.0, portion: 0.335, current_pnl: 60.0 };
+        let sold = secure.sell_amount(held);
+        assert_eq!(sold, held * U256::from(3_350u64) / U256::from(10_000u64));
+        assert!(held - sold > U256::ZERO);
+
+        assert_eq!(SellDecision::PartialManual { portion: 1.0 }.sell_amount(held), held);
+        assert_eq!(SellDecision::TrailingStop { current_pnl: 20.0 }.sell_amount(held), held);
+        assert_eq!(SellDecision::Rug { liquidity_drop_pct: 90.0 }.portion(), None);
+    }
+
+    #[test]
+    fn recoup_sells_the_cost_basis_then_runs_on_the_wider_stop() {
+        let mut config = config();
+        config.exit_mode = ExitMode::RecoupThenRunner;
+        let mut pos = position();
+
+        // Under the recoup multiple, and the ladder is off in this mode
+        assert!(matches!(evaluate_position(&mut pos, 1.9, 0.0, NOW, &config), SellDecision::Hold));
+        assert!(!pos.in_runner_mode);
+
+        let decision = evaluate_position(&mut pos, 4.0, 0.0, NOW, &config);
+        assert!(matches!(decision, SellDecision::Recoup { portion, .. } if portion == 0.25));
+        assert!(!pos.in_runner_mode);
+        decision.record_taken(&mut pos);
+        assert!(pos.in_runner_mode);
+        assert!(pos.fired_tp_levels.is_empty());
+
+        // Recouped once; a 30% drop is inside the 40% runner stop
+        assert!(matches!(evaluate_position(&mut pos, 2.8, 0.0, NOW, &config), SellDecision::Hold));
+        assert!(matches!(evaluate_position(&mut pos, 2.3, 0.0, NOW, &config), SellDecision::TrailingStop { .. }));
+    }
+
+    #[test]
+    fn runner_stop_never_tightens_the_base_drop() {
+        let mut config = config();
+        config.exit_mode = ExitMode::RecoupThenRunner;
+        config.runner_drop_pct = 10.0;
+        let mut pos = position();
+        pos.in_runner_mode = true;
+        pos.highest_price = 4.0;
+
+        // 15% off the high: the 20% base drop still applies
+        assert!(matches!(evaluate_position(&mut pos, 3.4, 0.0, NOW, &config), SellDecision::Hold));
+    }
+
+    #[test]
+    fn recoup_portion_rounds_up_to_a_basis_point() {
+        assert_eq!(recoup_portion(1.0, 3.0), Some(0.3334));
+        assert_eq!(recoup_portion(1.0, 1.0), None);
+    }
+
+    #[test]
+    fn drop_decay_interpolates_and_clamps() {
+        let mut config = config();
+        config.drop_decay = vec![(0.0, 30.0), (60.0, 10.0)];
+        assert_eq!(config.effective_drop_pct(None), 20.0);
+        assert_eq!(config.effective_drop_pct(Some(0)), 30.0);
+        assert!((config.effective_drop_pct(Some(30 * 60)) - 20.0).abs() < 1e-9);
+        assert_eq!(config.effective_drop_pct(Some(5 * 3600)), 10.0);
+    }
+
+    #[test]
+    fn drop_decay_holds_flat_segments_and_the_first_point_before_it_starts() {
+        let mut config = config();
+        config.drop_decay = vec![(0.0, 30.0), (10.0, 30.0), (60.0, 10.0)];
+        assert_eq!(config.effective_drop_pct(Some(5 * 60)), 30.0);
+        assert!((config.effective_drop_pct(Some(35 * 60)) - 20.0).abs() < 1e-9);
+
+        config.drop_decay = vec![(10.0, 25.0)];
+        assert_eq!(config.effective_drop_pct(Some(0)), 25.0);
+        assert_eq!(config.effective_drop_pct(Some(3600)), 25.0);
+    }
+
+    #[test]
+    fn aged_position_trails_on_the_decayed_drop() {
+        let mut config = config();
+        config.tp_ladder.clear();
+        // 15% off a +100% high: inside the constant 20% drop
+        let mut pos = position();
+        pos.highest_price = 2.0;
+        assert!(matches!(evaluate_position(&mut pos, 1.7, 0.0, NOW, &config), SellDecision::Hold));
+
+        // An hour in, the curve has narrowed to 10%
+        config.drop_decay = vec![(0.0, 30.0), (10.0, 30.0), (60.0, 10.0)];
+        let decision = evaluate_position(&mut pos, 1.7, 0.0, NOW, &config);
+        assert!(matches!(decision, SellDecision::TrailingStop { .. }));
+
+        // A five-minute-old position still gets 30%
+        let mut young = position();
+        young.buy_time = NOW - 300;
+        young.highest_price = 2.0;
+        assert!(matches!(evaluate_position(&mut young, 1.5, 0.0, NOW, &config), SellDecision::Hold));
+    }
+
+    #[test]
+    fn liquidity_collapse_from_peak_is_a_rug() {
+        let mut pos = position();
+        assert!(evaluate_liquidity(&mut pos, 100.0, 50.0).is_none());
+        assert_eq!(pos.peak_liquidity_mon, 100.0);
+        assert!(evaluate_liquidity(&mut pos, 60.0, 50.0).is_none());
+        let decision = evaluate_liquidity(&mut pos, 40.0, 50.0);
+        assert!(matches!(decision, Some(SellDecision::Rug { liquidity_drop_pct }) if (liquidity_drop_pct - 60.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn exit_mode_parse_falls_back_to_standard() {
+        assert_eq!(ExitMode::parse(" Recoup_Then_Runner "), ExitMode::RecoupThenRunner);
+        assert_eq!(ExitMode::parse("runner"), ExitMode::RecoupThenRunner);
+        assert_eq!(ExitMode::parse("ladder"), ExitMode::Standard);
+    }
+
+    /// Prices every token at `price` and records which were marked graduated.
+    struct RecordingPrices {
+        price: f64,
+        graduated: Arc<std::sync::Mutex<Vec<Address>>>,
+    }
+
+    impl PriceSource for RecordingPrices {
+        fn price_mon(&self, _token: Address, _amount: U256) -> PriceFuture<'_> {
+            let price = self.price;
+            Box::pin(async move { Ok(price) })
+        }
+
+        fn mark_graduated(&self, token: Address) {
+            self.graduated.lock().unwrap().push(token);
+        }
+    }
+
+    #[tokio::test]
+    async fn graduated_positions_are_priced_off_the_curve() {
+        let graduated = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let monitor = PositionMonitor::new(
+            Box::new(RecordingPrices { price: 1.0, graduated: Arc::clone(&graduated) }),
+            config(),
+            MonadFilters::default(),
+            MonPriceOracle::new(None),
+            Arc::new(HealthStatus::new()),
+        );
+
+        let mut pos = position();
+        monitor.check_position_at(&mut pos, NOW).await;
+        assert!(graduated.lock().unwrap().is_empty());
+
+        pos.graduated = true;
+        monitor.check_position_at(&mut pos, NOW).await;
+        assert_eq!(*graduated.lock().unwrap(), vec![pos.token]);
+    }
+
+    /// A pool whose MON reserve the test moves between checks.
+    struct Pool(Arc<std::sync::Mutex<f64>>);
+
+    impl PriceSource for Pool {
+        fn price_mon(&self, _token: Address, _amount: U256) -> PriceFuture<'_> {
+            Box::pin(async move { Ok(1.0) })
+        }
+
+        fn liquidity_mon(&self, _token: Address) -> PriceFuture<'_> {
+            let liquidity = *self.0.lock().unwrap();
+            Box::pin(async move { Ok(liquidity) })
+        }
+    }
+
+    fn pool_monitor(rug_liquidity_drop_pct: f64) -> (PositionMonitor, Arc<std::sync::Mutex<f64>>) {
+        let liquidity = Arc::new(std::sync::Mutex::new(100.0));
+        let mut config = config();
+        config.rug_liquidity_drop_pct = rug_liquidity_drop_pct;
+        let monitor = PositionMonitor::new(
+            Box::new(Pool(Arc::clone(&liquidity))),
+            config,
+            MonadFilters::default(),
+            MonPriceOracle::new(None),
+            Arc::new(HealthStatus::new()),
+        );
+        (monitor, liquidity)
+    }
+
+    #[tokio::test]
+    async fn monitor_sells_when_pool_liquidity_collapses() {
+        let (monitor, liquidity) = pool_monitor(50.0);
+        let mut pos = position();
+        assert!(matches!(monitor.check_position_at(&mut pos, NOW).await, SellDecision::Hold));
+        assert_eq!(pos.peak_liquidity_mon, 100.0);
+
+        *liquidity.lock().unwrap() = 40.0;
+        let decision = monitor.check_position_at(&mut pos, NOW).await;
+        assert!(matches!(decision, SellDecision::Rug { liquidity_drop_pct } if (liquidity_drop_pct - 60.0).abs() < 1e-9));
+    }
+
+    #[tokio::test]
+    async fn rug_check_is_off_at_zero() {
+        let (monitor, liquidity) = pool_monitor(0.0);
+        let mut pos = position();
+        monitor.check_position_at(&mut pos, NOW).await;
+        *liquidity.lock().unwrap() = 1.0;
+        assert!(matches!(monitor.check_position_at(&mut pos, NOW).await, SellDecision::Hold));
+        assert_eq!(pos.peak_liquidity_mon, 0.0);
+    }
+
+    /// Prices at 1 MON with a fixed market cap in MON, or no supply data when `None`.
+    struct Capped(Option<f64>);
+
+    impl PriceSource for Capped {
+        fn price_mon(&self, _token: Address, _amount: U256) -> PriceFuture<'_> {
+            Box::pin(async move { Ok(1.0) })
+        }
+
+        fn market_cap_mon(&self, _token: Address) -> PriceFuture<'_> {
+            let mcap = self.0;
+            Box::pin(async move { mcap.ok_or_else(|| "no supply".to_string()) })
+        }
+    }
+
+    /// MON at $2, so the default 1.3M cap is near from 520k MON.
+    fn migration_monitor(sell_before_migration: bool, mcap_mon: Option<f64>) -> PositionMonitor {
+        let mut config = config();
+        config.sell_before_migration = sell_before_migration;
+        PositionMonitor::new(
+            Box::new(Capped(mcap_mon)),
+            config,
+            MonadFilters::default(),
+            MonPriceOracle::new(Some(2.0)),
+            Arc::new(HealthStatus::new()),
+        )
+    }
+
+    #[tokio::test]
+    async fn monitor_exits_near_the_migration_cap_in_usd() {
+        let mut pos = position();
+        let decision = migration_monitor(true, Some(550_000.0)).check_position_at(&mut pos, NOW).await;
+        assert!(matches!(decision, SellDecision::NearMigration { mcap_usd } if mcap_usd == 1_100_000.0));
+
+        let decision = migration_monitor(true, Some(500_000.0)).check_position_at(&mut pos, NOW).await;
+        assert!(matches!(decision, SellDecision::Hold));
+    }
+
+    #[tokio::test]
+    async fn migration_exit_needs_the_flag_a_curve_token_and_supply_data() {
+        let mut pos = position();
+        let decision = migration_monitor(false, Some(1_000_000.0)).check_position_at(&mut pos, NOW).await;
+        assert!(matches!(decision, SellDecision::Hold));
+        let decision = migration_monitor(true, None).check_position_at(&mut pos, NOW).await;
+        assert!(matches!(decision, SellDecision::Hold));
+
+        pos.graduated = true;
+        let decision = migration_monitor(true, Some(1_000_000.0)).check_position_at(&mut pos, NOW).await;
+        assert!(matches!(decision, SellDecision::Hold));
+    }
+
+    /// Prices everything at 1 MON after a short wait, counting the checks in flight.
+    #[derive(Default)]
+    struct Slow {
+        in_flight: std::sync::atomic::AtomicUsize,
+        most_in_flight: std::sync::atomic::AtomicUsize,
+    }
+
+    impl PriceSource for Arc<Slow> {
+        fn price_mon(&self, _token: Address, _amount: U256) -> PriceFuture<'_> {
+            use std::sync::atomic::Ordering;
+            Box::pin(async move {
+                let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.most_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(1.0)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn check_all_prices_in_parallel_up_to_the_limit() {
+        let prices = Arc::new(Slow::default());
+        let monitor = PositionMonitor::new(
+            Box::new(Arc::clone(&prices)),
+            config(),
+            MonadFilters::default(),
+            MonPriceOracle::new(None),
+            Arc::new(HealthStatus::new()),
+        );
+        let snapshot: Vec<Position> = (1..=10u8)
+            .map(|byte| Position { token: Address::repeat_byte(byte), buy_time: 0, ..position() })
+            .collect();
+
+        let checked = monitor.check_all(snapshot, 3).await;
+        assert_eq!(checked.len(), 10);
+        assert!(checked.iter().all(|(_, decision)| matches!(decision, SellDecision::Hold)));
+        assert_eq!(prices.most_in_flight.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn apply_checks_writes_back_state_and_returns_signals() {
+        let mut tracker = PositionTracker::in_memory();
+        let held = position();
+        tracker.add(held.clone());
+
+        let mut checked = held.clone();
+        checked.highest_price = 2.0;
+        checked.peak_liquidity_mon = 80.0;
+        let signals = apply_checks(
+            &mut tracker,
+            vec![(checked, SellDecision::SecureProfit { level: 50.0, portion: 0.25, current_pnl: 100.0 })],
+        );
+
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].0, held.token);
+        let stored = tracker.get(&held.token).unwrap();
+        assert_eq!(stored.highest_price, 2.0);
+        // The tier is recorded by the sell handler once the sell fills
+        assert!(stored.fired_tp_levels.is_empty());
+        assert_eq!(stored.peak_liquidity_mon, 80.0);
+        assert!(!stored.in_runner_mode);
+
+    }
+
+    #[test]
+    fn unsold_recoup_stays_out_of_runner_mode_and_fires_again() {
+        let mut config = config();
+        config.exit_mode = ExitMode::RecoupThenRunner;
+        let mut tracker = PositionTracker::in_memory();
+        tracker.add(position());
+
+        let mut pos = tracker.get(&position().token).unwrap().clone();
+        let decision = evaluate_position(&mut pos, 4.0, 0.0, NOW, &config);
+        let signals = apply_checks(&mut tracker, vec![(pos, decision)]);
+        assert!(matches!(signals[..], [(_, SellDecision::Recoup { .. })]));
+
+        // Skipped on cooldown or failed on every venue: no runner stop without the recoup
+        let stored = tracker.get(&position().token).unwrap();
+        assert!(!stored.in_runner_mode);
+        assert!(!signals[0].1.already_taken(stored));
+        let mut pos = stored.clone();
+        assert!(matches!(evaluate_position(&mut pos, 4.0, 0.0, NOW + 30, &config), SellDecision::Recoup { .. }));
+    }
+
+    #[test]
+    fn tier_skipped_by_the_sell_cooldown_fires_again() {
+        let config = config();
+        let mut tracker = PositionTracker::in_memory();
+        tracker.add(position());
+
+        let mut pos = tracker.get(&position().token).unwrap().clone();
+        let decision = evaluate_position(&mut pos, 1.6, 0.0, NOW, &config);
+        let signals = apply_checks(&mut tracker, vec![(pos, decision)]);
+        assert!(matches!(signals[..], [(_, SellDecision::SecureProfit { level, .. })] if level == 50.0));
+
+        // The handler skips it on cooldown, so nothing records the tier: the next check raises it again
+        let stored = tracker.get(&position().token).unwrap();
+        assert!(!signals[0].1.already_taken(stored));
+        assert!(stored.fired_tp_levels.is_empty());
+        let mut pos = stored.clone();
+        let decision = evaluate_position(&mut pos, 1.6, 0.0, NOW + 30, &config);
+        assert!(matches!(decision, SellDecision::SecureProfit { level, .. } if level == 50.0));
+    }
+
+    #[test]
+    fn apply_checks_drops_results_for_positions_changed_meanwhile() {
+        let mut tracker = PositionTracker::in_memory();
+        let held = position();
+        tracker.add(held.clone());
+        let stale = Position { highest_price: 5.0, ..held.clone() };
+
+        // Partially sold while being priced
+        tracker.get_mut(&held.token).unwrap().amount = U256::from(500u64);
+        assert!(apply_checks(&mut tracker, vec![(stale.clone(), SellDecision::HardStopLoss { current_pnl: -40.0 })]).is_empty());
+        assert_eq!(tracker.get(&held.token).unwrap().highest_price, 1.0);
+
+        // Marked stuck
+        let position = tracker.get_mut(&held.token).unwrap();
+        position.amount = held.amount;
+        position.stuck = true;
+        assert!(apply_checks(&mut tracker, vec![(stale.clone(), SellDecision::HardStopLoss { current_pnl: -40.0 })]).is_empty());
+
+        // Sold outright
+        tracker.remove(&held.token);
+        assert!(apply_checks(&mut tracker, vec![(stale, SellDecision::HardStopLoss { current_pnl: -40.0 })]).is_empty());
+    }
+
+    #[test]
+    fn round_trip_cost_prices_gas_at_the_base_fee_when_known() {
+        let cost = RoundTripCost { gas: 1_000_000, fallback_gas_price_gwei: 100.0, slippage_pct: 2.0 };
+        // 0.1 MON of gas plus 2% of 5 MON
+        assert!((cost.cost_mon(5.0, None) - 0.2).abs() < 1e-12);
+        assert!((cost.cost_mon(5.0, Some(50.0)) - 0.15).abs() < 1e-12);
+    }
+
+    #[test]
+    fn stops_ignore_fees() {
+        let mut pos = position();
+        // -25% gross stays above the -30% stop even though fees take it past
+        assert!(matches!(evaluate_position(&mut pos, 0.75, 0.2, NOW, &config()), SellDecision::Hold));
+    }
+
+    /// Prices at 1.65 MON, with a base fee when `Some`.
+    struct BaseFee(Option<f64>);
+
+    impl PriceSource for BaseFee {
+        fn price_mon(&self, _token: Address, _amount: U256) -> PriceFuture<'_> {
+            Box::pin(async move { Ok(1.65) })
+        }
+
+        fn base_fee_gwei(&self) -> PriceFuture<'_> {
+            let base_fee = self.0;
+            Box::pin(async move { base_fee.ok_or_else(|| "no block".to_string()) })
+        }
+    }
+
+    fn fee_monitor(base_fee_gwei: Option<f64>) -> PositionMonitor {
+        let mut config = config();
+        config.round_trip_cost = RoundTripCost { gas: 1_000_000, fallback_gas_price_gwei: 100.0, slippage_pct: 0.0 };
+        PositionMonitor::new(
+            Box::new(BaseFee(base_fee_gwei)),
+            config,
+            MonadFilters::default(),
+            MonPriceOracle::new(None),
+            Arc::new(HealthStatus::new()),
+        )
+    }
+
+    #[tokio::test]
+    async fn monitor_charges_gas_at_the_live_base_fee() {
+        // +65% gross: 0.5 MON of gas at 500 gwei leaves +15%, under the +50% tier
+        let mut pos = position();
+        let decision = fee_monitor(Some(500.0)).check_position_at(&mut pos, NOW).await;
+        assert!(matches!(decision, SellDecision::Hold));
+
+        // No base fee: 0.1 MON at the 100 gwei fallback leaves +55%
+        let decision = fee_monitor(None).check_position_at(&mut pos, NOW).await;
+        assert!(matches!(decision, SellDecision::SecureProfit { portion, .. } if portion == 0.25));
+    }
+
+    #[test]
+    fn recoup_waits_for_a_positive_net() {
+        let mut config = config();
+        config.exit_mode = ExitMode::RecoupThenRunner;
+        let mut pos = position();
+        // 2x gross, but 1.5 MON of fees leave it under water
+        assert!(matches!(evaluate_position(&mut pos, 2.0, 1.5, NOW, &config), SellDecision::Hold));
+        assert!(!pos.in_runner_mode);
+    }
+
+    #[test]
+    fn runner_keeps_the_hard_stop() {
+        let mut config = config();
+        config.exit_mode = ExitMode::RecoupThenRunner;
+        let mut pos = position();
+        pos.in_runner_mode = true;
+        pos.highest_price = 4.0;
+        let decision = evaluate_position(&mut pos, 0.6, 0.0, NOW, &config);
+        assert!(matches!(decision, SellDecision::HardStopLoss { .. }));
+        assert_eq!(decision.portion(), None);
+    }
+}
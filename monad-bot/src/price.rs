@@ -0,0 +1,101 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! MON/USD price oracle and USD formatting.
+
+use std::sync::{Arc, RwLock};
+
+/// MON price assumed for filters while no price is known.
+pub const FALLBACK_MON_PRICE_USD: f64 = 0.50;
+
+/// Shared, updatable MON price in USD.
+/// `None` means no trustworthy price is known; USD values are then omitted.
+#[derive(Debug, Clone, Default)]
+pub struct MonPriceOracle {
+    price_usd: Arc<RwLock<Option<f64>>>,
+}
+
+impl MonPriceOracle {
+    pub fn new(initial_price_usd: Option<f64>) -> Self {
+        let oracle = Self::default();
+        if let Some(price) = initial_price_usd {
+            oracle.set_price_usd(price);
+        }
+        oracle
+    }
+
+    /// Update the live price. Non-positive or non-finite prices are ignored.
+    pub fn set_price_usd(&self, price_usd: f64) {
+        if price_usd.is_finite() && price_usd > 0.0 {
+            if let Ok(mut guard) = self.price_usd.write() {
+                *guard = Some(price_usd);
+            }
+        }
+    }
+
+    /// Current MON price in USD, if known.
+    pub fn price_usd(&self) -> Option<f64> {
+        self.price_usd.read().ok().and_then(|guard| *guard)
+    }
+
+    /// Current MON price in USD, or `FALLBACK_MON_PRICE_USD` while unknown.
+    /// For filters that need a number; display should use `price_usd`.
+    pub fn price_usd_or_fallback(&self) -> f64 {
+        self.price_usd().unwrap_or(FALLBACK_MON_PRICE_USD)
+    }
+
+    /// USD suffix for a MON amount, e.g. `" ($1,234.56)"`.
+    /// Empty when the price is unavailable so no wrong number is shown.
+    pub fn format_usd(&self, mon: f64) -> String {
+        match self.price_usd() {
+            Some(price) => format!(" ({})", format_usd_amount(mon * price)),
+            None => String::new(),
+        }
+    }
+}
+
+/// Locale-neutral USD amount: `,` thousands separator, two decimals.
+pub fn format_usd_amount(usd: f64) -> String {
+    let cents = (usd.abs() * 100.0).round() as u128;
+    let whole = (cents / 100).to_string();
+    let fraction = cents % 100;
+
+    let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    let sign = if usd < 0.0 && cents > 0 { "-" } else { "" };
+    format!("{}${}.{:02}", sign, grouped, fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_prices_keep_the_last_good_one() {
+        let oracle = MonPriceOracle::new(None);
+        assert_eq!(oracle.price_usd(), None);
+        assert_eq!(oracle.price_usd_or_fallback(), FALLBACK_MON_PRICE_USD);
+        assert_eq!(oracle.format_usd(10.0), "");
+
+        oracle.set_price_usd(0.4);
+        for bad in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            oracle.set_price_usd(bad);
+        }
+        assert_eq!(oracle.price_usd(), Some(0.4));
+        assert_eq!(oracle.format_usd(10.0), " ($4.00)");
+    }
+
+    #[test]
+    fn usd_amounts_are_grouped_and_signed() {
+        assert_eq!(format_usd_amount(1_234_567.891), "$1,234,567.89");
+        assert_eq!(format_usd_amount(999.999), "$1,000.00");
+        assert_eq!(format_usd_amount(-12.5), "-$12.50");
+        assert_eq!(format_usd_amount(-0.001), "$0.00");
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Live MON/USD price from the WMON/USDC pool on OctoSwap.
+
+use crate::arbitrage::octoswap;
+use crate::price::MonPriceOracle;
+use crate::units::{one_token, token_units_to_f64};
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use alloy::sol;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+sol! {
+    #[sol(rpc)]
+    interface IERC20Decimals {
+        function decimals() external view returns (uint8);
+    }
+}
+
+/// Quote 1 WMON -> USDC and return the MON price in USD.
+pub async fn fetch_mon_price_usd<P: Provider + Clone>(
+    provider: &P,
+    wmon: Address,
+    usdc: Address,
+) -> Result<f64, String> {
+    let decimals = IERC20Decimals::new(usdc, provider)
+        .decimals()
+        .call()
+        .await
+        .map_err(|e| format!("Failed to get USDC decimals: {}", e))?;
+
+    let usdc_out = octoswap::get_quote(provider, wmon, usdc, one_token(18)).await?;
+
+    let price = token_units_to_f64(usdc_out, decimals);
+    if price.is_finite() && price > 0.0 {
+        Ok(price)
+    } else {
+        Err(format!("Invalid MON price from pool: {}", price))
+    }
+}
+
+/// Refresh `oracle` from the pool every `ttl_secs`.
+/// On failure the oracle keeps its last price (initially `MON_PRICE_USD`).
+pub fn spawn_price_feed<P: Provider + Clone + Send + Sync + 'static>(
+    provider: P,
+    oracle: MonPriceOracle,
+    wmon: Address,
+    usdc: Address,
+    ttl_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    info!("💲 MON price feed: WMON/USDC every {}s", ttl_secs);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(ttl_secs));
+
+        loop {
+            ticker.tick().await;
+
+            match fetch_mon_price_usd(&provider, wmon, usdc).await {
+                Ok(price) => {
+                    debug!("💲 MON price: ${:.4}", price);
+                    oracle.set_price_usd(price);
+                }
+                Err(e) => warn!("⚠️ MON price refresh failed, keeping last price: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_call};
+    use alloy::primitives::U256;
+
+    async fn price_for(decimals: u8, usdc_out: U256) -> Result<f64, String> {
+        let (provider, asserter) = mock_provider();
+        push_call::<IERC20Decimals::decimalsCall>(&asserter, &decimals);
+        push_call::<octoswap::IOctoRouter::getAmountsOutCall>(&asserter, &vec![one_token(18), usdc_out]);
+        fetch_mon_price_usd(&provider, Address::repeat_byte(1), Address::repeat_byte(2)).await
+    }
+
+    #[tokio::test]
+    async fn price_is_the_usdc_quote_for_one_mon() {
+        let price = price_for(6, U256::from(352_500u64)).await.unwrap();
+        assert!((price - 0.3525).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn empty_pool_is_an_error_not_a_zero_price() {
+        assert!(price_for(6, U256::ZERO).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn oversized_quote_does_not_panic() {
+        assert!(price_for(6, U256::MAX).await.unwrap() > 0.0);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Persistent log of rejected tokens, kept as a dataset for tuning filters.
+
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use tracing::warn;
+
+const REJECTS_FILE: &str = "rejects.jsonl";
+const REJECTS_ROTATED_FILE: &str = "rejects.jsonl.1";
+
+/// Which filter rejected the token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RejectReason {
+    Blacklist,
+    Name,
+    Liquidity,
+    Safety,
+    DevHolding,
+    Age,
+    MarketCap,
+    RiskReward,
+    Trading,
+    RateLimit,
+    PositionCap,
+    Creator,
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self {
+            Self::Blacklist => "BLACKLIST",
+            Self::Name => "NAME",
+            Self::Liquidity => "LIQUIDITY",
+            Self::Safety => "SAFETY",
+            Self::DevHolding => "DEV",
+            Self::Age => "AGE",
+            Self::MarketCap => "MCAP",
+            Self::RiskReward => "R/R",
+            Self::Trading => "TRADING",
+            Self::RateLimit => "RATE",
+            Self::PositionCap => "CAP",
+            Self::Creator => "CREATOR",
+        };
+        f.pad(tag)
+    }
+}
+
+/// One rejected token with the metrics the filters saw.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectRecord {
+    pub token: Address,
+    pub name: String,
+    pub symbol: String,
+    pub reason: RejectReason,
+    pub detail: String,
+    pub market_cap_usd: f64,
+    pub dev_holding_pct: f64,
+    pub age_minutes: Option<u64>,
+    pub liquidity_mon: f64,
+    pub timestamp: u64,
+}
+
+/// Append-only `rejects.jsonl`, rotated to `rejects.jsonl.1` past `max_bytes`.
+#[derive(Debug, Clone)]
+pub struct RejectLog {
+    max_bytes: u64,
+}
+
+impl RejectLog {
+    pub fn new(max_bytes: u64) -> Self {
+        Self { max_bytes }
+    }
+
+    /// Append a record. Failures are logged, never fatal.
+    pub fn append(&self, record: &RejectRecord) {
+        if let Err(e) = self.try_append(record) {
+            warn!("Failed to write reject log: {}", e);
+        }
+    }
+
+    fn try_append(&self, record: &RejectRecord) -> Result<(), String> {
+        let size = fs::metadata(REJECTS_FILE).map(|m| m.len()).unwrap_or(0);
+        if self.max_bytes > 0 && size >= self.max_bytes {
+            fs::rename(REJECTS_FILE, REJECTS_ROTATED_FILE)
+                .map_err(|e| format!("Failed to rotate {}: {}", REJECTS_FILE, e))?;
+        }
+
+        let line = serde_json::to_string(record)
+            .map_err(|e| format!("Failed to serialize reject: {}", e))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(REJECTS_FILE)
+            .map_err(|e| format!("Failed to open {}: {}", REJECTS_FILE, e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to append reject: {}", e))
+    }
+
+    /// All records from the rotated and current files, oldest first.
+    /// Unparseable lines (e.g. a torn last write) are skipped.
+    pub fn load_all() -> Vec<RejectRecord> {
+        [REJECTS_ROTATED_FILE, REJECTS_FILE]
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .flat_map(|contents| {
+                contents
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect::<Vec<RejectRecord>>()
+            })
+            .collect()
+    }
+}
+
+/// Rejection counts per reason, most frequent first.
+pub fn count_by_reason(records: &[RejectRecord]) -> Vec<(RejectReason, usize)> {
+    let mut counts: HashMap<RejectReason, usize> = HashMap::new();
+    for record in records {
+        *counts.entry(record.reason).or_insert(0) += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reason_tags_pad_to_the_log_column() {
+        assert_eq!(format!("[{:<9}]", RejectReason::Creator), "[CREATOR  ]");
+        assert_eq!(RejectReason::RiskReward.to_string(), "R/R");
+    }
+
+    #[test]
+    fn reason_round_trips_through_json() {
+        let json = serde_json::to_string(&RejectReason::Creator).unwrap();
+        assert_eq!(json, "\"Creator\"");
+        assert_eq!(serde_json::from_str::<RejectReason>(&json).unwrap(), RejectReason::Creator);
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Endpoint failover for the HTTP RPC and the WebSocket listeners.
+//!
+//! `MONAD_RPC_URLS` / `MONAD_WS_URLS` list endpoints in order of preference.
+//! Requests go to the current endpoint until it fails `max_failures` times in
+//! a row (errors or timeouts); the next endpoint then takes over, wrapping
+//! around at the end of the list. A single success resets the count.
+
+use crate::config::Config;
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::http::reqwest::{Client, Url};
+use alloy::transports::http::Http;
+use alloy::transports::{TransportError, TransportErrorKind, TransportFut};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::Service;
+use tracing::warn;
+
+/// When to give up on an endpoint.
+#[derive(Debug, Clone, Copy)]
+pub struct FailoverPolicy {
+    /// Consecutive failures on the current endpoint before switching.
+    pub max_failures: u32,
+    /// An HTTP request taking longer than this counts as a failure.
+    pub request_timeout: Duration,
+}
+
+impl FailoverPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_failures: config.rpc_failover_errors.max(1),
+            request_timeout: Duration::from_secs(config.rpc_request_timeout_secs),
+        }
+    }
+}
+
+/// Which endpoint of a list is in use, and how often it has failed in a row.
+#[derive(Debug, Clone)]
+pub struct EndpointRotation {
+    /// Used in log lines ("RPC", "CurveStream", ...)
+    name: &'static str,
+    urls: Vec<String>,
+    current: usize,
+    failures: u32,
+    max_failures: u32,
+}
+
+impl EndpointRotation {
+    /// `urls` must not be empty.
+    pub fn new(name: &'static str, urls: Vec<String>, max_failures: u32) -> Self {
+        Self {
+            name,
+            urls,
+            current: 0,
+            failures: 0,
+            max_failures: max_failures.max(1),
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.current
+    }
+
+    pub fn current(&self) -> &str {
+        &self.urls[self.current]
+    }
+
+    pub fn record_success(&mut self, index: usize) {
+        if index == self.current {
+            self.failures = 0;
+        }
+    }
+
+    /// Count a failure on endpoint `index`. Returns true if this moved the
+    /// rotation to another endpoint. Failures reported for an endpoint that
+    /// is no longer current (requests sent before a switch) are ignored.
+    pub fn record_failure(&mut self, index: usize) -> bool {
+        if index != self.current {
+            return false;
+        }
+        self.failures += 1;
+        if self.failures < self.max_failures || self.urls.len() < 2 {
+            return false;
+        }
+
+        let from = self.current;
+        self.current = (self.current + 1) % self.urls.len();
+        self.failures = 0;
+        warn!(
+            "🔀 {} endpoint {} failed {} times in a row, switching to {} ({}/{})",
+            self.name,
+            self.urls[from],
+            self.max_failures,
+            self.urls[self.current],
+            self.current + 1,
+            self.urls.len()
+        );
+        true
+    }
+}
+
+/// HTTP transport over several RPC endpoints, one active at a time.
+/// A request that fails on the endpoint that just got rotated away from is
+/// retried once on the next one, so the switch doesn't cost a call.
+#[derive(Clone)]
+pub struct FailoverTransport {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// One transport per URL, in rotation order
+    endpoints: Vec<Http<Client>>,
+    rotation: Mutex<EndpointRotation>,
+    request_timeout: Duration,
+}
+
+impl FailoverTransport {
+    pub fn new(urls: &[String], policy: FailoverPolicy) -> Result<Self, String> {
+        if urls.is_empty() {
+            return Err("No RPC URL configured".to_string());
+        }
+        let client = Client::new();
+        let endpoints = urls
+            .iter()
+            .map(|raw| {
+                let url: Url = raw.parse().map_err(|e| format!("Invalid RPC URL {}: {}", raw, e))?;
+                Ok(Http::with_client(client.clone(), url))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                endpoints,
+                rotation: Mutex::new(EndpointRotation::new("RPC", urls.to_vec(), policy.max_failures)),
+                request_timeout: policy.request_timeout,
+            }),
+        })
+    }
+
+    fn current_index(&self) -> usize {
+        self.inner.rotation.lock().unwrap().index()
+    }
+
+    async fn send(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let index = self.current_index();
+            let mut http = self.inner.endpoints[index].clone();
+
+            let error = match tokio::time::timeout(self.inner.request_timeout, http.call(request.clone())).await {
+                Ok(Ok(response)) => {
+                    self.inner.rotation.lock().unwrap().record_success(index);
+                    return Ok(response);
+                }
+                Ok(Err(e)) => e,
+                Err(_) => TransportErrorKind::custom_str(&format!(
+                    "RPC request timed out after {}s",
+                    self.inner.request_timeout.as_secs_f64()
+                )),
+            };
+
+            let rotated = self.inner.rotation.lock().unwrap().record_failure(index);
+            if !rotated || attempts >= self.inner.endpoints.len() {
+                return Err(error);
+            }
+        }
+    }
+}
+
+impl Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().send(request))
+    }
+}
+
+/// RPC client over `urls` with failover, for `ProviderBuilder::connect_client`.
+pub fn failover_client(urls: &[String], policy: FailoverPolicy) -> Result<RpcClient, String> {
+    let transport = FailoverTransport::new(urls, policy)?;
+    Ok(RpcClient::new(transport, false))
+}
+
+/// Endpoints from the comma-separated `list_var`, or from `url_var` when the
+/// list is unset or empty.
+pub fn urls_from_env(list_var: &str, url_var: &str) -> Result<Vec<String>, String> {
+    let urls: Vec<String> = std::env::var(list_var)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if !urls.is_empty() {
+        return Ok(urls);
+    }
+    std::env::var(url_var)
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| vec![url.trim().to_string()])
+        .ok_or_else(|| format!("{} (or {}) not set", url_var, list_var))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::providers::{Provider, ProviderBuilder};
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    fn endpoints(urls: &[&str], max_failures: u32) -> EndpointRotation {
+        EndpointRotation::new("RPC", urls.iter().map(|u| u.to_string()).collect(), max_failures)
+    }
+
+    #[test]
+    fn switches_after_consecutive_failures_and_wraps() {
+        let mut rotation = endpoints(&["a", "b"], 2);
+        assert!(!rotation.record_failure(0));
+        // A success in between starts the count over
+        rotation.record_success(0);
+        assert!(!rotation.record_failure(0));
+        assert!(rotation.record_failure(0));
+        assert_eq!(rotation.current(), "b");
+
+        assert!(!rotation.record_failure(1));
+        assert!(rotation.record_failure(1));
+        assert_eq!(rotation.current(), "a");
+    }
+
+    #[test]
+    fn stale_failures_and_single_endpoints_never_switch() {
+        let mut rotation = endpoints(&["a", "b"], 1);
+        assert!(rotation.record_failure(0));
+        // Sent to "a" before the switch
+        assert!(!rotation.record_failure(0));
+        assert_eq!(rotation.index(), 1);
+
+        let mut single = endpoints(&["a"], 1);
+        assert!(!single.record_failure(0));
+        assert_eq!(single.current(), "a");
+    }
+
+    /// A JSON-RPC endpoint answering every request with chain id 143.
+    async fn node() -> String {
+        let app = Router::new().route(
+            "/",
+            post(|Json(request): Json<serde_json::Value>| async move {
+                Json(serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": "0x8f"}))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    #[tokio::test]
+    async fn failed_request_is_retried_on_the_next_endpoint() {
+        let urls = vec!["http://127.0.0.1:1/".to_string(), node().await];
+        let policy = FailoverPolicy { max_failures: 1, request_timeout: Duration::from_secs(5) };
+        let transport = FailoverTransport::new(&urls, policy).unwrap();
+        let provider = ProviderBuilder::new().connect_client(RpcClient::new(transport.clone(), false));
+
+        assert_eq!(provider.get_chain_id().await.unwrap(), 143);
+        assert_eq!(transport.current_index(), 1);
+    }
+
+    #[tokio::test]
+    async fn errors_below_the_threshold_are_returned() {
+        let urls = vec!["http://127.0.0.1:1/".to_string(), node().await];
+        let policy = FailoverPolicy { max_failures: 2, request_timeout: Duration::from_secs(5) };
+        let transport = FailoverTransport::new(&urls, policy).unwrap();
+        let provider = ProviderBuilder::new().connect_client(RpcClient::new(transport.clone(), false));
+
+        assert!(provider.get_chain_id().await.is_err());
+        assert_eq!(transport.current_index(), 0);
+        assert_eq!(provider.get_chain_id().await.unwrap(), 143);
+    }
+
+    #[test]
+    fn bad_endpoint_lists_are_rejected() {
+        let policy = FailoverPolicy { max_failures: 1, request_timeout: Duration::from_secs(1) };
+        assert!(FailoverTransport::new(&[], policy).is_err());
+        assert!(FailoverTransport::new(&["not a url".to_string()], policy).is_err());
+    }
+
+    #[test]
+    fn url_list_falls_back_to_the_single_url() {
+        let (list, single) = ("FAILOVER_TEST_URLS", "FAILOVER_TEST_URL");
+        std::env::set_var(single, "http://one");
+        assert_eq!(urls_from_env(list, single), Ok(vec!["http://one".to_string()]));
+
+        std::env::set_var(list, " http://a , ,http://b");
+        assert_eq!(urls_from_env(list, single), Ok(vec!["http://a".to_string(), "http://b".to_string()]));
+
+        std::env::remove_var(list);
+        std::env::remove_var(single);
+        assert!(urls_from_env(list, single).is_err());
+    }
+}
@@ -4,6 +4,8 @@
 //! RPC module for interacting with Monad blockchain.
 
 mod executor;
+mod failover;
 mod provider;
 
-pub use provider::{create_provider, RpcConfig};
+pub use provider::{create_provider, network_name, verify_chain_id, RpcConfig, MONAD_MAINNET_CHAIN_ID, MONAD_TESTNET_CHAIN_ID};
+pub use failover::{failover_client, urls_from_env, EndpointRotation, FailoverPolicy};
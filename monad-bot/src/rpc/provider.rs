@@ -3,17 +3,27 @@
 
 //! Provider setup and configuration for Monad RPC.
 
+use super::failover::{failover_client, urls_from_env, FailoverPolicy};
+use crate::config::Config;
 use alloy::{
     network::EthereumWallet,
     providers::{Provider, ProviderBuilder},
     signers::local::PrivateKeySigner,
-    transports::http::reqwest::Url,
 };
+use std::time::Duration;
+
+/// Monad mainnet chain id (the network the nad.fun contract addresses are for).
+pub const MONAD_MAINNET_CHAIN_ID: u64 = 143;
+
+/// Monad testnet chain id.
+pub const MONAD_TESTNET_CHAIN_ID: u64 = 10143;
 
 /// Configuration for RPC connection.
 #[derive(Debug, Clone)]
 pub struct RpcConfig {
-    pub rpc_url: String,
+    /// Endpoints in order of preference; later ones take over on failure
+    pub rpc_urls: Vec<String>,
+    pub failover: FailoverPolicy,
     pub private_key: String,
     pub chain_id: u64,
 }
@@ -23,24 +33,47 @@ impl RpcConfig {
     pub fn from_env() -> Result<Self, String> {
         dotenvy::dotenv().ok();
 
-        let rpc_url = std::env::var("MONAD_RPC_URL")
-            .map_err(|_| "MONAD_RPC_URL not set")?;
+        let rpc_urls = urls_from_env("MONAD_RPC_URLS", "MONAD_RPC_URL")?;
+        let failover = FailoverPolicy {
+            max_failures: std::env::var("RPC_FAILOVER_ERRORS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            request_timeout: Duration::from_secs(
+                std::env::var("RPC_REQUEST_TIMEOUT_SEC")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(10),
+            ),
+        };
         let private_key = std::env::var("PRIVATE_KEY")
             .map_err(|_| "PRIVATE_KEY not set")?;
         let chain_id = std::env::var("CHAIN_ID")
-            .unwrap_or_else(|_| "10143".to_string()) // Monad testnet default
+            .unwrap_or_else(|_| MONAD_MAINNET_CHAIN_ID.to_string())
             .parse()
             .map_err(|_| "Invalid CHAIN_ID")?;
 
         Ok(Self {
-            rpc_url,
+            rpc_urls,
+            failover,
             private_key,
             chain_id,
         })
     }
+
+    /// The RPC settings of the bot config.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            rpc_urls: config.rpc_urls.clone(),
+            failover: FailoverPolicy::from_config(config),
+            private_key: config.private_key.clone(),
+            chain_id: config.chain_id,
+        }
+    }
 }
 
 /// Create a provider with signer from config.
+/// Requests fail over between `rpc_urls` (see `FailoverTransport`).
 pub fn create_provider(
     config: &RpcConfig,
 ) -> Result<(impl Provider + Clone, EthereumWallet), String> {
@@ -51,14 +84,73 @@ pub fn create_provider(
 
     let wallet = EthereumWallet::from(signer);
 
-    let url: Url = config
-        .rpc_url
-        .parse()
-        .map_err(|e| format!("Invalid RPC URL: {e}"))?;
+    let client = failover_client(&config.rpc_urls, config.failover)?;
 
     let provider = ProviderBuilder::new()
         .wallet(wallet.clone())
-        .connect_http(url);
+        .connect_client(client);
 
     Ok((provider, wallet))
 }
+
+/// Fail unless the node at the other end of `provider` is on chain `expected`.
+/// Signing for one chain against a node on another only surfaces as failed
+/// or wrong-chain transactions, so this runs before anything is sent.
+pub async fn verify_chain_id<P: Provider>(provider: &P, expected: u64) -> Result<(), String> {
+    let actual = provider
+        .get_chain_id()
+        .await
+        .map_err(|e| format!("Failed to get chain id from RPC: {e}"))?;
+    check_chain_id(actual, expected)
+}
+
+/// `verify_chain_id` against a known chain id.
+pub fn check_chain_id(actual: u64, expected: u64) -> Result<(), String> {
+    if actual == expected {
+        return Ok(());
+    }
+    Err(format!(
+        "RPC is on chain {} ({}) but CHAIN_ID is {} ({}); point MONAD_RPC_URL(S) at the right network or fix CHAIN_ID",
+        actual,
+        network_name(actual),
+        expected,
+        network_name(expected)
+    ))
+}
+
+/// Human-readable name of a Monad chain id.
+pub fn network_name(chain_id: u64) -> &'static str {
+    match chain_id {
+        MONAD_MAINNET_CHAIN_ID => "Monad mainnet",
+        MONAD_TESTNET_CHAIN_ID => "Monad testnet",
+        _ => "unknown network",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_quantity};
+
+    #[test]
+    fn mismatch_names_both_networks() {
+        assert_eq!(check_chain_id(143, 143), Ok(()));
+        let err = check_chain_id(10143, 143).unwrap_err();
+        assert!(err.starts_with("RPC is on chain 10143 (Monad testnet) but CHAIN_ID is 143 (Monad mainnet)"), "{}", err);
+        assert_eq!(network_name(1), "unknown network");
+    }
+
+    #[tokio::test]
+    async fn verify_asks_the_node() {
+        let (provider, asserter) = mock_provider();
+        push_quantity(&asserter, 143);
+        assert_eq!(verify_chain_id(&provider, MONAD_MAINNET_CHAIN_ID).await, Ok(()));
+
+        push_quantity(&asserter, 1);
+        assert!(verify_chain_id(&provider, MONAD_MAINNET_CHAIN_ID).await.is_err());
+
+        asserter.push_failure_msg("connection refused");
+        let err = verify_chain_id(&provider, MONAD_MAINNET_CHAIN_ID).await.unwrap_err();
+        assert!(err.starts_with("Failed to get chain id"), "{}", err);
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Trades in flight, so shutdown can wait for them to settle.
+//!
+//! A sell or front-run takes a `TradeGuard` before sending and drops it once
+//! its receipt is handled. On Ctrl-C, `drain` refuses new guards and waits
+//! (bounded) for the outstanding ones, so `positions.json` is saved after the
+//! chain state it describes rather than before it.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Count of trades sent but not yet settled.
+#[derive(Debug, Default)]
+pub struct InFlightTrades {
+    count: AtomicUsize,
+    /// Set by `drain`; no new trades start after it.
+    closed: AtomicBool,
+    settled: Notify,
+}
+
+impl InFlightTrades {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Register a trade about to be sent. `None` once shutdown has begun,
+    /// in which case the trade should be skipped.
+    pub fn start(self: &Arc<Self>) -> Option<TradeGuard> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        let guard = TradeGuard(Arc::clone(self));
+        // Checked after counting, so `drain` either sees this trade or refuses it
+        (!self.closed.load(Ordering::SeqCst)).then_some(guard)
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Stop new trades and wait up to `timeout` for the outstanding ones.
+    /// Returns how many were still in flight when the wait ended.
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        self.closed.store(true, Ordering::SeqCst);
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            // Created before reading the count so a settle in between isn't missed
+            let settled = self.settled.notified();
+            let count = self.count();
+            if count == 0 {
+                return 0;
+            }
+            if tokio::time::timeout_at(deadline, settled).await.is_err() {
+                return self.count();
+            }
+        }
+    }
+}
+
+/// Held for the life of one trade; settles it when dropped.
+#[derive(Debug)]
+pub struct TradeGuard(Arc<InFlightTrades>);
+
+impl Drop for TradeGuard {
+    fn drop(&mut self) {
+        self.0.count.fetch_sub(1, Ordering::SeqCst);
+        self.0.settled.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn drain_waits_for_outstanding_trades() {
+        let in_flight = InFlightTrades::new();
+        let trade = in_flight.start().unwrap();
+        assert_eq!(in_flight.count(), 1);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(trade);
+        });
+        assert_eq!(in_flight.drain(Duration::from_secs(5)).await, 0);
+        assert_eq!(in_flight.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn drain_gives_up_after_the_timeout() {
+        let in_flight = InFlightTrades::new();
+        let _stuck = in_flight.start().unwrap();
+        let _other = in_flight.start().unwrap();
+        assert_eq!(in_flight.drain(Duration::from_millis(10)).await, 2);
+    }
+
+    #[tokio::test]
+    async fn no_new_trades_once_draining() {
+        let in_flight = InFlightTrades::new();
+        assert_eq!(in_flight.drain(Duration::ZERO).await, 0);
+        assert!(in_flight.start().is_none());
+        // The refused trade isn't left counted
+        assert_eq!(in_flight.count(), 0);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Name/symbol blacklist for the sniper.
+//!
+//! Entries match whole words: the name is split on anything that isn't a
+//! letter or digit, so "rug" rejects "Rug Pull" and "RUG-2" but not "Ruger".
+//! Entries prefixed with `re:` are case-insensitive regexes matched against
+//! the full name or symbol. `BLACKLIST_STRICT_SUBSTRING=true` restores plain
+//! substring matching for word entries.
+
+use crate::config::Config;
+use regex::{Regex, RegexBuilder};
+use tracing::warn;
+
+/// Prefix marking a regex entry.
+const REGEX_PREFIX: &str = "re:";
+
+/// One blacklist entry.
+#[derive(Debug, Clone)]
+enum Entry {
+    /// Lowercased entry, and its words (`rug pull`) matched as consecutive segments.
+    Words { raw: String, words: Vec<String> },
+    Regex(Regex),
+}
+
+/// Words and patterns a launch's name or symbol must not match.
+#[derive(Debug, Clone, Default)]
+pub struct Blacklist {
+    entries: Vec<Entry>,
+    /// Word entries match anywhere in the text, not only whole words.
+    strict_substring: bool,
+}
+
+impl Blacklist {
+    /// Build from raw entries. Empty entries and invalid regexes are skipped.
+    pub fn new(entries: &[String], strict_substring: bool) -> Self {
+        let entries = entries
+            .iter()
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| match entry.strip_prefix(REGEX_PREFIX) {
+                Some(pattern) => match RegexBuilder::new(pattern).case_insensitive(true).build() {
+                    Ok(regex) => Some(Entry::Regex(regex)),
+                    Err(e) => {
+                        warn!("⚠️ Ignoring invalid blacklist regex '{}': {}", pattern, e);
+                        None
+                    }
+                },
+                None => {
+                    let words = segments(entry);
+                    (!words.is_empty()).then(|| Entry::Words {
+                        raw: entry.to_lowercase(),
+                        words,
+                    })
+                }
+            })
+            .collect();
+
+        Self {
+            entries,
+            strict_substring,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.blacklist, config.blacklist_strict_substring)
+    }
+
+    /// The first entry `text` matches, for the reject reason.
+    pub fn matched(&self, text: &str) -> Option<String> {
+        let lower = text.to_lowercase();
+        let text_segments = segments(text);
+
+        self.entries.iter().find_map(|entry| {
+            let hit = match entry {
+                Entry::Words { raw, .. } if self.strict_substring => lower.contains(raw.as_str()),
+                Entry::Words { words, .. } => text_segments.windows(words.len()).any(|window| window == words.as_slice()),
+                Entry::Regex(regex) => regex.is_match(text),
+            };
+            hit.then(|| match entry {
+                Entry::Words { raw, .. } => raw.clone(),
+                Entry::Regex(regex) => format!("{}{}", REGEX_PREFIX, regex.as_str()),
+            })
+        })
+    }
+}
+
+/// Lowercased runs of letters and digits.
+fn segments(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blacklist(entries: &[&str], strict_substring: bool) -> Blacklist {
+        let entries: Vec<String> = entries.iter().map(|entry| entry.to_string()).collect();
+        Blacklist::new(&entries, strict_substring)
+    }
+
+    #[test]
+    fn words_match_whole_segments_only() {
+        let blacklist = blacklist(&["rug", "Scam"], false);
+        assert_eq!(blacklist.matched("Rug Pull").as_deref(), Some("rug"));
+        assert_eq!(blacklist.matched("RUG-2").as_deref(), Some("rug"));
+        assert_eq!(blacklist.matched("$rug").as_deref(), Some("rug"));
+        assert_eq!(blacklist.matched("SCAM coin").as_deref(), Some("scam"));
+        assert_eq!(blacklist.matched("Ruger"), None);
+        assert_eq!(blacklist.matched("Scameleon"), None);
+    }
+
+    #[test]
+    fn multi_word_entries_match_consecutive_segments() {
+        let blacklist = blacklist(&["rug pull"], false);
+        assert_eq!(blacklist.matched("Big RUG_PULL Energy").as_deref(), Some("rug pull"));
+        assert_eq!(blacklist.matched("rug then pull"), None);
+        assert_eq!(blacklist.matched("rug"), None);
+    }
+
+    #[test]
+    fn strict_substring_matches_inside_words() {
+        let blacklist = blacklist(&["rug"], true);
+        assert_eq!(blacklist.matched("Ruger").as_deref(), Some("rug"));
+        assert_eq!(blacklist.matched("Moon"), None);
+    }
+
+    #[test]
+    fn regex_entries_are_case_insensitive_over_the_whole_text() {
+        let blacklist = blacklist(&["re:^elon", "re:v[0-9]$"], false);
+        assert_eq!(blacklist.matched("ELONDOGE").as_deref(), Some("re:^elon"));
+        assert_eq!(blacklist.matched("Pepe V2").as_deref(), Some("re:v[0-9]$"));
+        assert_eq!(blacklist.matched("Not Elon"), None);
+    }
+
+    #[test]
+    fn empty_entries_and_bad_regexes_are_skipped() {
+        let blacklist = blacklist(&["", "  ", "re:(", "--", "fake"], false);
+        assert_eq!(blacklist.entries.len(), 1);
+        assert_eq!(blacklist.matched("(((").as_deref(), None);
+        assert_eq!(blacklist.matched("Fake Moon").as_deref(), Some("fake"));
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Limit-buy (stop-buy) mode.
+//!
+//! Tokens that pass safety at their Create event but are still below the
+//! entry zone are parked here. Each one is re-priced on its own interval and
+//! bought automatically once its MCap rises into the zone, or dropped after
+//! the max wait.
+
+use crate::config::Config;
+use crate::executor::{SdkExecutor, SwapExecutor, TradeError};
+use crate::listeners::NewTokenEvent;
+use crate::price::MonPriceOracle;
+use crate::strategies::{BuyDecision, SniperStrategy};
+use crate::validators::TradingCheck;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Limit-buy configuration.
+#[derive(Debug, Clone)]
+pub struct LimitBuyConfig {
+    /// Seconds between MCap polls of the same token.
+    pub poll_interval_sec: u64,
+    /// Minutes to wait for a token to reach the zone before giving up.
+    pub max_wait_minutes: u64,
+    /// Entry zone lower bound (USD).
+    pub min_market_cap_usd: f64,
+    /// Entry zone upper bound (USD).
+    pub max_market_cap_usd: f64,
+    /// Live MON price used for MCap.
+    pub mon_price: MonPriceOracle,
+    /// Amount to buy when the order triggers.
+    pub amount_wei: U256,
+}
+
+impl LimitBuyConfig {
+    pub fn from_config(config: &Config, strategy: &SniperStrategy) -> Self {
+        Self {
+            poll_interval_sec: config.limit_buy_poll_interval_sec,
+            max_wait_minutes: config.limit_buy_max_wait_minutes,
+            min_market_cap_usd: strategy.filters.min_market_cap_usd,
+            max_market_cap_usd: strategy.filters.max_market_cap_usd,
+            mon_price: strategy.mon_price.clone(),
+            amount_wei: strategy.snipe_amount_wei,
+        }
+    }
+}
+
+/// A parked token waiting for its MCap to enter the zone.
+#[derive(Debug, Clone)]
+struct LimitBuyOrder {
+    event: NewTokenEvent,
+    registered_at: Instant,
+    next_poll: Instant,
+}
+
+/// Spawn the limit-buy poller as a background task.
+/// Registrations arrive on `register_rx`; triggered buys go out on `buy_tx`.
+/// The MCap is quoted by `buy_executor`, the venue the triggered buy goes through.
+/// Trading is re-checked before a triggered buy, since it may have been disabled at creation.
+pub fn spawn_limit_buyer<P: Provider + Clone + 'static>(
+    buy_executor: Arc<SwapExecutor<P>>,
+    trading_check: Option<Arc<TradingCheck>>,
+    config: LimitBuyConfig,
+    mut register_rx: mpsc::Receiver<NewTokenEvent>,
+    buy_tx: mpsc::Sender<BuyDecision>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            "🎯 Limit-buy poller started (zone ${:.0}k-${:.0}k, poll {}s, expiry {}min)",
+            config.min_market_cap_usd / 1000.0,
+            config.max_market_cap_usd / 1000.0,
+            config.poll_interval_sec,
+            config.max_wait_minutes
+        );
+
+        let poll_interval = Duration::from_secs(config.poll_interval_sec);
+        let max_wait = Duration::from_secs(config.max_wait_minutes * 60);
+        let mut orders: HashMap<Address, LimitBuyOrder> = HashMap::new();
+        let mut tick = tokio::time::interval(Duration::from_secs(1));
+
+        loop {
+            tokio::select! {
+                Some(event) = register_rx.recv() => {
+                    if orders.contains_key(&event.token_address) {
+                        continue;
+                    }
+                    info!(
+                        "🎯 LIMIT BUY armed: {} ({}) - waiting for ${:.0}k mcap",
+                        event.name, event.symbol, config.min_market_cap_usd / 1000.0
+                    );
+                    let now = Instant::now();
+                    orders.insert(event.token_address, LimitBuyOrder {
+                        event,
+                        registered_at: now,
+                        next_poll: now + poll_interval,
+                    });
+                }
+                _ = tick.tick() => {
+                    let now = Instant::now();
+                    let due: Vec<Address> = orders
+                        .values()
+                        .filter(|o| o.next_poll <= now)
+                        .map(|o| o.event.token_address)
+                        .collect();
+
+                    for token in due {
+                        let Some(order) = orders.get_mut(&token) else { continue };
+
+                        if order.registered_at.elapsed() >= max_wait {
+                            info!(
+                                "⌛ LIMIT BUY expired: {} ({}) never reached the entry zone",
+                                order.event.name, order.event.symbol
+                            );
+                            orders.remove(&token);
+                            continue;
+                        }
+                        order.next_poll = now + poll_interval;
+
+                        let market_cap_usd = match buy_executor.market_cap_usd(token, config.mon_price.price_usd_or_fallback()).await {
+                            Ok(mcap) => mcap,
+                            Err(e) => {
+                                debug!("Limit-buy poll failed for {:?}: {}", token, e);
+                                continue;
+                            }
+                        };
+
+                        if market_cap_usd > config.max_market_cap_usd {
+                            warn!(
+                                "❌ LIMIT BUY cancelled: {:?} jumped past the zone (${:.0}k)",
+                                token, market_cap_usd / 1000.0
+                            );
+                            orders.remove(&token);
+                            continue;
+                        }
+
+                        if market_cap_usd < config.min_market_cap_usd {
+                            debug!("Limit-buy {:?} at ${:.0}k, still waiting", token, market_cap_usd / 1000.0);
+                            continue;
+                        }
+
+                        if let Some(trading_check) = &trading_check {
+                            if !trading_check.is_enabled(token).await {
+                                debug!("Limit-buy {:?} in zone but trading disabled, waiting", token);
+                                continue;
+                            }
+                        }
+
+                        let Some(order) = orders.remove(&token) else { continue };
+                        info!(
+                            "🟢 LIMIT BUY triggered: {} ({}) entered zone at ${:.0}k",
+                            order.event.name, order.event.symbol, market_cap_usd / 1000.0
+                        );
+
+                        let decision = BuyDecision {
+                            token,
+                            amount_wei: config.amount_wei,
+                            name: order.event.name,
+                            symbol: order.event.symbol,
+                            reason: format!("Limit buy at ${:.0}k mcap", market_cap_usd / 1000.0),
+                            priority: false,
+                        };
+                        if let Err(e) = buy_tx.send(decision).await {
+                            warn!("Failed to send limit buy: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Live MCap in USD from the bonding curve price of one token and total supply,
+/// both scaled by the token's own decimals. Buys go through the DEX, so entry
+/// gates quote `SwapExecutor::market_cap_usd` instead.
+pub async fn get_market_cap_usd(
+    sdk_executor: &SdkExecutor,
+    token: Address,
+    mon_price_usd: f64,
+) -> Result<f64, TradeError> {
+    let meta = sdk_executor.token_meta(token).await;
+    let price_mon = sdk_executor.get_token_price_mon(token, meta.one_token()).await?;
+    let supply = sdk_executor.get_total_supply(token).await?;
+    let supply_tokens = meta.to_tokens(supply);
+
+    Ok(price_mon * supply_tokens * mon_price_usd)
+}
@@ -3,6 +3,16 @@
 
 //! Trading strategies.
 
+pub mod blacklist;
+pub mod limit_buy;
+pub mod pending_orders;
+pub mod smart_wallets;
 pub mod sniper;
+pub mod throttle;
 
+pub use blacklist::Blacklist;
+pub use limit_buy::{get_market_cap_usd, spawn_limit_buyer, LimitBuyConfig};
+pub use pending_orders::{spawn_order_watcher, PendingOrderConfig};
+pub use smart_wallets::SmartWallets;
 pub use sniper::{BuyDecision, SniperStrategy};
+pub use throttle::BuyThrottle;
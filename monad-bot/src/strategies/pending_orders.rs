@@ -0,0 +1,379 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Pending (dip) orders: the mirror image of limit-buy mode.
+//!
+//! Tokens that pass safety but are already above the entry zone are parked
+//! as a conditional buy at a target MCap (`PENDING_ORDER_TARGET_MCAP_USD`,
+//! default the top of the zone). A background task prices every order each
+//! interval and buys once the MCap retraces to the target, as long as it
+//! hasn't fallen through the bottom of the zone. Orders expire after a TTL
+//! and survive restarts in `pending_orders.json`.
+
+use crate::config::Config;
+use crate::executor::SwapExecutor;
+use crate::listeners::NewTokenEvent;
+use crate::persist::write_atomic;
+use crate::price::MonPriceOracle;
+use crate::strategies::{BuyDecision, SniperStrategy};
+use crate::validators::TradingCheck;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+const PENDING_ORDERS_FILE: &str = "pending_orders.json";
+
+/// Pending-order configuration.
+#[derive(Debug, Clone)]
+pub struct PendingOrderConfig {
+    /// Seconds between pricing rounds.
+    pub poll_interval_sec: u64,
+    /// Minutes an order stays armed.
+    pub ttl_minutes: u64,
+    /// Buy once the MCap is at or below this (USD).
+    pub target_market_cap_usd: f64,
+    /// Below this (USD) the dip is treated as a collapse and the order cancelled.
+    pub min_market_cap_usd: f64,
+    /// Live MON price used for MCap.
+    pub mon_price: MonPriceOracle,
+    /// Amount to buy when the order triggers.
+    pub amount_wei: U256,
+}
+
+impl PendingOrderConfig {
+    pub fn from_config(config: &Config, strategy: &SniperStrategy) -> Self {
+        Self {
+            poll_interval_sec: config.pending_order_poll_interval_sec.max(1),
+            ttl_minutes: config.pending_order_ttl_minutes,
+            target_market_cap_usd: config
+                .pending_order_target_mcap_usd
+                .unwrap_or(strategy.filters.max_market_cap_usd),
+            min_market_cap_usd: strategy.filters.min_market_cap_usd,
+            mon_price: strategy.mon_price.clone(),
+            amount_wei: strategy.snipe_amount_wei,
+        }
+    }
+}
+
+/// A conditional buy waiting for its token to dip to the target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingOrder {
+    pub token: Address,
+    pub name: String,
+    pub symbol: String,
+    pub target_market_cap_usd: f64,
+    pub amount_wei: U256,
+    /// Unix secs.
+    pub created_at: u64,
+    /// Unix secs; the order is dropped from this point on.
+    pub expires_at: u64,
+}
+
+impl PendingOrder {
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Whether a market cap of `market_cap_usd` fills the order.
+    pub fn is_triggered(&self, market_cap_usd: f64) -> bool {
+        market_cap_usd <= self.target_market_cap_usd
+    }
+}
+
+/// Armed orders by token, mirrored to `pending_orders.json`.
+#[derive(Debug, Default)]
+pub struct PendingOrderBook {
+    orders: HashMap<Address, PendingOrder>,
+}
+
+impl PendingOrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load orders from file, dropping any that expired while the bot was down.
+    pub fn load(now: u64) -> Self {
+        let path = Path::new(PENDING_ORDERS_FILE);
+        if !path.exists() {
+            return Self::new();
+        }
+
+        let orders = match fs::read_to_string(path) {
+            Ok(content) => match serde_json::from_str::<HashMap<Address, PendingOrder>>(&content) {
+                Ok(orders) => orders,
+                Err(e) => {
+                    error!("Failed to parse pending orders file: {}", e);
+                    return Self::new();
+                }
+            },
+            Err(e) => {
+                error!("Failed to read pending orders file: {}", e);
+                return Self::new();
+            }
+        };
+
+        let total = orders.len();
+        let orders: HashMap<Address, PendingOrder> =
+            orders.into_iter().filter(|(_, order)| !order.is_expired(now)).collect();
+        info!("Loaded {} pending orders from file ({} expired)", orders.len(), total - orders.len());
+        Self { orders }
+    }
+
+    /// Save orders to file.
+    pub fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&self.orders)
+            .map_err(|e| format!("Failed to serialize pending orders: {}", e))?;
+        write_atomic(PENDING_ORDERS_FILE, content)
+            .map_err(|e| format!("Failed to write pending orders file: {}", e))
+    }
+
+    /// Arm `order`. Returns false if one is already armed for the token.
+    pub fn insert(&mut self, order: PendingOrder) -> bool {
+        if self.orders.contains_key(&order.token) {
+            return false;
+        }
+        self.orders.insert(order.token, order);
+        true
+    }
+
+    pub fn remove(&mut self, token: &Address) -> Option<PendingOrder> {
+        self.orders.remove(token)
+    }
+
+    pub fn get(&self, token: &Address) -> Option<&PendingOrder> {
+        self.orders.get(token)
+    }
+
+    pub fn tokens(&self) -> Vec<Address> {
+        self.orders.keys().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Remove and return the orders expired at `now`.
+    pub fn take_expired(&mut self, now: u64) -> Vec<PendingOrder> {
+        let expired: Vec<Address> = self
+            .orders
+            .values()
+            .filter(|order| order.is_expired(now))
+            .map(|order| order.token)
+            .collect();
+        expired.iter().filter_map(|token| self.orders.remove(token)).collect()
+    }
+}
+
+/// Spawn the pending-order watcher as a background task.
+/// Tokens to park arrive on `register_rx`; filled orders go out on `buy_tx`.
+/// Orders are priced by `buy_executor`, the venue the filled buy goes through.
+/// Trading is re-checked before a filled buy, like limit buys.
+pub fn spawn_order_watcher<P: Provider + Clone + 'static>(
+    buy_executor: Arc<SwapExecutor<P>>,
+    trading_check: Option<Arc<TradingCheck>>,
+    config: PendingOrderConfig,
+    mut register_rx: mpsc::Receiver<NewTokenEvent>,
+    buy_tx: mpsc::Sender<BuyDecision>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut book = PendingOrderBook::load(unix_now());
+        info!(
+            "📌 Pending-order watcher started (target ${:.0}k, poll {}s, TTL {}min, {} armed)",
+            config.target_market_cap_usd / 1000.0,
+            config.poll_interval_sec,
+            config.ttl_minutes,
+            book.len()
+        );
+
+        let mut tick = tokio::time::interval(Duration::from_secs(config.poll_interval_sec));
+
+        loop {
+            tokio::select! {
+                Some(event) = register_rx.recv() => {
+                    let now = unix_now();
+                    let order = PendingOrder {
+                        token: event.token_address,
+                        name: event.name,
+                        symbol: event.symbol,
+                        target_market_cap_usd: config.target_market_cap_usd,
+                        amount_wei: config.amount_wei,
+                        created_at: now,
+                        expires_at: now + config.ttl_minutes * 60,
+                    };
+                    let (name, symbol) = (order.name.clone(), order.symbol.clone());
+                    if !book.insert(order) {
+                        continue;
+                    }
+                    info!(
+                        "📌 DIP ORDER armed: {} ({}) - buying if mcap retraces to ${:.0}k",
+                        name, symbol, config.target_market_cap_usd / 1000.0
+                    );
+                    save(&book);
+                }
+                _ = tick.tick() => {
+                    if book.is_empty() {
+                        continue;
+                    }
+                    let mut changed = false;
+
+                    for order in book.take_expired(unix_now()) {
+                        info!(
+                            "⌛ DIP ORDER expired: {} ({}) never retraced to ${:.0}k",
+                            order.name, order.symbol, order.target_market_cap_usd / 1000.0
+                        );
+                        changed = true;
+                    }
+
+                    for token in book.tokens() {
+                        let market_cap_usd = match buy_executor.market_cap_usd(token, config.mon_price.price_usd_or_fallback()).await {
+                            Ok(mcap) => mcap,
+                            Err(e) => {
+                                debug!("Pending-order poll failed for {:?}: {}", token, e);
+                                continue;
+                            }
+                        };
+
+                        if market_cap_usd < config.min_market_cap_usd {
+                            if let Some(order) = book.remove(&token) {
+                                warn!(
+                                    "❌ DIP ORDER cancelled: {} ({}) fell through the zone (${:.0}k)",
+                                    order.name, order.symbol, market_cap_usd / 1000.0
+                                );
+                                changed = true;
+                            }
+                            continue;
+                        }
+
+                        let Some(order) = book.get(&token) else { continue };
+                        if !order.is_triggered(market_cap_usd) {
+                            debug!("Pending order {:?} at ${:.0}k, still waiting", token, market_cap_usd / 1000.0);
+                            continue;
+                        }
+
+                        if let Some(trading_check) = &trading_check {
+                            if !trading_check.is_enabled(token).await {
+                                debug!("Pending order {:?} at target but trading disabled, waiting", token);
+                                continue;
+                            }
+                        }
+
+                        let Some(order) = book.remove(&token) else { continue };
+                        changed = true;
+                        info!(
+                            "🟢 DIP ORDER filled: {} ({}) retraced to ${:.0}k",
+                            order.name, order.symbol, market_cap_usd / 1000.0
+                        );
+
+                        let decision = BuyDecision {
+                            token,
+                            amount_wei: order.amount_wei,
+                            name: order.name,
+                            symbol: order.symbol,
+                            reason: format!(
+                                "Dip buy at ${:.0}k mcap (target ${:.0}k)",
+                                market_cap_usd / 1000.0,
+                                order.target_market_cap_usd / 1000.0
+                            ),
+                            priority: false,
+                        };
+                        if let Err(e) = buy_tx.send(decision).await {
+                            warn!("Failed to send dip buy: {}", e);
+                        }
+                    }
+
+                    if changed {
+                        save(&book);
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn save(book: &PendingOrderBook) {
+    if let Err(e) = book.save() {
+        error!("{}", e);
+    }
+}
+
+fn unix_now() -> u64 {
+    chrono::Utc::now().timestamp() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(byte: u8, expires_at: u64) -> PendingOrder {
+        PendingOrder {
+            token: Address::repeat_byte(byte),
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            target_market_cap_usd: 200_000.0,
+            amount_wei: U256::from(1u64),
+            created_at: 0,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn order_fills_at_or_below_the_target_until_it_expires() {
+        let order = order(1, 100);
+        assert!(order.is_triggered(200_000.0));
+        assert!(order.is_triggered(150_000.0));
+        assert!(!order.is_triggered(200_001.0));
+        assert!(!order.is_expired(99));
+        assert!(order.is_expired(100));
+    }
+
+    #[test]
+    fn one_order_per_token() {
+        let mut book = PendingOrderBook::new();
+        assert!(book.insert(order(1, 100)));
+        assert!(!book.insert(order(1, 500)));
+        assert_eq!(book.get(&Address::repeat_byte(1)).map(|o| o.expires_at), Some(100));
+        assert!(book.remove(&Address::repeat_byte(1)).is_some());
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn expired_orders_are_taken_out() {
+        let mut book = PendingOrderBook::new();
+        book.insert(order(1, 100));
+        book.insert(order(2, 300));
+
+        let expired = book.take_expired(200);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].token, Address::repeat_byte(1));
+        assert_eq!(book.tokens(), vec![Address::repeat_byte(2)]);
+    }
+
+    #[test]
+    fn target_defaults_to_the_top_of_the_entry_zone() {
+        let config = crate::config::tests::config_with(&[]).unwrap();
+        let strategy = SniperStrategy::from_config(&config);
+        let orders = PendingOrderConfig::from_config(&config, &strategy);
+        assert_eq!(orders.target_market_cap_usd, strategy.filters.max_market_cap_usd);
+        assert_eq!(orders.min_market_cap_usd, strategy.filters.min_market_cap_usd);
+
+        let config = crate::config::tests::config_with(&[
+            ("PENDING_ORDER_TARGET_MCAP_USD", "150000"),
+            ("PENDING_ORDER_POLL_INTERVAL_SEC", "0"),
+        ])
+        .unwrap();
+        let orders = PendingOrderConfig::from_config(&config, &strategy);
+        assert_eq!(orders.target_market_cap_usd, 150_000.0);
+        assert_eq!(orders.poll_interval_sec, 1);
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Smart wallets to copy, with optional per-wallet sizing.
+//!
+//! `SMART_WALLETS_FILE` points to a JSON array such as
+//!
+//! ```json
+//! [
+//!   { "address": "0x038a…", "copy_pct": 30.0, "max_mon": 10.0, "enabled": true },
+//!   { "address": "0x571b…", "enabled": false }
+//! ]
+//! ```
+//!
+//! `copy_pct` and `max_mon` fall back to `WHALE_COPY_PCT` and
+//! `MAX_SNIPE_AMOUNT`; `enabled` defaults to true. The file is re-read on
+//! SIGHUP. Without it (or if it can't be read at startup) the `SMART_WALLETS`
+//! env list is used with the global settings.
+
+use crate::config::Config;
+use alloy::primitives::Address;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+/// One entry of the wallets file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WalletSettings {
+    pub address: Address,
+    /// Share of the wallet's buy to copy, in percent (None = `WHALE_COPY_PCT`)
+    #[serde(default)]
+    pub copy_pct: Option<f64>,
+    /// Most MON committed per copied buy (None = `MAX_SNIPE_AMOUNT`)
+    #[serde(default)]
+    pub max_mon: Option<f64>,
+    /// Disabled wallets stay watched but their buys aren't copied
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl WalletSettings {
+    /// Entry for an env-list wallet: global sizing, enabled.
+    fn with_defaults(address: Address) -> Self {
+        Self {
+            address,
+            copy_pct: None,
+            max_mon: None,
+            enabled: true,
+        }
+    }
+}
+
+/// The configured smart wallets and their copy settings.
+#[derive(Debug, Clone)]
+pub struct SmartWallets {
+    wallets: HashMap<Address, WalletSettings>,
+    /// Reloaded on SIGHUP; None when the env list is used
+    file: Option<String>,
+    default_copy_pct: f64,
+    default_max_mon: f64,
+}
+
+impl SmartWallets {
+    pub fn new(wallets: Vec<WalletSettings>, file: Option<String>, default_copy_pct: f64, default_max_mon: f64) -> Self {
+        Self {
+            wallets: wallets.into_iter().map(|w| (w.address, w)).collect(),
+            file,
+            default_copy_pct,
+            default_max_mon,
+        }
+    }
+
+    /// Load `SMART_WALLETS_FILE` if set and readable, else the `SMART_WALLETS` list.
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = (config.whale_copy_pct, config.max_snipe_amount);
+
+        if let Some(path) = &config.smart_wallets_file {
+            match load_file(path) {
+                Ok(wallets) => {
+                    let enabled = wallets.iter().filter(|w| w.enabled).count();
+                    info!("👀 Loaded {} smart wallets from {} ({} enabled)", wallets.len(), path, enabled);
+                    return Self::new(wallets, Some(path.clone()), defaults.0, defaults.1);
+                }
+                Err(e) => warn!("⚠️ {}; falling back to SMART_WALLETS", e),
+            }
+        }
+
+        let wallets = config
+            .smart_wallets
+            .iter()
+            .filter_map(|raw| match Address::from_str(raw) {
+                Ok(address) => Some(WalletSettings::with_defaults(address)),
+                Err(e) => {
+                    warn!("⚠️ Ignoring invalid smart wallet '{}': {}", raw, e);
+                    None
+                }
+            })
+            .collect();
+        Self::new(wallets, None, defaults.0, defaults.1)
+    }
+
+    /// Re-read the wallets file. On error the current list is kept.
+    /// Returns the number of wallets loaded.
+    pub fn reload(&mut self) -> Result<usize, String> {
+        let Some(path) = self.file.clone() else {
+            return Err("SMART_WALLETS_FILE not set, nothing to reload".to_string());
+        };
+        let wallets = load_file(&path)?;
+        let count = wallets.len();
+        self.wallets = wallets.into_iter().map(|w| (w.address, w)).collect();
+        Ok(count)
+    }
+
+    /// Lowercased addresses, enabled or not, in the `SMART_WALLETS` format the listeners use.
+    pub fn addresses(&self) -> Vec<String> {
+        let mut addresses: Vec<String> = self.wallets.keys().map(|a| format!("{:?}", a).to_lowercase()).collect();
+        addresses.sort();
+        addresses
+    }
+
+    pub fn contains(&self, wallet: &Address) -> bool {
+        self.wallets.contains_key(wallet)
+    }
+
+    pub fn get(&self, wallet: &Address) -> Option<&WalletSettings> {
+        self.wallets.get(wallet)
+    }
+
+    /// Whether buys by `wallet` are copied. Wallets not in the list (e.g. promoted by the scout) are.
+    pub fn is_enabled(&self, wallet: &Address) -> bool {
+        self.get(wallet).map(|w| w.enabled).unwrap_or(true)
+    }
+
+    /// Percent of the wallet's buy to copy.
+    pub fn copy_pct(&self, wallet: &Address) -> f64 {
+        self.get(wallet).and_then(|w| w.copy_pct).unwrap_or(self.default_copy_pct)
+    }
+
+    /// Most MON to commit when copying the wallet.
+    pub fn max_mon(&self, wallet: &Address) -> f64 {
+        self.get(wallet).and_then(|w| w.max_mon).unwrap_or(self.default_max_mon)
+    }
+}
+
+/// Read and parse a wallets file.
+pub fn load_file(path: &str) -> Result<Vec<WalletSettings>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    parse_wallets(&contents).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Parse the JSON array of wallet entries. Later duplicates replace earlier ones.
+pub fn parse_wallets(json: &str) -> Result<Vec<WalletSettings>, String> {
+    let wallets: Vec<WalletSettings> = serde_json::from_str(json).map_err(|e| format!("Invalid wallets JSON: {}", e))?;
+    for wallet in &wallets {
+        if wallet.copy_pct.is_some_and(|pct| !pct.is_finite() || pct <= 0.0) {
+            return Err(format!("copy_pct for {:?} must be positive", wallet.address));
+        }
+        if wallet.max_mon.is_some_and(|max| !max.is_finite() || max <= 0.0) {
+            return Err(format!("max_mon for {:?} must be positive", wallet.address));
+        }
+    }
+    Ok(wallets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: Address = Address::new([0x11; 20]);
+
+    fn wallets(json: &str) -> SmartWallets {
+        SmartWallets::new(parse_wallets(json).unwrap(), None, 20.0, 5.0)
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_the_global_settings() {
+        let wallets = wallets(r#"[{ "address": "0x1111111111111111111111111111111111111111" }]"#);
+        assert!(wallets.is_enabled(&WALLET));
+        assert_eq!(wallets.copy_pct(&WALLET), 20.0);
+        assert_eq!(wallets.max_mon(&WALLET), 5.0);
+    }
+
+    #[test]
+    fn per_wallet_settings_override_the_globals() {
+        let wallets = wallets(
+            r#"[{ "address": "0x1111111111111111111111111111111111111111", "copy_pct": 30, "max_mon": 2.5, "enabled": false }]"#,
+        );
+        assert!(!wallets.is_enabled(&WALLET));
+        assert_eq!(wallets.copy_pct(&WALLET), 30.0);
+        assert_eq!(wallets.max_mon(&WALLET), 2.5);
+        // Disabled wallets are still watched
+        assert_eq!(wallets.addresses(), ["0x1111111111111111111111111111111111111111"]);
+    }
+
+    #[test]
+    fn unlisted_wallets_are_copied_at_the_globals() {
+        let wallets = wallets("[]");
+        let promoted = Address::repeat_byte(0x22);
+        assert!(!wallets.contains(&promoted));
+        assert!(wallets.is_enabled(&promoted));
+        assert_eq!(wallets.copy_pct(&promoted), 20.0);
+    }
+
+    #[test]
+    fn non_positive_sizing_is_rejected() {
+        let entry = |field: &str| format!(r#"[{{ "address": "0x1111111111111111111111111111111111111111", {} }}]"#, field);
+        assert!(parse_wallets(&entry(r#""copy_pct": 0"#)).unwrap_err().contains("copy_pct"));
+        assert!(parse_wallets(&entry(r#""max_mon": -1"#)).unwrap_err().contains("max_mon"));
+        assert!(parse_wallets("{}").is_err());
+    }
+
+    #[test]
+    fn bad_reload_keeps_the_current_list() {
+        let path = std::env::temp_dir().join(format!("smart-wallets-{}.json", std::process::id()));
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(&path, r#"[{ "address": "0x1111111111111111111111111111111111111111" }]"#).unwrap();
+        let mut wallets = SmartWallets::new(load_file(&path_str).unwrap(), Some(path_str), 20.0, 5.0);
+
+        fs::write(&path, r#"[{ "address": "0x2222222222222222222222222222222222222222" }]"#).unwrap();
+        assert_eq!(wallets.reload(), Ok(1));
+        assert!(wallets.contains(&Address::repeat_byte(0x22)));
+        assert!(!wallets.contains(&WALLET));
+
+        fs::write(&path, "not json").unwrap();
+        assert!(wallets.reload().is_err());
+        assert!(wallets.contains(&Address::repeat_byte(0x22)));
+
+        let _ = fs::remove_file(&path);
+        assert!(SmartWallets::new(Vec::new(), None, 20.0, 5.0).reload().is_err());
+    }
+
+    #[test]
+    fn unreadable_file_falls_back_to_the_env_list() {
+        let config = crate::config::tests::config_with(&[
+            ("SMART_WALLETS_FILE", "/nonexistent/wallets.json"),
+            ("SMART_WALLETS", "0x1111111111111111111111111111111111111111,not-an-address"),
+        ])
+        .unwrap();
+        let wallets = SmartWallets::from_config(&config);
+        assert_eq!(wallets.addresses(), ["0x1111111111111111111111111111111111111111"]);
+        assert_eq!(wallets.copy_pct(&WALLET), config.whale_copy_pct);
+    }
+}
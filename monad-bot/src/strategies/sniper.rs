@@ -9,11 +9,25 @@
 //! - DEX: Capricorn CLMM
 
 use crate::config::Config;
+use crate::jitter::Jitter;
 use crate::listeners::NewTokenEvent;
-use crate::validators::{check_liquidity, liquidity::mon_to_wei};
+use crate::position::{Position, PositionTracker};
+use crate::price::MonPriceOracle;
+use crate::reject_log::{RejectLog, RejectReason, RejectRecord};
+use crate::strategies::Blacklist;
+use crate::units::{mon_to_wei, wei_to_mon};
+use crate::validators::{check_liquidity, TradingCheck};
 use alloy::primitives::{Address, U256};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
+/// How far a launch timestamp may be ahead of the local clock and still count as age 0.
+const MAX_CLOCK_SKEW_SECS: u64 = 30;
+
 /// Decision to buy a token.
 #[derive(Debug, Clone)]
 pub struct BuyDecision {
@@ -22,12 +36,50 @@ pub struct BuyDecision {
     pub name: String,
     pub symbol: String,
     pub reason: String,
+    /// Allowlisted token/creator: skipped the soft filters, sized up.
+    pub priority: bool,
+}
+
+/// Sliding one-minute window limiting buy signals for one bucket.
+#[derive(Debug)]
+pub struct BuyBucket {
+    /// Max buys per minute (0 = unlimited).
+    limit_per_minute: u32,
+    recent: Mutex<VecDeque<Instant>>,
+}
+
+impl BuyBucket {
+    pub fn new(limit_per_minute: u32) -> Self {
+        Self {
+            limit_per_minute,
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Take a slot if the bucket has room this minute.
+    fn try_acquire(&self) -> bool {
+        if self.limit_per_minute == 0 {
+            return true;
+        }
+        let Ok(mut recent) = self.recent.lock() else { return true };
+        let window = Duration::from_secs(60);
+        while recent.front().is_some_and(|t| t.elapsed() >= window) {
+            recent.pop_front();
+        }
+        if recent.len() >= self.limit_per_minute as usize {
+            return false;
+        }
+        recent.push_back(Instant::now());
+        true
+    }
 }
 
 /// Monad/nad.fun specific filter configuration.
 /// Optimized for Monad's bonding curve mechanics.
 #[derive(Debug, Clone)]
 pub struct MonadFilters {
+    /// Minimum token age in minutes (default: 0 = no minimum).
+    pub min_age_minutes: u64,
     /// Maximum token age in minutes (default: 60 for Monad).
     pub max_age_minutes: u64,
     /// Maximum dev holding percentage (default: 10%).
@@ -42,8 +94,6 @@ pub struct MonadFilters {
     pub take_profit_mcap_usd: f64,
     /// Migration market cap (~$1.3M on nad.fun).
     pub migration_mcap_usd: f64,
-    /// MON price in USD (fetched dynamically ideally).
-    pub mon_price_usd: f64,
     /// Fixed profit multiplier (2x-3x target).
     pub profit_target_multiplier: f64,
 }
@@ -52,6 +102,7 @@ impl Default for MonadFilters {
     fn default() -> Self {
         Self {
             // Monad-specific parameters
+            min_age_minutes: 0,
             max_age_minutes: 60,           // Slower market than Solana
             max_dev_holding_pct: 10.0,     // Slightly more lenient
             max_insider_pct: 30.0,         // Higher threshold for Monad
@@ -59,23 +110,64 @@ impl Default for MonadFilters {
             max_market_cap_usd: 200_000.0, // Entry zone end
             take_profit_mcap_usd: 500_000.0, // TP target
             migration_mcap_usd: 1_300_000.0, // 80% sold = migration
-            mon_price_usd: 0.50,           // ~$0.50 per MON estimate
             profit_target_multiplier: 2.5, // 2.5x target
         }
     }
 }
 
+impl MonadFilters {
+    /// Check if approaching migration (80% sold).
+    pub fn is_near_migration(&self, current_mcap_usd: f64) -> bool {
+        current_mcap_usd >= self.migration_mcap_usd * 0.8
+    }
+}
+
 /// Sniper strategy configuration and logic.
 pub struct SniperStrategy {
     pub enabled: bool,
-    pub min_liquidity_wei: u128,
+    /// Set by the Telegram `/pause` command; new snipes are skipped while set.
+    pub paused: Arc<AtomicBool>,
+    /// MON-denominated liquidity floor (used when no USD floor applies).
+    pub min_liquidity_wei: U256,
+    /// USD-denominated liquidity floor, converted at evaluation time.
+    pub min_liquidity_usd: Option<f64>,
+    /// Live MON price for USD thresholds.
+    pub mon_price: MonPriceOracle,
     pub snipe_amount_wei: U256,
+    /// Random spread on buy amounts; never pushes a buy past `max_snipe_wei`.
+    pub amount_jitter: Jitter,
+    pub max_snipe_wei: U256,
     pub whale_min_wei: U256,
     pub whale_max_wei: U256,
     pub ai_filter_enabled: bool,
     pub ai_min_score: u32,
-    pub blacklist: Vec<String>,
+    pub blacklist: Blacklist,
+    /// Tokens always sniped (soft filters bypassed).
+    pub snipe_allowlist: Vec<Address>,
+    /// Creators whose launches are always sniped (soft filters bypassed).
+    pub creator_allowlist: Vec<Address>,
+    /// Treat `creator_allowlist` as an exclusive filter instead: launches by
+    /// anyone else (or by an unknown creator) are rejected, listed creators
+    /// still go through the regular filters.
+    pub creator_allowlist_only: bool,
+    pub allowlist_amount_wei: U256,
+    /// Max total MON committed to one token across buys (zero = no cap).
+    pub max_per_token_wei: U256,
+    /// Rate limit for regular snipes.
+    pub snipe_bucket: BuyBucket,
+    /// Separate rate limit for allowlisted snipes.
+    pub priority_bucket: BuyBucket,
     pub filters: MonadFilters,
+    /// Reject tokens whose creation time is unknown (instead of treating them as fresh).
+    pub require_known_age: bool,
+    /// Tokens that pass safety but sit below the entry zone are parked here.
+    pub limit_buy_tx: Option<mpsc::Sender<NewTokenEvent>>,
+    /// Tokens that pass safety but sit above the entry zone are parked here as dip orders.
+    pub pending_order_tx: Option<mpsc::Sender<NewTokenEvent>>,
+    /// Simulated-buy precheck for tokens that launch with trading disabled.
+    pub trading_check: Option<Arc<TradingCheck>>,
+    /// Where rejections are persisted for later review (`--review-rejects`).
+    pub reject_log: Option<RejectLog>,
 }
 
 impl SniperStrategy {
@@ -83,57 +175,109 @@ impl SniperStrategy {
     pub fn from_config(config: &Config) -> Self {
         Self {
             enabled: config.auto_snipe_enabled,
-            min_liquidity_wei: mon_to_wei(100.0), // 100 MON minimum for Monad
+            paused: Arc::new(AtomicBool::new(false)),
+            min_liquidity_wei: mon_to_wei(config.min_liquidity_mon),
+            min_liquidity_usd: config.min_liquidity_usd,
+            mon_price: MonPriceOracle::new(config.mon_price_usd),
             snipe_amount_wei: config.mon_to_wei(config.snipe_amount_mon),
+            amount_jitter: config.amount_jitter(),
+            max_snipe_wei: config.mon_to_wei(config.max_snipe_amount),
             whale_min_wei: config.mon_to_wei(config.whale_min_amount),
             whale_max_wei: config.mon_to_wei(config.whale_max_amount),
             ai_filter_enabled: config.ai_filter_enabled,
             ai_min_score: config.ai_min_score,
-            blacklist: config.blacklist.clone(),
-            filters: MonadFilters::default(),
+            blacklist: Blacklist::from_config(config),
+            snipe_allowlist: config.snipe_allowlist.clone(),
+            creator_allowlist: config.creator_allowlist.clone(),
+            creator_allowlist_only: config.creator_allowlist_only,
+            allowlist_amount_wei: config.mon_to_wei(config.allowlist_snipe_amount_mon),
+            max_per_token_wei: config.mon_to_wei(config.max_per_token_mon),
+            snipe_bucket: BuyBucket::new(config.max_snipes_per_minute),
+            priority_bucket: BuyBucket::new(config.max_priority_snipes_per_minute),
+            filters: MonadFilters {
+                min_age_minutes: config.min_token_age_minutes,
+                ..MonadFilters::default()
+            },
+            require_known_age: config.require_known_age,
+            limit_buy_tx: None,
+            pending_order_tx: None,
+            trading_check: None,
+            reject_log: config
+                .reject_log_enabled
+                .then(|| RejectLog::new(config.reject_log_max_mb * 1024 * 1024)),
         }
     }
 
     /// Evaluate whether to buy a new token on nad.fun.
     ///
     /// Returns `Some(BuyDecision)` if we should buy, `None` otherwise.
-    pub async fn should_buy(&self, token: &NewTokenEvent, analysis: &crate::validators::TokenAnalysis) -> Option<BuyDecision> {
+    /// `existing` is our open position in the token, if any; the buy is shrunk
+    /// or dropped so the total stays within `max_per_token_wei`.
+    pub async fn should_buy(
+        &self,
+        token: &NewTokenEvent,
+        analysis: &crate::validators::TokenAnalysis,
+        existing: Option<&Position>,
+    ) -> Option<BuyDecision> {
         if !self.enabled {
             debug!("Sniper disabled, skipping");
             return None;
         }
+        if self.paused.load(Ordering::Relaxed) {
+            debug!("Sniper paused, skipping");
+            return None;
+        }
 
         // ========================================
-        // FILTER 1: Blacklist check
+        // PRIORITY: Allowlisted token or creator
+        // ========================================
+        if self.is_allowlisted(token) {
+            return self.priority_buy(token, analysis, existing).await;
+        }
+
+        // ========================================
+        // FILTER 0: Creator allowlist (exclusive mode)
         // ========================================
-        let name_lower = token.name.to_lowercase();
-        let symbol_lower = token.symbol.to_lowercase();
+        if let Some(detail) = self.creator_not_allowed(token) {
+            self.reject(token, analysis, RejectReason::Creator, detail);
+            return None;
+        }
 
-        for word in &self.blacklist {
-            if name_lower.contains(word) || symbol_lower.contains(word) {
-                warn!(
-                    "❌ REJECT [BLACKLIST]: {} ({}) contains '{}'",
-                    token.name, token.symbol, word
-                );
-                return None;
-            }
+        // ========================================
+        // FILTER 1: Blacklist check
+        // ========================================
+        let blacklisted = self
+            .blacklist
+            .matched(&token.name)
+            .or_else(|| self.blacklist.matched(&token.symbol));
+        if let Some(entry) = blacklisted {
+            self.reject(
+                token,
+                analysis,
+                RejectReason::Blacklist,
+                format!("matches '{}'", entry),
+            );
+            return None;
         }
 
         // ========================================
         // FILTER 2: Minimum name length
         // ========================================
         if token.name.len() < 2 || token.symbol.len() < 1 {
-            warn!("❌ REJECT [NAME]: {} ({}) - too short", token.name, token.symbol);
+            self.reject(token, analysis, RejectReason::Name, "too short".to_string());
             return None;
         }
 
         // ========================================
-        // FILTER 3: Liquidity check (100 MON min for Monad)
+        // FILTER 3: Liquidity check (USD floor if priced, else MON floor)
         // ========================================
-        if !check_liquidity(token.initial_liquidity, Some(self.min_liquidity_wei)) {
-            warn!(
-                "❌ REJECT [LIQUIDITY]: {} ({}) - below 100 MON minimum",
-                token.name, token.symbol
+        let min_liquidity_wei = self.min_liquidity_threshold_wei();
+        if !check_liquidity(token.initial_liquidity, Some(min_liquidity_wei)) {
+            self.reject(
+                token,
+                analysis,
+                RejectReason::Liquidity,
+                format!("below {:.1} MON minimum", wei_to_mon(min_liquidity_wei)),
             );
             return None;
         }
@@ -142,19 +286,32 @@ impl SniperStrategy {
         // FILTER 3.5: Safety Analysis (On-Chain)
         // ========================================
         if !analysis.is_safe {
-            warn!(
-                "❌ REJECT [SAFETY]: {} ({}) - Unsafe: {}",
-                token.name, token.symbol, 
-                analysis.rejection_reason.as_deref().unwrap_or("Unknown reason")
+            self.reject(
+                token,
+                analysis,
+                RejectReason::Safety,
+                format!(
+                    "Unsafe: {}",
+                    analysis.rejection_reason.as_deref().unwrap_or("Unknown reason")
+                ),
             );
             return None;
         }
 
+        if analysis.is_bundled {
+            self.reject(token, analysis, RejectReason::Safety, "Bundled top holders".to_string());
+            return None;
+        }
+
         if analysis.dev_holding_pct > self.filters.max_dev_holding_pct {
-            warn!(
-                "❌ REJECT [DEV]: {} ({}) - Dev holds {:.1}% > {}%",
-                token.name, token.symbol, 
-                analysis.dev_holding_pct, self.filters.max_dev_holding_pct
+            self.reject(
+                token,
+                analysis,
+                RejectReason::DevHolding,
+                format!(
+                    "Dev holds {:.1}% > {}%",
+                    analysis.dev_holding_pct, self.filters.max_dev_holding_pct
+                ),
             );
             return None;
         }
@@ -162,11 +319,37 @@ impl SniperStrategy {
         // ========================================
         // FILTER 4: Token Age (max 60 min for Monad)
         // ========================================
-        let age_minutes = self.get_token_age_minutes(token);
+        let age_minutes = match self.get_token_age_minutes(token) {
+            Some(age) => age,
+            None if self.require_known_age => {
+                self.reject(
+                    token,
+                    analysis,
+                    RejectReason::Age,
+                    "creation time unknown".to_string(),
+                );
+                return None;
+            }
+            None => {
+                debug!("Creation time unknown for {:?}, treating as fresh", token.token_address);
+                0
+            }
+        };
+        if age_minutes < self.filters.min_age_minutes {
+            self.reject(
+                token,
+                analysis,
+                RejectReason::Age,
+                format!("{} min < {} min", age_minutes, self.filters.min_age_minutes),
+            );
+            return None;
+        }
         if age_minutes > self.filters.max_age_minutes {
-            warn!(
-                "❌ REJECT [AGE]: {} ({}) - {} min > {} max",
-                token.name, token.symbol, age_minutes, self.filters.max_age_minutes
+            self.reject(
+                token,
+                analysis,
+                RejectReason::Age,
+                format!("{} min > {} max", age_minutes, self.filters.max_age_minutes),
             );
             return None;
         }
@@ -183,15 +366,35 @@ impl SniperStrategy {
                 market_cap_usd / 1000.0, 
                 self.filters.min_market_cap_usd / 1000.0
             );
+            if let Some(limit_buy_tx) = &self.limit_buy_tx {
+                if let Err(e) = limit_buy_tx.try_send(token.clone()) {
+                    warn!("Failed to register limit buy for {:?}: {}", token.token_address, e);
+                }
+            }
             return None;
         }
         
         if market_cap_usd > self.filters.max_market_cap_usd {
-            warn!(
-                "❌ REJECT [MCAP]: {} ({}) - ${:.0}k > ${:.0}k (past entry zone)",
-                token.name, token.symbol, 
-                market_cap_usd / 1000.0, 
-                self.filters.max_market_cap_usd / 1000.0
+            if let Some(pending_order_tx) = &self.pending_order_tx {
+                info!(
+                    "⏳ WAIT [MCAP]: {} ({}) - ${:.0}k > ${:.0}k (past entry zone, parking dip order)",
+                    token.name, token.symbol,
+                    market_cap_usd / 1000.0,
+                    self.filters.max_market_cap_usd / 1000.0
+                );
+                if let Err(e) = pending_order_tx.try_send(token.clone()) {
+                    warn!("Failed to register dip order for {:?}: {}", token.token_address, e);
+                }
+                return None;
+            }
+            self.reject(
+                token,
+                analysis,
+                RejectReason::MarketCap,
+                format!(
+                    "${:.0}k > ${:.0}k (past entry zone)",
+                    market_cap_usd / 1000.0, self.filters.max_market_cap_usd / 1000.0
+                ),
             );
             return None;
         }
@@ -201,9 +404,38 @@ impl SniperStrategy {
         // ========================================
         let potential_profit = self.filters.take_profit_mcap_usd / market_cap_usd;
         if potential_profit < 2.0 {
-            warn!(
-                "❌ REJECT [R/R]: {} ({}) - only {:.1}x potential (need 2x+)",
-                token.name, token.symbol, potential_profit
+            self.reject(
+                token,
+                analysis,
+                RejectReason::RiskReward,
+                format!("only {:.1}x potential (need 2x+)", potential_profit),
+            );
+            return None;
+        }
+
+        // ========================================
+        // FILTER 7: Trading enabled (simulated buy)
+        // ========================================
+        if let Some(trading_check) = &self.trading_check {
+            if !trading_check.is_enabled(token.token_address).await {
+                self.reject(
+                    token,
+                    analysis,
+                    RejectReason::Trading,
+                    "trading not enabled yet".to_string(),
+                );
+                return None;
+            }
+        }
+
+        let amount = self.capped_amount(token, analysis, self.jittered(self.snipe_amount_wei), existing)?;
+
+        if !self.snipe_bucket.try_acquire() {
+            self.reject(
+                token,
+                analysis,
+                RejectReason::RateLimit,
+                "snipe rate limit reached".to_string(),
             );
             return None;
         }
@@ -211,7 +443,6 @@ impl SniperStrategy {
         // ========================================
         // ALL FILTERS PASSED - BUY SIGNAL!
         // ========================================
-        let amount = self.snipe_amount_wei;
         let distance_to_migration = self.filters.migration_mcap_usd / market_cap_usd;
 
         info!(
@@ -232,29 +463,229 @@ impl SniperStrategy {
                 "Entry at ${:.0}k mcap, {:.1}x potential, {:.1}x to migration",
                 market_cap_usd / 1000.0, potential_profit, distance_to_migration
             ),
+            priority: false,
         })
     }
 
-    /// Calculate token age in minutes.
-    fn get_token_age_minutes(&self, token: &NewTokenEvent) -> u64 {
-        let now = chrono::Utc::now().timestamp() as u64;
-        if let Some(ts) = token.timestamp {
-            if ts > 0 && ts < now {
-                return (now - ts) / 60;
+    /// Whether the token or its creator is allowlisted for a priority snipe.
+    /// In exclusive mode the creator list only gates launches, so it never
+    /// grants priority.
+    fn is_allowlisted(&self, token: &NewTokenEvent) -> bool {
+        self.snipe_allowlist.contains(&token.token_address)
+            || (!self.creator_allowlist_only
+                && token
+                    .creator
+                    .is_some_and(|creator| self.creator_allowlist.contains(&creator)))
+    }
+
+    /// Why the exclusive creator allowlist rejects `token`, if it does. An
+    /// unknown creator fails: with the filter on, only a known listed creator passes.
+    fn creator_not_allowed(&self, token: &NewTokenEvent) -> Option<String> {
+        if !self.creator_allowlist_only || self.creator_allowlist.is_empty() {
+            return None;
+        }
+        match token.creator {
+            Some(creator) if self.creator_allowlist.contains(&creator) => None,
+            Some(creator) => Some(format!("creator {:?} not allowlisted", creator)),
+            None => Some("creator unknown".to_string()),
+        }
+    }
+
+    /// Allowlisted launches skip the soft filters (blacklist words, liquidity,
+    /// age, MCap zone, R/R) but still must pass the hard safety checks.
+    async fn priority_buy(
+        &self,
+        token: &NewTokenEvent,
+        analysis: &crate::validators::TokenAnalysis,
+        existing: Option<&Position>,
+    ) -> Option<BuyDecision> {
+        if !analysis.is_safe {
+            self.reject(
+                token,
+                analysis,
+                RejectReason::Safety,
+                format!(
+                    "allowlisted - Unsafe: {}",
+                    analysis.rejection_reason.as_deref().unwrap_or("Unknown reason")
+                ),
+            );
+            return None;
+        }
+
+        if analysis.is_bundled {
+            self.reject(
+                token,
+                analysis,
+                RejectReason::Safety,
+                "allowlisted - Bundled top holders".to_string(),
+            );
+            return None;
+        }
+
+        if let Some(trading_check) = &self.trading_check {
+            if !trading_check.is_enabled(token.token_address).await {
+                self.reject(
+                    token,
+                    analysis,
+                    RejectReason::Trading,
+                    "allowlisted - trading not enabled yet".to_string(),
+                );
+                return None;
+            }
+        }
+
+        let amount = self.capped_amount(token, analysis, self.jittered(self.allowlist_amount_wei), existing)?;
+
+        if !self.priority_bucket.try_acquire() {
+            self.reject(
+                token,
+                analysis,
+                RejectReason::RateLimit,
+                "priority rate limit reached".to_string(),
+            );
+            return None;
+        }
+
+        info!("⭐ PRIORITY BUY SIGNAL: {} ({}) - allowlisted", token.name, token.symbol);
+
+        Some(BuyDecision {
+            token: token.token_address,
+            amount_wei: amount,
+            name: token.name.clone(),
+            symbol: token.symbol.clone(),
+            reason: "Allowlisted token/creator (priority snipe)".to_string(),
+            priority: true,
+        })
+    }
+
+    /// `amount` with the configured jitter applied. Jitter never lifts a buy
+    /// above `max_snipe_wei` (or above `amount`, if that is already larger).
+    fn jittered(&self, amount: U256) -> U256 {
+        self.amount_jitter.apply_wei(amount).min(amount.max(self.max_snipe_wei))
+    }
+
+    /// Shrink `amount` so our total in the token stays within `max_per_token_wei`.
+    /// Rejects (returns `None`) once the position is already at the cap.
+    fn capped_amount(
+        &self,
+        token: &NewTokenEvent,
+        analysis: &crate::validators::TokenAnalysis,
+        amount: U256,
+        existing: Option<&Position>,
+    ) -> Option<U256> {
+        let held_wei = held_cost_wei(existing);
+        match per_token_allowance(amount, held_wei, self.max_per_token_wei) {
+            Some(capped) if capped < amount => {
+                info!(
+                    "✂️ Position cap: {} ({}) buy reduced {:.2} -> {:.2} MON",
+                    token.name,
+                    token.symbol,
+                    wei_to_mon(amount),
+                    wei_to_mon(capped)
+                );
+                Some(capped)
+            }
+            Some(capped) => Some(capped),
+            None => {
+                self.reject(
+                    token,
+                    analysis,
+                    RejectReason::PositionCap,
+                    format!(
+                        "already holding {:.2} MON (cap {:.2} MON)",
+                        wei_to_mon(held_wei),
+                        wei_to_mon(self.max_per_token_wei)
+                    ),
+                );
+                None
+            }
+        }
+    }
+
+    /// Shrink a buy of `token` so our total stays within `max_per_token_wei`,
+    /// counting what `positions` already holds of it. Every buy path checks
+    /// this right before sending, since the position may have grown since the
+    /// buy was sized. Logs and returns `None` once the position is at the cap.
+    pub fn per_token_amount(&self, positions: &PositionTracker, token: Address, amount: U256) -> Option<U256> {
+        let held_wei = held_cost_wei(positions.get(&token));
+        match per_token_allowance(amount, held_wei, self.max_per_token_wei) {
+            Some(capped) if capped < amount => {
+                info!(
+                    "✂️ Position cap: buy of {:?} reduced {:.2} -> {:.2} MON",
+                    token,
+                    wei_to_mon(amount),
+                    wei_to_mon(capped)
+                );
+                Some(capped)
+            }
+            Some(capped) => Some(capped),
+            None => {
+                warn!(
+                    "🧢 Position cap: skipping buy of {:?}, already holding {:.2} MON (cap {:.2} MON)",
+                    token,
+                    wei_to_mon(held_wei),
+                    wei_to_mon(self.max_per_token_wei)
+                );
+                None
             }
         }
-        0 // Fresh token
+    }
+
+    /// Log a rejection and persist it with the metrics the filters saw.
+    fn reject(
+        &self,
+        token: &NewTokenEvent,
+        analysis: &crate::validators::TokenAnalysis,
+        reason: RejectReason,
+        detail: String,
+    ) {
+        warn!("❌ REJECT [{}]: {} ({}) - {}", reason, token.name, token.symbol, detail);
+
+        if let Some(reject_log) = &self.reject_log {
+            reject_log.append(&RejectRecord {
+                token: token.token_address,
+                name: token.name.clone(),
+                symbol: token.symbol.clone(),
+                reason,
+                detail,
+                market_cap_usd: self.estimate_market_cap(token),
+                dev_holding_pct: analysis.dev_holding_pct,
+                age_minutes: self.get_token_age_minutes(token),
+                liquidity_mon: token
+                    .initial_liquidity
+                    .map(wei_to_mon)
+                    .unwrap_or(0.0),
+                timestamp: chrono::Utc::now().timestamp() as u64,
+            });
+        }
+    }
+
+    /// Liquidity floor in wei: the USD floor at the live MON price when both are
+    /// available, otherwise the MON floor.
+    fn min_liquidity_threshold_wei(&self) -> U256 {
+        match (self.min_liquidity_usd, self.mon_price.price_usd()) {
+            (Some(min_usd), Some(price)) => mon_to_wei(min_usd / price),
+            _ => self.min_liquidity_wei,
+        }
+    }
+
+    /// Token age in minutes from its creation (launch block) timestamp.
+    /// `None` when the timestamp is missing, zero, or further in the future
+    /// than block times drift from the local clock.
+    fn get_token_age_minutes(&self, token: &NewTokenEvent) -> Option<u64> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        match token.timestamp {
+            Some(ts) if ts > 0 && ts <= now + MAX_CLOCK_SKEW_SECS => Some(now.saturating_sub(ts) / 60),
+            _ => None,
+        }
     }
 
     /// Estimate market cap in USD.
     /// For nad.fun bonding curve: MCap ≈ Liquidity * 2
     /// Migration happens at ~$1.3M when 80% tokens sold
     fn estimate_market_cap(&self, token: &NewTokenEvent) -> f64 {
-        let liquidity_wei = token.initial_liquidity
-            .map(|l| l.to::<u128>())
-            .unwrap_or(0);
-        let liquidity_mon = liquidity_wei as f64 / 1e18;
-        let liquidity_usd = liquidity_mon * self.filters.mon_price_usd;
+        let liquidity_mon = token.initial_liquidity.map_or(0.0, wei_to_mon);
+        let liquidity_usd = liquidity_mon * self.mon_price.price_usd_or_fallback();
         
         // Market cap ≈ 2x liquidity for bonding curve tokens
         liquidity_usd * 2.0
@@ -268,6 +699,217 @@ impl SniperStrategy {
 
     /// Check if approaching migration (80% sold).
     pub fn is_near_migration(&self, current_mcap_usd: f64) -> bool {
-        current_mcap_usd >= self.filters.migration_mcap_usd * 0.8
+        self.filters.is_near_migration(current_mcap_usd)
+    }
+}
+
+/// MON committed to `existing`, in wei (zero when nothing is held).
+fn held_cost_wei(existing: Option<&Position>) -> U256 {
+    existing.map_or(U256::ZERO, |position| mon_to_wei(position.buy_price_mon))
+}
+
+/// How much of `amount` can still be bought with `held` already committed
+/// under a per-token `cap` (zero = no cap). `None` once the cap is reached.
+pub fn per_token_allowance(amount: U256, held: U256, cap: U256) -> Option<U256> {
+    if cap.is_zero() {
+        return Some(amount);
+    }
+    let remaining = cap.saturating_sub(held);
+    (!remaining.is_zero()).then(|| amount.min(remaining))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mon(amount: u64) -> U256 {
+        U256::from(amount) * U256::from(10u64).pow(U256::from(18))
+    }
+
+    #[test]
+    fn no_cap_leaves_the_buy_alone() {
+        assert_eq!(per_token_allowance(mon(5), mon(100), U256::ZERO), Some(mon(5)));
+    }
+
+    #[test]
+    fn buy_is_shrunk_to_the_room_under_the_cap() {
+        assert_eq!(per_token_allowance(mon(5), U256::ZERO, mon(10)), Some(mon(5)));
+        assert_eq!(per_token_allowance(mon(5), mon(7), mon(10)), Some(mon(3)));
+    }
+
+    #[test]
+    fn position_at_or_over_the_cap_is_refused() {
+        assert_eq!(per_token_allowance(mon(5), mon(10), mon(10)), None);
+        assert_eq!(per_token_allowance(mon(5), mon(12), mon(10)), None);
+    }
+
+    #[test]
+    fn bucket_limits_buys_per_minute() {
+        let bucket = BuyBucket::new(2);
+        assert!(bucket.try_acquire());
+        assert!(bucket.try_acquire());
+        assert!(!bucket.try_acquire());
+        assert!((0..100).all(|_| BuyBucket::new(0).try_acquire()));
+    }
+
+    #[test]
+    fn near_migration_at_eighty_percent_of_the_cap() {
+        let filters = MonadFilters::default();
+        assert!(!filters.is_near_migration(1_000_000.0));
+        assert!(filters.is_near_migration(1_040_000.0));
+    }
+
+    const LISTED: Address = Address::new([0x22; 20]);
+
+    fn launch(creator: Option<Address>) -> NewTokenEvent {
+        NewTokenEvent {
+            token_address: Address::repeat_byte(0x11),
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            creator,
+            bonding_curve: None,
+            initial_liquidity: None,
+            timestamp: None,
+            tx_hash: None,
+        }
+    }
+
+    fn sniper(only: &str) -> SniperStrategy {
+        let config = crate::config::tests::config_with(&[
+            ("CREATOR_ALLOWLIST", "0x2222222222222222222222222222222222222222"),
+            ("CREATOR_ALLOWLIST_ONLY", only),
+        ])
+        .unwrap();
+        SniperStrategy::from_config(&config)
+    }
+
+    #[test]
+    fn listed_creator_gets_priority_unless_the_list_is_exclusive() {
+        assert!(sniper("false").is_allowlisted(&launch(Some(LISTED))));
+        assert!(!sniper("true").is_allowlisted(&launch(Some(LISTED))));
+    }
+
+    #[test]
+    fn exclusive_list_rejects_other_and_unknown_creators() {
+        let strategy = sniper("true");
+        assert_eq!(strategy.creator_not_allowed(&launch(Some(LISTED))), None);
+        assert!(strategy
+            .creator_not_allowed(&launch(Some(Address::repeat_byte(0x33))))
+            .is_some_and(|detail| detail.ends_with("not allowlisted")));
+        assert_eq!(strategy.creator_not_allowed(&launch(None)).as_deref(), Some("creator unknown"));
+    }
+
+    #[test]
+    fn exclusive_mode_is_off_with_an_empty_list_or_the_flag_unset() {
+        assert_eq!(sniper("false").creator_not_allowed(&launch(None)), None);
+
+        let mut strategy = sniper("true");
+        strategy.creator_allowlist.clear();
+        assert_eq!(strategy.creator_not_allowed(&launch(None)), None);
+    }
+
+    #[test]
+    fn jitter_never_lifts_a_buy_over_the_max_snipe() {
+        let mut strategy = sniper("false");
+        strategy.amount_jitter = Jitter::new(50.0, Some(9));
+        strategy.max_snipe_wei = mon(10);
+        for _ in 0..50 {
+            assert!(strategy.jittered(mon(8)) <= mon(10));
+            // Already over the max: jitter may only shrink it
+            assert!(strategy.jittered(mon(20)) <= mon(20));
+        }
+    }
+
+    #[test]
+    fn token_age_tolerates_small_clock_skew() {
+        let strategy = sniper("false");
+        let now = chrono::Utc::now().timestamp() as u64;
+        let launched = |timestamp| NewTokenEvent { timestamp, ..launch(None) };
+
+        assert_eq!(strategy.get_token_age_minutes(&launched(Some(now - 600))), Some(10));
+        // A block stamped a few seconds ahead of us is brand new, not unknown
+        assert_eq!(strategy.get_token_age_minutes(&launched(Some(now + 10))), Some(0));
+        assert_eq!(strategy.get_token_age_minutes(&launched(Some(now + 600))), None);
+        assert_eq!(strategy.get_token_age_minutes(&launched(Some(0))), None);
+        assert_eq!(strategy.get_token_age_minutes(&launched(None)), None);
+    }
+
+    fn safe_analysis(token: Address) -> crate::validators::TokenAnalysis {
+        crate::validators::TokenAnalysis {
+            token,
+            dev_wallet: None,
+            dev_holding_pct: 0.0,
+            top_holder_pct: 0.0,
+            holder_count: None,
+            holders_complete: false,
+            is_bundled: false,
+            total_supply: U256::ZERO,
+            market_cap_usd: 0.0,
+            age_minutes: 0,
+            is_safe: true,
+            rejection_reason: None,
+        }
+    }
+
+    #[test]
+    fn repeated_copy_buys_stop_at_the_per_token_cap() {
+        let config = crate::config::tests::config_with(&[("MAX_PER_TOKEN_MON", "10")]).unwrap();
+        let strategy = SniperStrategy::from_config(&config);
+        let mut positions = PositionTracker::in_memory();
+        let token = Address::repeat_byte(0x11);
+        let copy_buy = |buy_price_mon: f64| Position {
+            token,
+            name: "Moon".to_string(),
+            symbol: "MOON".to_string(),
+            amount: mon(1),
+            buy_price_mon,
+            buy_time: 1_700_000_000,
+            highest_price: buy_price_mon,
+            tx_hash: "0xcopy".to_string(),
+            provisional: false,
+            fired_tp_levels: Vec::new(),
+            sell_failures: 0,
+            stuck: false,
+            graduated: false,
+            peak_liquidity_mon: 0.0,
+            in_runner_mode: false,
+            decimals: 18,
+        };
+
+        // Each whale buy sizes a 6 MON copy; the tracker folds them into one position
+        assert_eq!(strategy.per_token_amount(&positions, token, mon(6)), Some(mon(6)));
+        positions.add(copy_buy(6.0));
+        assert_eq!(strategy.per_token_amount(&positions, token, mon(6)), Some(mon(4)));
+        positions.add(copy_buy(4.0));
+        assert_eq!(strategy.per_token_amount(&positions, token, mon(6)), None);
+
+        // Another token has the whole cap to itself
+        assert_eq!(strategy.per_token_amount(&positions, Address::repeat_byte(0x12), mon(6)), Some(mon(6)));
+    }
+
+    #[tokio::test]
+    async fn launch_past_the_entry_zone_is_parked_as_a_dip_order() {
+        let config = crate::config::tests::config_with(&[("REJECT_LOG_ENABLED", "false")]).unwrap();
+        let mut strategy = SniperStrategy::from_config(&config);
+        strategy.mon_price = MonPriceOracle::new(Some(1.0));
+        let (pending_order_tx, mut pending_order_rx) = mpsc::channel(1);
+        strategy.pending_order_tx = Some(pending_order_tx);
+
+        // 150k MON of liquidity at $1 is a $300k cap, over the $200k zone
+        let token = NewTokenEvent {
+            name: "Moon".to_string(),
+            symbol: "MOON".to_string(),
+            initial_liquidity: Some(mon(150_000)),
+            timestamp: Some(chrono::Utc::now().timestamp() as u64),
+            ..launch(None)
+        };
+        let analysis = safe_analysis(token.token_address);
+        assert!(strategy.should_buy(&token, &analysis, None).await.is_none());
+        assert_eq!(pending_order_rx.try_recv().map(|parked| parked.token_address), Ok(token.token_address));
+
+        // Inside the zone it is bought, not parked
+        let token = NewTokenEvent { initial_liquidity: Some(mon(50_000)), ..token };
+        assert!(strategy.should_buy(&token, &analysis, None).await.is_some());
+        assert!(pending_order_rx.try_recv().is_err());
     }
 }
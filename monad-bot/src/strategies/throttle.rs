@@ -0,0 +1,117 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Global buy throttle for the main loop's buy paths (snipes, limit buys, copy trades).
+//!
+//! The per-bucket `BuyBucket` limits only cap how many signals the sniper
+//! emits per minute; a burst of launches and copy events can still fire buys
+//! back-to-back and drain the wallet. This spaces buys out and caps how many
+//! positions are open at once. Blocked buys are skipped, not queued.
+
+use crate::config::Config;
+use std::time::{Duration, Instant};
+
+/// Minimum spacing between buys plus a cap on open positions.
+#[derive(Debug)]
+pub struct BuyThrottle {
+    /// Minimum time between two buys (zero = no spacing).
+    min_interval: Duration,
+    /// Max open positions (0 = unlimited).
+    max_open_positions: usize,
+    last_buy: Option<Instant>,
+}
+
+impl BuyThrottle {
+    pub fn new(min_interval: Duration, max_open_positions: usize) -> Self {
+        Self {
+            min_interval,
+            max_open_positions,
+            last_buy: None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            Duration::from_secs(config.min_seconds_between_buys),
+            config.max_open_positions,
+        )
+    }
+
+    /// Why a buy can't go ahead right now, if it can't. `open_positions` is the
+    /// current count; `adds_to_existing` buys top up a held token and don't
+    /// open a new position, so the cap doesn't apply to them.
+    pub fn check(&self, open_positions: usize, adds_to_existing: bool) -> Result<(), String> {
+        if let Some(last_buy) = self.last_buy {
+            let elapsed = last_buy.elapsed();
+            if elapsed < self.min_interval {
+                return Err(format!(
+                    "last buy {:.1}s ago (min {}s between buys)",
+                    elapsed.as_secs_f64(),
+                    self.min_interval.as_secs()
+                ));
+            }
+        }
+
+        if !adds_to_existing && self.max_open_positions > 0 && open_positions >= self.max_open_positions {
+            return Err(format!(
+                "{} positions open (max {})",
+                open_positions, self.max_open_positions
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Start the spacing window. Called when a buy is attempted, whether or
+    /// not it lands: a failed buy still spends gas.
+    pub fn record_buy(&mut self) {
+        self.last_buy = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buys_are_spaced_by_the_min_interval() {
+        let mut throttle = BuyThrottle::new(Duration::from_secs(60), 0);
+        assert_eq!(throttle.check(0, false), Ok(()));
+
+        throttle.record_buy();
+        let err = throttle.check(0, false).unwrap_err();
+        assert!(err.ends_with("(min 60s between buys)"), "{}", err);
+        // Spacing applies to top-ups too
+        assert!(throttle.check(0, true).is_err());
+    }
+
+    #[test]
+    fn zero_interval_never_spaces() {
+        let mut throttle = BuyThrottle::new(Duration::ZERO, 0);
+        throttle.record_buy();
+        assert_eq!(throttle.check(100, false), Ok(()));
+    }
+
+    #[test]
+    fn position_cap_blocks_new_positions_only() {
+        let throttle = BuyThrottle::new(Duration::ZERO, 3);
+        assert_eq!(throttle.check(2, false), Ok(()));
+        assert_eq!(throttle.check(3, false), Err("3 positions open (max 3)".to_string()));
+        assert_eq!(throttle.check(3, true), Ok(()));
+    }
+
+    #[test]
+    fn reads_its_limits_from_config() {
+        let config = crate::config::tests::config_with(&[
+            ("MIN_SECONDS_BETWEEN_BUYS", "30"),
+            ("MAX_OPEN_POSITIONS", "5"),
+        ])
+        .unwrap();
+        let throttle = BuyThrottle::from_config(&config);
+        assert_eq!(throttle.min_interval, Duration::from_secs(30));
+        assert_eq!(throttle.max_open_positions, 5);
+
+        let defaults = BuyThrottle::from_config(&crate::config::tests::config_with(&[]).unwrap());
+        assert_eq!((defaults.min_interval, defaults.max_open_positions), (Duration::ZERO, 0));
+    }
+}
@@ -1,42 +1,440 @@
 // Copyright (C) 2025 Category Labs, Inc.
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-//! Telegram notifier module.
+//! Telegram notifier module, plus the optional command listener
+//! (`/sell`, `/pause`, `/resume`, `/status`).
 
+use crate::config::Config;
+use crate::handlers::{emergency_slippage, sell_all};
+use crate::notify::{FlushFuture, MessageQueue, Notifier};
+use crate::position::{PositionTracker, SellDecision};
+use crate::trade_history::TradeHistory;
+use alloy::primitives::Address;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use teloxide::prelude::*;
-use tracing::{error, info};
+use teloxide::RequestError;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{error, info, warn};
 
+/// Telegram rejects messages longer than 4096 chars; coalesced batches stay under this.
+const MAX_BATCH_CHARS: usize = 4000;
+
+/// Minimum gap between sends (Telegram allows ~1 msg/sec per chat).
+const SEND_INTERVAL_MS: u64 = 1000;
+
+/// Attempts per batch when Telegram answers 429 Too Many Requests.
+const MAX_SEND_ATTEMPTS: u32 = 3;
+
+/// Notifier posting to one Telegram chat.
 #[derive(Clone)]
 pub struct TelegramNotifier {
-    bot: Option<Bot>,
-    chat_id: Option<ChatId>,
+    queue: MessageQueue,
 }
 
 impl TelegramNotifier {
-    pub fn new(token: Option<String>, chat_id: Option<String>) -> Self {
+    /// Create the notifier and spawn its background sender; `None` unless Telegram is configured.
+    pub fn new(token: Option<String>, chat_id: Option<String>) -> Option<Self> {
         info!("📱 Initializing Telegram: token={}, chat_id={}", 
               token.as_ref().map(|_| "SET").unwrap_or("NONE"),
               chat_id.as_ref().map(|_| "SET").unwrap_or("NONE"));
         
-        let bot = token.map(Bot::new);
-        let chat_id = chat_id.map(|id| {
-            if let Ok(num) = id.parse::<i64>() {
-                ChatId(num)
-            } else {
-                ChatId(0) // Invalid chat ID, won't send
+        let bot = Bot::new(token?);
+        let chat_id = match chat_id?.parse::<i64>() {
+            Ok(num) => ChatId(num),
+            Err(_) => ChatId(0), // Invalid chat ID, won't send
+        };
+
+        let queue = MessageQueue::spawn(
+            "Telegram",
+            Duration::from_millis(SEND_INTERVAL_MS),
+            MAX_BATCH_CHARS,
+            move |text| {
+                let bot = bot.clone();
+                async move { send_with_retry(&bot, chat_id, &text).await }
+            },
+        );
+        Some(Self { queue })
+    }
+}
+
+impl Notifier for TelegramNotifier {
+    fn send(&self, message: &str) {
+        self.queue.push(message.to_string());
+    }
+
+    fn flush(&self) -> FlushFuture<'_> {
+        Box::pin(self.queue.flush())
+    }
+}
+
+/// Send one message, honouring Telegram's `retry_after` on 429.
+async fn send_with_retry(bot: &Bot, chat_id: ChatId, text: &str) {
+    for attempt in 1..=MAX_SEND_ATTEMPTS {
+        match bot.send_message(chat_id, text).await {
+            Ok(_) => {
+                info!("📤 Sent Telegram message");
+                return;
             }
-        });
+            Err(RequestError::RetryAfter(retry_after)) if attempt < MAX_SEND_ATTEMPTS => {
+                warn!("⏳ Telegram rate limited, retrying in {}s", retry_after.seconds());
+                tokio::time::sleep(retry_after.duration()).await;
+            }
+            Err(e) => {
+                error!("Failed to send Telegram message: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// A command sent to the bot from the configured chat.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TelegramCommand {
+    /// Force-sell a token: the given percentage of the position, or all of it.
+    Sell(Address, Option<f64>),
+    /// Stop opening new positions (exits keep running).
+    Pause,
+    /// Resume opening new positions.
+    Resume,
+    /// Reply with the sniping state and open positions.
+    Status,
+    /// Cancel a stuck pending tx and resync the executors' nonces.
+    Unstick,
+    /// Pause sniping and emergency-sell every open position.
+    Panic,
+    /// Unwrap the wallet's whole WMON balance back to native MON.
+    Unwrap,
+    /// Write the trade history to a CSV file and reply with its path.
+    Export,
+}
+
+impl TelegramCommand {
+    /// Parse a message like `/sell 0xabc...` or `/status@my_bot`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts = text.split_whitespace();
+        let command = parts.next().unwrap_or_default();
+        // Group chats append the bot name: `/pause@my_bot`
+        let command = command.split('@').next().unwrap_or_default().to_lowercase();
 
-        Self { bot, chat_id }
+        match command.as_str() {
+            "/sell" => {
+                let token = parts.next().ok_or("Usage: /sell <token address> [percent]")?;
+                let token = Address::from_str(token).map_err(|_| format!("Invalid token address: {}", token))?;
+                let percent = match parts.next() {
+                    Some(pct) => match pct.trim_end_matches('%').parse::<f64>() {
+                        Ok(pct) if pct > 0.0 && pct <= 100.0 => Some(pct),
+                        _ => return Err(format!("Invalid percent: {} (expected 1-100)", pct)),
+                    },
+                    None => None,
+                };
+                Ok(Self::Sell(token, percent))
+            }
+            "/pause" => Ok(Self::Pause),
+            "/resume" => Ok(Self::Resume),
+            "/status" => Ok(Self::Status),
+            "/unstick" => Ok(Self::Unstick),
+            "/panic" => Ok(Self::Panic),
+            "/unwrap" => Ok(Self::Unwrap),
+            "/export" => Ok(Self::Export),
+            _ => Err("Commands: /sell <token> [percent], /pause, /resume, /status, /unstick, /panic, /unwrap, /export".to_string()),
+        }
     }
+}
+
+/// State the command listener acts on.
+struct CommandContext {
+    chat_id: ChatId,
+    positions: Arc<Mutex<PositionTracker>>,
+    sell_signal_tx: mpsc::Sender<(Address, SellDecision)>,
+    paused: Arc<AtomicBool>,
+    /// Asks the main loop to clear stuck transactions (it owns the executors).
+    unstick_tx: mpsc::Sender<()>,
+    /// Asks the main loop to unwrap WMON (the sell executor owns the nonce).
+    unwrap_tx: mpsc::Sender<()>,
+    trade_history: Arc<Mutex<TradeHistory>>,
+    /// How emergency sells are priced, for the /panic reply.
+    emergency_slippage: String,
+}
+
+/// Listen for commands from the configured chat and act on them.
+/// Messages from any other chat are ignored. Not spawned unless Telegram is configured.
+pub fn spawn_command_listener(
+    config: &Config,
+    positions: Arc<Mutex<PositionTracker>>,
+    sell_signal_tx: mpsc::Sender<(Address, SellDecision)>,
+    paused: Arc<AtomicBool>,
+    unstick_tx: mpsc::Sender<()>,
+    unwrap_tx: mpsc::Sender<()>,
+    trade_history: Arc<Mutex<TradeHistory>>,
+) -> Option<tokio::task::JoinHandle<()>> {
+    let chat_id = config.telegram_chat_id.as_deref().and_then(|id| id.parse::<i64>().ok());
+    let (Some(token), Some(chat_id)) = (config.telegram_token.clone(), chat_id) else {
+        warn!("⚠️ Telegram commands enabled but TELEGRAM_TOKEN / TELEGRAM_CHAT_ID are missing");
+        return None;
+    };
+
+    info!("🎛️ Telegram commands enabled (/sell, /pause, /resume, /status, /unstick, /panic, /unwrap, /export)");
+
+    let context = Arc::new(CommandContext {
+        chat_id: ChatId(chat_id),
+        positions,
+        sell_signal_tx,
+        paused,
+        unstick_tx,
+        unwrap_tx,
+        trade_history,
+        emergency_slippage: emergency_slippage(config),
+    });
 
-    pub async fn send_message(&self, message: &str) {
-        if let (Some(bot), Some(chat_id)) = (&self.bot, &self.chat_id) {
-            let result = bot.send_message(*chat_id, message).await;
-            match result {
-                Ok(_) => info!("📤 Sent Telegram message"),
-                Err(e) => error!("Failed to send Telegram message: {}", e),
+    Some(tokio::spawn(async move {
+        let handler = Update::filter_message().endpoint(move |bot: Bot, msg: Message| {
+            let context = Arc::clone(&context);
+            async move {
+                if msg.chat.id != context.chat_id {
+                    warn!("🚫 Ignoring Telegram message from unauthorized chat {}", msg.chat.id);
+                    return Ok(());
+                }
+                let Some(text) = msg.text() else { return Ok(()) };
+
+                let reply = match TelegramCommand::parse(text) {
+                    Ok(command) => run_command(&context, command).await,
+                    Err(usage) => usage,
+                };
+                bot.send_message(msg.chat.id, reply).await?;
+                Ok::<(), RequestError>(())
             }
+        });
+
+        Dispatcher::builder(Bot::new(token), handler)
+            .build()
+            .dispatch()
+            .await;
+    }))
+}
+
+/// Carry out a command and return the reply.
+async fn run_command(context: &CommandContext, command: TelegramCommand) -> String {
+    info!("🎛️ Telegram command: {:?}", command);
+
+    match command {
+        TelegramCommand::Sell(token, percent) => {
+            let Some(symbol) = context.positions.lock().await.get(&token).map(|p| p.symbol.clone()) else {
+                return format!("No open position in {:?}", token);
+            };
+            let (decision, what) = match percent {
+                Some(pct) if pct < 100.0 => (SellDecision::PartialManual { portion: pct / 100.0 }, format!("{}% of", pct)),
+                _ => (
+                    SellDecision::CopySell {
+                        reason: "Manual /sell from Telegram".to_string(),
+                    },
+                    "all of".to_string(),
+                ),
+            };
+            match context.sell_signal_tx.send((token, decision)).await {
+                Ok(()) => format!("📉 Selling {} {} ({:?})", what, symbol, token),
+                Err(e) => format!("❌ Failed to queue sell: {}", e),
+            }
+        }
+        TelegramCommand::Pause => {
+            context.paused.store(true, Ordering::Relaxed);
+            "⏸️ Sniping paused. Open positions are still managed.".to_string()
+        }
+        TelegramCommand::Resume => {
+            context.paused.store(false, Ordering::Relaxed);
+            "▶️ Sniping resumed.".to_string()
         }
+        TelegramCommand::Status => {
+            let positions = context.positions.lock().await;
+            let mut reply = format!(
+                "Sniping: {}\nOpen positions: {}",
+                if context.paused.load(Ordering::Relaxed) { "paused" } else { "active" },
+                positions.len()
+            );
+            for position in positions.all() {
+                reply.push_str(&format!(
+                    "\n• {} {:?} ({:.4} MON in{})",
+                    position.symbol,
+                    position.token,
+                    position.buy_price_mon,
+                    if position.stuck { ", stuck" } else { "" }
+                ));
+            }
+            reply
+        }
+        TelegramCommand::Unstick => match context.unstick_tx.try_send(()) {
+            Ok(()) => "🧹 Clearing stuck transactions...".to_string(),
+            Err(_) => "⏳ Already clearing stuck transactions".to_string(),
+        },
+        TelegramCommand::Panic => {
+            // Stop buying first so nothing new opens while the book is dumped
+            context.paused.store(true, Ordering::Relaxed);
+            let queued = sell_all(&context.positions, &context.sell_signal_tx, "Manual /panic from Telegram").await;
+            format!(
+                "🚨 Panic-selling {} positions at {}. Sniping paused; /resume to restart.",
+                queued, context.emergency_slippage
+            )
+        }
+        TelegramCommand::Unwrap => match context.unwrap_tx.try_send(()) {
+            Ok(()) => "📦 Unwrapping WMON...".to_string(),
+            Err(_) => "⏳ Already unwrapping WMON".to_string(),
+        },
+        TelegramCommand::Export => {
+            let path = format!("trades_{}.csv", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+            match context.trade_history.lock().await.export_csv(&path) {
+                Ok(()) => {
+                    let full_path = std::fs::canonicalize(&path).map(|p| p.display().to_string()).unwrap_or(path);
+                    format!("📤 Trades exported to {}", full_path)
+                }
+                Err(e) => format!("❌ Export failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::Position;
+
+    const TOKEN: &str = "0x1111111111111111111111111111111111111111";
+
+    #[test]
+    fn parses_commands_with_or_without_the_bot_name() {
+        assert_eq!(TelegramCommand::parse("/pause"), Ok(TelegramCommand::Pause));
+        assert_eq!(TelegramCommand::parse("/RESUME@my_bot"), Ok(TelegramCommand::Resume));
+        assert_eq!(TelegramCommand::parse("  /status  "), Ok(TelegramCommand::Status));
+    }
+
+    #[test]
+    fn sell_takes_a_token_and_an_optional_percent() {
+        let token = Address::from_str(TOKEN).unwrap();
+        assert_eq!(TelegramCommand::parse(&format!("/sell {}", TOKEN)), Ok(TelegramCommand::Sell(token, None)));
+        assert_eq!(
+            TelegramCommand::parse(&format!("/sell {} 25%", TOKEN)),
+            Ok(TelegramCommand::Sell(token, Some(25.0)))
+        );
+        assert!(TelegramCommand::parse("/sell").is_err());
+        assert!(TelegramCommand::parse("/sell 0xnope").is_err());
+        assert!(TelegramCommand::parse(&format!("/sell {} 0", TOKEN)).is_err());
+        assert!(TelegramCommand::parse(&format!("/sell {} 150", TOKEN)).is_err());
+    }
+
+    #[test]
+    fn unknown_text_gets_the_usage() {
+        assert!(TelegramCommand::parse("hello").unwrap_err().starts_with("Commands:"));
+        assert!(TelegramCommand::parse("").is_err());
+    }
+
+    fn context() -> (CommandContext, mpsc::Receiver<(Address, SellDecision)>) {
+        let token = Address::from_str(TOKEN).unwrap();
+        let mut positions = PositionTracker::in_memory();
+        positions.add(Position {
+            token,
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            amount: alloy::primitives::U256::from(1_000u64),
+            buy_price_mon: 1.0,
+            buy_time: 1_700_000_000,
+            highest_price: 1.0,
+            tx_hash: "0xa".to_string(),
+            provisional: false,
+            fired_tp_levels: Vec::new(),
+            sell_failures: 0,
+            stuck: false,
+            graduated: false,
+            peak_liquidity_mon: 0.0,
+            in_runner_mode: false,
+            decimals: 18,
+        });
+        let (sell_signal_tx, sell_signal_rx) = mpsc::channel(4);
+        let context = CommandContext {
+            chat_id: ChatId(1),
+            positions: Arc::new(Mutex::new(positions)),
+            sell_signal_tx,
+            paused: Arc::new(AtomicBool::new(false)),
+            unstick_tx: mpsc::channel(1).0,
+            unwrap_tx: mpsc::channel(1).0,
+            trade_history: Arc::new(Mutex::new(TradeHistory::in_memory())),
+            emergency_slippage: "30% slippage on the curve, 15% on the DEX".to_string(),
+        };
+        (context, sell_signal_rx)
+    }
+
+    #[tokio::test]
+    async fn sell_queues_a_decision_for_open_positions_only() {
+        let (context, mut sells) = context();
+        let token = Address::from_str(TOKEN).unwrap();
+
+        run_command(&context, TelegramCommand::Sell(token, Some(40.0))).await;
+        let (sold, decision) = sells.try_recv().unwrap();
+        assert_eq!(sold, token);
+        assert_eq!(decision.portion(), Some(0.4));
+
+        run_command(&context, TelegramCommand::Sell(token, None)).await;
+        assert!(matches!(sells.try_recv().unwrap().1, SellDecision::CopySell { .. }));
+
+        let reply = run_command(&context, TelegramCommand::Sell(Address::ZERO, None)).await;
+        assert!(reply.starts_with("No open position"));
+        assert!(sells.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_flip_the_shared_flag() {
+        let (context, _sells) = context();
+        run_command(&context, TelegramCommand::Pause).await;
+        assert!(context.paused.load(Ordering::Relaxed));
+        assert!(run_command(&context, TelegramCommand::Status).await.contains("Sniping: paused"));
+
+        run_command(&context, TelegramCommand::Resume).await;
+        assert!(!context.paused.load(Ordering::Relaxed));
+        let status = run_command(&context, TelegramCommand::Status).await;
+        assert!(status.contains("Sniping: active") && status.contains("Open positions: 1") && status.contains("TST"));
+    }
+
+    #[tokio::test]
+    async fn panic_pauses_sniping_and_dumps_every_position() {
+        assert_eq!(TelegramCommand::parse("/panic"), Ok(TelegramCommand::Panic));
+
+        let (context, mut sells) = context();
+        let reply = run_command(&context, TelegramCommand::Panic).await;
+        assert!(reply.starts_with("🚨 Panic-selling 1 positions at 30% slippage on the curve"), "{}", reply);
+        assert!(context.paused.load(Ordering::Relaxed));
+
+        let (token, decision) = sells.recv().await.unwrap();
+        assert_eq!(token, Address::from_str(TOKEN).unwrap());
+        assert!(matches!(decision, SellDecision::Emergency { .. }));
+    }
+
+    #[tokio::test]
+    async fn partial_sell_reply_names_the_share_and_100_percent_sells_everything() {
+        let (context, mut sells) = context();
+        let token = Address::from_str(TOKEN).unwrap();
+
+        let reply = run_command(&context, TelegramCommand::Sell(token, Some(40.0))).await;
+        assert!(reply.starts_with("📉 Selling 40% of TST"), "{}", reply);
+        assert!(matches!(sells.try_recv().unwrap().1, SellDecision::PartialManual { portion } if portion == 0.4));
+
+        let reply = run_command(&context, TelegramCommand::Sell(token, Some(100.0))).await;
+        assert!(reply.starts_with("📉 Selling all of TST"), "{}", reply);
+        let (_, decision) = sells.try_recv().unwrap();
+        assert_eq!(decision.sell_amount(alloy::primitives::U256::from(1_000u64)), alloy::primitives::U256::from(1_000u64));
+    }
+
+    #[tokio::test]
+    async fn unwrap_asks_the_main_loop_once_at_a_time() {
+        assert_eq!(TelegramCommand::parse("/unwrap"), Ok(TelegramCommand::Unwrap));
+        assert_eq!(TelegramCommand::parse("/export@sniper_bot"), Ok(TelegramCommand::Export));
+
+        let (mut context, _sells) = context();
+        let (unwrap_tx, mut unwrap_rx) = mpsc::channel(1);
+        context.unwrap_tx = unwrap_tx;
+
+        assert_eq!(run_command(&context, TelegramCommand::Unwrap).await, "📦 Unwrapping WMON...");
+        assert_eq!(run_command(&context, TelegramCommand::Unwrap).await, "⏳ Already unwrapping WMON");
+        unwrap_rx.recv().await.unwrap();
+        assert_eq!(run_command(&context, TelegramCommand::Unwrap).await, "📦 Unwrapping WMON...");
     }
 }
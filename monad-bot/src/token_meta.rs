@@ -0,0 +1,155 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! ERC20 name / symbol / decimals, fetched once per token.
+//!
+//! Metadata never changes after deployment, so repeat lookups during a snipe
+//! or a price check are served from memory instead of costing three RPC calls.
+
+use crate::units::{one_token, token_units_to_f64};
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::sol;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+sol! {
+    #[sol(rpc)]
+    interface IERC20Metadata {
+        function name() external view returns (string);
+        function symbol() external view returns (string);
+        function decimals() external view returns (uint8);
+    }
+}
+
+/// Decimals assumed when a token doesn't report them (nad.fun tokens use 18).
+const DEFAULT_DECIMALS: u8 = 18;
+
+/// Largest `decimals` taken at face value; anything above is treated as a bad read.
+const MAX_DECIMALS: u8 = 36;
+
+/// A token's ERC20 metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMeta {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl TokenMeta {
+    /// Smallest-unit amount of one whole token.
+    pub fn one_token(&self) -> U256 {
+        one_token(self.decimals)
+    }
+
+    /// `amount` in whole tokens.
+    pub fn to_tokens(&self, amount: U256) -> f64 {
+        token_units_to_f64(amount, self.decimals)
+    }
+}
+
+/// Shared metadata cache. Clones share the same entries.
+#[derive(Debug, Clone, Default)]
+pub struct TokenMetadataCache {
+    entries: Arc<Mutex<HashMap<Address, TokenMeta>>>,
+}
+
+impl TokenMetadataCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cached metadata for `token`, if it has been fetched.
+    pub fn cached(&self, token: &Address) -> Option<TokenMeta> {
+        self.entries.lock().ok()?.get(token).cloned()
+    }
+
+    /// Metadata for `token`, fetched on first use. A field that fails to load
+    /// gets a placeholder, and the result is only cached once all three load,
+    /// so a flaky RPC doesn't pin the placeholder.
+    pub async fn get<P: Provider>(&self, provider: &P, token: Address) -> TokenMeta {
+        if let Some(meta) = self.cached(&token) {
+            return meta;
+        }
+
+        let contract = IERC20Metadata::new(token, provider);
+        let name = contract.name().call().await.ok();
+        let symbol = contract.symbol().call().await.ok();
+        let decimals = contract
+            .decimals()
+            .call()
+            .await
+            .ok()
+            .filter(|decimals| *decimals <= MAX_DECIMALS);
+
+        let complete = name.is_some() && symbol.is_some() && decimals.is_some();
+        let meta = TokenMeta {
+            name: name.unwrap_or_else(|| "Unknown".to_string()),
+            symbol: symbol.unwrap_or_else(|| "???".to_string()),
+            decimals: decimals.unwrap_or(DEFAULT_DECIMALS),
+        };
+
+        if complete {
+            if let Ok(mut entries) = self.entries.lock() {
+                entries.insert(token, meta.clone());
+            }
+        } else {
+            debug!("Incomplete metadata for {:?}, not caching: {:?}", token, meta);
+        }
+        meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_call};
+
+    const TOKEN: Address = Address::new([0x11; 20]);
+
+    fn push_meta(asserter: &alloy::providers::mock::Asserter, decimals: u8) {
+        push_call::<IERC20Metadata::nameCall>(asserter, &"Test".to_string());
+        push_call::<IERC20Metadata::symbolCall>(asserter, &"TST".to_string());
+        push_call::<IERC20Metadata::decimalsCall>(asserter, &decimals);
+    }
+
+    #[tokio::test]
+    async fn complete_metadata_is_fetched_once() {
+        let (provider, asserter) = mock_provider();
+        let cache = TokenMetadataCache::new();
+        push_meta(&asserter, 6);
+
+        let meta = cache.get(&provider, TOKEN).await;
+        assert_eq!(meta, TokenMeta { name: "Test".to_string(), symbol: "TST".to_string(), decimals: 6 });
+        // Nothing left to answer with: a second fetch would get placeholders
+        assert_eq!(cache.get(&provider, TOKEN).await, meta);
+        assert_eq!(cache.clone().cached(&TOKEN), Some(meta));
+    }
+
+    #[tokio::test]
+    async fn partial_metadata_gets_placeholders_and_is_retried() {
+        let (provider, asserter) = mock_provider();
+        let cache = TokenMetadataCache::new();
+        push_call::<IERC20Metadata::nameCall>(&asserter, &"Test".to_string());
+        asserter.push_failure_msg("execution reverted");
+        push_call::<IERC20Metadata::decimalsCall>(&asserter, &6u8);
+
+        let meta = cache.get(&provider, TOKEN).await;
+        assert_eq!((meta.symbol.as_str(), meta.decimals), ("???", 6));
+        assert_eq!(cache.cached(&TOKEN), None);
+
+        push_meta(&asserter, 6);
+        assert_eq!(cache.get(&provider, TOKEN).await.symbol, "TST");
+    }
+
+    #[tokio::test]
+    async fn absurd_decimals_fall_back_to_18() {
+        let (provider, asserter) = mock_provider();
+        push_meta(&asserter, 200);
+        let meta = TokenMetadataCache::new().get(&provider, TOKEN).await;
+        assert_eq!(meta.decimals, DEFAULT_DECIMALS);
+        assert_eq!(meta.one_token(), U256::from(10u64).pow(U256::from(18)));
+        assert_eq!(meta.to_tokens(meta.one_token() * U256::from(3u64)), 3.0);
+    }
+}
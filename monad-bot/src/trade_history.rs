@@ -3,14 +3,20 @@
 
 //! Trade history tracking and profit logging.
 
-use alloy::primitives::Address;
+use crate::persist::write_atomic;
+use crate::price::MonPriceOracle;
+use alloy::primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::str::FromStr;
 use tracing::{info, warn};
 
 const TRADES_FILE: &str = "trades.json";
 
+/// Header row of `TradeHistory::export_csv`.
+const CSV_HEADER: &str = "timestamp,type,token,symbol,amount_tokens,amount_mon,tx_hash,running_balance_mon";
+
 /// A record of a single trade (buy or sell).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeRecord {
@@ -22,6 +28,33 @@ pub struct TradeRecord {
     pub amount_mon: f64,
     pub timestamp: u64,
     pub tx_hash: String,
+    /// Sells only: MON received minus the cost basis of the tokens sold.
+    #[serde(default)]
+    pub realized_pnl_mon: Option<f64>,
+}
+
+impl TradeRecord {
+    /// A confirmed buy of `amount` tokens for `amount_mon`, timestamped now.
+    pub fn buy(
+        token: Address,
+        token_name: String,
+        token_symbol: String,
+        amount: U256,
+        amount_mon: f64,
+        tx_hash: String,
+    ) -> Self {
+        Self {
+            token,
+            token_name,
+            token_symbol,
+            trade_type: TradeType::Buy,
+            amount_tokens: amount.to_string(),
+            amount_mon,
+            timestamp: chrono::Utc::now().timestamp() as u64,
+            tx_hash,
+            realized_pnl_mon: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -34,6 +67,10 @@ pub enum TradeType {
 #[derive(Debug)]
 pub struct TradeHistory {
     trades: Vec<TradeRecord>,
+    /// Running sum of `realized_pnl_mon` over all trades.
+    realized_pnl_mon: f64,
+    /// Write `trades.json` on every record (off for backtests).
+    persist: bool,
 }
 
 impl TradeHistory {
@@ -53,14 +90,28 @@ impl TradeHistory {
         };
         
         info!("📊 Loaded {} historical trades", trades.len());
-        Self { trades }
+        let realized_pnl_mon = trades.iter().filter_map(|t: &TradeRecord| t.realized_pnl_mon).sum();
+        Self {
+            trades,
+            realized_pnl_mon,
+            persist: true,
+        }
+    }
+
+    /// Empty history that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            trades: Vec::new(),
+            realized_pnl_mon: 0.0,
+            persist: false,
+        }
     }
 
     /// Save trade history to file.
     pub fn save(&self) -> Result<(), String> {
         let json = serde_json::to_string_pretty(&self.trades)
             .map_err(|e| format!("Failed to serialize trades: {}", e))?;
-        fs::write(TRADES_FILE, json)
+        write_atomic(TRADES_FILE, json)
             .map_err(|e| format!("Failed to write trades file: {}", e))?;
         Ok(())
     }
@@ -79,21 +130,110 @@ impl TradeHistory {
             trade.amount_mon
         );
         
+        if let Some(pnl) = trade.realized_pnl_mon {
+            self.realized_pnl_mon += pnl;
+            info!("💹 Realized PnL: {:+.4} MON (total {:+.4} MON)", pnl, self.realized_pnl_mon);
+        }
         self.trades.push(trade);
         
+        if !self.persist {
+            return;
+        }
         if let Err(e) = self.save() {
             warn!("Failed to save trades: {}", e);
         }
     }
 
+    /// Write every trade to `path` as CSV, oldest first, for bookkeeping.
+    /// `running_balance_mon` is the net MON flow so far: sells add, buys subtract.
+    pub fn export_csv(&self, path: &str) -> Result<(), String> {
+        let mut trades: Vec<&TradeRecord> = self.trades.iter().collect();
+        trades.sort_by_key(|t| t.timestamp);
+
+        let mut csv = String::from(CSV_HEADER);
+        csv.push('\n');
+        let mut balance_mon = 0.0;
+        for trade in trades {
+            let trade_type = match trade.trade_type {
+                TradeType::Buy => {
+                    balance_mon -= trade.amount_mon;
+                    "buy"
+                }
+                TradeType::Sell => {
+                    balance_mon += trade.amount_mon;
+                    "sell"
+                }
+            };
+            let timestamp = chrono::DateTime::from_timestamp(trade.timestamp as i64, 0)
+                .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+                .unwrap_or_else(|| trade.timestamp.to_string());
+            // Stored as U256::to_string, but normalize in case the file was edited by hand
+            let amount_tokens = U256::from_str(&trade.amount_tokens)
+                .map(|amount| amount.to_string())
+                .unwrap_or_else(|_| csv_field(&trade.amount_tokens));
+
+            let row = [
+                timestamp,
+                trade_type.to_string(),
+                format!("{:?}", trade.token),
+                csv_field(&trade.token_symbol),
+                amount_tokens,
+                trade.amount_mon.to_string(),
+                csv_field(&trade.tx_hash),
+                balance_mon.to_string(),
+            ];
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        write_atomic(path, csv).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        info!("📤 Exported {} trades to {}", self.trades.len(), path);
+        Ok(())
+    }
+
+    /// Every token we have traded.
+    pub fn traded_tokens(&self) -> HashSet<Address> {
+        self.trades.iter().map(|t| t.token).collect()
+    }
+
+    /// Total realized PnL across all sells, in MON.
+    pub fn realized_pnl_mon(&self) -> f64 {
+        self.realized_pnl_mon
+    }
+
+    /// Realized PnL per token, in MON (tokens without a priced sell are omitted).
+    pub fn get_per_token_pnl(&self) -> HashMap<Address, f64> {
+        let mut pnl: HashMap<Address, f64> = HashMap::new();
+        for trade in &self.trades {
+            if let Some(realized) = trade.realized_pnl_mon {
+                *pnl.entry(trade.token).or_insert(0.0) += realized;
+            }
+        }
+        pnl
+    }
+
+    /// Share of priced sells that realized a profit, 0.0-1.0 (None before the first one).
+    /// Each partial sell counts as its own trade.
+    pub fn win_rate(&self) -> Option<f64> {
+        self.get_summary().win_rate
+    }
+
     /// Get profit/loss summary.
     pub fn get_summary(&self) -> TradeSummary {
+        self.summary_since(0)
+    }
+
+    /// Profit/loss summary of trades at or after `since` (unix seconds).
+    pub fn summary_since(&self, since: u64) -> TradeSummary {
         let mut total_bought = 0.0;
         let mut total_sold = 0.0;
         let mut buy_count = 0;
         let mut sell_count = 0;
+        let mut realized_pnl = 0.0;
+        let mut priced_sells = 0;
+        let mut wins = 0;
         
-        for trade in &self.trades {
+        for trade in self.trades.iter().filter(|t| t.timestamp >= since) {
             match trade.trade_type {
                 TradeType::Buy => {
                     total_bought += trade.amount_mon;
@@ -104,6 +244,13 @@ impl TradeHistory {
                     sell_count += 1;
                 }
             }
+            if let Some(pnl) = trade.realized_pnl_mon {
+                realized_pnl += pnl;
+                priced_sells += 1;
+                if pnl > 0.0 {
+                    wins += 1;
+                }
+            }
         }
         
         TradeSummary {
@@ -112,26 +259,184 @@ impl TradeHistory {
             net_pnl: total_sold - total_bought,
             buy_count,
             sell_count,
+            realized_pnl,
+            win_rate: (priced_sells > 0).then(|| wins as f64 / priced_sells as f64),
         }
     }
 
     /// Log summary on startup.
-    pub fn log_summary(&self) {
+    pub fn log_summary(&self, mon_price: &MonPriceOracle) {
         let summary = self.get_summary();
         info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
         info!("📊 Trade History Summary:");
-        info!("   Buys: {} trades, {:.4} MON total", summary.buy_count, summary.total_bought);
-        info!("   Sells: {} trades, {:.4} MON total", summary.sell_count, summary.total_sold);
-        info!("   Net P/L: {:.4} MON", summary.net_pnl);
+        info!("   Buys: {} trades, {:.4} MON total{}", summary.buy_count, summary.total_bought, mon_price.format_usd(summary.total_bought));
+        info!("   Sells: {} trades, {:.4} MON total{}", summary.sell_count, summary.total_sold, mon_price.format_usd(summary.total_sold));
+        info!("   Net P/L: {:.4} MON{}", summary.net_pnl, mon_price.format_usd(summary.net_pnl));
+        info!("   Realized P/L: {:.4} MON{}", self.realized_pnl_mon, mon_price.format_usd(self.realized_pnl_mon));
+        if let Some(rate) = self.win_rate() {
+            info!("   Win rate: {:.0}%", rate * 100.0);
+        }
         info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     }
 }
 
-#[derive(Debug)]
+/// Quote a CSV field if it contains a separator, quote or newline (symbols are user-chosen).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct TradeSummary {
     pub total_bought: f64,
     pub total_sold: f64,
     pub net_pnl: f64,
     pub buy_count: usize,
     pub sell_count: usize,
+    /// Sum of realized PnL over sells with a known cost basis.
+    pub realized_pnl: f64,
+    /// Share of those sells that were profitable, 0.0-1.0.
+    pub win_rate: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sell(token: Address, amount_mon: f64, pnl: Option<f64>, timestamp: u64) -> TradeRecord {
+        TradeRecord {
+            token,
+            token_name: "Test".to_string(),
+            token_symbol: "TST".to_string(),
+            trade_type: TradeType::Sell,
+            amount_tokens: "100".to_string(),
+            amount_mon,
+            timestamp,
+            tx_hash: "0xsell".to_string(),
+            realized_pnl_mon: pnl,
+        }
+    }
+
+    #[test]
+    fn realized_pnl_is_totalled_overall_and_per_token() {
+        let a = Address::repeat_byte(1);
+        let b = Address::repeat_byte(2);
+        let mut history = TradeHistory::in_memory();
+        history.record(TradeRecord::buy(a, "A".into(), "A".into(), U256::from(100), 1.0, "0xbuy".into()));
+        history.record(sell(a, 0.9, Some(0.5), 10));
+        history.record(sell(a, 0.6, Some(-0.25), 20));
+        history.record(sell(b, 2.0, Some(1.0), 30));
+
+        assert!((history.realized_pnl_mon() - 1.25).abs() < 1e-9);
+        let per_token = history.get_per_token_pnl();
+        assert!((per_token[&a] - 0.25).abs() < 1e-9);
+        assert!((per_token[&b] - 1.0).abs() < 1e-9);
+        // Each priced sell counts on its own: two wins out of three
+        assert!((history.win_rate().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sells_without_a_basis_are_left_out_of_pnl_and_win_rate() {
+        let token = Address::repeat_byte(1);
+        let mut history = TradeHistory::in_memory();
+        history.record(sell(token, 1.0, None, 10));
+
+        assert_eq!(history.win_rate(), None);
+        assert!(history.get_per_token_pnl().is_empty());
+        let summary = history.get_summary();
+        assert_eq!(summary.sell_count, 1);
+        assert_eq!(summary.realized_pnl, 0.0);
+    }
+
+    #[test]
+    fn summary_since_only_counts_later_trades() {
+        let token = Address::repeat_byte(1);
+        let mut history = TradeHistory::in_memory();
+        history.record(sell(token, 1.0, Some(-1.0), 10));
+        history.record(sell(token, 3.0, Some(2.0), 20));
+
+        let summary = history.summary_since(15);
+        assert_eq!(summary.sell_count, 1);
+        assert_eq!(summary.realized_pnl, 2.0);
+        assert_eq!(summary.win_rate, Some(1.0));
+    }
+
+    #[test]
+    fn trades_saved_before_pnl_tracking_load_without_it() {
+        let json = r#"{
+            "token": "0x1111111111111111111111111111111111111111",
+            "token_name": "Test",
+            "token_symbol": "TST",
+            "trade_type": "Sell",
+            "amount_tokens": "100",
+            "amount_mon": 1.5,
+            "timestamp": 1700000000,
+            "tx_hash": "0xabc"
+        }"#;
+        let trade: TradeRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(trade.realized_pnl_mon, None);
+    }
+
+    #[test]
+    fn buy_record_counts_towards_total_bought() {
+        let record = TradeRecord::buy(
+            Address::repeat_byte(1),
+            "Test".to_string(),
+            "TST".to_string(),
+            U256::from(1_000u64),
+            1.5,
+            "0xbuy".to_string(),
+        );
+        assert_eq!(record.trade_type, TradeType::Buy);
+        assert_eq!(record.amount_tokens, "1000");
+        assert_eq!(record.realized_pnl_mon, None);
+        assert!(record.timestamp > 0);
+
+        let mut history = TradeHistory::in_memory();
+        history.record(record);
+        let summary = history.summary_since(0);
+        assert_eq!((summary.buy_count, summary.total_bought), (1, 1.5));
+        assert_eq!(summary.net_pnl, -1.5);
+    }
+
+    #[test]
+    fn csv_fields_with_metacharacters_are_quoted() {
+        assert_eq!(csv_field("TST"), "TST");
+        assert_eq!(csv_field("A,B"), "\"A,B\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn export_lists_trades_oldest_first_with_a_running_balance() {
+        let token = Address::repeat_byte(1);
+        let mut history = TradeHistory::in_memory();
+        history.record(TradeRecord { token_symbol: "$A,B".to_string(), ..sell(token, 1.5, None, 200) });
+        history.record(TradeRecord {
+            trade_type: TradeType::Buy,
+            amount_tokens: "0x64".to_string(),
+            timestamp: 100,
+            tx_hash: "0xbuy".to_string(),
+            ..sell(token, 1.0, None, 0)
+        });
+
+        let path = std::env::temp_dir().join(format!("trades-export-{}.csv", std::process::id()));
+        history.export_csv(&path.to_string_lossy()).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            format!("1970-01-01T00:01:40Z,buy,{:?},TST,100,1,0xbuy,-1", token)
+        );
+        assert_eq!(
+            lines[2],
+            format!("1970-01-01T00:03:20Z,sell,{:?},\"$A,B\",100,1.5,0xsell,0.5", token)
+        );
+        assert_eq!(lines.len(), 3);
+    }
 }
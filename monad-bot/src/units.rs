@@ -0,0 +1,114 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! MON / wei conversions (18 decimals).
+//!
+//! `x.to::<u128>() as f64 / 1e18` panics above `u128::MAX` and drops the
+//! fractional digits of large values; these helpers cover the full `U256`
+//! range and split whole and fractional MON so neither loses precision.
+
+use alloy::primitives::U256;
+
+/// Wei per MON.
+pub const WEI_PER_MON: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// Wei (or any 18-decimal token amount) to MON. Never panics.
+pub fn wei_to_mon(wei: U256) -> f64 {
+    let whole = wei / WEI_PER_MON;
+    // Always < 1e18, so it fits in a u64
+    let frac = (wei % WEI_PER_MON).to::<u64>();
+    to_f64(whole) + frac as f64 / 1e18
+}
+
+/// MON to wei, rounded to the nearest wei. Negative, NaN and infinite amounts give zero.
+pub fn mon_to_wei(mon: f64) -> U256 {
+    if !mon.is_finite() || mon <= 0.0 {
+        return U256::ZERO;
+    }
+
+    let whole = mon.trunc();
+    let frac_wei = ((mon - whole) * 1e18).round() as u128;
+    U256::from(whole as u128) * WEI_PER_MON + U256::from(frac_wei)
+}
+
+/// Smallest-unit amount of one whole token with `decimals` decimals.
+pub fn one_token(decimals: u8) -> U256 {
+    U256::from(10u64).pow(U256::from(decimals))
+}
+
+/// Token amount with `decimals` decimals to whole tokens. Never panics for
+/// `decimals` up to 77 (the largest power of ten a `U256` holds).
+pub fn token_units_to_f64(amount: U256, decimals: u8) -> f64 {
+    if decimals == 18 {
+        return wei_to_mon(amount);
+    }
+    let one = one_token(decimals);
+    to_f64(amount / one) + to_f64(amount % one) / 10f64.powi(decimals as i32)
+}
+
+/// Whole tokens to the smallest unit of a token with `decimals` decimals.
+pub fn tokens_to_units(tokens: f64, decimals: u8) -> U256 {
+    if decimals == 18 {
+        return mon_to_wei(tokens);
+    }
+    // Go through 18 decimals so the fraction survives, then rescale
+    mon_to_wei(tokens) * one_token(decimals) / WEI_PER_MON
+}
+
+/// Nearest `f64` to a `U256`, limb by limb (most significant first).
+fn to_f64(value: U256) -> f64 {
+    value
+        .as_limbs()
+        .iter()
+        .rev()
+        .fold(0.0, |acc, &limb| acc * 18_446_744_073_709_551_616.0 + limb as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn wei_to_mon_covers_the_full_range() {
+        assert_eq!(wei_to_mon(U256::ZERO), 0.0);
+        assert_eq!(wei_to_mon(WEI_PER_MON), 1.0);
+        assert_eq!(wei_to_mon(U256::from(1u64)), 1e-18);
+        // Above u128::MAX, where `to::<u128>()` would panic
+        let huge = U256::from(u128::MAX) * U256::from(4u64);
+        assert!((wei_to_mon(huge) / (u128::MAX as f64 * 4.0 / 1e18) - 1.0).abs() < 1e-12);
+        assert!(wei_to_mon(U256::MAX).is_finite());
+    }
+
+    #[test]
+    fn small_fractions_keep_their_precision_next_to_large_whole_amounts() {
+        let wei = U256::from(1_000_000u64) * WEI_PER_MON + U256::from(500_000_000_000_000_000u64);
+        assert_eq!(wei_to_mon(wei), 1_000_000.5);
+    }
+
+    #[test]
+    fn mon_to_wei_rounds_and_rejects_invalid_amounts() {
+        assert_eq!(mon_to_wei(1.5), U256::from(1_500_000_000_000_000_000u64));
+        assert_eq!(mon_to_wei(0.1), U256::from(100_000_000_000_000_000u64));
+        assert_eq!(mon_to_wei(-1.0), U256::ZERO);
+        assert_eq!(mon_to_wei(f64::NAN), U256::ZERO);
+        assert_eq!(mon_to_wei(f64::INFINITY), U256::ZERO);
+    }
+
+    #[test]
+    fn token_units_follow_the_token_decimals() {
+        assert_eq!(one_token(6), U256::from(1_000_000u64));
+        assert_eq!(token_units_to_f64(U256::from(2_500_000u64), 6), 2.5);
+        assert_eq!(token_units_to_f64(U256::from(7u64), 0), 7.0);
+        assert_eq!(tokens_to_units(2.5, 6), U256::from(2_500_000u64));
+        assert_eq!(tokens_to_units(1.25, 18), mon_to_wei(1.25));
+    }
+
+    proptest! {
+        #[test]
+        fn mon_round_trips_through_wei(mon in 0.0..1e9f64) {
+            let back = wei_to_mon(mon_to_wei(mon));
+            prop_assert!((back - mon).abs() <= mon * 1e-12 + 1e-18);
+        }
+    }
+}
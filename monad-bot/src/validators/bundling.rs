@@ -4,11 +4,19 @@
 
 //! Bundling detection - identify coordinated wallet manipulation.
 
-use alloy::primitives::Address;
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{Address, U256};
 use alloy::providers::Provider;
+use alloy::rpc::types::Filter;
+use alloy::sol;
+use alloy::sol_types::SolEvent;
 use std::collections::HashMap;
 use tracing::{debug, warn};
 
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+}
+
 /// Bundling analysis result.
 #[derive(Debug, Clone)]
 pub struct BundlingAnalysis {
@@ -20,10 +28,14 @@ pub struct BundlingAnalysis {
 }
 
 /// Check if token holders show signs of bundling.
+/// Funding sources are looked up from `from_block`; transfers from `excluded`
+/// (e.g. the bonding curve every buyer receives from) don't count as funding.
 pub async fn check_bundling<P: Provider + Clone>(
     provider: &P,
     token: Address,
     top_holders: Vec<Address>,
+    from_block: u64,
+    excluded: &[Address],
 ) -> BundlingAnalysis {
     if top_holders.is_empty() {
         return BundlingAnalysis {
@@ -40,7 +52,7 @@ pub async fn check_bundling<P: Provider + Clone>(
 
     // Check funding source for each holder
     for holder in &top_holders {
-        if let Some(source) = get_first_funding_source(provider, *holder).await {
+        if let Some(source) = get_first_funding_source(provider, *holder, from_block, excluded).await {
             funding_sources
                 .entry(source)
                 .or_insert_with(Vec::new)
@@ -81,45 +93,56 @@ pub async fn check_bundling<P: Provider + Clone>(
     }
 }
 
-/// Get the first funding source for a wallet.
+/// Get the first funding source for a wallet: the sender of its earliest
+/// inbound ERC20 transfer since `from_block`, skipping mints and `excluded`.
+/// Native MON transfers emit no logs, so wallets funded only with MON yield None.
 async fn get_first_funding_source<P: Provider + Clone>(
     provider: &P,
     wallet: Address,
+    from_block: u64,
+    excluded: &[Address],
 ) -> Option<Address> {
-    // Get first incoming transaction to this wallet
-    // This is a simplified version - full implementation would need transaction history
-    
-    // For now, we check the wallet's nonce to see if it's a fresh wallet
-    match provider.get_transaction_count(wallet).await {
-        Ok(nonce) => {
-            if nonce == 0 {
-                // Fresh wallet with no outgoing txs - suspicious
-                debug!("Fresh wallet detected: {:?}", wallet);
-            }
-            // TODO: Get actual funding source from tx history
-            // Would need an indexer or archive node
-            None
+    let filter = Filter::new()
+        .event_signature(Transfer::SIGNATURE_HASH)
+        .topic2(wallet.into_word())
+        .from_block(from_block)
+        .to_block(BlockNumberOrTag::Latest);
+
+    let logs = match provider.get_logs(&filter).await {
+        Ok(logs) => logs,
+        Err(e) => {
+            debug!("Failed to get inbound transfers for {:?}: {}", wallet, e);
+            return None;
         }
-        Err(_) => None,
+    };
+
+    // Logs come back in chain order, so the first usable one is the earliest
+    let source = logs
+        .iter()
+        .filter_map(|log| log.log_decode::<Transfer>().ok())
+        .map(|transfer| transfer.inner.data.from)
+        .find(|from| *from != Address::ZERO && !excluded.contains(from));
+
+    if let Some(source) = source {
+        debug!("Wallet {:?} first funded by {:?}", wallet, source);
     }
+    source
 }
 
 /// Quick heuristic check for bundling without full tx history.
 pub async fn quick_bundling_check<P: Provider + Clone>(
     provider: &P,
-    holders: Vec<(Address, u64)>, // (address, balance)
+    holders: &[(Address, U256)], // (address, balance)
 ) -> bool {
     // Check for identical balances (sign of coordinated distribution)
-    let balances: Vec<u64> = holders.iter().map(|(_, b)| *b).collect();
-    
-    let mut balance_counts: HashMap<u64, u32> = HashMap::new();
-    for bal in &balances {
+    let mut balance_counts: HashMap<U256, u32> = HashMap::new();
+    for (_, bal) in holders {
         *balance_counts.entry(*bal).or_insert(0) += 1;
     }
 
     // If 3+ wallets have identical balance, suspicious
     for (balance, count) in balance_counts {
-        if count >= 3 && balance > 0 {
+        if count >= 3 && balance > U256::ZERO {
             warn!(
                 "🚨 Suspicious: {} wallets with identical balance {}",
                 count, balance
@@ -130,7 +153,7 @@ pub async fn quick_bundling_check<P: Provider + Clone>(
 
     // Check for fresh wallets (nonce = 0)
     let mut fresh_count = 0;
-    for (holder, _) in &holders {
+    for (holder, _) in holders {
         if let Ok(nonce) = provider.get_transaction_count(*holder).await {
             if nonce == 0 {
                 fresh_count += 1;
@@ -150,3 +173,76 @@ pub async fn quick_bundling_check<P: Provider + Clone>(
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_quantity};
+    use alloy::rpc::types::Log;
+
+    fn wallet(n: u8) -> Address {
+        Address::repeat_byte(n)
+    }
+
+    fn transfer_log(from: Address, to: Address) -> Log {
+        let data = Transfer { from, to, value: U256::from(1u64) }.encode_log_data();
+        Log {
+            inner: alloy::primitives::Log { address: wallet(0xee), data },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_balances_are_flagged_without_rpc() {
+        let (provider, _) = mock_provider();
+        let holders = [(wallet(1), U256::from(77u64)), (wallet(2), U256::from(77u64)), (wallet(3), U256::from(77u64))];
+        assert!(quick_bundling_check(&provider, &holders).await);
+    }
+
+    #[tokio::test]
+    async fn mostly_fresh_wallets_are_flagged() {
+        let holders = [(wallet(1), U256::from(1u64)), (wallet(2), U256::from(2u64)), (wallet(3), U256::from(3u64))];
+
+        let (provider, asserter) = mock_provider();
+        for nonce in [0, 0, 5] {
+            push_quantity(&asserter, nonce);
+        }
+        assert!(quick_bundling_check(&provider, &holders).await);
+
+        let (provider, asserter) = mock_provider();
+        for nonce in [0, 4, 5] {
+            push_quantity(&asserter, nonce);
+        }
+        assert!(!quick_bundling_check(&provider, &holders).await);
+    }
+
+    #[tokio::test]
+    async fn three_wallets_funded_by_one_source_are_bundled() {
+        let (provider, asserter) = mock_provider();
+        let funder = wallet(9);
+        let curve = wallet(8);
+        let holders = vec![wallet(1), wallet(2), wallet(3), wallet(4)];
+        // The curve's transfers and mints don't count as funding
+        asserter.push_success(&vec![transfer_log(curve, wallet(1)), transfer_log(funder, wallet(1))]);
+        asserter.push_success(&vec![transfer_log(Address::ZERO, wallet(2)), transfer_log(funder, wallet(2))]);
+        asserter.push_success(&vec![transfer_log(funder, wallet(3))]);
+        asserter.push_success(&vec![transfer_log(wallet(7), wallet(4))]);
+
+        let analysis = check_bundling(&provider, wallet(0xee), holders, 0, &[curve]).await;
+        assert!(analysis.is_bundled);
+        assert_eq!(analysis.common_funding_source, Some(funder));
+        assert_eq!(analysis.suspicious_wallets, vec![wallet(1), wallet(2), wallet(3)]);
+    }
+
+    #[tokio::test]
+    async fn separately_funded_wallets_are_not_bundled() {
+        let (provider, asserter) = mock_provider();
+        asserter.push_success(&vec![transfer_log(wallet(7), wallet(1))]);
+        asserter.push_failure_msg("log range too large");
+        asserter.push_success(&Vec::<Log>::new());
+
+        let analysis = check_bundling(&provider, wallet(0xee), vec![wallet(1), wallet(2), wallet(3)], 0, &[]).await;
+        assert!(!analysis.is_bundled);
+        assert!(analysis.reason.is_none());
+    }
+}
@@ -3,17 +3,36 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Honeypot detection - simulates sell to verify token is not a honeypot.
+//!
+//! `simulate_round_trip` quotes a buy, then `eth_call`s a real router sell from
+//! a throwaway sender whose token balance and allowance are injected with a
+//! state override. A token that quotes fine but reverts on the sell (blocked
+//! transfers, transfer tax breaking the pair's K check) is a honeypot.
 
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{keccak256, Address, Bytes, B256, U256};
 use alloy::providers::Provider;
+use alloy::rpc::types::state::{AccountOverride, StateOverride};
+use alloy::rpc::types::TransactionRequest;
 use alloy::sol;
+use alloy::sol_types::SolValue;
 use tracing::{debug, warn};
 
+/// Sender for simulated sells; never holds anything on-chain.
+const SIMULATION_SENDER: Address = Address::new([0x5a; 20]);
+
+/// Storage slots probed for the balance and allowance mappings.
+/// Covers OpenZeppelin and most hand-rolled ERC20 layouts.
+const MAX_PROBED_SLOT: u64 = 10;
+
+/// Base of the per-slot values written while probing (unlikely to be a real balance).
+const SLOT_MARKER: u64 = 0x5a5a_0000;
+
 // ERC20 interface for simulation
 sol! {
     #[sol(rpc)]
     interface IERC20 {
         function balanceOf(address account) external view returns (uint256);
+        function allowance(address owner, address spender) external view returns (uint256);
         function approve(address spender, uint256 amount) external returns (bool);
         function transfer(address to, uint256 amount) external returns (bool);
     }
@@ -25,9 +44,139 @@ sol! {
     interface IRouter {
         function getAmountsOut(uint256 amountIn, address[] calldata path)
             external view returns (uint256[] memory amounts);
+
+        function swapExactTokensForETH(
+            uint256 amountIn,
+            uint256 amountOutMin,
+            address[] calldata path,
+            address to,
+            uint256 deadline
+        ) external returns (uint256[] memory amounts);
     }
 }
 
+/// Outcome of a simulated buy+sell round trip.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HoneypotVerdict {
+    /// Bought and sold back in simulation.
+    Sellable,
+    /// The sell (or the buy quote) fails: don't buy.
+    Honeypot(String),
+    /// Couldn't simulate (no DEX pool yet, unusual storage layout, RPC error).
+    Inconclusive(String),
+}
+
+/// Simulate buying `probe_mon` worth of `token` through `router` and selling it back.
+pub async fn simulate_round_trip<P: Provider + Clone>(
+    provider: &P,
+    token: Address,
+    router: Address,
+    wmon: Address,
+    probe_mon: U256,
+) -> HoneypotVerdict {
+    let router_contract = IRouter::new(router, provider);
+
+    // Buy side: how many tokens the probe would get
+    let bought = match router_contract.getAmountsOut(probe_mon, vec![wmon, token]).call().await {
+        Ok(amounts) => match amounts.get(1) {
+            Some(out) if *out > U256::ZERO => *out,
+            Some(_) => return HoneypotVerdict::Honeypot("buy quote is zero".to_string()),
+            None => return HoneypotVerdict::Inconclusive("malformed buy quote".to_string()),
+        },
+        // Bonding-curve tokens have no DEX pool until graduation
+        Err(e) => return HoneypotVerdict::Inconclusive(format!("no DEX buy route: {}", e)),
+    };
+
+    let token_contract = IERC20::new(token, provider);
+    let balance_call = token_contract.balanceOf(SIMULATION_SENDER).calldata().clone();
+    let Some(balance_slot) =
+        find_mapping_slot(provider, token, balance_call, |slot| balance_key(SIMULATION_SENDER, slot)).await
+    else {
+        return HoneypotVerdict::Inconclusive("balance storage slot not found".to_string());
+    };
+    let allowance_call = token_contract.allowance(SIMULATION_SENDER, router).calldata().clone();
+    let Some(allowance_slot) = find_mapping_slot(provider, token, allowance_call, |slot| {
+        allowance_key(SIMULATION_SENDER, router, slot)
+    })
+    .await
+    else {
+        return HoneypotVerdict::Inconclusive("allowance storage slot not found".to_string());
+    };
+
+    // Sell side: the real swap, from a sender that holds and has approved `bought`
+    let overrides = token_overrides(
+        token,
+        [
+            (balance_key(SIMULATION_SENDER, balance_slot), bought),
+            (allowance_key(SIMULATION_SENDER, router, allowance_slot), bought),
+        ],
+    );
+    let deadline = U256::from(chrono::Utc::now().timestamp() as u64 + 300);
+    let sell_call = router_contract
+        .swapExactTokensForETH(bought, U256::ZERO, vec![token, wmon], SIMULATION_SENDER, deadline)
+        .calldata()
+        .clone();
+    let tx = TransactionRequest::default()
+        .from(SIMULATION_SENDER)
+        .to(router)
+        .input(sell_call.into());
+
+    match provider.call(tx).overrides(overrides).await {
+        Ok(output) => match Vec::<U256>::abi_decode(&output).ok().and_then(|amounts| amounts.last().copied()) {
+            Some(out) if out > U256::ZERO => {
+                debug!("Token {:?} round trip: {} MON -> {} tokens -> {} MON", token, probe_mon, bought, out);
+                HoneypotVerdict::Sellable
+            }
+            _ => HoneypotVerdict::Honeypot("simulated sell returns nothing".to_string()),
+        },
+        Err(e) => HoneypotVerdict::Honeypot(format!(
+            "simulated sell reverted (sell blocked or transfer tax): {}",
+            e
+        )),
+    }
+}
+
+/// Find which storage slot backs a mapping: write a distinct marker under every
+/// candidate key, call the getter once, and see which marker comes back.
+async fn find_mapping_slot<P: Provider>(
+    provider: &P,
+    token: Address,
+    getter: Bytes,
+    key_for: impl Fn(u64) -> B256,
+) -> Option<u64> {
+    let marker = |slot: u64| U256::from(SLOT_MARKER + slot);
+    let overrides = token_overrides(token, (0..=MAX_PROBED_SLOT).map(|slot| (key_for(slot), marker(slot))));
+    let tx = TransactionRequest::default().to(token).input(getter.into());
+
+    let output = provider.call(tx).overrides(overrides).await.ok()?;
+    let value = U256::try_from_be_slice(output.get(..32)?)?;
+    (0..=MAX_PROBED_SLOT).find(|slot| marker(*slot) == value)
+}
+
+/// Storage key of `balances[holder]` for a mapping at `slot`.
+fn balance_key(holder: Address, slot: u64) -> B256 {
+    keccak256((holder, U256::from(slot)).abi_encode())
+}
+
+/// Storage key of `allowances[owner][spender]` for a mapping at `slot`.
+fn allowance_key(owner: Address, spender: Address, slot: u64) -> B256 {
+    let inner = keccak256((owner, U256::from(slot)).abi_encode());
+    keccak256((spender, inner).abi_encode())
+}
+
+/// State override writing `values` into `token`'s storage.
+fn token_overrides(token: Address, values: impl IntoIterator<Item = (B256, U256)>) -> StateOverride {
+    let mut overrides = StateOverride::default();
+    overrides.insert(
+        token,
+        AccountOverride {
+            state_diff: Some(values.into_iter().map(|(key, value)| (key, B256::from(value))).collect()),
+            ..Default::default()
+        },
+    );
+    overrides
+}
+
 /// Check if a token is a honeypot by simulating a sell.
 ///
 /// Returns `true` if the token appears safe, `false` if it's likely a honeypot.
@@ -51,13 +200,15 @@ pub async fn check_honeypot<P: Provider + Clone>(
         .await
     {
         Ok(result) => {
-            let amounts = result;
-            if amounts.len() >= 2 && amounts[1] > U256::ZERO {
-                debug!("Token {:?} passed honeypot check, output: {:?}", token, amounts[1]);
-                Ok(true)
-            } else {
-                warn!("Token {:?} failed honeypot check: zero output", token);
-                Ok(false)
+            match result.get(1) {
+                Some(out) if *out > U256::ZERO => {
+                    debug!("Token {:?} passed honeypot check, output: {:?}", token, out);
+                    Ok(true)
+                }
+                _ => {
+                    warn!("Token {:?} failed honeypot check: zero or missing output", token);
+                    Ok(false)
+                }
             }
         }
         Err(e) => {
@@ -87,3 +238,109 @@ pub async fn quick_check<P: Provider + Clone>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_call};
+
+    const TOKEN: Address = Address::new([0x11; 20]);
+    const ROUTER: Address = Address::new([0x22; 20]);
+    const WMON: Address = Address::new([0x33; 20]);
+
+    async fn sell_quote_check(amounts: Option<Vec<U256>>) -> bool {
+        let (provider, asserter) = mock_provider();
+        match amounts {
+            Some(amounts) => push_call::<IRouter::getAmountsOutCall>(&asserter, &amounts),
+            None => asserter.push_failure_msg("execution reverted"),
+        }
+        check_honeypot(&provider, TOKEN, ROUTER, WMON).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn sell_quote_must_return_a_positive_output() {
+        assert!(sell_quote_check(Some(vec![U256::from(1u64), U256::from(5u64)])).await);
+        assert!(!sell_quote_check(Some(vec![U256::from(1u64), U256::ZERO])).await);
+        assert!(!sell_quote_check(None).await);
+    }
+
+    #[tokio::test]
+    async fn short_sell_quote_is_a_failed_check_not_a_panic() {
+        assert!(!sell_quote_check(Some(vec![U256::from(1u64)])).await);
+        assert!(!sell_quote_check(Some(Vec::new())).await);
+    }
+
+    /// Responses for a buy quote of `bought` and storage probes answered from
+    /// balance slot `balance_slot` and allowance slot `allowance_slot`.
+    fn push_probes(asserter: &alloy::providers::mock::Asserter, bought: u64, balance_slot: u64, allowance_slot: u64) {
+        push_call::<IRouter::getAmountsOutCall>(asserter, &vec![U256::from(1u64), U256::from(bought)]);
+        push_call::<IERC20::balanceOfCall>(asserter, &U256::from(SLOT_MARKER + balance_slot));
+        push_call::<IERC20::allowanceCall>(asserter, &U256::from(SLOT_MARKER + allowance_slot));
+    }
+
+    async fn round_trip(provider: &crate::executor::mock::MockProvider) -> HoneypotVerdict {
+        simulate_round_trip(provider, TOKEN, ROUTER, WMON, U256::from(1u64)).await
+    }
+
+    #[tokio::test]
+    async fn sellable_token_passes_the_round_trip() {
+        let (provider, asserter) = mock_provider();
+        push_probes(&asserter, 1_000, 0, 1);
+        push_call::<IRouter::swapExactTokensForETHCall>(&asserter, &vec![U256::from(1_000u64), U256::from(9u64)]);
+
+        assert_eq!(round_trip(&provider).await, HoneypotVerdict::Sellable);
+    }
+
+    #[tokio::test]
+    async fn reverting_or_empty_sell_is_a_honeypot() {
+        let (provider, asserter) = mock_provider();
+        push_probes(&asserter, 1_000, 0, 1);
+        asserter.push_failure_msg("execution reverted: TRANSFER_FAILED");
+        assert!(matches!(round_trip(&provider).await, HoneypotVerdict::Honeypot(_)));
+
+        let (provider, asserter) = mock_provider();
+        push_probes(&asserter, 1_000, 3, 4);
+        push_call::<IRouter::swapExactTokensForETHCall>(&asserter, &vec![U256::from(1_000u64), U256::ZERO]);
+        assert!(matches!(round_trip(&provider).await, HoneypotVerdict::Honeypot(_)));
+    }
+
+    #[tokio::test]
+    async fn buy_quote_decides_before_any_simulation() {
+        let (provider, asserter) = mock_provider();
+        asserter.push_failure_msg("execution reverted");
+        assert!(matches!(round_trip(&provider).await, HoneypotVerdict::Inconclusive(_)));
+
+        let (provider, asserter) = mock_provider();
+        push_call::<IRouter::getAmountsOutCall>(&asserter, &vec![U256::from(1u64), U256::ZERO]);
+        assert!(matches!(round_trip(&provider).await, HoneypotVerdict::Honeypot(_)));
+    }
+
+    #[tokio::test]
+    async fn unknown_storage_layout_is_inconclusive() {
+        let (provider, asserter) = mock_provider();
+        push_call::<IRouter::getAmountsOutCall>(&asserter, &vec![U256::from(1u64), U256::from(1_000u64)]);
+        // The getter ignores every probed slot
+        push_call::<IERC20::balanceOfCall>(&asserter, &U256::ZERO);
+        assert!(matches!(round_trip(&provider).await, HoneypotVerdict::Inconclusive(_)));
+    }
+
+    #[test]
+    fn mapping_keys_follow_solidity_layout() {
+        let holder = Address::repeat_byte(0xaa);
+        let spender = Address::repeat_byte(0xbb);
+
+        // keccak256(pad32(key) ++ pad32(slot))
+        let mut preimage = [0u8; 64];
+        preimage[12..32].copy_from_slice(holder.as_slice());
+        preimage[63] = 2;
+        assert_eq!(balance_key(holder, 2), keccak256(preimage));
+
+        // Nested mapping: the outer key hashes against the inner mapping's location
+        let inner = balance_key(holder, 2);
+        let mut preimage = [0u8; 64];
+        preimage[12..32].copy_from_slice(spender.as_slice());
+        preimage[32..].copy_from_slice(inner.as_slice());
+        assert_eq!(allowance_key(holder, spender, 2), keccak256(preimage));
+        assert_ne!(balance_key(holder, 2), balance_key(holder, 3));
+    }
+}
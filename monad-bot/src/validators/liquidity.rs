@@ -9,15 +9,15 @@ use tracing::debug;
 
 /// Minimum liquidity in MON (wei) required for snipe.
 /// 10 MON = 10 * 10^18
-const MIN_LIQUIDITY_WEI: u128 = 10_000_000_000_000_000_000;
+const MIN_LIQUIDITY_WEI: U256 = U256::from_limbs([10_000_000_000_000_000_000, 0, 0, 0]);
 
 /// Check if token has sufficient liquidity.
-pub fn check_liquidity(initial_liquidity: Option<U256>, min_liquidity_wei: Option<u128>) -> bool {
+pub fn check_liquidity(initial_liquidity: Option<U256>, min_liquidity_wei: Option<U256>) -> bool {
     let min = min_liquidity_wei.unwrap_or(MIN_LIQUIDITY_WEI);
 
     match initial_liquidity {
         Some(liquidity) => {
-            let is_sufficient = liquidity >= U256::from(min);
+            let is_sufficient = liquidity >= min;
             debug!(
                 "Liquidity check: {} >= {} = {}",
                 liquidity, min, is_sufficient
@@ -31,8 +31,3 @@ pub fn check_liquidity(initial_liquidity: Option<U256>, min_liquidity_wei: Optio
         }
     }
 }
-
-/// Convert MON to wei for comparison.
-pub fn mon_to_wei(mon: f64) -> u128 {
-    (mon * 1e18) as u128
-}
@@ -3,7 +3,9 @@ pub mod bundling;
 pub mod honeypot;
 pub mod liquidity;
 pub mod token_analysis;
+pub mod trading;
 pub mod wallet_tracker;
 
 pub use liquidity::check_liquidity;
 pub use token_analysis::{FilterConfig, TokenAnalysis, TokenAnalyzer};
+pub use trading::TradingCheck;
@@ -6,10 +6,20 @@
 
 // #![allow(unused)]
 
+use crate::config::Config;
+use crate::listeners::nadfun::{BONDING_CURVE, BONDING_CURVE_ROUTER};
+use crate::price::MonPriceOracle;
+use crate::units::token_units_to_f64;
+use crate::validators::bundling::{check_bundling, quick_bundling_check};
+use crate::validators::honeypot::{simulate_round_trip, HoneypotVerdict};
 use alloy::primitives::{Address, U256};
 use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log};
 use alloy::sol;
-use tracing::{info, warn};
+use alloy::sol_types::SolEvent;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
 /// Token analysis result.
 #[derive(Debug, Clone)]
@@ -18,6 +28,14 @@ pub struct TokenAnalysis {
     pub dev_wallet: Option<Address>,
     pub dev_holding_pct: f64,
     pub top_holder_pct: f64,
+    /// Distinct non-zero holders seen in Transfer logs, excluding the curve
+    /// and router contracts (None if not scanned).
+    pub holder_count: Option<usize>,
+    /// The Transfer logs reached back to the token's creation, so the holder
+    /// count and concentration are exact. An incomplete scan never rejects.
+    pub holders_complete: bool,
+    /// Top holders look coordinated (false if not checked).
+    pub is_bundled: bool,
     pub total_supply: U256,
     pub market_cap_usd: f64,
     pub age_minutes: u64,
@@ -38,6 +56,35 @@ pub struct FilterConfig {
     pub min_market_cap_usd: f64,
     /// Maximum market cap USD (default: 25000).
     pub max_market_cap_usd: f64,
+    /// Minimum distinct holders (default: 10, 0 disables).
+    pub min_holder_count: usize,
+    /// Only enforce `min_holder_count` once the token is this old (default: 10).
+    pub holder_count_min_age_minutes: u64,
+    /// Block range of each Transfer log request (default: 10000). The holder
+    /// scan pages back up to `MAX_HOLDER_SCAN_PAGES` of these.
+    pub max_log_scan_blocks: u64,
+    /// Minimum total supply in whole tokens (default: 0, no minimum).
+    pub min_total_supply: u64,
+    /// Maximum total supply in whole tokens (default: 1T, 0 disables).
+    pub max_total_supply: u64,
+    /// Re-read the total supply this long after the first read and reject if
+    /// it changed, i.e. the token is still being minted (default: 2, 0 disables).
+    pub supply_recheck_secs: u64,
+    /// Maximum share of supply held by the top 10 wallets (default: 50%, 0 disables).
+    pub max_top_holder_pct: f64,
+    /// Contracts left out of the holder checks (default: the nad.fun bonding curve and router).
+    pub excluded_holders: Vec<Address>,
+    /// Flag tokens whose top holders look coordinated (default: false).
+    pub bundling_check_enabled: bool,
+    /// Reject tokens that fail a simulated DEX buy+sell round trip (default: false).
+    pub honeypot_check_enabled: bool,
+    /// Without a known creator, treat the largest holder as the dev (default: false).
+    pub presume_largest_holder_dev: bool,
+    /// DEX router and WMON used by the honeypot simulation.
+    pub router: Address,
+    pub wmon: Address,
+    /// MON value of the simulated buy (default: 1 MON).
+    pub honeypot_probe_wei: U256,
 }
 
 impl Default for FilterConfig {
@@ -48,6 +95,43 @@ impl Default for FilterConfig {
             max_insider_pct: 25.0,
             min_market_cap_usd: 15_000.0,
             max_market_cap_usd: 25_000.0,
+            min_holder_count: 10,
+            holder_count_min_age_minutes: 10,
+            max_log_scan_blocks: 10_000,
+            min_total_supply: 0,
+            max_total_supply: 1_000_000_000_000,
+            supply_recheck_secs: 2,
+            max_top_holder_pct: 50.0,
+            excluded_holders: [BONDING_CURVE, BONDING_CURVE_ROUTER]
+                .iter()
+                .filter_map(|addr| addr.parse().ok())
+                .collect(),
+            bundling_check_enabled: false,
+            honeypot_check_enabled: false,
+            presume_largest_holder_dev: false,
+            router: Address::ZERO,
+            wmon: Address::ZERO,
+            honeypot_probe_wei: U256::from(1_000_000_000_000_000_000u128),
+        }
+    }
+}
+
+impl FilterConfig {
+    /// Defaults plus the on-chain checks and addresses configured in `.env`.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            bundling_check_enabled: config.bundling_check_enabled,
+            honeypot_check_enabled: config.honeypot_check_enabled,
+            presume_largest_holder_dev: config.presume_largest_holder_dev,
+            min_holder_count: config.min_holder_count,
+            holder_count_min_age_minutes: config.holder_count_min_age_minutes,
+            max_log_scan_blocks: config.max_log_scan_blocks,
+            min_total_supply: config.min_total_supply,
+            max_total_supply: config.max_total_supply,
+            supply_recheck_secs: config.supply_recheck_secs,
+            router: config.router_address,
+            wmon: config.wmon_address,
+            ..Self::default()
         }
     }
 }
@@ -59,6 +143,8 @@ sol! {
         function balanceOf(address account) external view returns (uint256);
         function totalSupply() external view returns (uint256);
         function decimals() external view returns (uint8);
+
+        event Transfer(address indexed from, address indexed to, uint256 value);
     }
 }
 
@@ -66,15 +152,15 @@ sol! {
 pub struct TokenAnalyzer<P: Provider + Clone> {
     provider: P,
     config: FilterConfig,
-    mon_price_usd: f64,
+    mon_price: MonPriceOracle,
 }
 
 impl<P: Provider + Clone> TokenAnalyzer<P> {
-    pub fn new(provider: P, config: FilterConfig, mon_price_usd: f64) -> Self {
+    pub fn new(provider: P, config: FilterConfig, mon_price: MonPriceOracle) -> Self {
         Self {
             provider,
             config,
-            mon_price_usd,
+            mon_price,
         }
     }
 
@@ -87,7 +173,8 @@ impl<P: Provider + Clone> TokenAnalyzer<P> {
         liquidity_mon: f64,
     ) -> TokenAnalysis {
         let now = chrono::Utc::now().timestamp() as u64;
-        let age_minutes = (now - creation_time) / 60;
+        // Block timestamps can run slightly ahead of the local clock
+        let age_minutes = now.saturating_sub(creation_time) / 60;
 
         // Get token contract
         let contract = IERC20::new(token, &self.provider);
@@ -100,6 +187,21 @@ impl<P: Provider + Clone> TokenAnalyzer<P> {
                 return self.reject(token, "Failed to get total supply");
             }
         };
+        let supply_read_at = Instant::now();
+
+        // Outlandish supplies break the percentage math below, so bounds come first
+        let decimals = contract.decimals().call().await.unwrap_or_else(|e| {
+            debug!("Failed to get decimals for {:?}, assuming 18: {}", token, e);
+            18
+        });
+        if let Some(reason) = supply_out_of_range(
+            total_supply,
+            decimals,
+            self.config.min_total_supply,
+            self.config.max_total_supply,
+        ) {
+            return self.reject(token, &reason);
+        }
 
         // If liquidity not provided, use default estimate for new launch (~85 MON)
         let liquidity_used = if liquidity_mon > 0.0 {
@@ -109,72 +211,286 @@ impl<P: Provider + Clone> TokenAnalyzer<P> {
         };
 
         // Calculate market cap (liquidity * 2 is rough estimate)
-        let market_cap_usd = liquidity_used * self.mon_price_usd * 2.0;
+        let market_cap_usd = liquidity_used * self.mon_price.price_usd_or_fallback() * 2.0;
 
         // Check dev holdings if dev wallet provided
         let dev_holding_pct = if let Some(dev) = dev_wallet {
             match contract.balanceOf(dev).call().await {
-                Ok(balance) => {
-                    if total_supply > U256::ZERO {
-                        let pct = (balance.to::<u128>() as f64 / total_supply.to::<u128>() as f64) * 100.0;
-                        pct
-                    } else {
-                        0.0
-                    }
-                }
+                Ok(balance) => holding_pct(balance, total_supply),
                 Err(_) => 0.0,
             }
         } else {
             0.0
         };
+        let presume_dev = dev_wallet.is_none() && self.config.presume_largest_holder_dev;
+
+        // Reconstruct holder balances if any distribution check needs them
+        let holder_gate_active = self.config.min_holder_count > 0
+            && age_minutes >= self.config.holder_count_min_age_minutes;
+        let needs_balances = holder_gate_active
+            || presume_dev
+            || self.config.max_top_holder_pct > 0.0
+            || self.config.bundling_check_enabled;
+        let scan = if needs_balances {
+            self.scan_holder_balances(token, total_supply).await
+        } else {
+            None
+        };
+        let holders_complete = scan.as_ref().is_some_and(|scan| scan.complete);
+        let balances = scan.map(|scan| scan.balances);
+        let holder_count = balances
+            .as_ref()
+            .map(|balances| count_holders(balances, &self.config.excluded_holders));
+        let top_holder_pct = balances.as_ref().map(|balances| {
+            top_holders_pct(balances, &self.config.excluded_holders, total_supply, TOP_HOLDERS)
+        });
+        let top_pct = top_holder_pct.unwrap_or(0.0);
+
+        // Creator unknown (e.g. the legacy listener): the largest holder stands in for the dev.
+        // Only from a complete scan, since a partial one can miss the real largest holder
+        let presumed_dev = match &balances {
+            Some(balances) if presume_dev && holders_complete => {
+                top_holders(balances, &self.config.excluded_holders, 1).into_iter().next()
+            }
+            _ => None,
+        };
+        let dev_holding_pct = match presumed_dev {
+            Some((holder, balance)) => {
+                debug!("No creator for {:?}, presuming largest holder {:?} is the dev", token, holder);
+                holding_pct(balance, total_supply)
+            }
+            None => dev_holding_pct,
+        };
+
+        let analysis = TokenAnalysis {
+            token,
+            dev_wallet,
+            dev_holding_pct,
+            top_holder_pct: top_pct,
+            holder_count,
+            holders_complete,
+            is_bundled: false,
+            total_supply,
+            market_cap_usd,
+            age_minutes,
+            is_safe: false,
+            rejection_reason: None,
+        };
 
         // Check age filter
         if age_minutes > self.config.max_age_minutes {
             return self.reject_with_analysis(
-                token, dev_wallet, dev_holding_pct, 0.0, total_supply, market_cap_usd, age_minutes,
+                analysis,
                 format!("Token too old: {} min > {} max", age_minutes, self.config.max_age_minutes)
             );
         }
 
         // Check dev holdings
         if dev_holding_pct > self.config.max_dev_holding_pct {
+            let reason = match presumed_dev {
+                Some((holder, _)) => format!(
+                    "Dev holdings too high (heuristic: largest holder {:?}): {:.1}% > {}%",
+                    holder, dev_holding_pct, self.config.max_dev_holding_pct
+                ),
+                None => format!("Dev holdings too high: {:.1}% > {}%", dev_holding_pct, self.config.max_dev_holding_pct),
+            };
             return self.reject_with_analysis(
-                token, dev_wallet, dev_holding_pct, 0.0, total_supply, market_cap_usd, age_minutes,
-                format!("Dev holdings too high: {:.1}% > {}%", dev_holding_pct, self.config.max_dev_holding_pct)
+                analysis,
+                reason
             );
         }
 
+        // Check holder distribution (skipped if logs were unavailable or didn't reach creation)
+        if !holders_complete && balances.is_some() {
+            debug!("Holder scan for {:?} incomplete, skipping the holder count and concentration checks", token);
+        }
+        if let (true, true, Some(count)) = (holder_gate_active, holders_complete, holder_count) {
+            if count < self.config.min_holder_count {
+                return self.reject_with_analysis(
+                    analysis,
+                    format!(
+                        "Too few holders: {} < {} after {} min",
+                        count, self.config.min_holder_count, age_minutes
+                    )
+                );
+            }
+        }
+
+        if let (true, Some(pct)) = (holders_complete, top_holder_pct) {
+            if self.config.max_top_holder_pct > 0.0 && pct > self.config.max_top_holder_pct {
+                return self.reject_with_analysis(
+                    analysis,
+                    format!("Top {} holders own too much: {:.1}% > {}%", TOP_HOLDERS, pct, self.config.max_top_holder_pct)
+                );
+            }
+        }
+
         // Check market cap zone
         if market_cap_usd < self.config.min_market_cap_usd {
             return self.reject_with_analysis(
-                token, dev_wallet, dev_holding_pct, 0.0, total_supply, market_cap_usd, age_minutes,
+                analysis,
                 format!("Market cap too low: ${:.0} < ${:.0}", market_cap_usd, self.config.min_market_cap_usd)
             );
         }
 
         if market_cap_usd > self.config.max_market_cap_usd {
             return self.reject_with_analysis(
-                token, dev_wallet, dev_holding_pct, 0.0, total_supply, market_cap_usd, age_minutes,
+                analysis,
                 format!("Market cap too high: ${:.0} > ${:.0}", market_cap_usd, self.config.max_market_cap_usd)
             );
         }
 
+        // Honeypot: simulate buying and selling back through the DEX
+        if self.config.honeypot_check_enabled {
+            match simulate_round_trip(
+                &self.provider,
+                token,
+                self.config.router,
+                self.config.wmon,
+                self.config.honeypot_probe_wei,
+            )
+            .await
+            {
+                HoneypotVerdict::Sellable => {}
+                HoneypotVerdict::Inconclusive(reason) => {
+                    debug!("Honeypot check inconclusive for {:?}: {}", token, reason);
+                }
+                HoneypotVerdict::Honeypot(reason) => {
+                    return self.reject_with_analysis(
+                        analysis,
+                        format!("Honeypot: {}", reason)
+                    );
+                }
+            }
+        }
+
+        // Mintable supply: the first read was a while ago, so most of the wait is already spent
+        if self.config.supply_recheck_secs > 0 {
+            let spacing = Duration::from_secs(self.config.supply_recheck_secs);
+            tokio::time::sleep(spacing.saturating_sub(supply_read_at.elapsed())).await;
+            match contract.totalSupply().call().await {
+                Ok(supply) if supply != total_supply => {
+                    return self.reject_with_analysis(
+                        analysis,
+                        format!(
+                            "Total supply changed: {} -> {} within {}s (mintable)",
+                            total_supply, supply, self.config.supply_recheck_secs
+                        )
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => debug!("Supply recheck failed for {:?}: {}", token, e),
+            }
+        }
+
+        // Bundling last: it costs an RPC call per top holder
+        let is_bundled = match &balances {
+            Some(balances) if self.config.bundling_check_enabled => {
+                self.detect_bundling(token, balances).await
+            }
+            _ => false,
+        };
+
         info!(
-            "✅ Token passed filters: age={}min, dev={:.1}%, mcap=${:.0}",
-            age_minutes, dev_holding_pct, market_cap_usd
+            "✅ Token passed filters: age={}min, dev={:.1}%, top{}={:.1}%, mcap=${:.0}",
+            age_minutes, dev_holding_pct, TOP_HOLDERS, top_pct, market_cap_usd
         );
 
         TokenAnalysis {
-            token,
-            dev_wallet,
-            dev_holding_pct,
-            top_holder_pct: 0.0, // TODO: implement top holder analysis
-            total_supply,
-            market_cap_usd,
-            age_minutes,
+            is_bundled,
             is_safe: true,
-            rejection_reason: None,
+            ..analysis
+        }
+    }
+
+    /// Reconstruct non-zero holder balances from the token's Transfer logs,
+    /// paging back `max_log_scan_blocks` at a time until the balances add up to
+    /// `total_supply` (the logs reach the token's creation), genesis, or
+    /// `MAX_HOLDER_SCAN_PAGES`. Returns None if the provider can't serve logs.
+    async fn scan_holder_balances(&self, token: Address, total_supply: U256) -> Option<HolderScan> {
+        let head = match self.provider.get_block_number().await {
+            Ok(block) => block,
+            Err(e) => {
+                warn!("Failed to get block number for holder scan: {}", e);
+                return None;
+            }
+        };
+
+        let window = self.config.max_log_scan_blocks.max(1);
+        let mut logs: Vec<Log> = Vec::new();
+        let mut to_block = head;
+        for page in 0..MAX_HOLDER_SCAN_PAGES {
+            let from_block = to_block.saturating_sub(window - 1);
+            let filter = Filter::new()
+                .address(token)
+                .event_signature(IERC20::Transfer::SIGNATURE_HASH)
+                .from_block(from_block)
+                .to_block(to_block);
+
+            match self.provider.get_logs(&filter).await {
+                // Older pages go in front so the transfers replay in order
+                Ok(mut older) => {
+                    older.append(&mut logs);
+                    logs = older;
+                }
+                Err(e) if page == 0 => {
+                    warn!("Failed to get Transfer logs for {:?}: {}", token, e);
+                    return None;
+                }
+                Err(e) => {
+                    debug!("Holder scan for {:?} stopped at block {}: {}", token, to_block, e);
+                    break;
+                }
+            }
+
+            let balances = replay_transfers(&logs);
+            if total_held(&balances) == total_supply {
+                debug!("Holder scan for {:?}: {} logs, {} holders", token, logs.len(), balances.len());
+                return Some(HolderScan { balances, complete: true });
+            }
+            if from_block == 0 {
+                break;
+            }
+            to_block = from_block - 1;
         }
+
+        let balances = replay_transfers(&logs);
+        debug!(
+            "Holder scan for {:?} incomplete: {} logs, {} holders hold {} of {}",
+            token,
+            logs.len(),
+            balances.len(),
+            total_held(&balances),
+            total_supply
+        );
+        Some(HolderScan { balances, complete: false })
+    }
+
+    /// Run the bundling heuristics over the top holders: identical balances or
+    /// mostly fresh wallets, then shared funding sources.
+    async fn detect_bundling(&self, token: Address, balances: &HashMap<Address, U256>) -> bool {
+        let holders = top_holders(balances, &self.config.excluded_holders, TOP_HOLDERS);
+        if quick_bundling_check(&self.provider, &holders).await {
+            return true;
+        }
+
+        let head = match self.provider.get_block_number().await {
+            Ok(block) => block,
+            Err(e) => {
+                warn!("Failed to get block number for bundling check: {}", e);
+                return false;
+            }
+        };
+
+        check_bundling(
+            &self.provider,
+            token,
+            holders.into_iter().map(|(holder, _)| holder).collect(),
+            head.saturating_sub(self.config.max_log_scan_blocks),
+            &self.config.excluded_holders,
+        )
+        .await
+        .is_bundled
     }
 
     fn reject(&self, token: Address, reason: &str) -> TokenAnalysis {
@@ -184,6 +500,9 @@ impl<P: Provider + Clone> TokenAnalyzer<P> {
             dev_wallet: None,
             dev_holding_pct: 0.0,
             top_holder_pct: 0.0,
+            holder_count: None,
+            holders_complete: false,
+            is_bundled: false,
             total_supply: U256::ZERO,
             market_cap_usd: 0.0,
             age_minutes: 0,
@@ -192,28 +511,372 @@ impl<P: Provider + Clone> TokenAnalyzer<P> {
         }
     }
 
-    fn reject_with_analysis(
-        &self,
-        token: Address,
-        dev_wallet: Option<Address>,
-        dev_holding_pct: f64,
-        top_holder_pct: f64,
-        total_supply: U256,
-        market_cap_usd: f64,
-        age_minutes: u64,
-        reason: String,
-    ) -> TokenAnalysis {
+    fn reject_with_analysis(&self, analysis: TokenAnalysis, reason: String) -> TokenAnalysis {
         warn!("❌ Token rejected: {}", reason);
         TokenAnalysis {
-            token,
-            dev_wallet,
-            dev_holding_pct,
-            top_holder_pct,
-            total_supply,
-            market_cap_usd,
-            age_minutes,
             is_safe: false,
             rejection_reason: Some(reason),
+            ..analysis
+        }
+    }
+}
+
+/// Holder balances reconstructed from Transfer logs.
+struct HolderScan {
+    balances: HashMap<Address, U256>,
+    /// The balances add up to the total supply, i.e. the logs reach back to creation.
+    complete: bool,
+}
+
+/// Pages of `max_log_scan_blocks` the holder scan walks back before giving up.
+const MAX_HOLDER_SCAN_PAGES: u64 = 20;
+
+/// Number of wallets counted by the concentration check.
+const TOP_HOLDERS: usize = 10;
+
+/// The `n` largest balances, largest first, ignoring `excluded`.
+fn top_holders(
+    balances: &HashMap<Address, U256>,
+    excluded: &[Address],
+    n: usize,
+) -> Vec<(Address, U256)> {
+    let mut held: Vec<(Address, U256)> = balances
+        .iter()
+        .filter(|(holder, _)| !excluded.contains(holder))
+        .map(|(holder, balance)| (*holder, *balance))
+        .collect();
+    held.sort_unstable_by_key(|&(_, balance)| std::cmp::Reverse(balance));
+    held.truncate(n);
+    held
+}
+
+/// Non-zero balances after replaying `logs` (oldest first). Mints and burns
+/// move tokens from and to the zero address, which is not a holder.
+fn replay_transfers(logs: &[Log]) -> HashMap<Address, U256> {
+    let mut balances: HashMap<Address, U256> = HashMap::new();
+    for log in logs {
+        let Ok(transfer) = log.log_decode::<IERC20::Transfer>() else {
+            continue;
+        };
+        let transfer = transfer.inner.data;
+
+        if transfer.from != Address::ZERO {
+            let balance = balances.entry(transfer.from).or_insert(U256::ZERO);
+            *balance = balance.saturating_sub(transfer.value);
+        }
+        if transfer.to != Address::ZERO {
+            *balances.entry(transfer.to).or_insert(U256::ZERO) += transfer.value;
+        }
+    }
+
+    balances.retain(|_, balance| *balance > U256::ZERO);
+    balances
+}
+
+/// Sum of all `balances`.
+fn total_held(balances: &HashMap<Address, U256>) -> U256 {
+    balances.values().fold(U256::ZERO, |sum, balance| sum.saturating_add(*balance))
+}
+
+/// Holders with a balance, ignoring `excluded`.
+fn count_holders(balances: &HashMap<Address, U256>, excluded: &[Address]) -> usize {
+    balances.keys().filter(|holder| !excluded.contains(holder)).count()
+}
+
+/// Why `total_supply` (in base units) falls outside `min_tokens..=max_tokens`
+/// whole tokens, if it does. A zero bound is not enforced.
+pub fn supply_out_of_range(total_supply: U256, decimals: u8, min_tokens: u64, max_tokens: u64) -> Option<String> {
+    let unit = U256::from(10u64).checked_pow(U256::from(decimals));
+    let in_units = |tokens: u64| unit.and_then(|unit| unit.checked_mul(U256::from(tokens)));
+    let whole = match unit {
+        Some(unit) => format!("{} tokens", total_supply / unit),
+        None => format!("{} base units", total_supply),
+    };
+
+    // A bound too large for a U256 can't be exceeded, and can't be reached
+    let too_high = max_tokens > 0 && in_units(max_tokens).is_some_and(|max| total_supply > max);
+    let too_low = min_tokens > 0 && in_units(min_tokens).map(|min| total_supply < min).unwrap_or(true);
+
+    if too_high {
+        Some(format!("Total supply too high: {} > {} max ({} decimals)", whole, max_tokens, decimals))
+    } else if too_low {
+        Some(format!("Total supply too low: {} < {} min ({} decimals)", whole, min_tokens, decimals))
+    } else {
+        None
+    }
+}
+
+/// `balance` as a percentage of `total_supply`.
+fn holding_pct(balance: U256, total_supply: U256) -> f64 {
+    if total_supply.is_zero() {
+        return 0.0;
+    }
+    // Only the ratio matters, so the token's decimals cancel out
+    token_units_to_f64(balance, 18) / token_units_to_f64(total_supply, 18) * 100.0
+}
+
+/// Percentage of `total_supply` held by the `n` largest balances, ignoring `excluded`.
+fn top_holders_pct(
+    balances: &HashMap<Address, U256>,
+    excluded: &[Address],
+    total_supply: U256,
+    n: usize,
+) -> f64 {
+    if total_supply.is_zero() {
+        return 0.0;
+    }
+
+    let top = top_holders(balances, excluded, n)
+        .into_iter()
+        .fold(U256::ZERO, |sum, (_, balance)| sum.saturating_add(balance));
+
+    holding_pct(top, total_supply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::mock::{mock_provider, push_call, push_quantity};
+    use alloy::transports::mock::Asserter;
+
+    fn transfer(from: Address, to: Address, value: u64) -> Log {
+        let data = IERC20::Transfer { from, to, value: U256::from(value) }.encode_log_data();
+        Log {
+            inner: alloy::primitives::Log { address: Address::ZERO, data },
+            ..Default::default()
+        }
+    }
+
+    fn holder(n: u8) -> Address {
+        Address::repeat_byte(n)
+    }
+
+    #[test]
+    fn replay_tracks_mints_transfers_and_burns() {
+        let logs = [
+            transfer(Address::ZERO, holder(1), 100),
+            transfer(holder(1), holder(2), 40),
+            transfer(holder(2), Address::ZERO, 10),
+        ];
+        let balances = replay_transfers(&logs);
+
+        assert_eq!(balances[&holder(1)], U256::from(60));
+        assert_eq!(balances[&holder(2)], U256::from(30));
+        assert!(!balances.contains_key(&Address::ZERO));
+        assert_eq!(total_held(&balances), U256::from(90));
+    }
+
+    #[test]
+    fn partial_replay_does_not_add_up_to_supply() {
+        // The mint is outside the scanned window, so the sender's balance is unknown
+        let logs = [transfer(holder(1), holder(2), 40)];
+        let balances = replay_transfers(&logs);
+
+        assert!(!balances.contains_key(&holder(1)));
+        assert_ne!(total_held(&balances), U256::from(100));
+    }
+
+    #[test]
+    fn emptied_holders_are_dropped() {
+        let logs = [
+            transfer(Address::ZERO, holder(1), 50),
+            transfer(holder(1), holder(2), 50),
+        ];
+        let balances = replay_transfers(&logs);
+
+        assert_eq!(balances.len(), 1);
+        assert_eq!(count_holders(&balances, &[]), 1);
+        assert_eq!(count_holders(&balances, &[holder(2)]), 0);
+    }
+
+    #[test]
+    fn holding_pct_handles_amounts_beyond_u128() {
+        let supply = U256::MAX;
+        assert!((holding_pct(supply / U256::from(4), supply) - 25.0).abs() < 1e-9);
+        assert_eq!(holding_pct(U256::from(1), U256::ZERO), 0.0);
+    }
+
+    #[test]
+    fn top_holders_pct_ignores_excluded_and_caps_at_n() {
+        let balances: HashMap<Address, U256> = [
+            (holder(1), U256::from(500)),
+            (holder(2), U256::from(300)),
+            (holder(3), U256::from(200)),
+        ]
+        .into_iter()
+        .collect();
+        let supply = U256::from(1000);
+
+        assert!((top_holders_pct(&balances, &[], supply, 2) - 80.0).abs() < 1e-9);
+        assert!((top_holders_pct(&balances, &[holder(1)], supply, 2) - 50.0).abs() < 1e-9);
+        assert_eq!(top_holders(&balances, &[], 1), vec![(holder(1), U256::from(500))]);
+    }
+
+    #[test]
+    fn curve_reserve_is_not_a_top_holder_by_default() {
+        let curve: Address = BONDING_CURVE.parse().unwrap();
+        let config = FilterConfig::default();
+        assert!(config.excluded_holders.contains(&curve));
+
+        // Unsold supply sits in the curve; only the wallet counts
+        let balances: HashMap<Address, U256> =
+            [(curve, U256::from(900)), (holder(1), U256::from(100))].into_iter().collect();
+        let pct = top_holders_pct(&balances, &config.excluded_holders, U256::from(1000), TOP_HOLDERS);
+        assert!((pct - 10.0).abs() < 1e-9);
+        assert!(pct <= config.max_top_holder_pct);
+    }
+
+    #[test]
+    fn supply_bounds() {
+        let one_billion = U256::from(1_000_000_000u64) * U256::from(10u64).pow(U256::from(18));
+        assert!(supply_out_of_range(one_billion, 18, 0, 1_000_000_000_000).is_none());
+        assert!(supply_out_of_range(one_billion, 18, 0, 1_000_000).is_some());
+        assert!(supply_out_of_range(one_billion, 18, 2_000_000_000, 0).is_some());
+        assert!(supply_out_of_range(U256::MAX, 18, 0, 0).is_none());
+    }
+
+    /// Only the dev-holding cap is live; the market cap window takes anything.
+    fn dev_cap_only(presume_largest_holder_dev: bool) -> FilterConfig {
+        FilterConfig {
+            presume_largest_holder_dev,
+            min_holder_count: 0,
+            max_top_holder_pct: 0.0,
+            min_market_cap_usd: 0.0,
+            max_market_cap_usd: f64::MAX,
+            supply_recheck_secs: 0,
+            ..FilterConfig::default()
+        }
+    }
+
+    /// Script a 1000-token supply, then a holder scan at block 5 returning `logs`.
+    fn push_supply_and_scan(asserter: &Asserter, logs: &[Log]) {
+        push_call::<IERC20::totalSupplyCall>(asserter, &U256::from(1000u64));
+        push_call::<IERC20::decimalsCall>(asserter, &18);
+        push_quantity(asserter, 5);
+        asserter.push_success(&logs.to_vec());
+    }
+
+    async fn analyze(config: FilterConfig, scan_logs: Option<&[Log]>) -> TokenAnalysis {
+        let (provider, asserter) = mock_provider();
+        match scan_logs {
+            Some(logs) => push_supply_and_scan(&asserter, logs),
+            None => {
+                push_call::<IERC20::totalSupplyCall>(&asserter, &U256::from(1000u64));
+                push_call::<IERC20::decimalsCall>(&asserter, &18);
+            }
+        }
+        let now = chrono::Utc::now().timestamp() as u64;
+        TokenAnalyzer::new(provider, config, MonPriceOracle::new(Some(1.0)))
+            .analyze(holder(0xaa), None, now, 100.0)
+            .await
+    }
+
+    #[tokio::test]
+    async fn largest_holder_stands_in_for_an_unknown_dev() {
+        let curve: Address = BONDING_CURVE.parse().unwrap();
+        // The curve holds the most, but it is excluded
+        let logs = [
+            transfer(Address::ZERO, curve, 1000),
+            transfer(curve, holder(1), 150),
+            transfer(curve, holder(2), 50),
+        ];
+        let analysis = analyze(dev_cap_only(true), Some(&logs)).await;
+
+        assert!(!analysis.is_safe);
+        assert!((analysis.dev_holding_pct - 15.0).abs() < 1e-9);
+        let reason = analysis.rejection_reason.unwrap();
+        assert!(reason.contains("heuristic"), "{}", reason);
+        assert!(reason.contains(&format!("{:?}", holder(1))), "{}", reason);
+    }
+
+    #[tokio::test]
+    async fn no_presumption_from_an_incomplete_scan() {
+        // The mint predates the scanned blocks, so the largest holder is unknown
+        let logs = [transfer(holder(1), holder(2), 150)];
+        let analysis = analyze(dev_cap_only(true), Some(&logs)).await;
+
+        assert!(!analysis.holders_complete);
+        assert_eq!(analysis.dev_holding_pct, 0.0);
+        assert!(analysis.is_safe, "{:?}", analysis.rejection_reason);
+    }
+
+    #[tokio::test]
+    async fn unknown_dev_is_unchecked_when_presumption_is_off() {
+        // No scan is needed, so none is scripted
+        let analysis = analyze(dev_cap_only(false), None).await;
+
+        assert_eq!(analysis.holder_count, None);
+        assert_eq!(analysis.dev_holding_pct, 0.0);
+        assert!(analysis.is_safe, "{:?}", analysis.rejection_reason);
+    }
+
+    fn holder_gate(min_age_minutes: u64) -> FilterConfig {
+        FilterConfig {
+            min_holder_count: 3,
+            holder_count_min_age_minutes: min_age_minutes,
+            ..dev_cap_only(false)
         }
     }
+
+    #[tokio::test]
+    async fn curve_does_not_count_toward_the_holder_minimum() {
+        let curve: Address = BONDING_CURVE.parse().unwrap();
+        let logs = [
+            transfer(Address::ZERO, curve, 1000),
+            transfer(curve, holder(1), 10),
+            transfer(curve, holder(2), 10),
+        ];
+        let analysis = analyze(holder_gate(0), Some(&logs)).await;
+
+        assert_eq!(analysis.holder_count, Some(2));
+        let reason = analysis.rejection_reason.unwrap();
+        assert!(reason.starts_with("Too few holders: 2 < 3"), "{}", reason);
+    }
+
+    #[tokio::test]
+    async fn holder_minimum_waits_for_the_token_to_age() {
+        // Brand new, so the holders aren't even scanned
+        let analysis = analyze(holder_gate(10), None).await;
+        assert_eq!(analysis.holder_count, None);
+        assert!(analysis.is_safe, "{:?}", analysis.rejection_reason);
+    }
+
+    #[tokio::test]
+    async fn supply_outside_the_bounds_is_rejected_before_the_scan() {
+        let config = FilterConfig { min_total_supply: 2_000, min_holder_count: 3, holder_count_min_age_minutes: 0, ..dev_cap_only(true) };
+        let (provider, asserter) = mock_provider();
+        // 1000 whole tokens at 0 decimals; nothing else is scripted
+        push_call::<IERC20::totalSupplyCall>(&asserter, &U256::from(1000u64));
+        push_call::<IERC20::decimalsCall>(&asserter, &0);
+        let analysis = TokenAnalyzer::new(provider, config, MonPriceOracle::new(Some(1.0)))
+            .analyze(holder(0xaa), None, 0, 100.0)
+            .await;
+
+        let reason = analysis.rejection_reason.unwrap();
+        assert_eq!(reason, "Total supply too low: 1000 tokens < 2000 min (0 decimals)");
+    }
+
+    #[tokio::test]
+    async fn supply_that_changes_before_the_recheck_is_mintable() {
+        let config = FilterConfig { supply_recheck_secs: 1, ..dev_cap_only(false) };
+        let (provider, asserter) = mock_provider();
+        push_call::<IERC20::totalSupplyCall>(&asserter, &U256::from(1000u64));
+        push_call::<IERC20::decimalsCall>(&asserter, &18);
+        push_call::<IERC20::totalSupplyCall>(&asserter, &U256::from(1500u64));
+        let now = chrono::Utc::now().timestamp() as u64;
+        let analysis = TokenAnalyzer::new(provider, config, MonPriceOracle::new(Some(1.0)))
+            .analyze(holder(0xaa), None, now, 100.0)
+            .await;
+
+        let reason = analysis.rejection_reason.unwrap();
+        assert_eq!(reason, "Total supply changed: 1000 -> 1500 within 1s (mintable)");
+    }
+
+    #[test]
+    fn supply_bounds_out_of_u256_range_are_unreachable() {
+        // 10^80 overflows a U256: no maximum applies, the minimum can never be met
+        assert!(supply_out_of_range(U256::from(1u64), 80, 0, 1).is_none());
+        let reason = supply_out_of_range(U256::from(1u64), 80, 1, 0).unwrap();
+        assert_eq!(reason, "Total supply too low: 1 base units < 1 min (80 decimals)");
+    }
 }
@@ -0,0 +1,108 @@
+// Copyright (C) 2025 Category Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! "Trading enabled" precheck - simulates a minimal buy so tokens that deploy
+//! with trading switched off are skipped instead of reverting on the real buy.
+
+use crate::executor::SdkExecutor;
+use alloy::primitives::{Address, U256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Value of the simulated buy (0.01 MON).
+const SIMULATION_AMOUNT_WEI: u128 = 10_000_000_000_000_000;
+
+/// How long a "trading disabled" result is trusted before re-simulating.
+const DISABLED_CACHE_SECS: u64 = 30;
+
+/// Outcome of a buy simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TradingStatus {
+    /// The buy would go through.
+    Enabled,
+    /// The token reverted because trading is not open yet.
+    Disabled(String),
+    /// The simulation failed for another reason (RPC, balance, quote...).
+    Unknown(String),
+}
+
+/// Simulate a minimal buy and classify the result.
+pub async fn trading_enabled(sdk_executor: &SdkExecutor, token: Address) -> TradingStatus {
+    match sdk_executor
+        .simulate_buy(token, U256::from(SIMULATION_AMOUNT_WEI))
+        .await
+    {
+        Ok(()) => TradingStatus::Enabled,
+        Err(e) => {
+            let reason = e.to_string();
+            if is_trading_disabled_error(&reason) {
+                TradingStatus::Disabled(reason)
+            } else {
+                TradingStatus::Unknown(reason)
+            }
+        }
+    }
+}
+
+/// Whether a revert looks like the token blocking trades (vs. slippage, balance, etc).
+fn is_trading_disabled_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    [
+        "trading not enabled",
+        "tradingnotenabled",
+        "trading is not enabled",
+        "trading disabled",
+        "trading not open",
+        "trading not started",
+        "tradingnotstarted",
+        "not tradable",
+        "not tradeable",
+    ]
+    .iter()
+    .any(|pattern| error.contains(pattern))
+}
+
+/// Trading precheck with a short-lived cache of negative results.
+pub struct TradingCheck {
+    sdk_executor: Arc<SdkExecutor>,
+    disabled: Mutex<HashMap<Address, Instant>>,
+}
+
+impl TradingCheck {
+    pub fn new(sdk_executor: Arc<SdkExecutor>) -> Self {
+        Self {
+            sdk_executor,
+            disabled: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns `false` only when trading is known to be disabled.
+    /// Inconclusive simulations don't block the buy; the executor handles real failures.
+    pub async fn is_enabled(&self, token: Address) -> bool {
+        let ttl = Duration::from_secs(DISABLED_CACHE_SECS);
+        {
+            let mut disabled = self.disabled.lock().await;
+            disabled.retain(|_, checked_at| checked_at.elapsed() < ttl);
+            if disabled.contains_key(&token) {
+                debug!("Trading still marked disabled for {:?} (cached)", token);
+                return false;
+            }
+        }
+
+        match trading_enabled(&self.sdk_executor, token).await {
+            TradingStatus::Enabled => true,
+            TradingStatus::Disabled(reason) => {
+                warn!("🚫 Trading disabled for {:?}: {}", token, reason);
+                self.disabled.lock().await.insert(token, Instant::now());
+                false
+            }
+            TradingStatus::Unknown(reason) => {
+                debug!("Trading precheck inconclusive for {:?}: {}", token, reason);
+                true
+            }
+        }
+    }
+}
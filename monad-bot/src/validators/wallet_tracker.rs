@@ -1,3 +1,4 @@
+use crate::persist::write_atomic;
 use alloy::primitives::Address;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -19,6 +20,8 @@ pub struct WalletStats {
     
     // Timing
     pub avg_hold_time_sec: u64,
+    #[serde(default)]
+    pub total_hold_time_sec: u64, // Exact sum, avg is derived from it
     pub last_trade_time: u64,
     
     // Advanced
@@ -37,6 +40,7 @@ impl Default for WalletStats {
             total_invested_mon: 0.0,
             avg_roi_pct: 0.0,
             avg_hold_time_sec: 0,
+            total_hold_time_sec: 0,
             last_trade_time: 0,
             win_streak: 0,
             best_trade_mon: 0.0,
@@ -45,6 +49,52 @@ impl Default for WalletStats {
     }
 }
 
+impl WalletStats {
+    /// Fold a closed trade into the stats: `invested_mon` in, `pnl` and `roi` (%)
+    /// out, held `hold_time` seconds and closed at `now`.
+    pub fn record_trade(&mut self, invested_mon: f64, pnl: f64, roi: f64, hold_time: u64, now: u64) {
+        // Update counts
+        self.total_trades += 1;
+        self.last_trade_time = now;
+        self.total_invested_mon += invested_mon;
+        self.total_pnl_mon += pnl;
+
+        // Updating averages
+        // ROI: New Avg = ((Old Avg * (N-1)) + New Val) / N
+        // Hold time: kept as an exact sum so integer division doesn't compound
+        let n = self.total_trades as u64;
+        if self.total_hold_time_sec == 0 && n > 1 {
+            // Stats saved before the sum existed: seed it from the old average
+            self.total_hold_time_sec = self.avg_hold_time_sec * (n - 1);
+        }
+        self.total_hold_time_sec += hold_time;
+        self.avg_hold_time_sec = self.total_hold_time_sec / n;
+
+        if n > 1 {
+            self.avg_roi_pct = ((self.avg_roi_pct * (n as f64 - 1.0)) + roi) / n as f64;
+        } else {
+            self.avg_roi_pct = roi;
+        }
+
+        // Win/Loss stats
+        if pnl > 0.0 {
+            self.wins += 1;
+            self.win_streak += 1;
+        } else {
+            self.losses += 1;
+            self.win_streak = 0;
+        }
+
+        // Records (the first trade sets both, even if it's a loss or a win)
+        if n == 1 || pnl > self.best_trade_mon {
+            self.best_trade_mon = pnl;
+        }
+        if n == 1 || pnl < self.worst_trade_mon {
+            self.worst_trade_mon = pnl;
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PositionEntry {
     pub entry_price_mon: f64, // Total MON spent
@@ -76,21 +126,24 @@ impl WalletTracker {
 
     pub fn save(&self) {
         let json = serde_json::to_string_pretty(&self.stats).unwrap_or_default();
-        if let Err(e) = fs::write(WALLET_STATS_FILE, json) {
+        if let Err(e) = write_atomic(WALLET_STATS_FILE, json) {
             warn!("Failed to save wallet stats: {}", e);
         }
     }
 
     pub fn record_buy(&mut self, wallet: Address, token: Address, entry_price_mon: f64) {
-        let entry = PositionEntry {
-            entry_price_mon,
-            entry_time: chrono::Utc::now().timestamp() as u64,
-        };
-        
+        let now = chrono::Utc::now().timestamp() as u64;
+
+        // Adding to an open position accumulates cost and keeps the first entry time
         self.active_positions
             .entry(wallet)
             .or_default()
-            .insert(token, entry);
+            .entry(token)
+            .and_modify(|entry| entry.entry_price_mon += entry_price_mon)
+            .or_insert(PositionEntry {
+                entry_price_mon,
+                entry_time: now,
+            });
     }
 
     pub fn record_sell(&mut self, wallet: Address, token: Address, exit_price_mon: f64) -> Option<f64> {
@@ -111,39 +164,7 @@ impl WalletTracker {
             let hold_time = now.saturating_sub(entry.entry_time);
 
             let stats = self.stats.entry(wallet).or_default();
-            
-            // Update counts
-            stats.total_trades += 1;
-            stats.last_trade_time = now;
-            stats.total_invested_mon += entry.entry_price_mon;
-            stats.total_pnl_mon += pnl;
-
-            // Updating averages (simple moving average approximation)
-            // New Avg = ((Old Avg * (N-1)) + New Val) / N
-            if stats.total_trades > 1 {
-                stats.avg_roi_pct = ((stats.avg_roi_pct * (stats.total_trades as f64 - 1.0)) + roi) / stats.total_trades as f64;
-                stats.avg_hold_time_sec = ((stats.avg_hold_time_sec * (stats.total_trades as u64 - 1)) + hold_time) / stats.total_trades as u64;
-            } else {
-                stats.avg_roi_pct = roi;
-                stats.avg_hold_time_sec = hold_time;
-            }
-
-            // Win/Loss stats
-            if pnl > 0.0 {
-                stats.wins += 1;
-                stats.win_streak += 1;
-            } else {
-                stats.losses += 1;
-                stats.win_streak = 0;
-            }
-            
-            // Records
-            if pnl > stats.best_trade_mon {
-                stats.best_trade_mon = pnl;
-            }
-            if pnl < stats.worst_trade_mon {
-                stats.worst_trade_mon = pnl;
-            }
+            stats.record_trade(entry.entry_price_mon, pnl, roi, hold_time, now);
 
             info!(
                 "📊 Tracker Update: {:?} | PnL: {:.4} MON | ROI: {:.2}% | Hold: {}s | Score: {:.1}",
@@ -167,6 +188,11 @@ impl WalletTracker {
         }
     }
     
+    /// Copy-size multiplier for a wallet: see `score_multiplier`.
+    pub fn size_multiplier(&self, wallet: &Address, tiers: &[(f64, f64)]) -> f64 {
+        score_multiplier(self.get_score(wallet), tiers)
+    }
+
     fn calculate_score(stats: &WalletStats) -> f64 {
         if stats.total_trades < 3 {
              return 50.0; // Needs data
@@ -194,3 +220,135 @@ impl WalletTracker {
         total_score.clamp(0.0, 100.0)
     }
 }
+
+/// Multiplier of the highest tier whose minimum score `score` reaches.
+/// `tiers` is `(min_score, multiplier)` sorted by score; below every tier (or with none) it is 1.0.
+pub fn score_multiplier(score: f64, tiers: &[(f64, f64)]) -> f64 {
+    tiers
+        .iter()
+        .rev()
+        .find(|(min_score, _)| score >= *min_score)
+        .map_or(1.0, |(_, multiplier)| *multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn stats_after(trades: &[(f64, f64, u64)]) -> WalletStats {
+        let mut stats = WalletStats::default();
+        for (i, &(invested, pnl, hold_time)) in trades.iter().enumerate() {
+            let roi = if invested > 0.0 { pnl / invested * 100.0 } else { 0.0 };
+            stats.record_trade(invested, pnl, roi, hold_time, 1_000 + i as u64);
+        }
+        stats
+    }
+
+    #[test]
+    fn first_trade_sets_both_records() {
+        let stats = stats_after(&[(1.0, -0.4, 60)]);
+        assert_eq!(stats.best_trade_mon, -0.4);
+        assert_eq!(stats.worst_trade_mon, -0.4);
+        assert_eq!(stats.losses, 1);
+        assert_eq!(stats.avg_roi_pct, -40.0);
+    }
+
+    #[test]
+    fn averages_and_streaks_follow_the_trades() {
+        let stats = stats_after(&[(1.0, 0.5, 10), (2.0, 1.0, 20), (1.0, -0.2, 31)]);
+        assert_eq!(stats.total_trades, 3);
+        assert_eq!((stats.wins, stats.losses), (2, 1));
+        assert_eq!(stats.win_streak, 0);
+        assert_eq!(stats.total_hold_time_sec, 61);
+        assert_eq!(stats.avg_hold_time_sec, 20);
+        assert!((stats.avg_roi_pct - 80.0 / 3.0).abs() < 1e-9);
+        assert!((stats.total_pnl_mon - 1.3).abs() < 1e-9);
+        assert_eq!(stats.best_trade_mon, 1.0);
+        assert_eq!(stats.worst_trade_mon, -0.2);
+        assert_eq!(stats.last_trade_time, 1_002);
+    }
+
+    #[test]
+    fn hold_time_sum_is_seeded_from_stats_saved_without_it() {
+        let mut stats = WalletStats {
+            total_trades: 2,
+            avg_hold_time_sec: 100,
+            ..WalletStats::default()
+        };
+        stats.record_trade(1.0, 0.1, 10.0, 400, 0);
+        assert_eq!(stats.total_hold_time_sec, 600);
+        assert_eq!(stats.avg_hold_time_sec, 200);
+    }
+
+    #[test]
+    fn score_is_neutral_until_three_trades() {
+        let mut tracker = WalletTracker {
+            stats: HashMap::new(),
+            active_positions: HashMap::new(),
+        };
+        let wallet = Address::repeat_byte(7);
+        assert_eq!(tracker.get_score(&wallet), 50.0);
+
+        tracker.stats.insert(wallet, stats_after(&[(1.0, 5.0, 1), (1.0, 5.0, 1)]));
+        assert_eq!(tracker.get_score(&wallet), 50.0);
+    }
+
+    #[test]
+    fn consistent_winner_outscores_consistent_loser() {
+        let winner = stats_after(&[(1.0, 1.0, 1); 5]);
+        let loser = stats_after(&[(1.0, -0.9, 1); 5]);
+        // Full win rate and ROI marks, 5 MON and a 5-trade streak
+        assert_eq!(WalletTracker::calculate_score(&winner), 80.0);
+        assert_eq!(WalletTracker::calculate_score(&loser), 0.0);
+    }
+
+    #[test]
+    fn score_multiplier_picks_the_highest_tier_reached() {
+        let tiers = [(60.0, 1.5), (80.0, 2.0)];
+        assert_eq!(score_multiplier(50.0, &tiers), 1.0);
+        assert_eq!(score_multiplier(60.0, &tiers), 1.5);
+        assert_eq!(score_multiplier(95.0, &tiers), 2.0);
+        assert_eq!(score_multiplier(95.0, &[]), 1.0);
+    }
+
+    #[test]
+    fn size_multiplier_uses_the_wallet_score() {
+        let good = Address::repeat_byte(1);
+        let stats = stats_after(&[(1.0, 1.0, 60), (1.0, 1.0, 60), (1.0, 1.0, 60)]);
+        assert!(WalletTracker::calculate_score(&stats) >= 75.0);
+        let tracker = WalletTracker {
+            stats: HashMap::from([(good, stats)]),
+            active_positions: HashMap::new(),
+        };
+        let tiers = [(0.0, 0.25), (40.0, 0.5), (75.0, 1.5)];
+
+        assert_eq!(tracker.size_multiplier(&good, &tiers), 1.5);
+        // Unknown wallets score a neutral 50
+        assert_eq!(tracker.size_multiplier(&Address::repeat_byte(2), &tiers), 0.5);
+    }
+
+    fn trade() -> impl Strategy<Value = (f64, f64, u64)> {
+        (0.0..1_000.0f64, -1_000.0..1_000.0f64, 0..86_400u64)
+    }
+
+    proptest! {
+        #[test]
+        fn score_stays_within_bounds(trades in prop::collection::vec(trade(), 0..50)) {
+            let score = WalletTracker::calculate_score(&stats_after(&trades));
+            prop_assert!((0.0..=100.0).contains(&score));
+        }
+
+        #[test]
+        fn counts_and_records_stay_consistent(trades in prop::collection::vec(trade(), 1..50)) {
+            let stats = stats_after(&trades);
+            prop_assert_eq!(stats.total_trades as usize, trades.len());
+            prop_assert_eq!(stats.wins + stats.losses, stats.total_trades);
+            prop_assert!(stats.win_streak <= stats.wins);
+            prop_assert!(stats.worst_trade_mon <= stats.best_trade_mon);
+            let hold_sum: u64 = trades.iter().map(|t| t.2).sum();
+            prop_assert_eq!(stats.total_hold_time_sec, hold_sum);
+            prop_assert_eq!(stats.avg_hold_time_sec, hold_sum / trades.len() as u64);
+        }
+    }
+}